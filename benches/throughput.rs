@@ -0,0 +1,227 @@
+use criterion::{BenchmarkId, Criterion, Throughput, black_box, criterion_group, criterion_main};
+use oxc_allocator::Allocator;
+use oxc_ast::Visit;
+use oxc_parser::Parser;
+use oxc_span::SourceType;
+use std::fs;
+use std::path::Path;
+use tempfile::TempDir;
+use windwarden::ProcessOptions;
+use windwarden::file_processor::{FileDiscoveryConfig, FileProcessingPipeline, ProcessingMode};
+use windwarden::parser::ClassExtractor;
+use windwarden::processor::FileProcessor;
+use windwarden::sorter::TailwindSorter;
+
+/// Class strings in the shapes seen in real components
+const CLASS_STRINGS: &[&str] = &[
+    "p-4 flex m-2",
+    "w-full h-screen bg-gray-100 flex items-center justify-center p-8",
+    "px-4 py-2 bg-blue-500 text-white rounded hover:bg-blue-600 focus:outline-none focus:ring-2 focus:ring-blue-500 focus:ring-opacity-50",
+    "inline-flex items-center justify-center font-medium transition-colors focus-visible:outline-none focus-visible:ring-2 disabled:opacity-50 disabled:pointer-events-none",
+    "grid grid-cols-1 md:grid-cols-2 lg:grid-cols-4 gap-4 md:gap-6 dark:bg-gray-900 dark:text-gray-100",
+    "absolute -top-2 -right-2 !p-1 w-[calc(100%-2rem)] bg-[#1da1f2] text-[14px]",
+];
+
+/// A realistic TSX component mixing JSX attributes, utility calls, and CVA
+const COMPONENT: &str = r#"import * as React from "react";
+import { cva } from "class-variance-authority";
+import { cn } from "@/lib/utils";
+
+const buttonVariants = cva(
+  "inline-flex items-center justify-center rounded-md text-sm font-medium transition-colors focus-visible:outline-none disabled:opacity-50",
+  {
+    variants: {
+      variant: {
+        default: "bg-primary text-primary-foreground hover:bg-primary/90",
+        outline: "border border-input bg-background hover:bg-accent",
+        ghost: "hover:bg-accent hover:text-accent-foreground",
+      },
+      size: {
+        default: "h-10 px-4 py-2",
+        sm: "h-9 rounded-md px-3",
+        lg: "h-11 rounded-md px-8",
+      },
+    },
+  }
+);
+
+export function Card({ title, active, children }) {
+  return (
+    <div className="shadow-md p-6 flex flex-col rounded-lg bg-white dark:bg-gray-800">
+      <h2 className={cn("text-lg font-bold mb-2", active && "text-blue-600 underline")}>
+        {title}
+      </h2>
+      <p className={active ? "text-gray-900 mt-2 text-sm" : "mt-2 text-gray-500 text-sm"}>
+        {children}
+      </p>
+      <button className={cn(buttonVariants({ variant: "outline" }), "mt-4 w-full")}>
+        Open
+      </button>
+    </div>
+  );
+}
+"#;
+
+/// Benchmark sorting representative class strings
+fn bench_sorter(c: &mut Criterion) {
+    let mut group = c.benchmark_group("sorter");
+    let sorter = TailwindSorter::new();
+
+    group.throughput(Throughput::Elements(CLASS_STRINGS.len() as u64));
+    group.bench_function("sort_classes", |b| {
+        b.iter(|| {
+            for class_string in CLASS_STRINGS {
+                black_box(sorter.sort_classes(black_box(class_string)));
+            }
+        })
+    });
+
+    // Parallel mode builds a sorter per file, so category lookups for
+    // repeated classes only pay off if the cache outlives the sorter
+    group.throughput(Throughput::Elements(100 * CLASS_STRINGS.len() as u64));
+    group.bench_function("new_sorter_per_file", |b| {
+        b.iter(|| {
+            for _file in 0..100 {
+                let sorter = TailwindSorter::new();
+                for class_string in CLASS_STRINGS {
+                    black_box(sorter.sort_classes(black_box(class_string)));
+                }
+            }
+        })
+    });
+
+    group.finish();
+}
+
+/// Benchmark class extraction from a realistic component
+fn bench_extractor(c: &mut Criterion) {
+    let mut group = c.benchmark_group("class_extractor");
+    let source_type = SourceType::default().with_jsx(true).with_typescript(true);
+    group.throughput(Throughput::Bytes(COMPONENT.len() as u64));
+
+    // Extraction alone, over an already parsed program
+    let allocator = Allocator::default();
+    let program = Parser::new(&allocator, COMPONENT, source_type)
+        .parse()
+        .program;
+    group.bench_function("visit", |b| {
+        b.iter(|| {
+            let mut extractor = ClassExtractor::new(COMPONENT);
+            extractor.visit_program(black_box(&program));
+            black_box(extractor.into_matches())
+        })
+    });
+
+    // Parsing plus extraction, as done per file
+    group.bench_function("parse_and_visit", |b| {
+        b.iter(|| {
+            let allocator = Allocator::default();
+            let program = Parser::new(&allocator, black_box(COMPONENT), source_type)
+                .parse()
+                .program;
+            let mut extractor = ClassExtractor::new(COMPONENT);
+            extractor.visit_program(&program);
+            black_box(extractor.into_matches())
+        })
+    });
+
+    // Many small files: a fresh processor (and Oxc arena) per file, as parallel
+    // mode used to do, against one processor reused by a worker
+    let small_files: Vec<String> = (0..200)
+        .map(|i| {
+            format!(
+                "export const C{} = () => <div className=\"p-4 flex m-{}\">{}</div>;",
+                i,
+                i % 8,
+                i
+            )
+        })
+        .collect();
+    let options = ProcessOptions {
+        dry_run: true,
+        write: false,
+        check_formatted: false,
+    };
+    group.throughput(Throughput::Elements(small_files.len() as u64));
+    group.bench_function("small_files_new_processor_per_file", |b| {
+        b.iter(|| {
+            for source in &small_files {
+                let processor = FileProcessor::new();
+                black_box(
+                    processor
+                        .process_content(black_box(source), "Small.tsx", options.clone())
+                        .unwrap(),
+                );
+            }
+        })
+    });
+    group.bench_function("small_files_reused_processor", |b| {
+        let processor = FileProcessor::new();
+        b.iter(|| {
+            for source in &small_files {
+                black_box(
+                    processor
+                        .process_content(black_box(source), "Small.tsx", options.clone())
+                        .unwrap(),
+                );
+            }
+        })
+    });
+
+    group.finish();
+}
+
+/// Write `count` components spread over nested directories
+fn create_tree(root: &Path, count: usize) {
+    for i in 0..count {
+        let dir = root
+            .join(format!("feature_{}", i % 10))
+            .join(format!("part_{}", i % 3));
+        fs::create_dir_all(&dir).expect("Failed to create directory");
+        fs::write(dir.join(format!("Component{}.tsx", i)), COMPONENT)
+            .expect("Failed to write test file");
+    }
+}
+
+/// Benchmark the full pipeline; throughput is reported in files per second
+fn bench_pipeline(c: &mut Criterion) {
+    let mut group = c.benchmark_group("pipeline_throughput");
+    group.sample_size(20);
+
+    let options = ProcessOptions {
+        dry_run: true,
+        write: false,
+        check_formatted: false,
+    };
+
+    for &file_count in &[50, 250] {
+        let temp_dir = TempDir::new().expect("Failed to create temp directory");
+        create_tree(temp_dir.path(), file_count);
+        let paths = [temp_dir.path().display().to_string()];
+
+        group.throughput(Throughput::Elements(file_count as u64));
+
+        for (name, mode) in [
+            ("sequential", ProcessingMode::Sequential),
+            ("parallel", ProcessingMode::Parallel),
+        ] {
+            group.bench_with_input(BenchmarkId::new(name, file_count), &paths, |b, paths| {
+                b.iter(|| {
+                    let pipeline =
+                        FileProcessingPipeline::new_with_mode(FileDiscoveryConfig::default(), mode)
+                            .expect("Failed to create pipeline");
+                    let results = pipeline
+                        .process_files(black_box(paths), options.clone())
+                        .expect("Failed to process files");
+                    assert_eq!(results.total_files, file_count);
+                    results
+                })
+            });
+        }
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_sorter, bench_extractor, bench_pipeline);
+criterion_main!(benches);
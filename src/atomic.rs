@@ -1,7 +1,8 @@
+use crate::config::SafetyConfig;
 use crate::{Result, WindWardenError};
 use std::fs;
-use std::io::Write;
-use std::path::{Path, PathBuf};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::{Component, Path, PathBuf};
 
 /// Atomic file writer that ensures safe file operations
 ///
@@ -11,6 +12,7 @@ pub struct AtomicWriter {
     target_path: PathBuf,
     temp_path: PathBuf,
     temp_file: Option<fs::File>,
+    preserve_permissions: bool,
 }
 
 impl AtomicWriter {
@@ -38,9 +40,19 @@ impl AtomicWriter {
             target_path,
             temp_path,
             temp_file: Some(temp_file),
+            preserve_permissions: true,
         })
     }
 
+    /// Copy the permissions of the file being replaced onto the new file (default: true)
+    ///
+    /// On Unix this covers the mode bits and, where allowed, the owner and
+    /// group; on Windows it covers the read-only attribute.
+    pub fn with_preserve_permissions(mut self, preserve: bool) -> Self {
+        self.preserve_permissions = preserve;
+        self
+    }
+
     /// Write content to the temporary file
     pub fn write(&mut self, content: &str) -> Result<()> {
         let file = self
@@ -70,6 +82,15 @@ impl AtomicWriter {
             drop(file);
         }
 
+        // The temp file was created with default permissions; carry over the original's
+        if self.preserve_permissions
+            && let Ok(metadata) = fs::metadata(&self.target_path)
+        {
+            copy_permissions(&metadata, &self.temp_path).inspect_err(|_| {
+                let _ = fs::remove_file(&self.temp_path);
+            })?;
+        }
+
         // Atomically move the temporary file to the target path
         fs::rename(&self.temp_path, &self.target_path).map_err(|e| {
             // Clean up temp file on failure
@@ -105,6 +126,35 @@ impl Drop for AtomicWriter {
     }
 }
 
+/// Apply a file's permissions to another path
+fn copy_permissions(metadata: &fs::Metadata, path: &Path) -> Result<()> {
+    fs::set_permissions(path, metadata.permissions())
+        .map_err(|e| WindWardenError::from_io_error(e, Some(&path.display().to_string())))?;
+
+    // Ownership can only be changed with sufficient privileges, so this is best effort
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::MetadataExt;
+        let _ = std::os::unix::fs::chown(path, Some(metadata.uid()), Some(metadata.gid()));
+    }
+
+    Ok(())
+}
+
+/// A directory's path relative to the working directory, reduced to plain names
+/// so it can be recreated under the backup directory
+fn mirrored_path(dir: &Path) -> PathBuf {
+    let relative = std::env::current_dir()
+        .ok()
+        .and_then(|cwd| dir.strip_prefix(cwd).ok())
+        .unwrap_or(dir);
+
+    relative
+        .components()
+        .filter(|component| matches!(component, Component::Normal(_)))
+        .collect()
+}
+
 /// Generate a random suffix for temporary files
 fn generate_random_suffix() -> u64 {
     use std::collections::hash_map::DefaultHasher;
@@ -125,50 +175,297 @@ fn generate_random_suffix() -> u64 {
     hasher.finish()
 }
 
+/// How `verifyWrites` checks a file after writing it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum VerifyStrategy {
+    /// Read the whole file back and compare it with the intended content
+    Content,
+    /// Compare the length, then hashes, streaming the file back in chunks
+    #[default]
+    Hash,
+}
+
+impl VerifyStrategy {
+    /// Names accepted by `safety.verifyStrategy`
+    pub const NAMES: &'static [&'static str] = &["content", "hash"];
+}
+
+impl std::str::FromStr for VerifyStrategy {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "content" => Ok(VerifyStrategy::Content),
+            "hash" => Ok(VerifyStrategy::Hash),
+            _ => Err(format!(
+                "Invalid verify strategy '{}'. Valid options: {}",
+                s,
+                Self::NAMES.join(", ")
+            )),
+        }
+    }
+}
+
+/// Size of the chunks files are hashed in
+const HASH_CHUNK_SIZE: usize = 64 * 1024;
+
+/// Hash everything `reader` yields
+///
+/// Input is fed to the hasher in full chunks of the same size whatever
+/// the reader returns per call, so equal bytes always hash the same.
+fn hash_contents(mut reader: impl Read) -> std::io::Result<u64> {
+    use std::collections::hash_map::DefaultHasher;
+    use std::hash::Hasher;
+
+    let mut hasher = DefaultHasher::new();
+    let mut buffer = vec![0; HASH_CHUNK_SIZE];
+    loop {
+        let mut filled = 0;
+        while filled < buffer.len() {
+            match reader.read(&mut buffer[filled..]) {
+                Ok(0) => break,
+                Ok(n) => filled += n,
+                Err(e) if e.kind() == std::io::ErrorKind::Interrupted => {}
+                Err(e) => return Err(e),
+            }
+        }
+        if filled == 0 {
+            return Ok(hasher.finish());
+        }
+        hasher.write(&buffer[..filled]);
+    }
+}
+
+/// Options for atomic writes
+#[derive(Debug, Clone)]
+pub struct WriteOptions {
+    /// Copy the original file before replacing it
+    pub create_backup: bool,
+    /// Keep the original file's permissions on the new file
+    pub preserve_permissions: bool,
+    /// Suffix appended to backup file names
+    pub backup_suffix: String,
+    /// Directory to collect backups in instead of next to the originals
+    pub backup_dir: Option<PathBuf>,
+}
+
+impl Default for WriteOptions {
+    fn default() -> Self {
+        Self {
+            create_backup: false,
+            preserve_permissions: true,
+            backup_suffix: ".bak".to_string(),
+            backup_dir: None,
+        }
+    }
+}
+
+impl From<&SafetyConfig> for WriteOptions {
+    fn from(safety: &SafetyConfig) -> Self {
+        Self {
+            create_backup: safety.create_backups,
+            preserve_permissions: safety.preserve_permissions,
+            backup_suffix: safety.backup_suffix.clone(),
+            backup_dir: safety.backup_dir.clone(),
+        }
+    }
+}
+
 /// Atomic file operations helper functions
 pub mod operations {
     use super::*;
 
     /// Atomically write content to a file
     pub fn write_file(path: impl AsRef<Path>, content: &str) -> Result<()> {
-        let mut writer = AtomicWriter::new(path)?;
+        write_file_with_options(path, content, &WriteOptions::default())
+    }
+
+    /// Atomically write content to a file with backup
+    pub fn write_file_with_backup(path: impl AsRef<Path>, content: &str) -> Result<()> {
+        let options = WriteOptions {
+            create_backup: true,
+            ..WriteOptions::default()
+        };
+        write_file_with_options(path, content, &options)
+    }
+
+    /// Atomically write content to a file with the given options
+    pub fn write_file_with_options(
+        path: impl AsRef<Path>,
+        content: &str,
+        options: &WriteOptions,
+    ) -> Result<()> {
+        let path = path.as_ref();
+        backup_if_requested(path, options)?;
+
+        // Write the file atomically
+        let mut writer =
+            AtomicWriter::new(path)?.with_preserve_permissions(options.preserve_permissions);
         writer.write(content)?;
         writer.commit()?;
+
         Ok(())
     }
 
-    /// Atomically write content to a file with backup
-    pub fn write_file_with_backup(path: impl AsRef<Path>, content: &str) -> Result<()> {
+    /// Check that `path` holds exactly `content` after a write
+    pub fn verify_written(
+        path: impl AsRef<Path>,
+        content: &str,
+        strategy: VerifyStrategy,
+    ) -> Result<()> {
+        let path = path.as_ref();
+        let io_error = |e| WindWardenError::from_io_error(e, Some(&path.display().to_string()));
+
+        let matches = match strategy {
+            VerifyStrategy::Content => fs::read_to_string(path).map_err(io_error)? == content,
+            VerifyStrategy::Hash => {
+                let file = fs::File::open(path).map_err(io_error)?;
+                let length = file.metadata().map_err(io_error)?.len();
+                length == content.len() as u64
+                    && hash_contents(std::io::BufReader::new(file)).map_err(io_error)?
+                        == hash_contents(content.as_bytes()).map_err(io_error)?
+            }
+        };
+
+        if !matches {
+            return Err(WindWardenError::internal_error(format!(
+                "File verification failed for {}: content mismatch",
+                path.display()
+            )));
+        }
+        Ok(())
+    }
+
+    /// Overwrite only the bytes of `path` that differ between `original`
+    /// and `content`
+    ///
+    /// This only works when both have the same length, and only while the
+    /// file still holds `original`. Returns `false` without touching the
+    /// file otherwise, so the caller can fall back to a full write. A crash
+    /// part way through can leave a mix of old and new ranges.
+    pub fn write_changed_ranges(
+        path: impl AsRef<Path>,
+        original: &str,
+        content: &str,
+        options: &WriteOptions,
+    ) -> Result<bool> {
         let path = path.as_ref();
+        let path_str = path.display().to_string();
+        if original.len() != content.len() {
+            return Ok(false);
+        }
+
+        let mut file = fs::OpenOptions::new()
+            .read(true)
+            .write(true)
+            .open(path)
+            .map_err(|e| WindWardenError::from_io_error(e, Some(&path_str)))?;
+        let mut current = Vec::with_capacity(original.len());
+        file.read_to_end(&mut current)
+            .map_err(|e| WindWardenError::from_io_error(e, Some(&path_str)))?;
+        if current != original.as_bytes() {
+            return Ok(false);
+        }
+
+        backup_if_requested(path, options)?;
+        for (start, end) in changed_ranges(original.as_bytes(), content.as_bytes()) {
+            file.seek(SeekFrom::Start(start as u64))
+                .and_then(|_| file.write_all(&content.as_bytes()[start..end]))
+                .map_err(|e| WindWardenError::from_io_error(e, Some(&path_str)))?;
+        }
+        file.sync_all()
+            .map_err(|e| WindWardenError::from_io_error(e, Some(&path_str)))?;
 
-        // Create backup if file exists
-        if path.exists() {
-            let backup_path = create_backup_path(path)?;
+        Ok(true)
+    }
+
+    /// Byte ranges where two equal-length buffers differ
+    pub(crate) fn changed_ranges(original: &[u8], content: &[u8]) -> Vec<(usize, usize)> {
+        let mut ranges: Vec<(usize, usize)> = Vec::new();
+        for (index, _) in original
+            .iter()
+            .zip(content)
+            .enumerate()
+            .filter(|(_, (a, b))| a != b)
+        {
+            match ranges.last_mut() {
+                Some((_, end)) if *end == index => *end += 1,
+                _ => ranges.push((index, index + 1)),
+            }
+        }
+        ranges
+    }
+
+    /// Copy the file to its backup location if backups are enabled
+    fn backup_if_requested(path: &Path, options: &WriteOptions) -> Result<()> {
+        if options.create_backup && path.exists() {
+            let backup_path = create_backup_path(path, options)?;
             fs::copy(path, &backup_path).map_err(|e| {
                 WindWardenError::from_io_error(e, Some(&backup_path.display().to_string()))
             })?;
         }
+        Ok(())
+    }
 
-        // Write the file atomically
-        write_file(path, content)?;
+    /// Check that a file could be replaced, without touching the disk
+    ///
+    /// Used by dry runs of write mode. This catches read-only files and
+    /// directories, but not every failure a real write could hit.
+    pub fn check_writable(path: impl AsRef<Path>) -> Result<()> {
+        let path = path.as_ref();
+        let path_str = path.display().to_string();
+
+        let metadata =
+            fs::metadata(path).map_err(|e| WindWardenError::from_io_error(e, Some(&path_str)))?;
+        if metadata.permissions().readonly() {
+            return Err(WindWardenError::permission_denied(path_str));
+        }
+
+        // Atomic writes create a temp file next to the target
+        let parent = match path.parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => parent,
+            _ => Path::new("."),
+        };
+        let parent_metadata = fs::metadata(parent)
+            .map_err(|e| WindWardenError::from_io_error(e, Some(&parent.display().to_string())))?;
+        if parent_metadata.permissions().readonly() {
+            return Err(WindWardenError::permission_denied(
+                parent.display().to_string(),
+            ));
+        }
 
         Ok(())
     }
 
     /// Create a backup path for a file
-    fn create_backup_path(path: &Path) -> Result<PathBuf> {
+    ///
+    /// Backups sit next to the original unless a backup directory is set, in
+    /// which case the file's path relative to the working directory is
+    /// recreated under it.
+    fn create_backup_path(path: &Path, options: &WriteOptions) -> Result<PathBuf> {
         let file_name = path
             .file_name()
             .ok_or_else(|| WindWardenError::config_error("Invalid file path for backup"))?
             .to_string_lossy();
 
         let parent = path.parent().unwrap_or_else(|| Path::new("."));
+        let parent = match &options.backup_dir {
+            Some(backup_dir) => {
+                let dir = backup_dir.join(mirrored_path(parent));
+                fs::create_dir_all(&dir).map_err(|e| {
+                    WindWardenError::from_io_error(e, Some(&dir.display().to_string()))
+                })?;
+                dir
+            }
+            None => parent.to_path_buf(),
+        };
 
         // Try different backup names until we find one that doesn't exist
         for i in 1..=999 {
             let backup_name = format!(
-                "{}.bak{}",
+                "{}{}{}",
                 file_name,
+                options.backup_suffix,
                 if i == 1 {
                     String::new()
                 } else {
@@ -207,6 +504,34 @@ mod tests {
         assert_eq!(content, "Hello, World!");
     }
 
+    #[test]
+    fn test_verify_written_detects_corruption() {
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = temp_dir.path().join("test.txt");
+        // Longer than one hash chunk, so the corruption isn't in the first
+        let content = format!("{}tail", "a".repeat(HASH_CHUNK_SIZE));
+        operations::write_file(&file_path, &content).unwrap();
+
+        for strategy in [VerifyStrategy::Content, VerifyStrategy::Hash] {
+            operations::verify_written(&file_path, &content, strategy).unwrap();
+        }
+
+        // Same length, different bytes
+        fs::write(&file_path, format!("{}TAIL", "a".repeat(HASH_CHUNK_SIZE))).unwrap();
+        for strategy in [VerifyStrategy::Content, VerifyStrategy::Hash] {
+            let error = operations::verify_written(&file_path, &content, strategy).unwrap_err();
+            assert!(
+                error.to_string().contains("content mismatch"),
+                "{:?}",
+                strategy
+            );
+        }
+
+        // Truncated
+        fs::write(&file_path, &content[..10]).unwrap();
+        assert!(operations::verify_written(&file_path, &content, VerifyStrategy::Hash).is_err());
+    }
+
     #[test]
     fn test_atomic_write_cleanup_on_drop() {
         let temp_dir = TempDir::new().unwrap();
@@ -226,6 +551,61 @@ mod tests {
         assert!(!temp_path.exists());
     }
 
+    #[test]
+    fn test_changed_ranges() {
+        assert_eq!(
+            operations::changed_ranges(b"p-4 flex m-2", b"flex m-2 p-4"),
+            [(0, 8), (9, 10), (11, 12)]
+        );
+        assert_eq!(
+            operations::changed_ranges(b"a-b-c-d", b"a-x-c-y"),
+            [(2, 3), (6, 7)]
+        );
+        assert!(operations::changed_ranges(b"same", b"same").is_empty());
+    }
+
+    #[test]
+    fn test_write_changed_ranges() {
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = temp_dir.path().join("test.tsx");
+        let original = r#"<a className="p-4 flex" /><b className="m-2 block" />"#;
+        let sorted = r#"<a className="flex p-4" /><b className="block m-2" />"#;
+        fs::write(&file_path, original).unwrap();
+
+        let options = WriteOptions {
+            create_backup: true,
+            ..WriteOptions::default()
+        };
+        assert!(operations::write_changed_ranges(&file_path, original, sorted, &options).unwrap());
+        assert_eq!(fs::read_to_string(&file_path).unwrap(), sorted);
+        assert_eq!(
+            fs::read_to_string(temp_dir.path().join("test.tsx.bak")).unwrap(),
+            original
+        );
+    }
+
+    #[test]
+    fn test_write_changed_ranges_declines_other_lengths_and_stale_files() {
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = temp_dir.path().join("test.tsx");
+        let original = r#"<a className="p-4 p-4 flex" />"#;
+        fs::write(&file_path, original).unwrap();
+        let options = WriteOptions::default();
+
+        // Deduplication shortens the file
+        let deduped = r#"<a className="flex p-4" />"#;
+        assert!(
+            !operations::write_changed_ranges(&file_path, original, deduped, &options).unwrap()
+        );
+        assert_eq!(fs::read_to_string(&file_path).unwrap(), original);
+
+        // The file changed on disk since it was read
+        let stale = r#"<a className="p-4 p-4 grid" />"#;
+        let sorted = r#"<a className="grid p-4 p-4" />"#;
+        assert!(!operations::write_changed_ranges(&file_path, stale, sorted, &options).unwrap());
+        assert_eq!(fs::read_to_string(&file_path).unwrap(), original);
+    }
+
     #[test]
     fn test_atomic_write_helper() {
         let temp_dir = TempDir::new().unwrap();
@@ -259,6 +639,99 @@ mod tests {
         assert_eq!(backup_content, "Original content");
     }
 
+    #[test]
+    fn test_write_with_backup_suffix() {
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = temp_dir.path().join("test.txt");
+        fs::write(&file_path, "Original content").unwrap();
+
+        let options = WriteOptions {
+            create_backup: true,
+            backup_suffix: ".orig".to_string(),
+            ..WriteOptions::default()
+        };
+        operations::write_file_with_options(&file_path, "New content", &options).unwrap();
+        operations::write_file_with_options(&file_path, "Newer content", &options).unwrap();
+
+        assert_eq!(
+            fs::read_to_string(temp_dir.path().join("test.txt.orig")).unwrap(),
+            "Original content"
+        );
+        assert_eq!(
+            fs::read_to_string(temp_dir.path().join("test.txt.orig.2")).unwrap(),
+            "New content"
+        );
+        assert!(!temp_dir.path().join("test.txt.bak").exists());
+    }
+
+    #[test]
+    fn test_write_with_backup_dir_mirrors_tree() {
+        let temp_dir = TempDir::new().unwrap();
+        let source_dir = temp_dir.path().join("src").join("components");
+        fs::create_dir_all(&source_dir).unwrap();
+        let file_path = source_dir.join("Button.tsx");
+        fs::write(&file_path, "Original content").unwrap();
+
+        let backup_dir = temp_dir.path().join("backups");
+        let options = WriteOptions {
+            create_backup: true,
+            backup_dir: Some(backup_dir.clone()),
+            ..WriteOptions::default()
+        };
+        operations::write_file_with_options(&file_path, "New content", &options).unwrap();
+
+        // Absolute paths outside the working directory are mirrored in full
+        let backup_path = backup_dir
+            .join(mirrored_path(&source_dir))
+            .join("Button.tsx.bak");
+        assert!(backup_path.ends_with("src/components/Button.tsx.bak"));
+        assert_eq!(
+            fs::read_to_string(&backup_path).unwrap(),
+            "Original content"
+        );
+        assert!(!source_dir.join("Button.tsx.bak").exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_write_preserves_permissions() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = temp_dir.path().join("secret.tsx");
+        fs::write(&file_path, "Original content").unwrap();
+        fs::set_permissions(&file_path, fs::Permissions::from_mode(0o600)).unwrap();
+
+        operations::write_file(&file_path, "New content").unwrap();
+        let mode = fs::metadata(&file_path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+
+        // Executable scripts stay executable, and backups keep the mode too
+        fs::set_permissions(&file_path, fs::Permissions::from_mode(0o755)).unwrap();
+        operations::write_file_with_backup(&file_path, "Newer content").unwrap();
+        let mode = fs::metadata(&file_path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o755);
+        assert_eq!(fs::read_to_string(&file_path).unwrap(), "Newer content");
+    }
+
+    #[test]
+    fn test_check_writable() {
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = temp_dir.path().join("test.txt");
+        fs::write(&file_path, "content").unwrap();
+        assert!(operations::check_writable(&file_path).is_ok());
+
+        let mut permissions = fs::metadata(&file_path).unwrap().permissions();
+        permissions.set_readonly(true);
+        fs::set_permissions(&file_path, permissions).unwrap();
+        assert!(matches!(
+            operations::check_writable(&file_path),
+            Err(WindWardenError::PermissionDenied { .. })
+        ));
+
+        assert!(operations::check_writable(temp_dir.path().join("missing.txt")).is_err());
+    }
+
     #[test]
     fn test_create_directories() {
         let temp_dir = TempDir::new().unwrap();
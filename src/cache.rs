@@ -0,0 +1,229 @@
+use crate::atomic::operations;
+use crate::config::Config;
+use crate::{Result, WindWardenError};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+/// Default location of the cache file
+pub const DEFAULT_CACHE_FILE: &str = ".windwarden-cache";
+
+/// Cached state of a single file from a previous run
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CacheEntry {
+    /// Modification time in milliseconds since the Unix epoch
+    pub mtime: u128,
+    /// File size in bytes
+    pub size: u64,
+    /// Hash of the file content
+    pub content_hash: u64,
+    /// Whether the file was already formatted when last processed
+    pub formatted: bool,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CacheFile {
+    version: String,
+    config_hash: u64,
+    entries: HashMap<String, CacheEntry>,
+}
+
+/// Cache of file results between runs, used to skip files that are
+/// unchanged and were already formatted
+///
+/// The cache is tied to the effective configuration and the WindWarden
+/// version; if either changes, all entries are discarded.
+#[derive(Debug)]
+pub struct ProcessingCache {
+    path: PathBuf,
+    config_hash: u64,
+    entries: HashMap<String, CacheEntry>,
+}
+
+impl ProcessingCache {
+    /// Load the cache from a file, starting empty if it is missing, unreadable,
+    /// or was written for a different configuration
+    pub fn load(path: impl AsRef<Path>, config: &Config) -> Self {
+        let path = path.as_ref().to_path_buf();
+        let config_hash = Self::hash_config(config);
+
+        let entries = fs::read_to_string(&path)
+            .ok()
+            .and_then(|content| serde_json::from_str::<CacheFile>(&content).ok())
+            .filter(|cache| {
+                cache.version == env!("CARGO_PKG_VERSION") && cache.config_hash == config_hash
+            })
+            .map(|cache| cache.entries)
+            .unwrap_or_default();
+
+        Self {
+            path,
+            config_hash,
+            entries,
+        }
+    }
+
+    /// Write the cache back to its file
+    pub fn save(&self) -> Result<()> {
+        let cache = CacheFile {
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            config_hash: self.config_hash,
+            entries: self.entries.clone(),
+        };
+
+        let json = serde_json::to_string(&cache).map_err(|e| {
+            WindWardenError::internal_error(format!("Failed to serialize cache: {}", e))
+        })?;
+
+        operations::write_file(&self.path, &json)
+    }
+
+    /// Get the path of the cache file
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Number of files in the cache
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Check whether the cache has no entries
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Get the cached entry for a file
+    pub fn get(&self, file_path: &Path) -> Option<&CacheEntry> {
+        self.entries.get(&Self::key(file_path))
+    }
+
+    /// Check whether a file can be skipped: its content matches the cached
+    /// hash and it was already formatted last time
+    pub fn is_fresh(&self, file_path: &Path, content: &str) -> bool {
+        self.get(file_path)
+            .is_some_and(|entry| entry.formatted && entry.content_hash == hash_content(content))
+    }
+
+    /// Record the result of processing a file with the given (current) content
+    pub fn record(&mut self, file_path: &Path, content: &str, formatted: bool) {
+        let metadata = fs::metadata(file_path).ok();
+        let mtime = metadata
+            .as_ref()
+            .and_then(|m| m.modified().ok())
+            .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+            .map(|d| d.as_millis())
+            .unwrap_or_default();
+        let size = metadata.map(|m| m.len()).unwrap_or(content.len() as u64);
+
+        self.entries.insert(
+            Self::key(file_path),
+            CacheEntry {
+                mtime,
+                size,
+                content_hash: hash_content(content),
+                formatted,
+            },
+        );
+    }
+
+    /// Forget a file, e.g. because it failed to process
+    pub fn remove(&mut self, file_path: &Path) {
+        self.entries.remove(&Self::key(file_path));
+    }
+
+    fn key(file_path: &Path) -> String {
+        file_path.display().to_string()
+    }
+
+    fn hash_config(config: &Config) -> u64 {
+        // Sort order, functions, categories, and every other setting affect results.
+        // Going through a `Value` sorts map keys so the hash is stable.
+        let json = serde_json::to_value(config)
+            .map(|value| value.to_string())
+            .unwrap_or_default();
+        hash_content(&json)
+    }
+}
+
+/// Stable 64-bit FNV-1a hash, so cache files stay valid across builds
+pub fn hash_content(content: &str) -> u64 {
+    const OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+    const PRIME: u64 = 0x100000001b3;
+
+    content.bytes().fold(OFFSET_BASIS, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(PRIME)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_cache_round_trip() {
+        let temp_dir = TempDir::new().unwrap();
+        let cache_path = temp_dir.path().join(".windwarden-cache");
+        let file_path = temp_dir.path().join("App.tsx");
+        fs::write(&file_path, "const a = 'flex p-4';").unwrap();
+
+        let config = Config::default();
+        let mut cache = ProcessingCache::load(&cache_path, &config);
+        assert!(cache.is_empty());
+
+        cache.record(&file_path, "const a = 'flex p-4';", true);
+        cache.save().unwrap();
+
+        let cache = ProcessingCache::load(&cache_path, &config);
+        assert_eq!(cache.len(), 1);
+        assert!(cache.is_fresh(&file_path, "const a = 'flex p-4';"));
+        assert!(!cache.is_fresh(&file_path, "const a = 'p-4 flex';"));
+
+        let entry = cache.get(&file_path).unwrap();
+        assert_eq!(entry.size, 21);
+        assert!(entry.mtime > 0);
+    }
+
+    #[test]
+    fn test_unformatted_entries_are_not_fresh() {
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = temp_dir.path().join("App.tsx");
+
+        let mut cache = ProcessingCache::load(temp_dir.path().join("cache"), &Config::default());
+        cache.record(&file_path, "const a = 'p-4 flex';", false);
+
+        assert!(!cache.is_fresh(&file_path, "const a = 'p-4 flex';"));
+    }
+
+    #[test]
+    fn test_config_change_invalidates_cache() {
+        let temp_dir = TempDir::new().unwrap();
+        let cache_path = temp_dir.path().join(".windwarden-cache");
+        let file_path = temp_dir.path().join("App.tsx");
+
+        let config = Config::default();
+        let mut cache = ProcessingCache::load(&cache_path, &config);
+        cache.record(&file_path, "content", true);
+        cache.save().unwrap();
+
+        let mut changed = Config::default();
+        changed.function_names.push("myCn".to_string());
+
+        assert!(ProcessingCache::load(&cache_path, &changed).is_empty());
+        assert_eq!(ProcessingCache::load(&cache_path, &config).len(), 1);
+    }
+
+    #[test]
+    fn test_corrupt_cache_starts_empty() {
+        let temp_dir = TempDir::new().unwrap();
+        let cache_path = temp_dir.path().join(".windwarden-cache");
+        fs::write(&cache_path, "not json").unwrap();
+
+        assert!(ProcessingCache::load(&cache_path, &Config::default()).is_empty());
+    }
+}
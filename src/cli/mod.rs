@@ -1,3 +1,4 @@
+use crate::ProcessOptions;
 use clap::{Parser, Subcommand, ValueEnum};
 use std::path::PathBuf;
 
@@ -9,17 +10,93 @@ use std::path::PathBuf;
 )]
 #[command(version = env!("CARGO_PKG_VERSION"))]
 #[command(
-    after_help = "Examples:\n  windwarden format src/                    # Check files in src/ directory\n  windwarden format --mode write src/      # Format files in place\n  windwarden format --mode verify src/     # Exit 1 if any files need formatting\n  windwarden config init                   # Create default configuration\n  windwarden --stdin < file.tsx           # Process from stdin\n\nFor more information, visit: https://github.com/your-org/windwarden"
+    after_help = "Examples:\n  windwarden format src/                    # Check files in src/ directory\n  windwarden format --mode write src/      # Format files in place\n  windwarden format --mode verify src/     # Exit 1 if any files need formatting\n  windwarden config init                   # Create default configuration\n  windwarden --stdin < file.tsx           # Process from stdin\n  windwarden --stdin --stdin-filepath a.ts < a.ts  # Parse stdin as TypeScript\n\nFor more information, visit: https://github.com/your-org/windwarden"
 )]
 pub struct Cli {
     /// Process input from stdin instead of files
     #[arg(long, help = "Read code from stdin and output to stdout")]
     pub stdin: bool,
 
+    /// File path used to pick the parser for stdin input
+    #[arg(
+        long,
+        help = "Treat stdin (--stdin, or --explain -) as this file, e.g. to parse it as .ts or .astro (default: stdin.tsx)",
+        value_name = "PATH"
+    )]
+    pub stdin_filepath: Option<String>,
+
+    /// Report on stderr whether stdin input was changed
+    #[arg(
+        long,
+        requires = "stdin",
+        help = "Print the formatted input as-is and write 'changed: true' or 'changed: false' to stderr"
+    )]
+    pub stdin_report_changed: bool,
+
+    /// Format a JSON array of snippets read from stdin
+    #[arg(
+        long,
+        conflicts_with = "stdin",
+        help = "Read a JSON array of {id, filepath, content} from stdin and write a JSON array of {id, content, changed}"
+    )]
+    pub stdin_json: bool,
+
     /// Configuration file path (searches for .windwarden.json by default)
     #[arg(short, long, help = "Path to configuration file", value_name = "FILE")]
     pub config: Option<PathBuf>,
 
+    /// Write a JSON lines log of every processing decision
+    #[arg(
+        long,
+        global = true,
+        help = "Write a JSON lines trace of discovery, parsing, and writes to FILE",
+        value_name = "FILE"
+    )]
+    pub trace_file: Option<PathBuf>,
+
+    /// Log per-file processing decisions to stderr
+    #[arg(
+        short,
+        long,
+        global = true,
+        help = "Log each file's discovery, skip reason, match count, and result to stderr"
+    )]
+    pub verbose: bool,
+
+    /// Format of diagnostic logging on stderr
+    #[arg(
+        long,
+        global = true,
+        value_enum,
+        default_value_t = LogFormat::Text,
+        help = "Format of warnings, errors, and --verbose logging on stderr (result output is set by --format)"
+    )]
+    pub log_format: LogFormat,
+
+    /// Keep duplicate classes regardless of preserveDuplicates
+    #[arg(
+        long,
+        global = true,
+        help = "Sort classes without removing duplicates (overrides preserveDuplicates)"
+    )]
+    pub no_dedup: bool,
+
+    /// Resolve conflicting utilities like tailwind-merge
+    #[arg(
+        long,
+        global = true,
+        help = "Keep only the last class of each conflicting group, e.g. 'p-2 p-4' becomes 'p-4' (skipped inside twMerge calls)"
+    )]
+    pub merge: bool,
+
+    /// Report classes that match no utility as errors
+    #[arg(
+        long,
+        global = true,
+        help = "Report classes that match no Tailwind utility or knownCustomClasses entry as errors and exit with code 2 (overrides unknownClasses)"
+    )]
+    pub strict_unknown: bool,
+
     #[command(subcommand)]
     pub command: Option<Commands>,
 }
@@ -42,24 +119,124 @@ pub enum OperationMode {
     Verify,
 }
 
+impl OperationMode {
+    /// The mode chosen by `--mode`, `--fix`, or `--check`, which clap
+    /// keeps mutually exclusive
+    pub fn from_flags(mode: Option<OperationMode>, fix: bool, check: bool) -> Option<Self> {
+        match (mode, fix, check) {
+            (Some(mode), _, _) => Some(mode),
+            (None, true, _) => Some(OperationMode::Write),
+            (None, _, true) => Some(OperationMode::Verify),
+            (None, false, false) => None,
+        }
+    }
+
+    /// How files are processed in this mode
+    pub fn process_options(self, dry_run: bool) -> ProcessOptions {
+        match self {
+            OperationMode::Check => ProcessOptions {
+                dry_run: true,
+                write: false,
+                check_formatted: false,
+            },
+            OperationMode::Write => ProcessOptions {
+                dry_run,
+                write: true,
+                check_formatted: false,
+            },
+            OperationMode::Verify => ProcessOptions {
+                dry_run: false,
+                write: false,
+                check_formatted: true,
+            },
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum LogFormat {
+    /// Human-readable lines
+    #[default]
+    Text,
+    /// One JSON object per line
+    Json,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum ProgressFormat {
+    /// A progress bar redrawn in place
+    #[default]
+    Bar,
+    /// `{"processed": n, "total": m}` lines
+    Json,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum DiffStyle {
+    /// One column with `-` and `+` lines, like `diff -u`
+    #[default]
+    Unified,
+    /// Original and sorted lines in two columns
+    SideBySide,
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub enum ReportFormat {
+    /// Human-readable text output
+    Text,
+    /// ESLint-style JSON diagnostics (with the sorted content under --dry-run)
+    Json,
+    /// JUnit XML test-suite document for CI result viewers
+    Junit,
+    /// File and class counts only
+    Summary,
+    /// File and class counts only, as JSON
+    SummaryJson,
+    /// Files needing formatting in the style of `prettier --check`
+    Check,
+    /// Files needing formatting, as JSON
+    CheckJson,
+}
+
 #[derive(Subcommand)]
 pub enum Commands {
     /// 🎨 Format Tailwind CSS classes in files and directories  
     #[command(
-        after_help = "Examples:\n  windwarden format src/                         # Preview changes in src/\n  windwarden format --mode write src/ tests/    # Format multiple directories\n  windwarden format --mode verify .             # Check if project is formatted\n  windwarden format --extensions tsx,jsx src/   # Process only specific file types"
+        after_help = "Examples:\n  windwarden format src/                         # Preview changes in src/\n  windwarden format --mode write src/ tests/    # Format multiple directories\n  windwarden format --fix src/                  # Same as --mode write\n  windwarden format --fix --interactive src/    # Confirm each file before writing\n  windwarden format --mode verify .             # Check if project is formatted\n  windwarden format --check .                   # Same as --mode verify\n  windwarden format --extensions tsx,jsx src/   # Process only specific file types"
     )]
     Format {
         /// Files, directories, or glob patterns to process
         #[arg(
-            required = true,
+            required_unless_present = "manifest",
             help = "Paths to files, directories, or glob patterns (e.g., 'src/**/*.tsx')",
             value_name = "PATH"
         )]
         paths: Vec<String>,
 
         /// What to do with the files
-        #[arg(short, long, value_enum, default_value_t = OperationMode::Check, help = "Operation to perform")]
-        mode: OperationMode,
+        #[arg(
+            short,
+            long,
+            value_enum,
+            help = "Operation to perform [default: check, or defaultMode from config]"
+        )]
+        mode: Option<OperationMode>,
+
+        /// Alias for `--mode write`
+        #[arg(
+            long,
+            conflicts_with_all = ["mode", "check"],
+            help = "Format files in place (alias for --mode write)"
+        )]
+        fix: bool,
+
+        /// Alias for `--mode verify`
+        #[arg(
+            long,
+            conflicts_with = "mode",
+            help = "Exit non-zero if any file needs formatting (alias for --mode verify)"
+        )]
+        check: bool,
 
         /// How to process files
         #[arg(short = 'j', long, value_enum, default_value_t = ProcessingMode::Parallel, help = "Process files sequentially or in parallel")]
@@ -73,6 +250,59 @@ pub enum Commands {
         )]
         threads: Option<usize>,
 
+        /// Fraction of CPU cores to use for parallel processing
+        #[arg(
+            long,
+            conflicts_with = "threads",
+            help = "Use this fraction of CPU cores, e.g. 0.5 for half (never more threads than files)",
+            value_name = "FRACTION"
+        )]
+        thread_scaling: Option<f64>,
+
+        /// Bound on concurrent file reads, separate from the thread count
+        #[arg(
+            long,
+            help = "Read at most N files at once in parallel mode (overrides maxConcurrentReads)",
+            value_name = "N"
+        )]
+        max_concurrent_reads: Option<usize>,
+
+        /// Refuse to process more files than this
+        #[arg(
+            long,
+            help = "Fail without processing anything if more than N files are found (overrides maxFiles)",
+            value_name = "N"
+        )]
+        max_files: Option<usize>,
+
+        /// Process every file found, however many there are
+        #[arg(long, alias = "yes", help = "Ignore the --max-files / maxFiles limit")]
+        force: bool,
+
+        /// Give up on files that take too long to process
+        #[arg(
+            long,
+            help = "Record files that take longer than MS milliseconds as timed out and move on (overrides perFileTimeoutMs; 0 disables)",
+            value_name = "MS"
+        )]
+        per_file_timeout: Option<u64>,
+
+        /// Exit code for unformatted files in verify mode
+        #[arg(
+            long,
+            help = "Exit with N when verify finds unformatted files (overrides verifyExitCode)",
+            value_name = "N"
+        )]
+        verify_exit_code: Option<i32>,
+
+        /// Read one file at a time while still sorting in parallel
+        #[arg(
+            long,
+            conflicts_with = "max_concurrent_reads",
+            help = "Read one file at a time, for network filesystems (same as --max-concurrent-reads 1)"
+        )]
+        no_parallel_io: bool,
+
         /// File extensions to include
         #[arg(
             long,
@@ -82,6 +312,30 @@ pub enum Commands {
         )]
         extensions: Option<Vec<String>>,
 
+        /// Git ref to compare against
+        #[arg(
+            long,
+            help = "Only process files changed since this git ref (e.g. origin/main)",
+            value_name = "REF"
+        )]
+        since: Option<String>,
+
+        /// JSON manifest selecting which files to process
+        #[arg(
+            long,
+            help = "Only process files the manifest marks \"sort\": true; with no PATH, process exactly those files",
+            value_name = "FILE"
+        )]
+        manifest: Option<PathBuf>,
+
+        /// Patterns that files must match to be processed
+        #[arg(
+            long,
+            help = "Only process files matching this glob pattern (repeatable; overrides includePaths)",
+            value_name = "PATTERN"
+        )]
+        include: Option<Vec<String>>,
+
         /// Patterns to exclude from processing
         #[arg(
             long,
@@ -91,6 +345,15 @@ pub enum Commands {
         )]
         exclude: Option<Vec<String>>,
 
+        /// Directory names to exclude wherever they appear
+        #[arg(
+            long,
+            value_delimiter = ',',
+            help = "Comma-separated directory names to skip at any depth (e.g. __mocks__); unlike --exclude, no glob matching",
+            value_name = "NAME"
+        )]
+        exclude_dir: Option<Vec<String>>,
+
         /// Maximum directory traversal depth
         #[arg(
             long,
@@ -111,9 +374,135 @@ pub enum Commands {
         #[arg(long, help = "Display progress bar when processing many files")]
         progress: bool,
 
+        /// How --progress is shown
+        #[arg(
+            long,
+            value_enum,
+            default_value_t = ProgressFormat::Bar,
+            requires = "progress",
+            help = "Show --progress as a bar or as JSON lines on stderr"
+        )]
+        progress_format: ProgressFormat,
+
         /// Show diff of changes that would be made
         #[arg(long, help = "Show a diff of the changes that would be made")]
         diff: bool,
+
+        /// Lines of context around each change in diffs
+        #[arg(
+            long,
+            default_value_t = 3,
+            help = "Number of context lines around each change in --diff output",
+            value_name = "N"
+        )]
+        diff_context: usize,
+
+        /// Layout of --diff output
+        #[arg(
+            long,
+            value_enum,
+            default_value_t = DiffStyle::Unified,
+            help = "Layout of --diff output (side-by-side falls back to unified in narrow terminals)"
+        )]
+        diff_style: DiffStyle,
+
+        /// Output format for results
+        #[arg(long, value_enum, default_value_t = ReportFormat::Text, help = "Output format for results")]
+        format: ReportFormat,
+
+        /// File to write results to instead of stdout
+        #[arg(
+            short,
+            long,
+            help = "Write results to this file instead of stdout, creating parent directories (errors still go to stderr)",
+            value_name = "FILE"
+        )]
+        output: Option<PathBuf>,
+
+        /// Treat files that aren't valid UTF-8 as errors
+        #[arg(
+            long,
+            help = "Count files that are not valid UTF-8 as errors instead of skipping them"
+        )]
+        strict_encoding: bool,
+
+        /// Stop at the first file that needs formatting
+        #[arg(long, help = "Stop at the first unformatted file (verify mode only)")]
+        fail_fast: bool,
+
+        /// Finish verify output with a `key=value` count line
+        #[arg(
+            long,
+            help = "End verify output with a line like `unformatted=3 errors=0 total=42` for scripts"
+        )]
+        summary_line: bool,
+
+        /// Run write mode without writing
+        #[arg(
+            long,
+            help = "Run write mode and show what would be written, without touching any files"
+        )]
+        dry_run: bool,
+
+        /// Show why each class sorts where it does instead of formatting
+        #[arg(
+            long,
+            conflicts_with_all = ["mode", "fix", "check", "diff", "output", "dry_run", "interactive"],
+            help = "Print each class string's classes with their category and category index instead of formatting (one file, or - for stdin)"
+        )]
+        explain: bool,
+
+        /// Confirm each file before writing it
+        #[arg(
+            long,
+            conflicts_with = "dry_run",
+            help = "Show each changed file's diff and ask before writing it (write mode only; processes files sequentially)"
+        )]
+        interactive: bool,
+
+        /// Command to run on each file after it is written
+        #[arg(
+            long,
+            conflicts_with_all = ["dry_run", "explain"],
+            help = "Run COMMAND with the file path appended for each file written, e.g. \"git add\" (write mode only; a failing command fails the run)",
+            value_name = "COMMAND"
+        )]
+        on_change: Option<String>,
+
+        /// Size above which files are scanned line by line instead of parsed
+        #[arg(
+            long,
+            help = "Scan files larger than this many bytes line by line instead of parsing them",
+            value_name = "BYTES"
+        )]
+        max_in_memory: Option<usize>,
+
+        /// Skip files that are unchanged since the last cached run
+        #[arg(long, help = "Skip unchanged, already formatted files using a cache")]
+        cache: bool,
+
+        /// Report configured functions that no file calls
+        #[arg(
+            long,
+            conflicts_with_all = ["cache", "cache_file"],
+            help = "After processing, list configured functionNames that were never called"
+        )]
+        report_unused_functions: bool,
+
+        /// Print the effective configuration instead of processing files
+        #[arg(
+            long,
+            help = "Print the resolved configuration for this invocation as JSON, with the source of each setting, and exit"
+        )]
+        print_config: bool,
+
+        /// Where to store the cache (implies --cache)
+        #[arg(
+            long,
+            help = "Cache file location (default: .windwarden-cache)",
+            value_name = "FILE"
+        )]
+        cache_file: Option<PathBuf>,
     },
 
     /// ✅ Check if files are properly formatted (alias for 'format --mode verify')
@@ -123,7 +512,7 @@ pub enum Commands {
     Check {
         /// Files, directories, or glob patterns to check
         #[arg(
-            required = true,
+            required_unless_present = "manifest",
             help = "Paths to check for proper formatting",
             value_name = "PATH"
         )]
@@ -137,6 +526,59 @@ pub enum Commands {
         #[arg(long, help = "Number of threads to use", value_name = "N")]
         threads: Option<usize>,
 
+        /// Fraction of CPU cores to use for parallel processing
+        #[arg(
+            long,
+            conflicts_with = "threads",
+            help = "Use this fraction of CPU cores, e.g. 0.5 for half (never more threads than files)",
+            value_name = "FRACTION"
+        )]
+        thread_scaling: Option<f64>,
+
+        /// Bound on concurrent file reads, separate from the thread count
+        #[arg(
+            long,
+            help = "Read at most N files at once in parallel mode (overrides maxConcurrentReads)",
+            value_name = "N"
+        )]
+        max_concurrent_reads: Option<usize>,
+
+        /// Refuse to process more files than this
+        #[arg(
+            long,
+            help = "Fail without processing anything if more than N files are found (overrides maxFiles)",
+            value_name = "N"
+        )]
+        max_files: Option<usize>,
+
+        /// Process every file found, however many there are
+        #[arg(long, alias = "yes", help = "Ignore the --max-files / maxFiles limit")]
+        force: bool,
+
+        /// Give up on files that take too long to process
+        #[arg(
+            long,
+            help = "Record files that take longer than MS milliseconds as timed out and move on (overrides perFileTimeoutMs; 0 disables)",
+            value_name = "MS"
+        )]
+        per_file_timeout: Option<u64>,
+
+        /// Exit code for unformatted files in verify mode
+        #[arg(
+            long,
+            help = "Exit with N when verify finds unformatted files (overrides verifyExitCode)",
+            value_name = "N"
+        )]
+        verify_exit_code: Option<i32>,
+
+        /// Read one file at a time while still sorting in parallel
+        #[arg(
+            long,
+            conflicts_with = "max_concurrent_reads",
+            help = "Read one file at a time, for network filesystems (same as --max-concurrent-reads 1)"
+        )]
+        no_parallel_io: bool,
+
         /// File extensions to include
         #[arg(
             long,
@@ -146,6 +588,30 @@ pub enum Commands {
         )]
         extensions: Option<Vec<String>>,
 
+        /// Git ref to compare against
+        #[arg(
+            long,
+            help = "Only check files changed since this git ref (e.g. origin/main)",
+            value_name = "REF"
+        )]
+        since: Option<String>,
+
+        /// JSON manifest selecting which files to process
+        #[arg(
+            long,
+            help = "Only process files the manifest marks \"sort\": true; with no PATH, process exactly those files",
+            value_name = "FILE"
+        )]
+        manifest: Option<PathBuf>,
+
+        /// Patterns that files must match to be checked
+        #[arg(
+            long,
+            help = "Only check files matching this glob pattern (repeatable; overrides includePaths)",
+            value_name = "PATTERN"
+        )]
+        include: Option<Vec<String>>,
+
         /// Patterns to exclude from checking
         #[arg(
             long,
@@ -155,6 +621,15 @@ pub enum Commands {
         )]
         exclude: Option<Vec<String>>,
 
+        /// Directory names to exclude wherever they appear
+        #[arg(
+            long,
+            value_delimiter = ',',
+            help = "Comma-separated directory names to skip at any depth (e.g. __mocks__); unlike --exclude, no glob matching",
+            value_name = "NAME"
+        )]
+        exclude_dir: Option<Vec<String>>,
+
         /// Show detailed checking statistics
         #[arg(long, help = "Display detailed statistics about checked files")]
         stats: bool,
@@ -163,9 +638,103 @@ pub enum Commands {
         #[arg(long, help = "Display progress bar when checking many files")]
         progress: bool,
 
+        /// How --progress is shown
+        #[arg(
+            long,
+            value_enum,
+            default_value_t = ProgressFormat::Bar,
+            requires = "progress",
+            help = "Show --progress as a bar or as JSON lines on stderr"
+        )]
+        progress_format: ProgressFormat,
+
         /// Show diff of changes that would be needed
         #[arg(long, help = "Show a diff of the changes that would be needed")]
         diff: bool,
+
+        /// Lines of context around each change in diffs
+        #[arg(
+            long,
+            default_value_t = 3,
+            help = "Number of context lines around each change in --diff output",
+            value_name = "N"
+        )]
+        diff_context: usize,
+
+        /// Layout of --diff output
+        #[arg(
+            long,
+            value_enum,
+            default_value_t = DiffStyle::Unified,
+            help = "Layout of --diff output (side-by-side falls back to unified in narrow terminals)"
+        )]
+        diff_style: DiffStyle,
+
+        /// Output format for results
+        #[arg(long, value_enum, default_value_t = ReportFormat::Text, help = "Output format for results")]
+        format: ReportFormat,
+
+        /// File to write results to instead of stdout
+        #[arg(
+            short,
+            long,
+            help = "Write results to this file instead of stdout, creating parent directories (errors still go to stderr)",
+            value_name = "FILE"
+        )]
+        output: Option<PathBuf>,
+
+        /// Treat files that aren't valid UTF-8 as errors
+        #[arg(
+            long,
+            help = "Count files that are not valid UTF-8 as errors instead of skipping them"
+        )]
+        strict_encoding: bool,
+
+        /// Stop at the first file that needs formatting
+        #[arg(long, help = "Stop at the first unformatted file (verify mode only)")]
+        fail_fast: bool,
+
+        /// Finish verify output with a `key=value` count line
+        #[arg(
+            long,
+            help = "End verify output with a line like `unformatted=3 errors=0 total=42` for scripts"
+        )]
+        summary_line: bool,
+
+        /// Size above which files are scanned line by line instead of parsed
+        #[arg(
+            long,
+            help = "Scan files larger than this many bytes line by line instead of parsing them",
+            value_name = "BYTES"
+        )]
+        max_in_memory: Option<usize>,
+
+        /// Skip files that are unchanged since the last cached run
+        #[arg(long, help = "Skip unchanged, already formatted files using a cache")]
+        cache: bool,
+
+        /// Report configured functions that no file calls
+        #[arg(
+            long,
+            conflicts_with_all = ["cache", "cache_file"],
+            help = "After processing, list configured functionNames that were never called"
+        )]
+        report_unused_functions: bool,
+
+        /// Print the effective configuration instead of processing files
+        #[arg(
+            long,
+            help = "Print the resolved configuration for this invocation as JSON, with the source of each setting, and exit"
+        )]
+        print_config: bool,
+
+        /// Where to store the cache (implies --cache)
+        #[arg(
+            long,
+            help = "Cache file location (default: .windwarden-cache)",
+            value_name = "FILE"
+        )]
+        cache_file: Option<PathBuf>,
     },
 
     /// ⚙️  Configuration file management
@@ -177,6 +746,12 @@ pub enum Commands {
         action: ConfigAction,
     },
 
+    /// 🩺 Check the configuration and environment for common problems
+    #[command(
+        after_help = "Checks that the configuration loads, customOrder categories are known, glob patterns compile, the thread count is sane, and whether the directory is a git repository.\n\nExamples:\n  windwarden doctor                           # Check the configuration found from here\n  windwarden --config ci.json doctor          # Check a specific configuration file"
+    )]
+    Doctor,
+
     /// 🐚 Generate shell completion scripts
     #[command(
         after_help = "Examples:\n  windwarden completions bash > /etc/bash_completion.d/windwarden\n  windwarden completions zsh > ~/.zsh/completions/_windwarden\n  windwarden completions fish > ~/.config/fish/completions/windwarden.fish"
@@ -200,16 +775,34 @@ pub enum ConfigAction {
             value_name = "FILE"
         )]
         path: PathBuf,
+
+        /// Ask about the common settings instead of writing the defaults
+        #[arg(
+            long,
+            help = "Ask for the sort order, extra function names, and ignore paths (Enter keeps each default; needs a terminal)"
+        )]
+        interactive: bool,
     },
 
     /// 📋 Show current effective configuration
     Show,
 
     /// ✅ Validate configuration file syntax and settings
+    #[command(
+        after_help = "Examples:\n  windwarden config validate                       # Validate the configuration found from here\n  windwarden config validate ci.json               # Validate a specific file\n  generate-config | windwarden config validate --stdin  # Validate JSON before writing it"
+    )]
     Validate {
         /// Configuration file to validate (searches for default if not provided)
         #[arg(help = "Path to configuration file", value_name = "FILE")]
         path: Option<PathBuf>,
+
+        /// Validate configuration JSON read from stdin
+        #[arg(
+            long,
+            conflicts_with = "path",
+            help = "Read the configuration JSON from stdin instead of a file"
+        )]
+        stdin: bool,
     },
 }
 
@@ -1,8 +1,19 @@
 use crate::WindWardenError;
+use crate::cli::OperationMode;
+use crate::output::{DEFAULT_VERIFY_EXIT_CODE, ERROR_EXIT_CODE};
+use crate::sorter::DEFAULT_MIN_CLASSES_TO_SORT;
+use crate::utils::glob::build_glob_set;
+use globset::GlobSet;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::borrow::Cow;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::fs;
-use std::path::{Path, PathBuf};
+use std::io::{self, BufRead, Write};
+use std::path::{Component, Path, PathBuf};
+
+/// Environment variable holding inline configuration JSON, used instead of
+/// a discovered configuration file
+pub const CONFIG_ENV_VAR: &str = "WINDWARDEN_CONFIG";
 
 /// WindWarden configuration structure
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -16,10 +27,74 @@ pub struct Config {
     #[serde(default)]
     pub custom_order: Vec<String>,
 
+    /// Variant precedence, e.g. `["responsive", "dark", "hover"]` (empty = count, then alphabetical)
+    #[serde(default)]
+    pub variant_order: Vec<String>,
+
+    /// Order classes exactly like prettier-plugin-tailwindcss, ignoring
+    /// `sortOrder`, `customOrder`, and `variantOrder`
+    #[serde(default)]
+    pub prettier_compat: bool,
+
+    /// Order of classes within a category: "lexical" or "numeric"
+    #[serde(default = "default_intra_category_sort")]
+    pub intra_category_sort: String,
+
+    /// Keep related spacing and sizing utilities together within their
+    /// category, e.g. `p-*`, then `px-*`, then `py-*`
+    #[serde(default)]
+    pub group_prefixes: bool,
+
+    /// Prefix order within categories, keyed by category, replacing the
+    /// `groupPrefixes` order for that category
+    #[serde(default)]
+    pub category_prefix_order: HashMap<String, Vec<String>>,
+
+    /// Non-Tailwind classes, or prefixes ending in `*`, that sort together
+    /// in a "custom" category instead of with unknown classes
+    #[serde(default)]
+    pub known_custom_classes: Vec<String>,
+
+    /// Where known custom classes go: "first" or "last" (before unknown classes)
+    #[serde(default = "default_custom_classes_position")]
+    pub custom_classes_position: String,
+
+    /// How classes that match no utility or known custom class are
+    /// reported: "ignore", "warn", or "error"
+    #[serde(default = "default_unknown_classes")]
+    pub unknown_classes: String,
+
     /// Custom function names to detect (in addition to defaults)
     #[serde(default)]
     pub function_names: Vec<String>,
 
+    /// Argument indices to sort per function (functions not listed sort every argument)
+    #[serde(default)]
+    pub function_arg_indices: HashMap<String, Vec<usize>>,
+
+    /// Kinds of code whose class strings are sorted, such as "jsxAttribute"
+    /// or "templateLiteral" (default: all of them)
+    #[serde(default = "default_sort_contexts")]
+    pub sort_contexts: Vec<String>,
+
+    /// Tags whose tagged templates are sorted, such as "tw"; `tw.div` and
+    /// `styled(Link)` match by the name they start with
+    #[serde(default = "default_template_tags")]
+    pub template_tags: Vec<String>,
+
+    /// Whether template literals without a tag are sorted
+    #[serde(default = "default_true")]
+    pub sort_untagged_templates: bool,
+
+    /// Props such as "classNames" whose object values are all class strings,
+    /// whatever their keys
+    #[serde(default)]
+    pub class_object_props: Vec<String>,
+
+    /// How many tokens of a string must look like Tailwind classes for it to be sorted
+    #[serde(default)]
+    pub tailwind_detection: TailwindDetection,
+
     /// Whether to remove null/undefined classes from output
     #[serde(default = "default_true")]
     pub remove_null_classes: bool,
@@ -28,6 +103,41 @@ pub struct Config {
     #[serde(default)]
     pub preserve_duplicates: bool,
 
+    /// Whitespace handling when sorting: "collapse", "preserve-edges", or "none"
+    #[serde(default = "default_normalize_whitespace")]
+    pub normalize_whitespace: String,
+
+    /// Separator placed between sorted classes: spaces and tabs only
+    #[serde(default = "default_whitespace_separator")]
+    pub whitespace_separator: String,
+
+    /// Quotes sorted class strings are written with: "preserve", "single", or "double"
+    #[serde(default = "default_quote_style")]
+    pub quote_style: String,
+
+    /// Collapse static string concatenations (`"a " + "b"`) into one sorted literal
+    /// instead of sorting each operand in place
+    #[serde(default)]
+    pub collapse_concatenation: bool,
+
+    /// Leave class strings with fewer classes than this as written
+    #[serde(default = "default_min_classes_to_sort")]
+    pub min_classes_to_sort: usize,
+
+    /// Only reorder the elements of class arrays, keeping each element's
+    /// quotes and the whitespace between elements
+    #[serde(default)]
+    pub preserve_formatting: bool,
+
+    /// Drop classes overridden by a later class of the same group, like
+    /// tailwind-merge (`p-2 p-4` becomes `p-4`)
+    #[serde(default)]
+    pub merge_conflicts: bool,
+
+    /// Glob patterns limiting processing to matching files (every file when empty)
+    #[serde(default)]
+    pub include_paths: Vec<String>,
+
     /// Paths to ignore during processing
     #[serde(default = "default_ignore_paths")]
     pub ignore_paths: Vec<String>,
@@ -36,6 +146,14 @@ pub struct Config {
     #[serde(default = "default_file_extensions")]
     pub file_extensions: Vec<String>,
 
+    /// Also process `.html` and `.htm` files by default
+    #[serde(default)]
+    pub html: bool,
+
+    /// Also process `.css` and `.scss` files by default, sorting `@apply` directives
+    #[serde(default)]
+    pub css: bool,
+
     /// Custom category definitions
     #[serde(default)]
     pub categories: HashMap<String, Vec<String>>,
@@ -48,6 +166,18 @@ pub struct Config {
     #[serde(default)]
     pub threads: usize,
 
+    /// Maximum number of files read at once in parallel runs (unbounded when unset)
+    #[serde(default)]
+    pub max_concurrent_reads: Option<usize>,
+
+    /// Give up on a file after this many milliseconds (0 = no limit)
+    #[serde(default)]
+    pub per_file_timeout_ms: u64,
+
+    /// Refuse to process more files than this in one run (no limit when unset)
+    #[serde(default)]
+    pub max_files: Option<usize>,
+
     /// Enable/disable colored output
     #[serde(default = "default_true")]
     pub colored_output: bool,
@@ -56,9 +186,172 @@ pub struct Config {
     #[serde(default)]
     pub default_mode: Option<String>,
 
+    /// Exit code for verify runs that find unformatted files
+    #[serde(default = "default_verify_exit_code")]
+    pub verify_exit_code: i32,
+
     /// Safety settings for file operations
     #[serde(default)]
     pub safety: SafetyConfig,
+
+    /// Settings for files matching specific globs, applied in order
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub overrides: Vec<ConfigOverride>,
+}
+
+/// Settings that apply only to files matching `files`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigOverride {
+    /// Globs matched against file paths relative to the working directory
+    pub files: Vec<String>,
+
+    /// Settings replacing the base configuration for matching files
+    pub config: OverrideSettings,
+}
+
+/// The subset of `Config` an override can change; unset fields are inherited
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OverrideSettings {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sort_order: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub custom_order: Option<Vec<String>>,
+
+    /// Replaces the base `functionNames`; the default functions still apply
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub function_names: Option<Vec<String>>,
+}
+
+impl ConfigOverride {
+    /// Compile the override's globs
+    fn matcher(&self) -> Result<GlobSet, WindWardenError> {
+        build_glob_set(self.files.iter().map(String::as_str))
+    }
+}
+
+impl OverrideSettings {
+    /// Layer these settings on top of `config`
+    fn apply_to(&self, config: &mut Config) {
+        if let Some(sort_order) = &self.sort_order {
+            config.sort_order = sort_order.clone();
+        }
+        if let Some(custom_order) = &self.custom_order {
+            config.custom_order = custom_order.clone();
+        }
+        if let Some(function_names) = &self.function_names {
+            config.function_names = function_names.clone();
+        }
+    }
+}
+
+/// A configuration's overrides, with their globs compiled for matching
+/// many file paths
+pub struct ConfigOverrides {
+    base: Config,
+    overrides: Vec<(GlobSet, OverrideSettings)>,
+    /// Directory the globs are relative to, or the working directory when unset
+    root: Option<PathBuf>,
+}
+
+impl ConfigOverrides {
+    pub fn new(config: &Config) -> Result<Self, WindWardenError> {
+        let overrides = config
+            .overrides
+            .iter()
+            .map(|entry| Ok((entry.matcher()?, entry.config.clone())))
+            .collect::<Result<_, WindWardenError>>()?;
+
+        Ok(Self {
+            base: config.clone(),
+            overrides,
+            root: None,
+        })
+    }
+
+    /// Match the globs relative to `root`, normally the configuration file's
+    /// directory, instead of the working directory
+    pub fn with_root(mut self, root: Option<&Path>) -> Self {
+        self.root = root.map(Path::to_path_buf);
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.overrides.is_empty()
+    }
+
+    /// The configuration for `path`, or `None` when no override matches it
+    /// and the base configuration applies
+    pub fn config_for(&self, path: &Path) -> Option<Config> {
+        if self.overrides.is_empty() {
+            return None;
+        }
+
+        let path = override_match_path(path, self.root.as_deref());
+        let mut matching = self
+            .overrides
+            .iter()
+            .filter(|(matcher, _)| matcher.is_match(&path))
+            .peekable();
+        matching.peek()?;
+
+        let mut config = self.base.clone();
+        for (_, settings) in matching {
+            settings.apply_to(&mut config);
+        }
+        Some(config)
+    }
+}
+
+/// The form of `path` override globs are matched against: relative to
+/// `root` (the working directory when `None`) when it is inside it, otherwise
+/// absolute, without `.` or `..` components
+fn override_match_path(path: &Path, root: Option<&Path>) -> PathBuf {
+    let cwd = std::env::current_dir().unwrap_or_default();
+    let root = normalize_path(&cwd.join(root.unwrap_or(Path::new(""))));
+    let absolute = normalize_path(&cwd.join(path));
+
+    match absolute.strip_prefix(&root) {
+        Ok(relative) => relative.to_path_buf(),
+        Err(_) => absolute,
+    }
+}
+
+/// `path` without `.` components, and with each `..` removing the
+/// component before it where there is one
+fn normalize_path(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir
+                if matches!(
+                    normalized.components().next_back(),
+                    Some(Component::Normal(_))
+                ) =>
+            {
+                normalized.pop();
+            }
+            component => normalized.push(component),
+        }
+    }
+    normalized
+}
+
+/// Thresholds for deciding whether a string of several tokens holds
+/// Tailwind classes
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TailwindDetection {
+    /// Tailwind-like tokens needed in a string of two to four tokens
+    #[serde(default = "default_detection_min_tokens")]
+    pub min_tokens: usize,
+
+    /// Share of Tailwind-like tokens needed in a string of five or more tokens
+    #[serde(default = "default_detection_ratio")]
+    pub ratio: f32,
 }
 
 /// Safety-specific configuration for file operations
@@ -76,6 +369,27 @@ pub struct SafetyConfig {
     /// Verify file content after writing
     #[serde(default)]
     pub verify_writes: bool,
+
+    /// How `verifyWrites` compares the written file: "hash" or "content"
+    #[serde(default = "default_verify_strategy")]
+    pub verify_strategy: String,
+
+    /// Overwrite only the changed bytes when a file's length is unchanged,
+    /// falling back to a full write otherwise
+    #[serde(default)]
+    pub in_place_edit: bool,
+
+    /// Keep the original file's permissions when replacing it atomically
+    #[serde(default = "default_true")]
+    pub preserve_permissions: bool,
+
+    /// Suffix appended to backup file names
+    #[serde(default = "default_backup_suffix")]
+    pub backup_suffix: String,
+
+    /// Directory to collect backups in, mirroring the source tree
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub backup_dir: Option<PathBuf>,
 }
 
 impl Default for Config {
@@ -83,17 +397,55 @@ impl Default for Config {
         Self {
             sort_order: default_sort_order(),
             custom_order: Vec::new(),
+            variant_order: Vec::new(),
+            prettier_compat: false,
+            intra_category_sort: default_intra_category_sort(),
+            group_prefixes: false,
+            category_prefix_order: HashMap::new(),
+            known_custom_classes: Vec::new(),
+            custom_classes_position: default_custom_classes_position(),
+            unknown_classes: default_unknown_classes(),
             function_names: Vec::new(),
+            function_arg_indices: HashMap::new(),
+            sort_contexts: default_sort_contexts(),
+            template_tags: default_template_tags(),
+            sort_untagged_templates: true,
+            class_object_props: Vec::new(),
+            tailwind_detection: TailwindDetection::default(),
             remove_null_classes: true,
             preserve_duplicates: false,
+            normalize_whitespace: default_normalize_whitespace(),
+            whitespace_separator: default_whitespace_separator(),
+            quote_style: default_quote_style(),
+            collapse_concatenation: false,
+            min_classes_to_sort: default_min_classes_to_sort(),
+            preserve_formatting: false,
+            merge_conflicts: false,
+            include_paths: Vec::new(),
             ignore_paths: default_ignore_paths(),
             file_extensions: default_file_extensions(),
+            html: false,
+            css: false,
             categories: HashMap::new(),
             max_file_size: default_max_file_size(),
             threads: 0,
+            max_concurrent_reads: None,
+            per_file_timeout_ms: 0,
+            max_files: None,
             colored_output: true,
             default_mode: None,
+            verify_exit_code: default_verify_exit_code(),
             safety: SafetyConfig::default(),
+            overrides: Vec::new(),
+        }
+    }
+}
+
+impl Default for TailwindDetection {
+    fn default() -> Self {
+        Self {
+            min_tokens: default_detection_min_tokens(),
+            ratio: default_detection_ratio(),
         }
     }
 }
@@ -104,6 +456,11 @@ impl Default for SafetyConfig {
             atomic_writes: true,
             create_backups: false,
             verify_writes: false,
+            verify_strategy: default_verify_strategy(),
+            in_place_edit: false,
+            preserve_permissions: true,
+            backup_suffix: default_backup_suffix(),
+            backup_dir: None,
         }
     }
 }
@@ -113,10 +470,64 @@ fn default_sort_order() -> String {
     "official".to_string()
 }
 
+fn default_detection_min_tokens() -> usize {
+    2
+}
+
+fn default_detection_ratio() -> f32 {
+    0.5
+}
+
+fn default_intra_category_sort() -> String {
+    "lexical".to_string()
+}
+
+fn default_custom_classes_position() -> String {
+    "first".to_string()
+}
+
+fn default_unknown_classes() -> String {
+    "ignore".to_string()
+}
+
+fn default_template_tags() -> Vec<String> {
+    crate::parser::DEFAULT_TEMPLATE_TAGS
+        .iter()
+        .map(|tag| tag.to_string())
+        .collect()
+}
+
+fn default_sort_contexts() -> Vec<String> {
+    crate::parser::SortContext::NAMES
+        .iter()
+        .map(|name| name.to_string())
+        .collect()
+}
+
+fn default_verify_strategy() -> String {
+    "hash".to_string()
+}
+
+fn default_quote_style() -> String {
+    "preserve".to_string()
+}
+
+fn default_normalize_whitespace() -> String {
+    "collapse".to_string()
+}
+
+fn default_whitespace_separator() -> String {
+    " ".to_string()
+}
+
 fn default_true() -> bool {
     true
 }
 
+fn default_backup_suffix() -> String {
+    ".bak".to_string()
+}
+
 fn default_ignore_paths() -> Vec<String> {
     vec![
         "node_modules".to_string(),
@@ -136,17 +547,87 @@ fn default_file_extensions() -> Vec<String> {
         "jsx".to_string(),
         "ts".to_string(),
         "js".to_string(),
+        "astro".to_string(),
+        "svelte".to_string(),
+        "mdx".to_string(),
     ]
 }
 
+fn default_verify_exit_code() -> i32 {
+    DEFAULT_VERIFY_EXIT_CODE
+}
+
+fn default_min_classes_to_sort() -> usize {
+    DEFAULT_MIN_CLASSES_TO_SORT
+}
+
 fn default_max_file_size() -> usize {
     10 * 1024 * 1024 // 10MB
 }
 
+/// The top-level keys of a JSON object, or none for anything else
+fn json_keys(content: &str) -> BTreeSet<String> {
+    match serde_json::from_str(content) {
+        Ok(serde_json::Value::Object(settings)) => {
+            settings.into_iter().map(|(key, _)| key).collect()
+        }
+        _ => BTreeSet::new(),
+    }
+}
+
 /// Configuration loading and management
+#[derive(Clone)]
 pub struct ConfigManager {
     config: Config,
     config_path: Option<PathBuf>,
+    cli_overrides: BTreeSet<String>,
+    /// Top-level keys of the configuration, when it came from [`CONFIG_ENV_VAR`]
+    env_keys: Option<BTreeSet<String>>,
+}
+
+/// Where an effective configuration value came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ValueSource {
+    Default,
+    File,
+    Env,
+    Cli,
+}
+
+/// Map a `defaultMode` value to the `--mode` it stands for
+///
+/// `"diff"` is kept as an alias for `"check"`, which previews changes.
+/// `"format"` is rejected because it reads as both "format in place" and
+/// "check formatting".
+fn parse_default_mode(mode: &str) -> Result<OperationMode, WindWardenError> {
+    match mode {
+        "check" | "diff" => Ok(OperationMode::Check),
+        "write" => Ok(OperationMode::Write),
+        "verify" => Ok(OperationMode::Verify),
+        "format" => Err(WindWardenError::config_error(
+            "Ambiguous defaultMode 'format'. Use 'write' to format files in place, \
+             'check' to preview changes, or 'verify' to fail on unformatted files",
+        )),
+        _ => Err(WindWardenError::config_error(format!(
+            "Invalid defaultMode '{}'. Valid options: check, write, verify, diff",
+            mode
+        ))),
+    }
+}
+
+/// Check that a verify exit code can't be mistaken for another outcome
+///
+/// 0 means success and [`ERROR_EXIT_CODE`] means files couldn't be
+/// processed; codes above 125 are reserved by shells.
+pub fn validate_verify_exit_code(code: i32) -> Result<(), String> {
+    if !(1..=125).contains(&code) || code == ERROR_EXIT_CODE {
+        return Err(format!(
+            "must be between 1 and 125 and not {} (reserved for errors), got {}",
+            ERROR_EXIT_CODE, code
+        ));
+    }
+    Ok(())
 }
 
 impl Default for ConfigManager {
@@ -161,6 +642,8 @@ impl ConfigManager {
         Self {
             config: Config::default(),
             config_path: None,
+            cli_overrides: BTreeSet::new(),
+            env_keys: None,
         }
     }
 
@@ -169,6 +652,8 @@ impl ConfigManager {
         Self {
             config,
             config_path,
+            cli_overrides: BTreeSet::new(),
+            env_keys: None,
         }
     }
 
@@ -185,9 +670,29 @@ impl ConfigManager {
         Ok(Self {
             config,
             config_path,
+            cli_overrides: BTreeSet::new(),
+            env_keys: None,
+        })
+    }
+
+    /// Load configuration from the JSON value of [`CONFIG_ENV_VAR`]
+    pub fn from_env_json(content: &str) -> Result<Self, WindWardenError> {
+        let config = Self::parse_config(
+            content,
+            &format!("the {} environment variable", CONFIG_ENV_VAR),
+        )?;
+
+        Ok(Self {
+            env_keys: Some(json_keys(content)),
+            ..Self::new_with_config(config, None)
         })
     }
 
+    /// Whether the configuration came from [`CONFIG_ENV_VAR`]
+    pub fn loaded_from_env(&self) -> bool {
+        self.env_keys.is_some()
+    }
+
     /// Find configuration file by walking up the directory tree
     fn find_config_file(start_dir: &Path) -> Result<Option<PathBuf>, WindWardenError> {
         let config_names = [
@@ -220,12 +725,13 @@ impl ConfigManager {
         let content = fs::read_to_string(path)
             .map_err(|e| WindWardenError::from_io_error(e, Some(&path.display().to_string())))?;
 
-        let config: Config = serde_json::from_str(&content).map_err(|e| {
-            WindWardenError::config_error(format!(
-                "Invalid configuration in {}: {}",
-                path.display(),
-                e
-            ))
+        Self::parse_config(&content, &path.display().to_string())
+    }
+
+    /// Parse and validate configuration JSON, naming `source` in errors
+    pub fn parse_config(content: &str, source: &str) -> Result<Config, WindWardenError> {
+        let config: Config = serde_json::from_str(content).map_err(|e| {
+            WindWardenError::config_error(format!("Invalid configuration in {}: {}", source, e))
         })?;
 
         Self::validate_config(&config)?;
@@ -233,6 +739,12 @@ impl ConfigManager {
         Ok(config)
     }
 
+    /// Check that a configuration's values are valid, as loading a
+    /// configuration file does
+    pub fn validate(config: &Config) -> Result<(), WindWardenError> {
+        Self::validate_config(config)
+    }
+
     /// Validate configuration values
     fn validate_config(config: &Config) -> Result<(), WindWardenError> {
         // Validate sort_order
@@ -267,6 +779,181 @@ impl ConfigManager {
             }
         }
 
+        // Validate variant order
+        let mut seen_variants = std::collections::HashSet::new();
+        for variant in &config.variant_order {
+            if variant.is_empty() || variant.contains(':') {
+                return Err(WindWardenError::config_error(format!(
+                    "Invalid variant '{}' in variant_order. Use variant names without ':'",
+                    variant
+                )));
+            }
+            if !seen_variants.insert(variant) {
+                return Err(WindWardenError::config_error(format!(
+                    "Duplicate variant '{}' in variant_order",
+                    variant
+                )));
+            }
+        }
+
+        // Validate sort contexts
+        for context in &config.sort_contexts {
+            if let Err(message) = context.parse::<crate::parser::SortContext>() {
+                return Err(WindWardenError::config_error(format!(
+                    "{} for sortContexts",
+                    message
+                )));
+            }
+        }
+
+        if let Some(tag) = config
+            .template_tags
+            .iter()
+            .find(|tag| tag.is_empty() || tag.contains(char::is_whitespace))
+        {
+            return Err(WindWardenError::config_error(format!(
+                "Invalid templateTags entry '{}': tags must be non-empty and contain no whitespace",
+                tag
+            )));
+        }
+
+        if let Some(prop) = config
+            .class_object_props
+            .iter()
+            .find(|prop| prop.is_empty() || prop.contains(char::is_whitespace))
+        {
+            return Err(WindWardenError::config_error(format!(
+                "Invalid classObjectProps entry '{}': prop names must be non-empty and contain no whitespace",
+                prop
+            )));
+        }
+
+        // Validate Tailwind detection thresholds
+        if !(0.0..=1.0).contains(&config.tailwind_detection.ratio) {
+            return Err(WindWardenError::config_error(format!(
+                "tailwindDetection.ratio {} must be between 0 and 1",
+                config.tailwind_detection.ratio
+            )));
+        }
+        if config.tailwind_detection.min_tokens > 4 {
+            return Err(WindWardenError::config_error(format!(
+                "tailwindDetection.minTokens {} is too high (max: 4, the most tokens it applies to)",
+                config.tailwind_detection.min_tokens
+            )));
+        }
+
+        // Validate category prefix orders
+        let available_categories = ConfigManager::get_available_categories();
+        for (category, prefixes) in &config.category_prefix_order {
+            if !available_categories.contains(category) {
+                return Err(WindWardenError::config_error(format!(
+                    "Unknown category '{}' in categoryPrefixOrder. Available categories: {}",
+                    category,
+                    available_categories.join(", ")
+                )));
+            }
+            let mut seen_prefixes = std::collections::HashSet::new();
+            for prefix in prefixes {
+                if prefix.is_empty()
+                    || prefix.contains(char::is_whitespace)
+                    || prefix.ends_with('-')
+                {
+                    return Err(WindWardenError::config_error(format!(
+                        "Invalid prefix '{}' in categoryPrefixOrder for {}. Use prefixes without a trailing '-', such as 'px'",
+                        prefix, category
+                    )));
+                }
+                if !seen_prefixes.insert(prefix) {
+                    return Err(WindWardenError::config_error(format!(
+                        "Duplicate prefix '{}' in categoryPrefixOrder for {}",
+                        prefix, category
+                    )));
+                }
+            }
+        }
+
+        // Validate known custom classes
+        if let Some(pattern) = config.known_custom_classes.iter().find(|pattern| {
+            let name = pattern.strip_suffix('*').unwrap_or(pattern);
+            name.is_empty() || name.contains(char::is_whitespace) || name.contains('*')
+        }) {
+            return Err(WindWardenError::config_error(format!(
+                "Invalid knownCustomClasses entry '{}': use a class name, or a prefix followed by a single trailing '*'",
+                pattern
+            )));
+        }
+        if let Err(message) = config
+            .custom_classes_position
+            .parse::<crate::sorter::CustomClassesPosition>()
+        {
+            return Err(WindWardenError::config_error(format!(
+                "{} for customClassesPosition",
+                message
+            )));
+        }
+        if let Err(message) = config
+            .unknown_classes
+            .parse::<crate::sorter::UnknownClassSeverity>()
+        {
+            return Err(WindWardenError::config_error(format!(
+                "{} for unknownClasses",
+                message
+            )));
+        }
+
+        // Validate intra-category sorting
+        if let Err(message) = config
+            .intra_category_sort
+            .parse::<crate::sorter::IntraCategorySort>()
+        {
+            return Err(WindWardenError::config_error(format!(
+                "{} for intraCategorySort",
+                message
+            )));
+        }
+
+        // Validate the output quote style
+        if let Err(message) = config.quote_style.parse::<crate::parser::QuotePreference>() {
+            return Err(WindWardenError::config_error(format!(
+                "{} for quoteStyle",
+                message
+            )));
+        }
+
+        // Validate how writes are verified
+        if let Err(message) = config
+            .safety
+            .verify_strategy
+            .parse::<crate::atomic::VerifyStrategy>()
+        {
+            return Err(WindWardenError::config_error(format!(
+                "{} for safety.verifyStrategy",
+                message
+            )));
+        }
+
+        // Validate whitespace handling
+        if let Err(message) = config
+            .normalize_whitespace
+            .parse::<crate::sorter::WhitespaceMode>()
+        {
+            return Err(WindWardenError::config_error(format!(
+                "{} for normalizeWhitespace",
+                message
+            )));
+        }
+        if config.whitespace_separator.is_empty()
+            || !config
+                .whitespace_separator
+                .chars()
+                .all(|c| c == ' ' || c == '\t')
+        {
+            return Err(WindWardenError::config_error(format!(
+                "Invalid whitespaceSeparator {:?}. It must be one or more spaces or tabs",
+                config.whitespace_separator
+            )));
+        }
+
         // Validate file extensions
         for ext in &config.file_extensions {
             if ext.is_empty() {
@@ -298,6 +985,16 @@ impl ConfigManager {
             }
         }
 
+        // Validate per-function argument indices
+        for func_name in config.function_arg_indices.keys() {
+            if func_name.is_empty() || func_name.contains(char::is_whitespace) {
+                return Err(WindWardenError::config_error(format!(
+                    "Invalid function name '{}' in functionArgIndices",
+                    func_name
+                )));
+            }
+        }
+
         // Validate thread count
         if config.threads > 1024 {
             return Err(WindWardenError::config_error(format!(
@@ -306,16 +1003,59 @@ impl ConfigManager {
             )));
         }
 
+        if config.max_concurrent_reads == Some(0) {
+            return Err(WindWardenError::config_error(
+                "maxConcurrentReads must be at least 1",
+            ));
+        }
+
+        if config.max_files == Some(0) {
+            return Err(WindWardenError::config_error("maxFiles must be at least 1"));
+        }
+
+        validate_verify_exit_code(config.verify_exit_code)
+            .map_err(|msg| WindWardenError::config_error(format!("verifyExitCode {}", msg)))?;
+
+        // Validate backup naming
+        let suffix = &config.safety.backup_suffix;
+        if suffix.is_empty() || suffix.contains(['/', '\\']) {
+            return Err(WindWardenError::config_error(format!(
+                "Invalid backupSuffix '{}'. It must be non-empty and cannot contain path separators",
+                suffix
+            )));
+        }
+
         // Validate default_mode if provided
         if let Some(ref mode) = config.default_mode {
-            let valid_modes = ["format", "check", "diff"];
-            if !valid_modes.contains(&mode.as_str()) {
+            parse_default_mode(mode)?;
+        }
+
+        // Validate overrides by checking the configuration each one produces
+        for (index, entry) in config.overrides.iter().enumerate() {
+            if entry.files.is_empty() {
                 return Err(WindWardenError::config_error(format!(
-                    "Invalid default_mode '{}'. Valid options: {}",
-                    mode,
-                    valid_modes.join(", ")
+                    "overrides[{}] must list at least one glob in files",
+                    index
                 )));
             }
+            entry.matcher().map_err(|e| {
+                WindWardenError::config_error(format!(
+                    "Invalid glob in overrides[{}]: {}",
+                    index, e
+                ))
+            })?;
+
+            let mut layered = Config {
+                overrides: Vec::new(),
+                ..config.clone()
+            };
+            entry.config.apply_to(&mut layered);
+            Self::validate_config(&layered).map_err(|e| match e {
+                WindWardenError::Config { message } => {
+                    WindWardenError::config_error(format!("In overrides[{}]: {}", index, message))
+                }
+                e => e,
+            })?;
         }
 
         Ok(())
@@ -326,9 +1066,33 @@ impl ConfigManager {
         &self.config
     }
 
-    /// Get the path of the loaded configuration file
-    pub fn config_path(&self) -> Option<&PathBuf> {
-        self.config_path.as_ref()
+    /// The operation mode to use when `--mode` isn't passed, from `defaultMode`
+    pub fn default_mode(&self) -> Result<Option<OperationMode>, WindWardenError> {
+        self.config
+            .default_mode
+            .as_deref()
+            .map(parse_default_mode)
+            .transpose()
+    }
+
+    /// Get the effective configuration for a file, with matching overrides applied
+    pub fn config_for_path(&self, path: &Path) -> Result<Cow<'_, Config>, WindWardenError> {
+        let overrides = ConfigOverrides::new(&self.config)?.with_root(self.config_dir());
+        Ok(match overrides.config_for(path) {
+            Some(config) => Cow::Owned(config),
+            None => Cow::Borrowed(&self.config),
+        })
+    }
+
+    /// Get the path of the loaded configuration file
+    pub fn config_path(&self) -> Option<&PathBuf> {
+        self.config_path.as_ref()
+    }
+
+    /// The directory of the loaded configuration file, which override globs
+    /// are relative to
+    pub fn config_dir(&self) -> Option<&Path> {
+        self.config_path.as_deref().and_then(Path::parent)
     }
 
     /// Merge configuration with CLI arguments
@@ -338,7 +1102,76 @@ impl ConfigManager {
             // stdin mode doesn't use file-based config much
         }
 
-        // Additional CLI overrides can be added here
+        if cli_args.no_dedup {
+            self.override_from_cli("preserveDuplicates", |config| {
+                config.preserve_duplicates = true
+            });
+        }
+
+        if cli_args.merge {
+            self.override_from_cli("mergeConflicts", |config| config.merge_conflicts = true);
+        }
+
+        if cli_args.strict_unknown {
+            self.override_from_cli("unknownClasses", |config| {
+                config.unknown_classes = "error".to_string()
+            });
+        }
+    }
+
+    /// Change a setting from a command-line flag, recording `key` (the
+    /// setting's name in the configuration file) as set by the CLI
+    pub fn override_from_cli(&mut self, key: &str, apply: impl FnOnce(&mut Config)) {
+        apply(&mut self.config);
+        self.cli_overrides.insert(key.to_string());
+    }
+
+    /// Where each top-level setting's effective value came from
+    pub fn value_sources(&self) -> BTreeMap<String, ValueSource> {
+        let file_keys = self.file_keys();
+        let Ok(serde_json::Value::Object(settings)) = serde_json::to_value(&self.config) else {
+            return BTreeMap::new();
+        };
+
+        settings
+            .keys()
+            .map(|key| {
+                let source = if self.cli_overrides.contains(key) {
+                    ValueSource::Cli
+                } else if self
+                    .env_keys
+                    .as_ref()
+                    .is_some_and(|keys| keys.contains(key))
+                {
+                    ValueSource::Env
+                } else if file_keys.contains(key) {
+                    ValueSource::File
+                } else {
+                    ValueSource::Default
+                };
+                (key.clone(), source)
+            })
+            .collect()
+    }
+
+    /// The effective configuration, derived function names, and the source
+    /// of each setting, as printed by `--print-config`
+    pub fn resolved(&self) -> serde_json::Value {
+        serde_json::json!({
+            "configFile": self.config_path,
+            "config": self.config,
+            "functionNames": self.get_function_names(),
+            "sources": self.value_sources(),
+        })
+    }
+
+    /// The top-level keys set in the loaded configuration file
+    fn file_keys(&self) -> BTreeSet<String> {
+        self.config_path
+            .as_ref()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .map(|content| json_keys(&content))
+            .unwrap_or_default()
     }
 
     /// Save current configuration to file
@@ -355,9 +1188,14 @@ impl ConfigManager {
 
     /// Create a default configuration file
     pub fn create_default_config(path: &Path) -> Result<(), WindWardenError> {
-        let config = Config::default();
-        let content = serde_json::to_string_pretty(&config).map_err(|e| {
-            WindWardenError::config_error(format!("Failed to serialize default config: {}", e))
+        Self::write_config(path, &Config::default())
+    }
+
+    /// Validate a configuration and write it as a configuration file
+    pub fn write_config(path: &Path, config: &Config) -> Result<(), WindWardenError> {
+        Self::validate_config(config)?;
+        let content = serde_json::to_string_pretty(config).map_err(|e| {
+            WindWardenError::config_error(format!("Failed to serialize config: {}", e))
         })?;
 
         fs::write(path, content)
@@ -376,14 +1214,22 @@ impl ConfigManager {
             "classNames".to_string(),
             "classList".to_string(),
             "cva".to_string(),
+            "cx".to_string(),
+            "tv".to_string(),
         ];
 
         names.extend(self.config.function_names.clone());
+        names.extend(self.config.function_arg_indices.keys().cloned());
         names.sort();
         names.dedup();
         names
     }
 
+    /// Get the per-function argument indices that should be sorted
+    pub fn get_function_arg_indices(&self) -> HashMap<String, Vec<usize>> {
+        self.config.function_arg_indices.clone()
+    }
+
     /// Get effective ignore patterns
     pub fn get_ignore_patterns(&self) -> Vec<String> {
         self.config.ignore_paths.clone()
@@ -406,6 +1252,97 @@ impl ConfigManager {
     pub fn get_available_categories() -> Vec<String> {
         crate::sorter::TailwindSorter::get_default_category_order()
     }
+
+    /// Ask about the common settings on `output`, reading answers from
+    /// `input` and keeping each default when the user just presses Enter
+    pub fn prompt_for_config(
+        input: &mut impl BufRead,
+        output: &mut impl Write,
+    ) -> io::Result<Config> {
+        let mut config = Config::default();
+        let categories = Self::get_available_categories();
+
+        let custom_order = loop {
+            match prompt(input, output, "Sort order, official or custom? [official]")?.as_deref() {
+                None | Some("official") => break false,
+                Some("custom") => break true,
+                Some(_) => writeln!(output, "Please answer official or custom")?,
+            }
+        };
+        if custom_order {
+            writeln!(output, "Categories: {}", categories.join(", "))?;
+            config.sort_order = "custom".to_string();
+            config.custom_order = loop {
+                let order = match prompt(
+                    input,
+                    output,
+                    "Categories in sort order, comma-separated [all, as listed]",
+                )? {
+                    Some(answer) => comma_list(&answer),
+                    None => Vec::new(),
+                };
+                if order.is_empty() {
+                    break categories.clone();
+                }
+                let unknown: Vec<&str> = order
+                    .iter()
+                    .filter(|category| !categories.contains(category))
+                    .map(String::as_str)
+                    .collect();
+                if unknown.is_empty() {
+                    break order;
+                }
+                writeln!(output, "Unknown categories: {}", unknown.join(", "))?;
+            };
+        }
+
+        if let Some(answer) = prompt(
+            input,
+            output,
+            "Extra functions that take class strings, comma-separated [none]",
+        )? {
+            config.function_names = comma_list(&answer);
+        }
+
+        let question = format!(
+            "Paths to ignore besides {}, comma-separated [none]",
+            config.ignore_paths.join(", ")
+        );
+        if let Some(answer) = prompt(input, output, &question)? {
+            config.ignore_paths.extend(comma_list(&answer));
+        }
+
+        Ok(config)
+    }
+}
+
+/// Ask `question` on `output`, returning the trimmed answer, or `None` when
+/// the answer is empty or input has ended
+fn prompt(
+    input: &mut impl BufRead,
+    output: &mut impl Write,
+    question: &str,
+) -> io::Result<Option<String>> {
+    write!(output, "{} ", question)?;
+    output.flush()?;
+
+    let mut line = String::new();
+    if input.read_line(&mut line)? == 0 {
+        writeln!(output)?;
+        return Ok(None);
+    }
+    let answer = line.trim();
+    Ok((!answer.is_empty()).then(|| answer.to_string()))
+}
+
+/// The non-empty entries of a comma-separated answer
+fn comma_list(answer: &str) -> Vec<String> {
+    answer
+        .split(',')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .map(String::from)
+        .collect()
 }
 
 #[cfg(test)]
@@ -465,6 +1402,33 @@ mod tests {
         assert!(ConfigManager::validate_config(&config).is_ok());
     }
 
+    #[test]
+    fn test_function_arg_indices() {
+        let mut arg_indices = HashMap::new();
+        arg_indices.insert("classesFor".to_string(), vec![0]);
+        let config = Config {
+            function_arg_indices: arg_indices,
+            ..Default::default()
+        };
+        assert!(ConfigManager::validate_config(&config).is_ok());
+
+        // Functions with argument restrictions are detected without also listing them
+        let manager = ConfigManager::new_with_config(config, None);
+        assert!(
+            manager
+                .get_function_names()
+                .contains(&"classesFor".to_string())
+        );
+
+        let mut arg_indices = HashMap::new();
+        arg_indices.insert("bad name".to_string(), vec![0]);
+        let config = Config {
+            function_arg_indices: arg_indices,
+            ..Default::default()
+        };
+        assert!(ConfigManager::validate_config(&config).is_err());
+    }
+
     #[test]
     fn test_thread_count_validation() {
         // Test excessive thread count
@@ -490,32 +1454,149 @@ mod tests {
     }
 
     #[test]
-    fn test_default_mode_validation() {
-        // Test invalid mode
+    fn test_variant_order_validation() {
         let config = Config {
-            default_mode: Some("invalid".to_string()),
+            variant_order: vec!["responsive".to_string(), "hover".to_string()],
+            ..Default::default()
+        };
+        assert!(ConfigManager::validate_config(&config).is_ok());
+
+        // Stacked variants are not single entries
+        let config = Config {
+            variant_order: vec!["dark:hover".to_string()],
             ..Default::default()
         };
         assert!(ConfigManager::validate_config(&config).is_err());
 
-        // Test valid modes
         let config = Config {
-            default_mode: Some("format".to_string()),
+            variant_order: vec!["hover".to_string(), "hover".to_string()],
             ..Default::default()
         };
+        assert!(ConfigManager::validate_config(&config).is_err());
+    }
+
+    #[test]
+    fn test_backup_suffix_validation() {
+        let mut config = Config::default();
+        config.safety.backup_suffix = ".orig".to_string();
         assert!(ConfigManager::validate_config(&config).is_ok());
 
+        config.safety.backup_suffix = String::new();
+        assert!(ConfigManager::validate_config(&config).is_err());
+
+        config.safety.backup_suffix = "/bak".to_string();
+        assert!(ConfigManager::validate_config(&config).is_err());
+    }
+
+    #[test]
+    fn test_verify_exit_code_validation() {
+        assert_eq!(Config::default().verify_exit_code, 1);
+
+        for code in [1, 3, 125] {
+            let config = Config {
+                verify_exit_code: code,
+                ..Default::default()
+            };
+            assert!(ConfigManager::validate_config(&config).is_ok());
+        }
+
+        for code in [0, 2, 126, -1] {
+            let config = Config {
+                verify_exit_code: code,
+                ..Default::default()
+            };
+            assert!(ConfigManager::validate_config(&config).is_err());
+        }
+    }
+
+    #[test]
+    fn test_max_concurrent_reads_validation() {
+        let config = Config {
+            max_concurrent_reads: Some(0),
+            ..Default::default()
+        };
+        assert!(ConfigManager::validate_config(&config).is_err());
+
         let config = Config {
-            default_mode: Some("check".to_string()),
+            max_concurrent_reads: Some(4),
             ..Default::default()
         };
         assert!(ConfigManager::validate_config(&config).is_ok());
+    }
+
+    #[test]
+    fn test_template_tags_validation() {
+        let config: Config = serde_json::from_str(
+            r#"{"templateTags": ["tw", "css"], "sortUntaggedTemplates": false}"#,
+        )
+        .unwrap();
+        assert_eq!(config.template_tags, ["tw", "css"]);
+        assert!(!config.sort_untagged_templates);
+        assert!(ConfigManager::validate_config(&config).is_ok());
+
+        for tag in ["", "my tag"] {
+            let config = Config {
+                template_tags: vec![tag.to_string()],
+                ..Default::default()
+            };
+            assert!(ConfigManager::validate_config(&config).is_err());
+        }
+    }
+
+    #[test]
+    fn test_class_object_props_validation() {
+        let config: Config =
+            serde_json::from_str(r#"{"classObjectProps": ["classNames"]}"#).unwrap();
+        assert_eq!(config.class_object_props, ["classNames"]);
+        assert!(ConfigManager::validate_config(&config).is_ok());
+        assert!(Config::default().class_object_props.is_empty());
 
         let config = Config {
-            default_mode: Some("diff".to_string()),
+            class_object_props: vec!["class Names".to_string()],
             ..Default::default()
         };
+        let error = ConfigManager::validate_config(&config).unwrap_err();
+        assert!(error.to_string().contains("Invalid classObjectProps entry"));
+    }
+
+    #[test]
+    fn test_max_files_validation() {
+        let config = Config {
+            max_files: Some(0),
+            ..Default::default()
+        };
+        assert!(ConfigManager::validate_config(&config).is_err());
+
+        let config: Config = serde_json::from_str(r#"{"maxFiles": 50000}"#).unwrap();
+        assert_eq!(config.max_files, Some(50000));
         assert!(ConfigManager::validate_config(&config).is_ok());
+    }
+
+    #[test]
+    fn test_default_mode_validation() {
+        // Test invalid mode
+        let config = Config {
+            default_mode: Some("invalid".to_string()),
+            ..Default::default()
+        };
+        assert!(ConfigManager::validate_config(&config).is_err());
+
+        // "format" could mean write or check
+        let config = Config {
+            default_mode: Some("format".to_string()),
+            ..Default::default()
+        };
+        let err = ConfigManager::validate_config(&config).unwrap_err();
+        assert!(err.to_string().contains("Ambiguous defaultMode 'format'"));
+
+        // Test valid modes
+        for mode in ["check", "write", "verify", "diff"] {
+            let config = Config {
+                default_mode: Some(mode.to_string()),
+                ..Default::default()
+            };
+            assert!(ConfigManager::validate_config(&config).is_ok(), "{}", mode);
+        }
 
         // Test None (no default)
         let config = Config {
@@ -525,6 +1606,31 @@ mod tests {
         assert!(ConfigManager::validate_config(&config).is_ok());
     }
 
+    #[test]
+    fn test_default_mode_maps_to_operation_mode() {
+        let manager = |mode: Option<&str>| {
+            let config = Config {
+                default_mode: mode.map(str::to_string),
+                ..Default::default()
+            };
+            ConfigManager::new_with_config(config, None)
+        };
+
+        assert!(manager(None).default_mode().unwrap().is_none());
+        assert!(matches!(
+            manager(Some("write")).default_mode().unwrap(),
+            Some(OperationMode::Write)
+        ));
+        assert!(matches!(
+            manager(Some("verify")).default_mode().unwrap(),
+            Some(OperationMode::Verify)
+        ));
+        assert!(matches!(
+            manager(Some("diff")).default_mode().unwrap(),
+            Some(OperationMode::Check)
+        ));
+    }
+
     #[test]
     fn test_config_file_discovery() {
         let temp_dir = TempDir::new().unwrap();
@@ -543,6 +1649,32 @@ mod tests {
         assert_eq!(found.unwrap(), config_path);
     }
 
+    #[test]
+    fn test_write_config() {
+        let temp_dir = TempDir::new().unwrap();
+        let config_path = temp_dir.path().join(".windwarden.json");
+
+        let config = Config {
+            sort_order: "custom".to_string(),
+            custom_order: vec!["spacing".to_string(), "layout".to_string()],
+            function_names: vec!["myCn".to_string()],
+            ..Default::default()
+        };
+        ConfigManager::write_config(&config_path, &config).unwrap();
+        let written = ConfigManager::load_config_file(&config_path).unwrap();
+        assert_eq!(written.custom_order, ["spacing", "layout"]);
+        assert_eq!(written.function_names, ["myCn"]);
+
+        // Invalid settings are rejected before anything is written
+        let other_path = temp_dir.path().join("other.json");
+        let config = Config {
+            sort_order: "custom".to_string(),
+            ..Default::default()
+        };
+        assert!(ConfigManager::write_config(&other_path, &config).is_err());
+        assert!(!other_path.exists());
+    }
+
     #[test]
     fn test_function_names() {
         let manager = ConfigManager::new();
@@ -551,6 +1683,405 @@ mod tests {
         assert!(function_names.contains(&"clsx".to_string()));
     }
 
+    #[test]
+    fn test_value_sources() {
+        let temp_dir = TempDir::new().unwrap();
+        let config_path = temp_dir.path().join(".windwarden.json");
+        fs::write(
+            &config_path,
+            r#"{"functionNames": ["tw"], "threads": 2, "preserveDuplicates": false}"#,
+        )
+        .unwrap();
+
+        let mut manager = ConfigManager::load_from_directory(temp_dir.path()).unwrap();
+        manager.override_from_cli("preserveDuplicates", |config| {
+            config.preserve_duplicates = true
+        });
+
+        let sources = manager.value_sources();
+        assert_eq!(sources["functionNames"], ValueSource::File);
+        assert_eq!(sources["threads"], ValueSource::File);
+        assert_eq!(sources["preserveDuplicates"], ValueSource::Cli);
+        assert_eq!(sources["sortOrder"], ValueSource::Default);
+
+        let resolved = manager.resolved();
+        assert_eq!(resolved["config"]["preserveDuplicates"], true);
+        assert_eq!(resolved["sources"]["threads"], "file");
+        assert!(
+            resolved["functionNames"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .any(|name| name == "tw")
+        );
+    }
+
+    #[test]
+    fn test_from_env_json() {
+        let manager =
+            ConfigManager::from_env_json(r#"{"functionNames": ["tw"], "threads": 2}"#).unwrap();
+        assert!(manager.loaded_from_env());
+        assert_eq!(manager.config_path(), None);
+        assert_eq!(manager.config().threads, 2);
+        let sources = manager.value_sources();
+        assert_eq!(sources["threads"], ValueSource::Env);
+        assert_eq!(sources["sortOrder"], ValueSource::Default);
+
+        // Invalid JSON names the variable; invalid values are validated too
+        let Err(error) = ConfigManager::from_env_json("{threads: 2}") else {
+            panic!("invalid JSON was accepted");
+        };
+        assert!(
+            error
+                .to_string()
+                .contains("Invalid configuration in the WINDWARDEN_CONFIG environment variable")
+        );
+        assert!(ConfigManager::from_env_json(r#"{"sortOrder": "alphabetical"}"#).is_err());
+    }
+
+    fn stories_override() -> ConfigOverride {
+        ConfigOverride {
+            files: vec!["**/*.stories.tsx".to_string()],
+            config: OverrideSettings {
+                function_names: Some(vec!["storyClasses".to_string()]),
+                ..Default::default()
+            },
+        }
+    }
+
+    #[test]
+    fn test_config_overrides_resolve_per_path() {
+        let config = Config {
+            function_names: vec!["tw".to_string()],
+            overrides: vec![
+                stories_override(),
+                ConfigOverride {
+                    files: vec!["src/legacy/**".to_string()],
+                    config: OverrideSettings {
+                        sort_order: Some("custom".to_string()),
+                        custom_order: Some(vec!["spacing".to_string(), "layout".to_string()]),
+                        ..Default::default()
+                    },
+                },
+            ],
+            ..Default::default()
+        };
+        assert!(ConfigManager::validate_config(&config).is_ok());
+        let manager = ConfigManager::new_with_config(config, None);
+
+        let base = manager
+            .config_for_path(Path::new("src/Button.tsx"))
+            .unwrap();
+        assert!(matches!(base, Cow::Borrowed(_)));
+        assert_eq!(base.function_names, ["tw"]);
+
+        let stories = manager
+            .config_for_path(Path::new("./src/Button.stories.tsx"))
+            .unwrap();
+        assert_eq!(stories.function_names, ["storyClasses"]);
+        assert_eq!(stories.sort_order, "official");
+
+        // Later overrides are layered on top of earlier ones
+        let legacy = manager
+            .config_for_path(Path::new("src/legacy/Card.stories.tsx"))
+            .unwrap();
+        assert_eq!(legacy.function_names, ["storyClasses"]);
+        assert_eq!(legacy.sort_order, "custom");
+        assert_eq!(legacy.custom_order, ["spacing", "layout"]);
+
+        // Globs are relative to the configuration file's directory
+        let config = manager.config().clone();
+        let cwd = std::env::current_dir().unwrap();
+        let manager =
+            ConfigManager::new_with_config(config, Some(cwd.join("web/.windwarden.json")));
+        let legacy = manager
+            .config_for_path(Path::new("./web/src/legacy/Card.tsx"))
+            .unwrap();
+        assert_eq!(legacy.sort_order, "custom");
+        let outside = manager
+            .config_for_path(Path::new("src/legacy/Card.tsx"))
+            .unwrap();
+        assert_eq!(outside.sort_order, "official");
+    }
+
+    #[test]
+    fn test_config_overrides_validation() {
+        let invalid = |entry: ConfigOverride| {
+            let config = Config {
+                overrides: vec![entry],
+                ..Default::default()
+            };
+            ConfigManager::validate_config(&config)
+                .unwrap_err()
+                .to_string()
+        };
+
+        assert!(
+            invalid(ConfigOverride {
+                files: Vec::new(),
+                ..stories_override()
+            })
+            .contains("at least one glob")
+        );
+        assert!(
+            invalid(ConfigOverride {
+                files: vec!["src/[".to_string()],
+                ..stories_override()
+            })
+            .contains("Invalid glob in overrides[0]")
+        );
+        assert!(
+            invalid(ConfigOverride {
+                files: vec!["**/*.tsx".to_string()],
+                config: OverrideSettings {
+                    sort_order: Some("custom".to_string()),
+                    ..Default::default()
+                },
+            })
+            .contains("In overrides[0]: custom_order must be provided")
+        );
+    }
+
+    #[test]
+    fn test_config_overrides_from_json() {
+        let config: Config = serde_json::from_str(
+            r#"{
+  "overrides": [
+    { "files": ["**/*.stories.tsx"], "config": { "functionNames": ["storyClasses"] } }
+  ]
+}"#,
+        )
+        .unwrap();
+
+        assert_eq!(config.overrides.len(), 1);
+        assert_eq!(config.overrides[0].files, ["**/*.stories.tsx"]);
+        assert!(config.overrides[0].config.sort_order.is_none());
+
+        // Configurations without overrides don't write an empty list
+        let json = serde_json::to_string(&Config::default()).unwrap();
+        assert!(!json.contains("overrides"));
+    }
+
+    #[test]
+    fn test_normalize_whitespace_validation() {
+        for mode in ["collapse", "preserve-edges", "none"] {
+            let config = Config {
+                normalize_whitespace: mode.to_string(),
+                ..Default::default()
+            };
+            assert!(ConfigManager::validate_config(&config).is_ok(), "{}", mode);
+        }
+
+        let config = Config {
+            normalize_whitespace: "trim".to_string(),
+            ..Default::default()
+        };
+        let error = ConfigManager::validate_config(&config).unwrap_err();
+        assert!(error.to_string().contains("Invalid whitespace mode 'trim'"));
+    }
+
+    #[test]
+    fn test_whitespace_separator_validation() {
+        for separator in [" ", "\t", "  "] {
+            let config = Config {
+                whitespace_separator: separator.to_string(),
+                ..Default::default()
+            };
+            assert!(
+                ConfigManager::validate_config(&config).is_ok(),
+                "{:?}",
+                separator
+            );
+        }
+
+        for separator in ["", "\n", ","] {
+            let config = Config {
+                whitespace_separator: separator.to_string(),
+                ..Default::default()
+            };
+            let error = ConfigManager::validate_config(&config).unwrap_err();
+            assert!(error.to_string().contains("Invalid whitespaceSeparator"));
+        }
+    }
+
+    #[test]
+    fn test_sort_contexts_validation() {
+        let config = Config::default();
+        assert_eq!(
+            config.sort_contexts.len(),
+            crate::parser::SortContext::ALL.len()
+        );
+
+        let config = Config {
+            sort_contexts: vec!["jsxAttribute".to_string(), "array".to_string()],
+            ..Default::default()
+        };
+        assert!(ConfigManager::validate_config(&config).is_ok());
+
+        let config = Config {
+            sort_contexts: vec!["jsx".to_string()],
+            ..Default::default()
+        };
+        let error = ConfigManager::validate_config(&config).unwrap_err();
+        assert!(error.to_string().contains("Invalid sort context 'jsx'"));
+    }
+
+    #[test]
+    fn test_tailwind_detection_config() {
+        let config: Config =
+            serde_json::from_str(r#"{ "tailwindDetection": { "ratio": 0.3 } }"#).unwrap();
+        assert_eq!(config.tailwind_detection.ratio, 0.3);
+        assert_eq!(config.tailwind_detection.min_tokens, 2);
+        assert!(ConfigManager::validate_config(&config).is_ok());
+
+        let mut config = Config::default();
+        config.tailwind_detection.ratio = 1.5;
+        let error = ConfigManager::validate_config(&config).unwrap_err();
+        assert!(error.to_string().contains("tailwindDetection.ratio"));
+
+        let mut config = Config::default();
+        config.tailwind_detection.min_tokens = 5;
+        let error = ConfigManager::validate_config(&config).unwrap_err();
+        assert!(error.to_string().contains("tailwindDetection.minTokens"));
+    }
+
+    #[test]
+    fn test_quote_style_validation() {
+        for style in ["preserve", "single", "double"] {
+            let config = Config {
+                quote_style: style.to_string(),
+                ..Default::default()
+            };
+            assert!(ConfigManager::validate_config(&config).is_ok(), "{}", style);
+        }
+
+        let config = Config {
+            quote_style: "backtick".to_string(),
+            ..Default::default()
+        };
+        let error = ConfigManager::validate_config(&config).unwrap_err();
+        assert!(error.to_string().contains("Invalid quote style 'backtick'"));
+    }
+
+    #[test]
+    fn test_verify_strategy_validation() {
+        for strategy in ["content", "hash"] {
+            let mut config = Config::default();
+            config.safety.verify_strategy = strategy.to_string();
+            assert!(
+                ConfigManager::validate_config(&config).is_ok(),
+                "{}",
+                strategy
+            );
+        }
+
+        let mut config = Config::default();
+        config.safety.verify_strategy = "checksum".to_string();
+        let error = ConfigManager::validate_config(&config).unwrap_err();
+        assert!(
+            error
+                .to_string()
+                .contains("Invalid verify strategy 'checksum'")
+        );
+    }
+
+    #[test]
+    fn test_known_custom_classes_validation() {
+        let config: Config = serde_json::from_str(
+            r#"{"knownCustomClasses": ["stack", "u-*"], "customClassesPosition": "last"}"#,
+        )
+        .unwrap();
+        assert!(ConfigManager::validate_config(&config).is_ok());
+
+        for pattern in ["", "*", "u-*-x", "my class"] {
+            let config = Config {
+                known_custom_classes: vec![pattern.to_string()],
+                ..Default::default()
+            };
+            let error = ConfigManager::validate_config(&config).unwrap_err();
+            assert!(
+                error
+                    .to_string()
+                    .contains("Invalid knownCustomClasses entry"),
+                "{}",
+                pattern
+            );
+        }
+
+        let config = Config {
+            custom_classes_position: "middle".to_string(),
+            ..Default::default()
+        };
+        let error = ConfigManager::validate_config(&config).unwrap_err();
+        assert!(error.to_string().contains("customClassesPosition"));
+    }
+
+    #[test]
+    fn test_category_prefix_order_validation() {
+        let config: Config = serde_json::from_str(
+            r#"{"groupPrefixes": true, "categoryPrefixOrder": {"spacing": ["p", "px", "py"]}}"#,
+        )
+        .unwrap();
+        assert!(ConfigManager::validate_config(&config).is_ok());
+
+        for (category, prefix, message) in [
+            ("spaces", "p", "Unknown category 'spaces'"),
+            ("spacing", "px-", "Invalid prefix 'px-'"),
+            ("spacing", "", "Invalid prefix ''"),
+            ("spacing", "p", "Duplicate prefix 'p'"),
+        ] {
+            let config = Config {
+                category_prefix_order: HashMap::from([(
+                    category.to_string(),
+                    vec!["p".to_string(), prefix.to_string()],
+                )]),
+                ..Default::default()
+            };
+            let error = ConfigManager::validate_config(&config).unwrap_err();
+            assert!(error.to_string().contains(message), "{}", error);
+        }
+    }
+
+    #[test]
+    fn test_unknown_classes_validation() {
+        for severity in ["ignore", "warn", "error"] {
+            let config = Config {
+                unknown_classes: severity.to_string(),
+                ..Default::default()
+            };
+            assert!(ConfigManager::validate_config(&config).is_ok());
+        }
+
+        let config = Config {
+            unknown_classes: "fatal".to_string(),
+            ..Default::default()
+        };
+        let error = ConfigManager::validate_config(&config).unwrap_err();
+        assert!(error.to_string().contains("for unknownClasses"));
+    }
+
+    #[test]
+    fn test_intra_category_sort_validation() {
+        for mode in ["lexical", "numeric"] {
+            let config = Config {
+                intra_category_sort: mode.to_string(),
+                ..Default::default()
+            };
+            assert!(ConfigManager::validate_config(&config).is_ok(), "{}", mode);
+        }
+
+        let config = Config {
+            intra_category_sort: "natural".to_string(),
+            ..Default::default()
+        };
+        let error = ConfigManager::validate_config(&config).unwrap_err();
+        assert!(
+            error
+                .to_string()
+                .contains("Invalid intra-category sort 'natural'")
+        );
+    }
+
     #[test]
     fn test_config_serialization() {
         let config = Config::default();
@@ -558,4 +2089,29 @@ mod tests {
         let parsed: Config = serde_json::from_str(&json).unwrap();
         assert_eq!(config.sort_order, parsed.sort_order);
     }
+
+    #[test]
+    fn test_prompt_for_config() {
+        let answers = "fancy\ncustom\nspacing, widgets\nspacing, layout\ntw, clsx\nlegacy/**\n";
+        let mut output = Vec::new();
+        let config =
+            ConfigManager::prompt_for_config(&mut answers.as_bytes(), &mut output).unwrap();
+
+        assert_eq!(config.sort_order, "custom");
+        assert_eq!(config.custom_order, vec!["spacing", "layout"]);
+        assert_eq!(config.function_names, vec!["tw", "clsx"]);
+        assert_eq!(config.ignore_paths.last().unwrap(), "legacy/**");
+
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.contains("Please answer official or custom"));
+        assert!(output.contains("Unknown categories: widgets"));
+
+        // Empty answers and ended input keep the defaults
+        let config =
+            ConfigManager::prompt_for_config(&mut "\n".as_bytes(), &mut Vec::new()).unwrap();
+        let defaults = Config::default();
+        assert_eq!(config.sort_order, "official");
+        assert_eq!(config.function_names, defaults.function_names);
+        assert_eq!(config.ignore_paths, defaults.ignore_paths);
+    }
 }
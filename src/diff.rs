@@ -1,5 +1,15 @@
+use crate::cli::DiffStyle;
 use colored::Colorize;
 
+/// Width assumed for side-by-side diffs when it can't be detected
+pub const DEFAULT_DIFF_WIDTH: usize = 120;
+
+/// Narrowest output side-by-side diffs are drawn in; below this they fall back to unified
+pub const MIN_SIDE_BY_SIDE_WIDTH: usize = 80;
+
+/// Separator between the two columns of a side-by-side diff
+const COLUMN_SEPARATOR: &str = " │ ";
+
 /// A single change in a diff
 #[derive(Debug, Clone, PartialEq)]
 pub struct DiffLine {
@@ -89,6 +99,8 @@ pub struct DiffSummary {
 pub struct DiffFormatter {
     context_lines: usize,
     use_colors: bool,
+    style: DiffStyle,
+    width: usize,
 }
 
 impl DiffFormatter {
@@ -96,6 +108,8 @@ impl DiffFormatter {
         Self {
             context_lines: 3,
             use_colors: true,
+            style: DiffStyle::Unified,
+            width: DEFAULT_DIFF_WIDTH,
         }
     }
 
@@ -109,12 +123,27 @@ impl DiffFormatter {
         self
     }
 
+    pub fn with_style(mut self, style: DiffStyle) -> Self {
+        self.style = style;
+        self
+    }
+
+    /// Set the output width side-by-side diffs are fitted to
+    pub fn with_width(mut self, width: usize) -> Self {
+        self.width = width;
+        self
+    }
+
     /// Format a file diff as a string
     pub fn format_diff(&self, diff: &FileDiff) -> String {
         if !diff.has_changes {
             return String::new();
         }
 
+        if self.style == DiffStyle::SideBySide && self.width >= MIN_SIDE_BY_SIDE_WIDTH {
+            return self.format_side_by_side(diff);
+        }
+
         let mut output = Vec::new();
 
         // File header
@@ -143,6 +172,186 @@ impl DiffFormatter {
         output.join("\n")
     }
 
+    /// Format a file diff with original lines on the left and sorted lines on the right
+    ///
+    /// Removed and added lines are paired row by row, and the part of each
+    /// pair that differs (usually the class list) is highlighted. Lines
+    /// longer than a column are truncated with `…`.
+    fn format_side_by_side(&self, diff: &FileDiff) -> String {
+        let column = (self.width - COLUMN_SEPARATOR.chars().count()) / 2;
+        let mut output = Vec::new();
+
+        let header = self.join_columns(
+            self.cell(
+                &format!("--- {}", diff.file_path),
+                column,
+                None,
+                Side::Old,
+                true,
+            ),
+            self.cell(
+                &format!("+++ {}", diff.file_path),
+                column,
+                None,
+                Side::New,
+                true,
+            ),
+        );
+        output.push(header);
+
+        for hunk in self.group_into_hunks(&diff.changes) {
+            output.push(self.format_hunk_header(&hunk));
+
+            let mut old_line = hunk.old_start;
+            let mut new_line = hunk.new_start;
+            let mut lines = hunk.lines.iter().peekable();
+            while let Some(line) = lines.next() {
+                if line.change_type == ChangeType::Unchanged {
+                    let left =
+                        self.numbered_cell(old_line, &line.content, column, None, Side::Both);
+                    let right =
+                        self.numbered_cell(new_line, &line.content, column, None, Side::Both);
+                    output.push(self.join_columns(left, right));
+                    old_line += 1;
+                    new_line += 1;
+                    continue;
+                }
+
+                // Collect the run of changes starting here and pair removals with additions
+                let mut removed = Vec::new();
+                let mut added = Vec::new();
+                let mut next = Some(line);
+                while let Some(line) = next {
+                    match line.change_type {
+                        ChangeType::Removed => removed.push(expand_tabs(&line.content)),
+                        ChangeType::Added => added.push(expand_tabs(&line.content)),
+                        ChangeType::Unchanged => unreachable!("runs stop at unchanged lines"),
+                    }
+                    next = lines.next_if(|line| line.change_type != ChangeType::Unchanged);
+                }
+
+                for row in 0..removed.len().max(added.len()) {
+                    let (old, new) = (removed.get(row), added.get(row));
+                    let (old_range, new_range) = match (old, new) {
+                        (Some(old), Some(new)) => changed_ranges(old, new),
+                        _ => (None, None),
+                    };
+
+                    let left = match old {
+                        Some(old) => {
+                            old_line += 1;
+                            self.numbered_cell(old_line - 1, old, column, old_range, Side::Old)
+                        }
+                        None => " ".repeat(column),
+                    };
+                    let right = match new {
+                        Some(new) => {
+                            new_line += 1;
+                            self.numbered_cell(new_line - 1, new, column, new_range, Side::New)
+                        }
+                        None => " ".repeat(column),
+                    };
+                    output.push(self.join_columns(left, right));
+                }
+            }
+        }
+
+        output.join("\n")
+    }
+
+    /// A line number and content fitted to a column
+    fn numbered_cell(
+        &self,
+        number: usize,
+        content: &str,
+        width: usize,
+        highlight: Option<ByteRange>,
+        side: Side,
+    ) -> String {
+        let prefix = format!("{:>4} ", number);
+        let content = self.cell(
+            content,
+            width.saturating_sub(prefix.len()),
+            highlight,
+            side,
+            false,
+        );
+        let prefix = if self.use_colors {
+            prefix.dimmed().to_string()
+        } else {
+            prefix
+        };
+        prefix + &content
+    }
+
+    /// Text truncated or padded to exactly `width` characters, then colored
+    fn cell(
+        &self,
+        text: &str,
+        width: usize,
+        highlight: Option<ByteRange>,
+        side: Side,
+        bold: bool,
+    ) -> String {
+        let text = expand_tabs(text);
+        let highlight = highlight.filter(|_| !text.is_empty());
+
+        let mut chars: Vec<(usize, char)> = text.char_indices().collect();
+        let truncated = chars.len() > width;
+        if truncated {
+            chars.truncate(width.saturating_sub(1));
+        }
+
+        let mut plain = String::new();
+        let mut segments: Vec<(String, bool)> = Vec::new();
+        for (index, c) in chars {
+            let in_highlight = highlight.is_some_and(|(start, end)| (start..end).contains(&index));
+            if segments
+                .last()
+                .is_none_or(|(_, last)| *last != in_highlight)
+            {
+                segments.push((String::new(), in_highlight));
+            }
+            if let Some((segment, _)) = segments.last_mut() {
+                segment.push(c);
+            }
+            plain.push(c);
+        }
+        if truncated {
+            segments.push(("…".to_string(), false));
+            plain.push('…');
+        }
+        let padding = " ".repeat(width.saturating_sub(plain.chars().count()));
+
+        if !self.use_colors {
+            return plain + &padding;
+        }
+
+        let mut marked = String::new();
+        for (segment, highlighted) in segments {
+            let colored = match side {
+                Side::Old => segment.red(),
+                Side::New => segment.green(),
+                Side::Both => segment.normal(),
+            };
+            let colored = if highlighted {
+                colored.bold().underline()
+            } else if bold {
+                colored.bold()
+            } else {
+                colored
+            };
+            marked.push_str(&colored.to_string());
+        }
+        marked + &padding
+    }
+
+    fn join_columns(&self, left: String, right: String) -> String {
+        format!("{}{}{}", left, COLUMN_SEPARATOR, right)
+            .trim_end()
+            .to_string()
+    }
+
     /// Format a concise summary of changes
     pub fn format_summary(&self, diff: &FileDiff) -> String {
         if !diff.has_changes {
@@ -198,59 +407,69 @@ impl DiffFormatter {
         }
     }
 
-    /// Group diff lines into hunks
+    /// Group diff lines into hunks with up to `context_lines` unchanged lines
+    /// around each change; changes whose context overlaps share a hunk
     fn group_into_hunks(&self, lines: &[DiffLine]) -> Vec<DiffHunk> {
-        let mut hunks = Vec::new();
-        let mut current_hunk: Option<DiffHunk> = None;
-
+        // Index ranges [start, end) of each hunk
+        let mut ranges: Vec<(usize, usize)> = Vec::new();
         for (i, line) in lines.iter().enumerate() {
-            let is_change = matches!(line.change_type, ChangeType::Added | ChangeType::Removed);
-
-            if is_change {
-                if current_hunk.is_none() {
-                    // Start new hunk with context
-                    let start_context = i.saturating_sub(self.context_lines);
-                    current_hunk = Some(DiffHunk {
-                        start_line: lines[start_context].line_number,
-                        lines: lines[start_context..i].to_vec(),
-                    });
-                }
+            if matches!(line.change_type, ChangeType::Unchanged) {
+                continue;
+            }
 
-                if let Some(ref mut hunk) = current_hunk {
-                    hunk.lines.push(line.clone());
-                }
-            } else if let Some(ref mut hunk) = current_hunk {
-                // Add context line to current hunk
-                hunk.lines.push(line.clone());
-
-                // Check if we should end the hunk
-                let context_after = lines[i..]
-                    .iter()
-                    .take(self.context_lines + 1)
-                    .all(|l| matches!(l.change_type, ChangeType::Unchanged));
-
-                if context_after {
-                    // Add trailing context and finish hunk
-                    let end_context = (i + self.context_lines + 1).min(lines.len());
-                    hunk.lines.extend_from_slice(&lines[i + 1..end_context]);
-                    hunks.push(current_hunk.take().unwrap());
-                }
+            let start = i.saturating_sub(self.context_lines);
+            let end = (i + 1 + self.context_lines).min(lines.len());
+            match ranges.last_mut() {
+                Some(last) if start <= last.1 => last.1 = last.1.max(end),
+                _ => ranges.push((start, end)),
             }
         }
 
-        // Finish any remaining hunk
-        if let Some(hunk) = current_hunk {
-            hunks.push(hunk);
+        // Lines of the original and modified file seen before each diff line
+        let mut old_line = 0;
+        let mut new_line = 0;
+        let mut positions = Vec::with_capacity(lines.len() + 1);
+        for line in lines {
+            positions.push((old_line, new_line));
+            match line.change_type {
+                ChangeType::Removed => old_line += 1,
+                ChangeType::Added => new_line += 1,
+                ChangeType::Unchanged => {
+                    old_line += 1;
+                    new_line += 1;
+                }
+            }
         }
-
-        hunks
+        positions.push((old_line, new_line));
+
+        ranges
+            .into_iter()
+            .map(|(start, end)| {
+                let (old_before, new_before) = positions[start];
+                let (old_after, new_after) = positions[end];
+                DiffHunk {
+                    old_start: old_before + 1,
+                    old_count: old_after - old_before,
+                    new_start: new_before + 1,
+                    new_count: new_after - new_before,
+                    lines: lines[start..end].to_vec(),
+                }
+            })
+            .collect()
     }
 
     /// Format a hunk header
     fn format_hunk_header(&self, hunk: &DiffHunk) -> String {
-        let start = hunk.start_line;
-        let count = hunk.lines.len();
-        let header = format!("@@ -{},{} +{},{} @@", start, count, start, count);
+        // An empty side is anchored on the line before it, as in `diff -u`
+        let range = |start: usize, count: usize| {
+            let start = if count == 0 { start - 1 } else { start };
+            format!("{},{}", start, count)
+        };
+        let header = format!(
+            "@@ -{} +{} @@",
+            range(hunk.old_start, hunk.old_count),
+            range(hunk.new_start, hunk.new_count)
+        );
 
         if self.use_colors {
             header.cyan().bold().to_string()
@@ -266,10 +485,83 @@ impl Default for DiffFormatter {
     }
 }
 
+/// Start and end byte offsets within a line
+type ByteRange = (usize, usize);
+
+/// Which column of a side-by-side diff a cell belongs to
+#[derive(Clone, Copy)]
+enum Side {
+    Old,
+    New,
+    Both,
+}
+
+/// Replace tabs, which would throw the columns out of line
+fn expand_tabs(text: &str) -> String {
+    text.replace('\t', "    ")
+}
+
+/// Byte ranges of the parts of two lines that differ, between their common
+/// prefix and suffix, or `None` for a side with nothing of its own
+fn changed_ranges(old: &str, new: &str) -> (Option<ByteRange>, Option<ByteRange>) {
+    let prefix = old
+        .char_indices()
+        .zip(new.chars())
+        .find(|((_, a), b)| a != b)
+        .map_or(old.len().min(new.len()), |((index, _), _)| index);
+    // A common prefix can't end inside a character shared by both lines
+    let prefix = (0..=prefix)
+        .rev()
+        .find(|&index| old.is_char_boundary(index) && new.is_char_boundary(index))
+        .unwrap_or(0);
+
+    let suffix = old[prefix..]
+        .chars()
+        .rev()
+        .zip(new[prefix..].chars().rev())
+        .take_while(|(a, b)| a == b)
+        .map(|(c, _)| c.len_utf8())
+        .sum::<usize>();
+
+    let range = |line: &str| {
+        let end = line.len() - suffix;
+        (prefix < end).then_some((prefix, end))
+    };
+    (range(old), range(new))
+}
+
+/// The width of the terminal on stdout, from `COLUMNS` or the terminal itself
+pub fn terminal_width() -> Option<usize> {
+    if let Some(columns) = std::env::var("COLUMNS")
+        .ok()
+        .and_then(|columns| columns.parse().ok())
+    {
+        return Some(columns);
+    }
+
+    #[cfg(unix)]
+    {
+        let mut size = std::mem::MaybeUninit::<libc::winsize>::zeroed();
+        // SAFETY: TIOCGWINSZ only writes a `winsize` into the pointer it is given
+        let result =
+            unsafe { libc::ioctl(libc::STDOUT_FILENO, libc::TIOCGWINSZ, size.as_mut_ptr()) };
+        // SAFETY: the struct was zero-initialized and ioctl filled it on success
+        let size = unsafe { size.assume_init() };
+        if result == 0 && size.ws_col > 0 {
+            return Some(size.ws_col as usize);
+        }
+    }
+
+    None
+}
+
 /// A group of related diff lines
 #[derive(Debug, Clone)]
 struct DiffHunk {
-    start_line: usize,
+    old_start: usize,
+    old_count: usize,
+    new_start: usize,
+    new_count: usize,
     lines: Vec<DiffLine>,
 }
 
@@ -428,6 +720,127 @@ mod tests {
         assert_eq!(summary.lines_removed, 1); // line2
     }
 
+    #[test]
+    fn test_context_lines_split_hunks() {
+        let original: Vec<String> = (1..=20).map(|i| format!("line{}", i)).collect();
+        let mut modified = original.clone();
+        modified[1] = "changed2".to_string();
+        modified[17] = "changed18".to_string();
+
+        let diff = FileDiff::new(
+            "test.txt".to_string(),
+            original.join("\n"),
+            modified.join("\n"),
+        );
+        let output = DiffFormatter::new()
+            .with_colors(false)
+            .with_context(2)
+            .format_diff(&diff);
+
+        assert!(output.contains("@@ -1,4 +1,4 @@"));
+        assert!(output.contains("@@ -16,5 +16,5 @@"));
+        assert!(output.contains(" line4\n@@"));
+        assert!(!output.contains("line10"));
+
+        // Wide enough context joins both changes into one hunk
+        let output = DiffFormatter::new()
+            .with_colors(false)
+            .with_context(8)
+            .format_diff(&diff);
+        assert_eq!(output.matches("@@ -").count(), 1);
+        assert!(output.contains("@@ -1,20 +1,20 @@"));
+    }
+
+    #[test]
+    fn test_zero_context() {
+        let original = "a\nb\nc";
+        let modified = "a\nB\nc";
+
+        let diff = FileDiff::new(
+            "test.txt".to_string(),
+            original.to_string(),
+            modified.to_string(),
+        );
+        let output = DiffFormatter::new()
+            .with_colors(false)
+            .with_context(0)
+            .format_diff(&diff);
+
+        assert_eq!(
+            output,
+            "--- test.txt\n+++ test.txt\n@@ -2,1 +2,1 @@\n-b\n+B"
+        );
+    }
+
+    #[test]
+    fn test_side_by_side() {
+        let original = "<div>\n  <a className=\"p-4 flex\" />\n</div>\nx";
+        let modified = "<div>\n  <a className=\"flex p-4\" />\n</div>\nx\ny";
+
+        let diff = FileDiff::new(
+            "test.jsx".to_string(),
+            original.to_string(),
+            modified.to_string(),
+        );
+        let output = DiffFormatter::new()
+            .with_colors(false)
+            .with_style(DiffStyle::SideBySide)
+            .with_width(80)
+            .format_diff(&diff);
+
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(
+            lines[0],
+            format!("--- test.jsx{}│ +++ test.jsx", " ".repeat(27))
+        );
+        assert_eq!(lines[1], "@@ -1,4 +1,5 @@");
+        assert_eq!(
+            lines[3],
+            format!(
+                "   2   <a className=\"p-4 flex\" />{}│    2   <a className=\"flex p-4\" />",
+                " ".repeat(6)
+            )
+        );
+        // An addition with no removal to pair with leaves the left column empty
+        assert_eq!(lines[6], format!("{}│    5 y", " ".repeat(39)));
+        assert!(lines.iter().all(|line| line.chars().count() <= 80));
+    }
+
+    #[test]
+    fn test_side_by_side_truncates_and_falls_back() {
+        let original = format!("<a className=\"p-4 flex\" title=\"{}\" />", "x".repeat(60));
+        let modified = original.replace("p-4 flex", "flex p-4");
+        let diff = FileDiff::new("test.jsx".to_string(), original, modified);
+
+        let output = DiffFormatter::new()
+            .with_colors(false)
+            .with_style(DiffStyle::SideBySide)
+            .with_width(80)
+            .format_diff(&diff);
+        assert!(output.contains("   1 <a className=\"p-4 flex\" title=\"x… │"));
+
+        // Too narrow for two columns
+        let output = DiffFormatter::new()
+            .with_colors(false)
+            .with_style(DiffStyle::SideBySide)
+            .with_width(60)
+            .format_diff(&diff);
+        assert!(output.contains("\n-<a className=\"p-4 flex\""));
+    }
+
+    #[test]
+    fn test_changed_ranges() {
+        let old = r#"<a className="p-4 flex" />"#;
+        let new = r#"<a className="flex p-4" />"#;
+        let (old_range, new_range) = changed_ranges(old, new);
+        assert_eq!(&old[old_range.unwrap().0..old_range.unwrap().1], "p-4 flex");
+        assert_eq!(&new[new_range.unwrap().0..new_range.unwrap().1], "flex p-4");
+
+        // Removed text leaves nothing to highlight on the other side
+        assert_eq!(changed_ranges("a b c", "a c"), (Some((2, 4)), None));
+        assert_eq!(changed_ranges("é a", "é b"), (Some((3, 4)), Some((3, 4))));
+    }
+
     #[test]
     fn test_format_summary() {
         let original = "old line";
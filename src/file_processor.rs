@@ -1,11 +1,22 @@
-use crate::config::Config;
+use crate::cache::ProcessingCache;
+use crate::config::{Config, ConfigOverrides};
 use crate::output::ProgressTracker;
-use crate::processor::FileProcessor as ContentProcessor;
+use crate::processor::{
+    ClassCounts, FileProcessor as ContentProcessor, ProcessOutcome, UnknownClass,
+};
+use crate::sorter::UnknownClassSeverity;
+use crate::trace::{TraceEvent, Tracer};
+use crate::utils::glob::{build_glob_set, compile_glob, expand_braces};
 use crate::{ProcessOptions, Result, WindWardenError};
-use globset::{Glob, GlobSet, GlobSetBuilder};
+use globset::{GlobMatcher, GlobSet};
 use rayon::prelude::*;
+use std::collections::{BTreeSet, HashSet};
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Condvar, Mutex, mpsc};
+use std::thread;
+use std::time::{Duration, Instant};
 use walkdir::WalkDir;
 
 /// Configuration for file discovery
@@ -13,12 +24,20 @@ use walkdir::WalkDir;
 pub struct FileDiscoveryConfig {
     /// File extensions to process (e.g., "tsx", "jsx", "ts", "js")
     pub extensions: Vec<String>,
-    /// Patterns to exclude (gitignore-style)
+    /// Patterns a discovered file must match at least one of (every file when empty)
+    pub include_patterns: Vec<String>,
+    /// Patterns to exclude (gitignore-style, `!pattern` re-includes; the last match wins)
     pub exclude_patterns: Vec<String>,
+    /// Directory names excluded wherever they appear in a path, regardless of patterns
+    pub exclude_dirs: Vec<String>,
     /// Maximum depth for directory traversal
     pub max_depth: Option<usize>,
     /// Follow symbolic links
     pub follow_links: bool,
+    /// Only keep discovered files whose canonical path is in this set, for `--since`
+    pub changed_files: Option<HashSet<PathBuf>>,
+    /// Only keep discovered files whose canonical path is in this set, for `--manifest`
+    pub selected_files: Option<HashSet<PathBuf>>,
 }
 
 impl Default for FileDiscoveryConfig {
@@ -29,7 +48,11 @@ impl Default for FileDiscoveryConfig {
                 "jsx".to_string(),
                 "ts".to_string(),
                 "js".to_string(),
+                "astro".to_string(),
+                "svelte".to_string(),
+                "mdx".to_string(),
             ],
+            include_patterns: Vec::new(),
             exclude_patterns: vec![
                 "node_modules/**".to_string(),
                 ".git/**".to_string(),
@@ -40,46 +63,110 @@ impl Default for FileDiscoveryConfig {
                 ".nuxt/**".to_string(),
                 "target/**".to_string(),
             ],
+            exclude_dirs: Vec::new(),
             max_depth: None,
             follow_links: false,
+            changed_files: None,
+            selected_files: None,
         }
     }
 }
 
+/// A single exclude pattern, evaluated in order
+struct ExcludeRule {
+    /// Matcher for the full path
+    matcher: GlobMatcher,
+    /// Directory name matched against any path component (for "dirname/**" and plain names)
+    dir_name: Option<String>,
+    /// Literal components of the pattern, used to decide whether to walk into excluded directories
+    components: Vec<String>,
+    /// Whether this is a `!pattern` that re-includes matching paths
+    negated: bool,
+}
+
+impl ExcludeRule {
+    fn is_match(&self, path: &Path) -> bool {
+        if let Some(dir_name) = &self.dir_name {
+            let in_dir = path.components().any(|component| {
+                matches!(component, std::path::Component::Normal(name) if name.to_string_lossy() == dir_name.as_str())
+            });
+            if in_dir {
+                return true;
+            }
+        }
+
+        self.matcher.is_match(path)
+            || self
+                .matcher
+                .is_match(path.strip_prefix("./").unwrap_or(path))
+    }
+}
+
+/// The canonical form of a path, or the path itself if it can't be resolved
+fn canonical_path(path: &Path) -> PathBuf {
+    fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf())
+}
+
+/// The canonical path of `file`, found under `root`, whose canonical path is
+/// `canonical_root`; this avoids resolving every file found by a walk
+fn rebase(file: &Path, root: &Path, canonical_root: &Path) -> PathBuf {
+    match file.strip_prefix(root) {
+        Ok(relative) => canonical_root.join(relative),
+        Err(_) => canonical_path(file),
+    }
+}
+
 /// File discovery engine for finding files to process
 pub struct FileDiscovery {
     config: FileDiscoveryConfig,
-    exclude_set: GlobSet,
+    include_set: Option<GlobSet>,
+    exclude_rules: Vec<ExcludeRule>,
+    tracer: Tracer,
+    warnings: Mutex<Vec<String>>,
 }
 
 impl FileDiscovery {
     pub fn new(config: FileDiscoveryConfig) -> Result<Self> {
-        let exclude_set = Self::build_exclude_set(&config.exclude_patterns)?;
+        let include_set = Self::build_include_set(&config.include_patterns)?;
+        let exclude_rules = Self::build_exclude_rules(&config.exclude_patterns)?;
+
+        if let Some(name) = config
+            .exclude_dirs
+            .iter()
+            .find(|name| name.is_empty() || name.contains(['/', '\\', '*', '?', '[']))
+        {
+            return Err(WindWardenError::config_error(format!(
+                "Invalid directory name '{}' for --exclude-dir. Use a plain name such as __mocks__, or --exclude for glob patterns",
+                name
+            )));
+        }
 
         Ok(Self {
             config,
-            exclude_set,
+            include_set,
+            exclude_rules,
+            tracer: Tracer::disabled(),
+            warnings: Mutex::new(Vec::new()),
         })
     }
 
-    /// Extract directory names from exclude patterns for direct directory name checking
-    fn get_excluded_directories(&self) -> Vec<String> {
-        let mut dirs = Vec::new();
-        for pattern in &self.config.exclude_patterns {
-            // Extract directory name from patterns like "dirname/**"
-            if let Some(dir_name) = pattern.strip_suffix("/**") {
-                dirs.push(dir_name.to_string());
-            } else if !pattern.contains('*') && !pattern.contains('?') && !pattern.contains('[') {
-                // Plain directory names without globs
-                dirs.push(pattern.clone());
-            }
-        }
-        dirs
+    /// Record discovery decisions to the given tracer
+    pub fn with_tracer(mut self, tracer: Tracer) -> Self {
+        self.tracer = tracer;
+        self
     }
 
     /// Find all files matching the criteria from the given paths
+    ///
+    /// Each file is discovered once however the arguments overlap: a
+    /// directory inside another directory argument isn't walked again, and
+    /// an explicit file or glob match already found is skipped. Files are
+    /// compared by their canonical path, so `src/App.tsx` and
+    /// `./src/App.tsx` are the same file.
     pub fn discover_files(&self, paths: &[String]) -> Result<Vec<PathBuf>> {
-        let mut files = Vec::new();
+        let mut dirs = Vec::new();
+        let mut globs = Vec::new();
+        let mut explicit_files = Vec::new();
 
         for path_str in paths {
             if path_str == "--stdin" || path_str == "-" {
@@ -88,8 +175,8 @@ impl FileDiscovery {
             }
 
             // Check if this is a glob pattern first
-            if path_str.contains('*') || path_str.contains('?') || path_str.contains('[') {
-                files.extend(self.discover_files_by_glob(path_str)?);
+            if path_str.contains(['*', '?', '[', '{']) {
+                globs.push(path_str.as_str());
                 continue;
             }
 
@@ -100,17 +187,82 @@ impl FileDiscovery {
             }
 
             if path.is_file() {
-                if self.should_process_file(path) {
-                    files.push(path.to_path_buf());
-                }
+                explicit_files.push(path);
             } else if path.is_dir() {
-                files.extend(self.discover_files_in_directory(path)?);
+                dirs.push((path, canonical_path(path)));
             }
         }
 
-        // Remove duplicates and sort for deterministic output
+        let mut files = Vec::new();
+        let mut seen = HashSet::new();
+        let mut add = |file: PathBuf, canonical: PathBuf| {
+            if seen.insert(canonical) {
+                files.push(file);
+            } else {
+                self.trace_skipped(&file, "already discovered");
+            }
+        };
+
+        // Walk outer directories first, so that nested ones can be skipped
+        dirs.sort_by_key(|(_, canonical)| canonical.components().count());
+        let mut walked: Vec<PathBuf> = Vec::new();
+        for (dir, canonical) in dirs {
+            // With a depth limit, an outer walk may stop above the nested directory
+            if let Some(outer) = walked.iter().find(|outer| {
+                **outer == canonical
+                    || (self.config.max_depth.is_none() && canonical.starts_with(outer))
+            }) {
+                self.trace_skipped(dir, &format!("covered by {}", outer.display()));
+                continue;
+            }
+
+            for file in self.discover_files_in_directory(dir)? {
+                let canonical_file = rebase(&file, dir, &canonical);
+                add(file, canonical_file);
+            }
+            walked.push(canonical);
+        }
+
+        for pattern in globs {
+            let base_dir = Self::glob_base_dir(pattern);
+            let canonical_base = canonical_path(&base_dir);
+            for file in self.discover_files_by_glob(pattern)? {
+                let canonical_file = rebase(&file, &base_dir, &canonical_base);
+                add(file, canonical_file);
+            }
+        }
+
+        // Explicit files last, so ones a walk already found are skipped
+        for path in explicit_files {
+            if self.should_process_file(path) {
+                add(path.to_path_buf(), canonical_path(path));
+            } else {
+                self.trace_skipped(path, "extension");
+            }
+        }
+
+        // Sort for deterministic output
         files.sort();
-        files.dedup();
+
+        for (kept, reason) in [
+            (&self.config.changed_files, "unchanged"),
+            (&self.config.selected_files, "not selected"),
+        ] {
+            let Some(kept) = kept else { continue };
+            files.retain(|file| {
+                let keep = fs::canonicalize(file).is_ok_and(|path| kept.contains(&path));
+                if !keep {
+                    self.trace_skipped(file, reason);
+                }
+                keep
+            });
+        }
+
+        for file in &files {
+            self.tracer.emit(|| TraceEvent::Discovered {
+                path: file.display().to_string(),
+            });
+        }
 
         Ok(files)
     }
@@ -119,46 +271,113 @@ impl FileDiscovery {
     fn discover_files_in_directory(&self, dir: &Path) -> Result<Vec<PathBuf>> {
         let mut files = Vec::new();
 
-        let walkdir = WalkDir::new(dir)
+        self.walk(dir, self.config.max_depth.unwrap_or(usize::MAX), |path| {
+            if !self.should_process_file(path) {
+                self.trace_skipped(path, "extension");
+            } else if !self.is_included(path, dir) {
+                self.trace_skipped(path, "not included");
+            } else {
+                files.push(path.to_path_buf());
+            }
+        })?;
+
+        Ok(files)
+    }
+
+    /// Walk a directory tree, calling `on_file` for every file that is not excluded
+    ///
+    /// When following links, directories that resolve to one already visited
+    /// (a symlink cycle or a second link to the same directory) are skipped
+    /// with a warning instead of being walked again.
+    fn walk(&self, root: &Path, max_depth: usize, mut on_file: impl FnMut(&Path)) -> Result<()> {
+        let mut visited_dirs = HashSet::new();
+
+        let walkdir = WalkDir::new(root)
             .follow_links(self.config.follow_links)
-            .max_depth(self.config.max_depth.unwrap_or(usize::MAX))
+            .max_depth(max_depth)
             .into_iter()
-            .filter_entry(|e| !self.is_excluded(e.path()));
+            .filter_entry(|e| {
+                let is_dir = e.file_type().is_dir();
+                if self.is_excluded_traced(e.path(), is_dir) {
+                    return false;
+                }
+
+                if is_dir
+                    && self.config.follow_links
+                    && let Ok(canonical) = fs::canonicalize(e.path())
+                    && !visited_dirs.insert(canonical)
+                {
+                    self.warn_symlink_cycle(e.path());
+                    return false;
+                }
+
+                true
+            });
 
         for entry in walkdir {
-            let entry = entry.map_err(|e| WindWardenError::Io(std::io::Error::other(e)))?;
+            let entry = match entry {
+                Ok(entry) => entry,
+                Err(e) if e.loop_ancestor().is_some() => {
+                    if let Some(path) = e.path() {
+                        self.warn_symlink_cycle(path);
+                    }
+                    continue;
+                }
+                Err(e) => return Err(WindWardenError::Io(std::io::Error::other(e))),
+            };
 
-            if entry.file_type().is_file() && self.should_process_file(entry.path()) {
-                files.push(entry.path().to_path_buf());
+            if entry.file_type().is_file() {
+                on_file(entry.path());
             }
         }
 
-        Ok(files)
+        Ok(())
+    }
+
+    /// Record a recoverable warning for a directory skipped as a symlink cycle
+    fn warn_symlink_cycle(&self, path: &Path) {
+        self.trace_skipped(path, "symlink cycle");
+        if let Ok(mut warnings) = self.warnings.lock() {
+            warnings.push(format!(
+                "Skipping {}: symbolic link points to a directory that was already visited",
+                path.display()
+            ));
+        }
+    }
+
+    /// Take the warnings recorded during discovery
+    pub fn take_warnings(&self) -> Vec<String> {
+        self.warnings
+            .lock()
+            .map(|mut warnings| std::mem::take(&mut *warnings))
+            .unwrap_or_default()
     }
 
     /// Discover files using glob patterns
     fn discover_files_by_glob(&self, pattern: &str) -> Result<Vec<PathBuf>> {
-        let glob = Glob::new(pattern)
-            .map_err(|e| WindWardenError::glob_pattern_error(pattern, e.to_string()))?;
-
-        let matcher = glob.compile_matcher();
+        let matcher = build_glob_set([pattern])?;
         let mut files = Vec::new();
 
-        // Find all files that match the glob pattern
-        // For now, we'll walk the current directory and match
-        // In a more sophisticated implementation, we could optimize this
-        let walkdir = WalkDir::new(".")
-            .follow_links(self.config.follow_links)
-            .max_depth(self.config.max_depth.unwrap_or(usize::MAX))
-            .into_iter()
-            .filter_entry(|e| !self.is_excluded(e.path()));
-
-        for entry in walkdir {
-            let entry = entry.map_err(|e| WindWardenError::Io(std::io::Error::other(e)))?;
+        // Only walk the literal directory prefix of the pattern (e.g. "packages/ui/src"
+        // for "packages/ui/src/**/*.tsx") instead of the whole current directory
+        let base_dir = Self::glob_base_dir(pattern);
+        if !base_dir.is_dir() {
+            return Ok(files);
+        }
 
-            let path = entry.path();
+        // Keep max_depth relative to the current directory, as if we had walked from "."
+        let base_depth = base_dir
+            .components()
+            .filter(|c| matches!(c, std::path::Component::Normal(_)))
+            .count();
+        let max_depth = match self.config.max_depth {
+            Some(depth) if !base_dir.is_absolute() => depth.saturating_sub(base_depth),
+            Some(depth) => depth,
+            None => usize::MAX,
+        };
 
-            if entry.file_type().is_file() && self.should_process_file(path) {
+        self.walk(&base_dir, max_depth, |path| {
+            if self.should_process_file(path) && self.is_included(path, &base_dir) {
                 // Try matching both the full path and just the relative path without "./"
                 let relative_path = path.strip_prefix("./").unwrap_or(path);
 
@@ -166,11 +385,38 @@ impl FileDiscovery {
                     files.push(path.to_path_buf());
                 }
             }
-        }
+        })?;
 
         Ok(files)
     }
 
+    /// Get the directory to start walking from for a glob pattern
+    ///
+    /// This is the longest leading run of path components without glob
+    /// metacharacters, or "." if the pattern starts with a glob.
+    fn glob_base_dir(pattern: &str) -> PathBuf {
+        let is_glob = |part: &str| part.contains(['*', '?', '[', '{']);
+
+        let parts: Vec<&str> = pattern.split('/').collect();
+        // The final component names files, so it never belongs to the base directory
+        let dir_parts = &parts[..parts.len().saturating_sub(1)];
+        let literal_len = dir_parts
+            .iter()
+            .position(|part| is_glob(part))
+            .unwrap_or(dir_parts.len());
+
+        let base = dir_parts[..literal_len].join("/");
+        if base.is_empty() {
+            if pattern.starts_with('/') {
+                PathBuf::from("/")
+            } else {
+                PathBuf::from(".")
+            }
+        } else {
+            PathBuf::from(base)
+        }
+    }
+
     /// Check if a file should be processed based on extension
     fn should_process_file(&self, path: &Path) -> bool {
         if let Some(extension) = path.extension().and_then(|ext| ext.to_str()) {
@@ -180,41 +426,195 @@ impl FileDiscovery {
         }
     }
 
+    /// Check if a discovered file matches an include pattern, when there are any
+    ///
+    /// Patterns match the path relative to `root`, the directory being
+    /// walked, so `check src/` and `check /abs/project` see the same paths
+    /// as `check .` from inside them. The path as walked is also tried, so
+    /// patterns relative to the current directory keep working.
+    fn is_included(&self, path: &Path, root: &Path) -> bool {
+        self.include_set.as_ref().is_none_or(|include_set| {
+            path.strip_prefix(root)
+                .is_ok_and(|relative| include_set.is_match(relative))
+                || include_set.is_match(path)
+                || include_set.is_match(path.strip_prefix("./").unwrap_or(path))
+        })
+    }
+
+    /// Check if a path is excluded, recording the decision when tracing
+    fn is_excluded_traced(&self, path: &Path, is_dir: bool) -> bool {
+        let excluded = self.is_excluded(path, is_dir);
+        if excluded {
+            self.trace_skipped(path, "excluded");
+        }
+        excluded
+    }
+
+    /// Record that a path was skipped during discovery
+    fn trace_skipped(&self, path: &Path, reason: &str) {
+        self.tracer.emit(|| TraceEvent::Skipped {
+            path: path.display().to_string(),
+            reason: reason.to_string(),
+        });
+    }
+
     /// Check if a path is excluded by patterns
-    fn is_excluded(&self, path: &Path) -> bool {
-        let excluded_dirs = self.get_excluded_directories();
-
-        // Check if any component of the path matches an exclude pattern
-        for component in path.components() {
-            if let std::path::Component::Normal(name) = component {
-                let name_str = name.to_string_lossy();
-                // Check if this directory name is in our exclude list
-                if excluded_dirs.iter().any(|dir| dir == &*name_str) {
-                    return true;
-                }
+    ///
+    /// The last matching pattern wins, so a later `!pattern` re-includes
+    /// paths excluded by earlier ones. Excluded directories are still walked
+    /// when a later negation names them, so files beneath can be re-included.
+    fn is_excluded(&self, path: &Path, is_dir: bool) -> bool {
+        if self.in_excluded_dir(path, is_dir) {
+            return true;
+        }
+
+        match self.last_matching_rule(path) {
+            Some(index) if !self.exclude_rules[index].negated => {
+                !(is_dir && self.may_reinclude_below(path, index))
             }
+            _ => false,
+        }
+    }
+
+    /// Check whether a directory in the path has an excluded name
+    ///
+    /// For files only the parent directories count, not the file name itself.
+    fn in_excluded_dir(&self, path: &Path, is_dir: bool) -> bool {
+        if self.config.exclude_dirs.is_empty() {
+            return false;
         }
 
-        // Also check the full path against patterns
-        self.exclude_set.is_match(path)
+        let dirs = if is_dir { Some(path) } else { path.parent() };
+        dirs.is_some_and(|dirs| {
+            dirs.components().any(|component| {
+                matches!(component, std::path::Component::Normal(name)
+                    if self.config.exclude_dirs.iter().any(|dir| name.to_string_lossy() == dir.as_str()))
+            })
+        })
+    }
+
+    /// Find the index of the last exclude rule matching a path
+    fn last_matching_rule(&self, path: &Path) -> Option<usize> {
+        self.exclude_rules
+            .iter()
+            .rposition(|rule| rule.is_match(path))
+    }
+
+    /// Check whether a negation after the given rule names this directory
+    fn may_reinclude_below(&self, dir: &Path, rule_index: usize) -> bool {
+        let Some(dir_name) = dir.file_name().map(|name| name.to_string_lossy()) else {
+            return false;
+        };
+
+        self.exclude_rules[rule_index + 1..]
+            .iter()
+            .any(|rule| rule.negated && rule.components.iter().any(|c| *c == dir_name))
+    }
+
+    /// Build the include matcher from patterns, or `None` to include every file
+    fn build_include_set(patterns: &[String]) -> Result<Option<GlobSet>> {
+        if patterns.is_empty() {
+            return Ok(None);
+        }
+
+        build_glob_set(patterns.iter().map(String::as_str)).map(Some)
     }
 
-    /// Build the exclude glob set from patterns
-    fn build_exclude_set(patterns: &[String]) -> Result<GlobSet> {
-        let mut builder = GlobSetBuilder::new();
+    /// Build the ordered exclude rules from patterns
+    fn build_exclude_rules(patterns: &[String]) -> Result<Vec<ExcludeRule>> {
+        let mut rules = Vec::with_capacity(patterns.len());
 
         for pattern in patterns {
-            let glob = Glob::new(pattern)
-                .map_err(|e| WindWardenError::glob_pattern_error(pattern, e.to_string()))?;
-            builder.add(glob);
+            let (negated, original) = match pattern.strip_prefix('!') {
+                Some(rest) => (true, rest),
+                None => (false, pattern.as_str()),
+            };
+
+            // Braces expand into consecutive rules, which keeps "last match wins"
+            for pattern in expand_braces(original)? {
+                let matcher = compile_glob(&pattern, original)?.compile_matcher();
+
+                // Extract directory name from patterns like "dirname/**", or plain directory names
+                let dir_name = if let Some(dir_name) = pattern.strip_suffix("/**") {
+                    Some(dir_name.to_string())
+                } else if !pattern.contains(['*', '?', '[']) {
+                    Some(pattern.clone())
+                } else {
+                    None
+                };
+
+                let components = pattern
+                    .split('/')
+                    .filter(|part| !part.is_empty() && !part.contains(['*', '?', '[', '{']))
+                    .map(str::to_string)
+                    .collect();
+
+                rules.push(ExcludeRule {
+                    matcher,
+                    dir_name,
+                    components,
+                    negated,
+                });
+            }
         }
 
-        builder.build().map_err(|e| {
-            WindWardenError::glob_pattern_error(
-                "exclude pattern set",
-                format!("Failed to build exclude pattern set: {}", e),
-            )
-        })
+        Ok(rules)
+    }
+}
+
+/// Why a file failed to process
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum FailureKind {
+    /// The file's syntax couldn't be parsed
+    Parse,
+    /// The file couldn't be read or written
+    Io,
+    /// The file's extension isn't supported
+    UnsupportedType,
+    /// The file is larger than `maxFileSize`
+    TooLarge,
+    /// Processing the file took longer than `perFileTimeoutMs`
+    TimedOut,
+    /// Any other failure
+    Other,
+}
+
+impl FailureKind {
+    /// Every kind, in the order they are reported
+    pub const ALL: [FailureKind; 6] = [
+        FailureKind::Parse,
+        FailureKind::Io,
+        FailureKind::UnsupportedType,
+        FailureKind::TooLarge,
+        FailureKind::TimedOut,
+        FailureKind::Other,
+    ];
+
+    /// Classify the error a file failed with
+    pub fn of(error: &WindWardenError) -> Self {
+        match error {
+            WindWardenError::ParseError { .. } => FailureKind::Parse,
+            WindWardenError::UnsupportedFileType { .. } => FailureKind::UnsupportedType,
+            WindWardenError::Io(_)
+            | WindWardenError::FileNotFound { .. }
+            | WindWardenError::PermissionDenied { .. }
+            | WindWardenError::FileNotReadable { .. }
+            | WindWardenError::FileNotWritable { .. }
+            | WindWardenError::InvalidUtf8 { .. } => FailureKind::Io,
+            _ => FailureKind::Other,
+        }
+    }
+
+    /// A short label for statistics
+    pub fn label(self) -> &'static str {
+        match self {
+            FailureKind::Parse => "Parse errors",
+            FailureKind::Io => "IO errors",
+            FailureKind::UnsupportedType => "Unsupported types",
+            FailureKind::TooLarge => "Too large",
+            FailureKind::TimedOut => "Timed out",
+            FailureKind::Other => "Other errors",
+        }
     }
 }
 
@@ -227,6 +627,24 @@ pub struct FileProcessingResult {
     pub original_content: Option<String>,
     pub processed_content: Option<String>,
     pub error: Option<String>,
+    /// Whether the file was skipped because the cache shows it is unchanged
+    pub cached: bool,
+    /// Class strings found in the file (zero for cached and failed files)
+    pub classes_found: usize,
+    /// Class strings whose order changed
+    pub classes_reordered: usize,
+    /// Supported functions called in the file (empty for cached and failed files)
+    pub called_functions: HashSet<String>,
+    /// Whether `called_functions` may be missing calls made in the file
+    pub calls_incomplete: bool,
+    /// Why the file failed, for failed files
+    pub failure_kind: Option<FailureKind>,
+    /// Whether the file was skipped because it isn't valid UTF-8
+    pub skipped_encoding: bool,
+    /// 1-based line and column of the error, for parse errors
+    pub error_location: Option<(usize, usize)>,
+    /// Classes that match no utility, unless `unknownClasses` ignores them
+    pub unknown_classes: Vec<UnknownClass>,
 }
 
 impl FileProcessingResult {
@@ -243,6 +661,44 @@ impl FileProcessingResult {
             original_content: Some(original_content),
             processed_content: Some(processed_content),
             error: None,
+            cached: false,
+            classes_found: 0,
+            classes_reordered: 0,
+            called_functions: HashSet::new(),
+            calls_incomplete: false,
+            failure_kind: None,
+            skipped_encoding: false,
+            error_location: None,
+            unknown_classes: Vec::new(),
+        }
+    }
+
+    /// Record how many class strings were found and reordered
+    pub fn with_class_counts(mut self, counts: ClassCounts) -> Self {
+        self.classes_found = counts.found;
+        self.classes_reordered = counts.reordered;
+        self
+    }
+
+    /// Record which supported functions the file calls, and whether some
+    /// calls may be missing
+    pub fn with_called_functions(mut self, functions: HashSet<String>, incomplete: bool) -> Self {
+        self.called_functions = functions;
+        self.calls_incomplete = incomplete;
+        self
+    }
+
+    /// Record the classes in the file that match no utility
+    pub fn with_unknown_classes(mut self, unknown_classes: Vec<UnknownClass>) -> Self {
+        self.unknown_classes = unknown_classes;
+        self
+    }
+
+    /// Result for a file skipped because it is unchanged and already formatted
+    pub fn cached(file_path: PathBuf, content: String) -> Self {
+        Self {
+            cached: true,
+            ..Self::success(file_path, false, content.clone(), content)
         }
     }
 
@@ -254,8 +710,43 @@ impl FileProcessingResult {
             original_content: None,
             processed_content: None,
             error: Some(error),
+            cached: false,
+            classes_found: 0,
+            classes_reordered: 0,
+            called_functions: HashSet::new(),
+            calls_incomplete: true,
+            failure_kind: Some(FailureKind::Other),
+            skipped_encoding: false,
+            error_location: None,
+            unknown_classes: Vec::new(),
+        }
+    }
+
+    /// Result for a file skipped because it isn't valid UTF-8
+    pub fn skipped_encoding(file_path: PathBuf, error: String) -> Self {
+        Self {
+            failure_kind: None,
+            skipped_encoding: true,
+            ..Self::error(file_path, error)
         }
     }
+
+    /// Whether the file failed, as opposed to being processed or skipped
+    pub fn failed(&self) -> bool {
+        !self.success && !self.skipped_encoding
+    }
+
+    /// Record why the file failed
+    pub fn with_failure_kind(mut self, kind: FailureKind) -> Self {
+        self.failure_kind = Some(kind);
+        self
+    }
+
+    /// Record where in the file the error is
+    pub fn with_error_location(mut self, line: usize, column: usize) -> Self {
+        self.error_location = Some((line, column));
+        self
+    }
 }
 
 /// Batch file processing results
@@ -265,7 +756,23 @@ pub struct BatchProcessingResults {
     pub processed_files: usize,
     pub files_with_changes: usize,
     pub failed_files: usize,
+    /// Files skipped because they aren't valid UTF-8
+    pub skipped_encoding: usize,
+    pub cache_hits: usize,
+    /// Class strings found across all files
+    pub classes_found: usize,
+    /// Class strings reordered across all files
+    pub classes_reordered: usize,
+    /// Supported functions called in any processed file
+    pub called_functions: BTreeSet<String>,
+    /// Files whose calls weren't all collected, such as failed files and
+    /// files scanned line by line
+    pub files_with_incomplete_calls: usize,
     pub results: Vec<FileProcessingResult>,
+    /// Recoverable problems encountered along the way, such as symlink cycles
+    pub warnings: Vec<String>,
+    /// File that stopped a fail-fast run because it needs formatting
+    pub fail_fast_file: Option<PathBuf>,
 }
 
 impl Default for BatchProcessingResults {
@@ -281,18 +788,43 @@ impl BatchProcessingResults {
             processed_files: 0,
             files_with_changes: 0,
             failed_files: 0,
+            skipped_encoding: 0,
+            cache_hits: 0,
+            classes_found: 0,
+            classes_reordered: 0,
+            called_functions: BTreeSet::new(),
+            files_with_incomplete_calls: 0,
             results: Vec::new(),
+            warnings: Vec::new(),
+            fail_fast_file: None,
         }
     }
 
     pub fn add_result(&mut self, result: FileProcessingResult) {
         self.total_files += 1;
 
+        if result.cached {
+            self.cache_hits += 1;
+        }
+        if result.calls_incomplete {
+            self.files_with_incomplete_calls += 1;
+        }
+
         if result.success {
             self.processed_files += 1;
+            self.classes_found += result.classes_found;
+            self.classes_reordered += result.classes_reordered;
+            self.called_functions
+                .extend(result.called_functions.iter().cloned());
             if result.changes_made {
                 self.files_with_changes += 1;
             }
+        } else if result.skipped_encoding {
+            self.skipped_encoding += 1;
+            self.warnings.push(format!(
+                "Skipped {}: not valid UTF-8 (use --strict-encoding to count it as an error)",
+                result.file_path.display()
+            ));
         } else {
             self.failed_files += 1;
         }
@@ -300,11 +832,67 @@ impl BatchProcessingResults {
         self.results.push(result);
     }
 
+    /// Order results by file path, so reports list files the same way
+    /// whatever order they were processed in
+    ///
+    /// The per-file warnings are sorted too. Each reads "Skipped <path>: …",
+    /// so sorting them as text orders them by path.
+    pub fn sort_by_path(&mut self) {
+        self.results.sort_by(|a, b| a.file_path.cmp(&b.file_path));
+        self.warnings.sort();
+    }
+
+    /// Add parallel results, each flagged with whether it stopped a fail-fast run
+    fn add_fail_fast_results(&mut self, file_results: Vec<(FileProcessingResult, bool)>) {
+        for (result, triggered) in file_results {
+            if triggered {
+                self.fail_fast_file = Some(result.file_path.clone());
+            }
+            self.add_result(result);
+        }
+    }
+
+    /// How many files failed for the given reason
+    pub fn failure_count(&self, kind: FailureKind) -> usize {
+        self.results
+            .iter()
+            .filter(|result| result.failure_kind == Some(kind))
+            .count()
+    }
+
+    /// Whether any file has unknown classes reported as errors
+    pub fn has_unknown_class_errors(&self) -> bool {
+        self.results
+            .iter()
+            .flat_map(|result| &result.unknown_classes)
+            .any(|unknown| unknown.severity == UnknownClassSeverity::Error)
+    }
+
+    /// Files processed successfully whose content changed, in processing order
+    pub fn changed_files(&self) -> impl Iterator<Item = &Path> {
+        self.results
+            .iter()
+            .filter(|result| result.success && result.changes_made)
+            .map(|result| result.file_path.as_path())
+    }
+
+    /// Share of files processed successfully, leaving out skipped files
     pub fn success_rate(&self) -> f64 {
-        if self.total_files == 0 {
+        let attempted = self.total_files - self.skipped_encoding;
+        if attempted == 0 {
             1.0
         } else {
-            self.processed_files as f64 / self.total_files as f64
+            self.processed_files as f64 / attempted as f64
+        }
+    }
+
+    /// Files handled per second over the given wall-clock duration
+    pub fn files_per_second(&self, duration: std::time::Duration) -> f64 {
+        let seconds = duration.as_secs_f64();
+        if seconds > 0.0 {
+            self.total_files as f64 / seconds
+        } else {
+            0.0
         }
     }
 }
@@ -327,6 +915,84 @@ pub struct FileProcessingPipeline {
     content_processor: ContentProcessor,
     processing_mode: ProcessingMode,
     windwarden_config: Option<Config>,
+    /// Per-file overrides of `windwarden_config`, if it has any
+    overrides: Option<ConfigOverrides>,
+    tracer: Tracer,
+    cache: Option<Mutex<ProcessingCache>>,
+    max_in_memory: Option<usize>,
+    fail_fast: bool,
+    thread_scaling: Option<f64>,
+    read_limiter: Option<ReadLimiter>,
+    per_file_timeout: Option<Duration>,
+    strict_encoding: bool,
+    max_files: Option<usize>,
+}
+
+/// A counting semaphore bounding how many files are read at once
+///
+/// Parallel workers take a permit around each read, so IO concurrency can be
+/// kept below the number of compute threads on slow or network filesystems.
+pub struct ReadLimiter {
+    available: Mutex<usize>,
+    released: Condvar,
+}
+
+/// A permit from a [`ReadLimiter`], returned when dropped
+pub struct ReadPermit<'a> {
+    limiter: &'a ReadLimiter,
+}
+
+impl ReadLimiter {
+    /// Allow up to `max_concurrent` reads at once (at least one)
+    pub fn new(max_concurrent: usize) -> Self {
+        Self {
+            available: Mutex::new(max_concurrent.max(1)),
+            released: Condvar::new(),
+        }
+    }
+
+    /// Wait until a read may start
+    pub fn acquire(&self) -> ReadPermit<'_> {
+        let mut available = self.available.lock().unwrap_or_else(|e| e.into_inner());
+        while *available == 0 {
+            available = self
+                .released
+                .wait(available)
+                .unwrap_or_else(|e| e.into_inner());
+        }
+        *available -= 1;
+        ReadPermit { limiter: self }
+    }
+}
+
+impl Drop for ReadPermit<'_> {
+    fn drop(&mut self) {
+        let mut available = self
+            .limiter
+            .available
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        *available += 1;
+        self.limiter.released.notify_one();
+    }
+}
+
+/// Number of threads to process `file_count` files with
+///
+/// An explicit thread count wins over `scaling`, a fraction of `cores`.
+/// Either way there are never more threads than files, and at least one.
+pub fn resolve_thread_count(
+    configured: Option<usize>,
+    scaling: Option<f64>,
+    cores: usize,
+    file_count: usize,
+) -> usize {
+    let threads = match (configured, scaling) {
+        (Some(threads), _) => threads,
+        (None, Some(fraction)) => (cores as f64 * fraction).ceil() as usize,
+        (None, None) => cores,
+    };
+    threads.min(file_count).max(1)
 }
 
 impl FileProcessingPipeline {
@@ -343,6 +1009,16 @@ impl FileProcessingPipeline {
             content_processor: ContentProcessor::new(),
             processing_mode,
             windwarden_config: None,
+            overrides: None,
+            tracer: Tracer::disabled(),
+            cache: None,
+            max_in_memory: None,
+            fail_fast: false,
+            thread_scaling: None,
+            read_limiter: None,
+            per_file_timeout: None,
+            strict_encoding: false,
+            max_files: None,
         })
     }
 
@@ -351,14 +1027,106 @@ impl FileProcessingPipeline {
         windwarden_config: &Config,
         processing_mode: ProcessingMode,
     ) -> Result<Self> {
+        let overrides = ConfigOverrides::new(windwarden_config)?;
+
         Ok(Self {
             discovery: FileDiscovery::new(file_config)?,
             content_processor: ContentProcessor::new_with_config(windwarden_config),
             processing_mode,
             windwarden_config: Some(windwarden_config.clone()),
+            overrides: (!overrides.is_empty()).then_some(overrides),
+            tracer: Tracer::disabled(),
+            cache: None,
+            max_in_memory: None,
+            fail_fast: false,
+            thread_scaling: None,
+            read_limiter: None,
+            per_file_timeout: None,
+            strict_encoding: false,
+            max_files: None,
         })
     }
 
+    /// Record discovery, parse, match, and write decisions to the given tracer
+    /// Match override globs relative to `dir`, the configuration file's
+    /// directory, instead of the working directory
+    pub fn with_config_dir(mut self, dir: Option<&Path>) -> Self {
+        self.overrides = self.overrides.map(|overrides| overrides.with_root(dir));
+        self
+    }
+
+    pub fn with_tracer(mut self, tracer: Tracer) -> Self {
+        self.discovery = self.discovery.with_tracer(tracer.clone());
+        self.content_processor =
+            std::mem::take(&mut self.content_processor).with_tracer(tracer.clone());
+        self.tracer = tracer;
+        self
+    }
+
+    /// Skip files that the cache shows are unchanged and already formatted
+    pub fn with_cache(mut self, cache: ProcessingCache) -> Self {
+        self.cache = Some(Mutex::new(cache));
+        self
+    }
+
+    /// Scan files larger than `bytes` line by line instead of parsing them
+    pub fn with_max_in_memory(mut self, bytes: Option<usize>) -> Self {
+        self.content_processor =
+            std::mem::take(&mut self.content_processor).with_max_in_memory(bytes);
+        self.max_in_memory = bytes;
+        self
+    }
+
+    /// Stop processing once the first file that needs changes is found
+    ///
+    /// Files not yet processed when that happens are left out of the results.
+    pub fn with_fail_fast(mut self, fail_fast: bool) -> Self {
+        self.fail_fast = fail_fast;
+        self
+    }
+
+    /// Count files that aren't valid UTF-8 as failures instead of skipping them
+    pub fn with_strict_encoding(mut self, strict: bool) -> Self {
+        self.strict_encoding = strict;
+        self
+    }
+
+    /// Use this fraction of the available cores in parallel mode
+    pub fn with_thread_scaling(mut self, fraction: Option<f64>) -> Self {
+        self.thread_scaling = fraction;
+        self
+    }
+
+    /// Read at most this many files at once, however many threads process them
+    pub fn with_max_concurrent_reads(mut self, max_concurrent: Option<usize>) -> Self {
+        self.read_limiter = max_concurrent.map(ReadLimiter::new);
+        self
+    }
+
+    /// Fail before processing anything when more than `max` files are found
+    pub fn with_max_files(mut self, max: Option<usize>) -> Self {
+        self.max_files = max;
+        self
+    }
+
+    /// Give up on files that take longer than `timeout` to process, recording
+    /// them as failures instead of waiting for them
+    pub fn with_per_file_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.per_file_timeout = timeout;
+        self
+    }
+
+    /// Write the cache back to disk, if caching is enabled
+    pub fn save_cache(&self) -> Result<()> {
+        match &self.cache {
+            Some(cache) => cache
+                .lock()
+                .map_err(|_| WindWardenError::internal_error("Cache lock poisoned"))?
+                .save(),
+            None => Ok(()),
+        }
+    }
+
     /// Process multiple files or paths using the configured processing mode
     pub fn process_files(
         &self,
@@ -378,16 +1146,46 @@ impl FileProcessingPipeline {
         // Discover all files to process
         let files = self.discovery.discover_files(paths)?;
 
-        match self.processing_mode {
+        if let Some(max) = self.max_files
+            && files.len() > max
+        {
+            return Err(WindWardenError::config_error(format!(
+                "Found {} files to process, more than the limit of {} (--max-files or maxFiles). Narrow the paths, add --exclude patterns, or pass --force to process them all",
+                files.len(),
+                max
+            )));
+        }
+
+        // Small batches don't need a thread per core
+        let cores = rayon::current_num_threads();
+        let threads = match self.processing_mode {
+            ProcessingMode::Sequential => 1,
+            ProcessingMode::Parallel => {
+                resolve_thread_count(None, self.thread_scaling, cores, files.len())
+            }
+            ProcessingMode::ParallelWithThreads(num_threads) => {
+                resolve_thread_count(Some(num_threads), None, cores, files.len())
+            }
+        };
+
+        let mut results = match self.processing_mode {
             ProcessingMode::Sequential => {
                 self.process_files_sequential(files, options, progress_tracker)
             }
-            ProcessingMode::Parallel => {
+            ProcessingMode::Parallel if threads >= cores => {
                 self.process_files_parallel(files, options, progress_tracker)
             }
-            ProcessingMode::ParallelWithThreads(num_threads) => self
-                .process_files_parallel_with_threads(files, options, num_threads, progress_tracker),
-        }
+            ProcessingMode::Parallel | ProcessingMode::ParallelWithThreads(_) => {
+                self.process_files_parallel_with_threads(files, options, threads, progress_tracker)
+            }
+        }?;
+        results.sort_by_path();
+
+        // Discovery warnings come before those about individual files
+        let mut warnings = self.discovery.take_warnings();
+        warnings.append(&mut results.warnings);
+        results.warnings = warnings;
+        Ok(results)
     }
 
     /// Process files sequentially (single-threaded)
@@ -401,13 +1199,21 @@ impl FileProcessingPipeline {
 
         // Process each file sequentially
         for file_path in files {
+            let started = Instant::now();
             let result = self.process_single_file(&file_path, &options);
+            Self::trace_result(&self.tracer, &result, started);
+            let stop = self.fail_fast && result.changes_made;
             results.add_result(result);
 
             // Update progress if tracker is provided
             if let Some(ref tracker) = progress_tracker {
                 tracker.increment();
             }
+
+            if stop {
+                results.fail_fast_file = Some(file_path);
+                break;
+            }
         }
 
         Ok(results)
@@ -424,36 +1230,53 @@ impl FileProcessingPipeline {
 
         // Clone the config outside the parallel block to avoid Sync issues
         let config_clone = self.windwarden_config.clone();
+        let overrides = self.overrides.as_ref();
+        let tracer = self.tracer.clone();
+        let cache = self.cache.as_ref();
+        let max_in_memory = self.max_in_memory;
+        let read_limiter = self.read_limiter.as_ref();
+        let per_file_timeout = self.per_file_timeout;
+        let strict_encoding = self.strict_encoding;
+        let fail_fast = self.fail_fast;
+        let stopped = AtomicBool::new(false);
 
         // Process files in parallel and collect results
-        // Each thread gets its own ContentProcessor to avoid Sync issues with Oxc allocator
-        let file_results: Vec<FileProcessingResult> = files
+        // Each worker reuses its own ContentProcessor (and Oxc allocator) across files
+        let file_results: Vec<(FileProcessingResult, bool)> = files
             .par_iter()
-            .map(|file_path| {
-                let thread_processor = if let Some(ref config) = config_clone {
-                    ContentProcessor::new_with_config(config)
-                } else {
-                    ContentProcessor::new()
-                };
-                let result = Self::process_single_file_with_processor(
-                    &thread_processor,
-                    file_path,
-                    &options,
-                );
-
-                // Update progress if tracker is provided
-                if let Some(ref tracker) = progress_tracker {
-                    tracker.increment();
-                }
+            .filter(|_| !stopped.load(Ordering::Relaxed))
+            .map_init(
+                || Self::build_processor(config_clone.as_ref(), &tracer, max_in_memory),
+                |worker_processor, file_path| {
+                    // Files matching an override get a processor built from their own configuration
+                    let override_processor = overrides
+                        .and_then(|o| o.config_for(file_path))
+                        .map(|config| Self::build_processor(Some(&config), &tracer, max_in_memory));
+                    let started = Instant::now();
+                    let result = Self::process_single_file_with_processor(
+                        override_processor.as_ref().unwrap_or(worker_processor),
+                        file_path,
+                        &options,
+                        cache,
+                        read_limiter,
+                        per_file_timeout,
+                        strict_encoding,
+                    );
+                    Self::trace_result(&tracer, &result, started);
 
-                result
-            })
+                    // Update progress if tracker is provided
+                    if let Some(ref tracker) = progress_tracker {
+                        tracker.increment();
+                    }
+
+                    let triggered =
+                        fail_fast && result.changes_made && !stopped.swap(true, Ordering::Relaxed);
+                    (result, triggered)
+                },
+            )
             .collect();
 
-        // Add all results to the batch
-        for result in file_results {
-            results.add_result(result);
-        }
+        results.add_fail_fast_results(file_results);
 
         Ok(results)
     }
@@ -481,64 +1304,196 @@ impl FileProcessingPipeline {
 
         // Clone the config outside the parallel block to avoid Sync issues
         let config_clone = self.windwarden_config.clone();
+        let overrides = self.overrides.as_ref();
+        let tracer = self.tracer.clone();
+        let cache = self.cache.as_ref();
+        let max_in_memory = self.max_in_memory;
+        let read_limiter = self.read_limiter.as_ref();
+        let per_file_timeout = self.per_file_timeout;
+        let strict_encoding = self.strict_encoding;
+        let fail_fast = self.fail_fast;
+        let stopped = AtomicBool::new(false);
 
         // Process files in parallel with the configured thread pool
-        // Each thread gets its own ContentProcessor to avoid Sync issues with Oxc allocator
-        let file_results: Vec<FileProcessingResult> = pool.install(|| {
+        // Each worker reuses its own ContentProcessor (and Oxc allocator) across files
+        let file_results: Vec<(FileProcessingResult, bool)> = pool.install(|| {
             files
                 .par_iter()
-                .map(|file_path| {
-                    // Create a new ContentProcessor for this thread
-                    let thread_processor = if let Some(ref config) = config_clone {
-                        ContentProcessor::new_with_config(config)
-                    } else {
-                        ContentProcessor::new()
-                    };
-                    let result = Self::process_single_file_with_processor(
-                        &thread_processor,
-                        file_path,
-                        &options,
-                    );
-
-                    // Update progress if tracker is provided
-                    if let Some(ref tracker) = progress_tracker {
-                        tracker.increment();
-                    }
+                .filter(|_| !stopped.load(Ordering::Relaxed))
+                .map_init(
+                    || Self::build_processor(config_clone.as_ref(), &tracer, max_in_memory),
+                    |worker_processor, file_path| {
+                        // Files matching an override get a processor built from their own configuration
+                        let override_processor = overrides
+                            .and_then(|o| o.config_for(file_path))
+                            .map(|config| {
+                                Self::build_processor(Some(&config), &tracer, max_in_memory)
+                            });
+                        let started = Instant::now();
+                        let result = Self::process_single_file_with_processor(
+                            override_processor.as_ref().unwrap_or(worker_processor),
+                            file_path,
+                            &options,
+                            cache,
+                            read_limiter,
+                            per_file_timeout,
+                            strict_encoding,
+                        );
+                        Self::trace_result(&tracer, &result, started);
+
+                        // Update progress if tracker is provided
+                        if let Some(ref tracker) = progress_tracker {
+                            tracker.increment();
+                        }
 
-                    result
-                })
+                        let triggered = fail_fast
+                            && result.changes_made
+                            && !stopped.swap(true, Ordering::Relaxed);
+                        (result, triggered)
+                    },
+                )
                 .collect()
         });
 
-        // Add all results to the batch
-        for result in file_results {
-            results.add_result(result);
-        }
+        results.add_fail_fast_results(file_results);
 
         Ok(results)
     }
 
+    /// Build a content processor for one worker or one overridden file
+    fn build_processor(
+        config: Option<&Config>,
+        tracer: &Tracer,
+        max_in_memory: Option<usize>,
+    ) -> ContentProcessor {
+        match config {
+            Some(config) => ContentProcessor::new_with_config(config),
+            None => ContentProcessor::new(),
+        }
+        .with_tracer(tracer.clone())
+        .with_max_in_memory(max_in_memory)
+    }
+
+    /// Record the outcome of processing a file that started at `started`
+    fn trace_result(tracer: &Tracer, result: &FileProcessingResult, started: Instant) {
+        tracer.emit(|| TraceEvent::Processed {
+            file: result.file_path.display().to_string(),
+            success: result.success,
+            changes_made: result.changes_made,
+            error: result.error.clone(),
+            duration_ms: started.elapsed().as_millis() as u64,
+        });
+    }
+
     /// Process a single file and return detailed result
     fn process_single_file(
         &self,
         file_path: &Path,
         options: &ProcessOptions,
     ) -> FileProcessingResult {
-        Self::process_single_file_with_processor(&self.content_processor, file_path, options)
+        // Files matching an override get a processor built from their own configuration
+        let override_processor = self
+            .overrides
+            .as_ref()
+            .and_then(|overrides| overrides.config_for(file_path))
+            .map(|config| Self::build_processor(Some(&config), &self.tracer, self.max_in_memory));
+
+        Self::process_single_file_with_processor(
+            override_processor
+                .as_ref()
+                .unwrap_or(&self.content_processor),
+            file_path,
+            options,
+            self.cache.as_ref(),
+            self.read_limiter.as_ref(),
+            self.per_file_timeout,
+            self.strict_encoding,
+        )
     }
 
-    /// Process a single file with a specific processor (for parallel processing)
+    /// Process a single file with a specific processor (for parallel processing),
+    /// consulting and updating the cache if one is given
     fn process_single_file_with_processor(
         processor: &ContentProcessor,
         file_path: &Path,
         options: &ProcessOptions,
+        cache: Option<&Mutex<ProcessingCache>>,
+        read_limiter: Option<&ReadLimiter>,
+        timeout: Option<Duration>,
+        strict_encoding: bool,
+    ) -> FileProcessingResult {
+        let result = Self::process_file_content(
+            processor,
+            file_path,
+            options,
+            cache,
+            read_limiter,
+            timeout,
+            strict_encoding,
+        );
+
+        if let Some(mut cache) = cache.and_then(|c| c.lock().ok())
+            && !result.cached
+        {
+            // Files with unknown classes are checked again next time, so
+            // they keep getting reported
+            let clean = result.unknown_classes.is_empty();
+            match (&result.original_content, &result.processed_content) {
+                // Written files now hold the formatted content
+                (_, Some(processed))
+                    if options.write && !options.dry_run && result.changes_made =>
+                {
+                    cache.record(file_path, processed, clean)
+                }
+                (Some(original), _) if result.success => {
+                    cache.record(file_path, original, clean && !result.changes_made)
+                }
+                _ => cache.remove(file_path),
+            }
+        }
+
+        result
+    }
+
+    /// Read and process a file, or skip it when the cache shows it is
+    /// unchanged or, unless `strict_encoding` is set, it isn't valid UTF-8
+    fn process_file_content(
+        processor: &ContentProcessor,
+        file_path: &Path,
+        options: &ProcessOptions,
+        cache: Option<&Mutex<ProcessingCache>>,
+        read_limiter: Option<&ReadLimiter>,
+        timeout: Option<Duration>,
+        strict_encoding: bool,
     ) -> FileProcessingResult {
-        // Read file content
-        let original_content = match fs::read_to_string(file_path) {
+        if let Some(limit) = processor.max_file_size()
+            && let Ok(metadata) = fs::metadata(file_path)
+            && metadata.len() > limit as u64
+        {
+            return FileProcessingResult::error(
+                file_path.to_path_buf(),
+                format!(
+                    "File too large: {} ({} bytes, maxFileSize is {})",
+                    file_path.display(),
+                    metadata.len(),
+                    limit
+                ),
+            )
+            .with_failure_kind(FailureKind::TooLarge);
+        }
+
+        // Read file content, holding a read permit only for the read itself
+        let read = {
+            let _permit = read_limiter.map(ReadLimiter::acquire);
+            fs::read_to_string(file_path)
+        };
+        let original_content = match read {
             Ok(content) => content,
             Err(e) => {
                 let path_str = file_path.display().to_string();
-                let error_msg = match WindWardenError::from_io_error(e, Some(&path_str)) {
+                let error = WindWardenError::from_io_error(e, Some(&path_str));
+                let kind = FailureKind::of(&error);
+                let error_msg = match error {
                     WindWardenError::FileNotFound { .. } => {
                         format!("File not found: {}", path_str)
                     }
@@ -546,47 +1501,122 @@ impl FileProcessingPipeline {
                         format!("Permission denied: {}", path_str)
                     }
                     WindWardenError::InvalidUtf8 { .. } => {
-                        format!("File contains invalid UTF-8: {}", path_str)
+                        let message = format!("File contains invalid UTF-8: {}", path_str);
+                        if !strict_encoding {
+                            return FileProcessingResult::skipped_encoding(
+                                file_path.to_path_buf(),
+                                message,
+                            );
+                        }
+                        message
                     }
                     err => format!("Failed to read file {}: {}", path_str, err),
                 };
 
-                return FileProcessingResult::error(file_path.to_path_buf(), error_msg);
+                return FileProcessingResult::error(file_path.to_path_buf(), error_msg)
+                    .with_failure_kind(kind);
             }
         };
 
+        if cache
+            .and_then(|c| c.lock().ok())
+            .is_some_and(|c| c.is_fresh(file_path, &original_content))
+        {
+            processor.tracer().emit(|| TraceEvent::CacheHit {
+                file: file_path.display().to_string(),
+            });
+            return FileProcessingResult::cached(file_path.to_path_buf(), original_content);
+        }
+
         // Process content
         let file_path_str = file_path.to_string_lossy();
-        let processed_content =
-            match processor.process_content(&original_content, &file_path_str, options.clone()) {
-                Ok(content) => content,
-                Err(e) => {
-                    let error_msg = match &e {
-                        WindWardenError::ParseError {
-                            file,
-                            line,
-                            message,
-                        } => {
-                            format!("Parse error in {} at line {}: {}", file, line, message)
-                        }
-                        WindWardenError::SortError { context, message } => {
-                            format!("Sort error in {}: {}", context, message)
-                        }
-                        WindWardenError::UnsupportedFileType {
-                            extension,
-                            supported,
-                        } => {
+        let sorted = match timeout {
+            Some(timeout) => {
+                match Self::sort_content_with_timeout(
+                    processor,
+                    &original_content,
+                    &file_path_str,
+                    options,
+                    timeout,
+                ) {
+                    Some(sorted) => sorted,
+                    None => {
+                        return FileProcessingResult::error(
+                            file_path.to_path_buf(),
                             format!(
-                                "Unsupported file type .{} (supported: {})",
-                                extension, supported
-                            )
-                        }
-                        _ => format!("Processing failed: {}", e),
-                    };
-
-                    return FileProcessingResult::error(file_path.to_path_buf(), error_msg);
+                                "Timed out after {} ms: {}",
+                                timeout.as_millis(),
+                                file_path.display()
+                            ),
+                        )
+                        .with_failure_kind(FailureKind::TimedOut);
+                    }
                 }
-            };
+            }
+            None => Self::sort_content(processor, &original_content, &file_path_str, options),
+        };
+        let (outcome, changes_made) = match sorted {
+            Ok(sorted) => sorted,
+            Err(e) => {
+                let error_msg = match &e {
+                    WindWardenError::ParseError {
+                        file,
+                        line,
+                        column,
+                        message,
+                    } => {
+                        format!(
+                            "Parse error in {} at {}: {}",
+                            file,
+                            crate::parse_location(*line, *column),
+                            message
+                        )
+                    }
+                    WindWardenError::SortError { context, message } => {
+                        format!("Sort error in {}: {}", context, message)
+                    }
+                    WindWardenError::UnsupportedFileType {
+                        extension,
+                        supported,
+                    } => {
+                        format!(
+                            "Unsupported file type .{} (supported: {})",
+                            extension, supported
+                        )
+                    }
+                    _ => format!("Processing failed: {}", e),
+                };
+
+                let result = FileProcessingResult::error(file_path.to_path_buf(), error_msg)
+                    .with_failure_kind(FailureKind::of(&e));
+                return match e {
+                    WindWardenError::ParseError { line, column, .. } => {
+                        result.with_error_location(line, column.unwrap_or(1))
+                    }
+                    _ => result,
+                };
+            }
+        };
+
+        FileProcessingResult::success(
+            file_path.to_path_buf(),
+            changes_made,
+            original_content,
+            outcome.content,
+        )
+        .with_class_counts(outcome.counts)
+        .with_called_functions(outcome.called_functions, outcome.calls_incomplete)
+        .with_unknown_classes(outcome.unknown_classes)
+    }
+
+    /// Sort a file's content, and report whether sorting changes it
+    fn sort_content(
+        processor: &ContentProcessor,
+        content: &str,
+        file_path: &str,
+        options: &ProcessOptions,
+    ) -> Result<(ProcessOutcome, bool)> {
+        let outcome = processor.process_content_detailed(content, file_path, options.clone())?;
 
         // Determine if changes were made
         let changes_made = if options.check_formatted {
@@ -597,23 +1627,70 @@ impl FileProcessingPipeline {
                 write: false,
                 check_formatted: false,
             };
-            match processor.process_content(&original_content, &file_path_str, temp_options) {
-                Ok(temp_processed) => {
-                    original_content != temp_processed && !temp_processed.is_empty()
-                }
+            match processor.process_content(content, file_path, temp_options) {
+                Ok(temp_processed) => content != temp_processed && !temp_processed.is_empty(),
                 Err(_) => false, // If processing fails, assume no changes
             }
         } else {
             // Compare original and processed content
-            original_content != processed_content && !processed_content.is_empty()
+            content != outcome.content && !outcome.content.is_empty()
         };
 
-        FileProcessingResult::success(
-            file_path.to_path_buf(),
-            changes_made,
-            original_content,
-            processed_content,
-        )
+        Ok((outcome, changes_made))
+    }
+
+    /// Sort a file's content on a thread of its own, giving up after
+    /// `timeout`; `None` means it timed out
+    ///
+    /// Parsing can't be interrupted, so a file that times out keeps its
+    /// thread until it finishes, and its result is dropped. The file is
+    /// written here once the result arrives, never by that thread, so a
+    /// file that timed out is never written.
+    fn sort_content_with_timeout(
+        processor: &ContentProcessor,
+        content: &str,
+        file_path: &str,
+        options: &ProcessOptions,
+        timeout: Duration,
+    ) -> Option<Result<(ProcessOutcome, bool)>> {
+        let worker = processor.clone();
+        let worker_content = content.to_string();
+        let worker_path = file_path.to_string();
+        let worker_options = ProcessOptions {
+            dry_run: true,
+            ..options.clone()
+        };
+        let (sender, receiver) = mpsc::channel();
+
+        let spawned = thread::Builder::new()
+            .name("windwarden-file".to_string())
+            .spawn(move || {
+                let sorted =
+                    Self::sort_content(&worker, &worker_content, &worker_path, &worker_options);
+                // The receiver is gone if the file already timed out
+                let _ = sender.send(sorted);
+            });
+        if let Err(e) = spawned {
+            return Some(Err(WindWardenError::thread_pool_error(format!(
+                "Failed to start a thread for {}: {}",
+                file_path, e
+            ))));
+        }
+
+        let sorted = match receiver.recv_timeout(timeout) {
+            Ok(sorted) => sorted,
+            Err(mpsc::RecvTimeoutError::Timeout) => return None,
+            Err(mpsc::RecvTimeoutError::Disconnected) => Err(WindWardenError::internal_error(
+                format!("Processing {} panicked", file_path),
+            )),
+        };
+
+        Some(sorted.and_then(|(outcome, changes_made)| {
+            if options.write && !options.dry_run && changes_made {
+                processor.write_processed(file_path, content, &outcome.content)?;
+            }
+            Ok((outcome, changes_made))
+        }))
     }
 
     /// Get the underlying file discovery configuration
@@ -719,89 +1796,428 @@ mod tests {
         assert!(!file_names.contains(&"bundle.js".to_string())); // from dist
     }
 
+    #[test]
+    fn test_overlapping_paths_are_discovered_once() {
+        let temp_dir = TempDir::new().unwrap();
+        create_test_files(&temp_dir).unwrap();
+        fs::write(temp_dir.path().join("src/components/notes.md"), "# notes").unwrap();
+        let trace_path = temp_dir.path().join("trace.jsonl");
+
+        let base = temp_dir.path().to_string_lossy().to_string();
+        let paths = vec![
+            format!("{}/src", base),
+            format!("{}/src/components", base),
+            format!("{}/src/components/Button.tsx", base),
+            format!("{}/src/./App.tsx", base),
+            format!("{}/src/pages/*.ts", base),
+        ];
+
+        let discovery = FileDiscovery::new(FileDiscoveryConfig::default())
+            .unwrap()
+            .with_tracer(Tracer::to_file(&trace_path).unwrap());
+        let files = discovery.discover_files(&paths).unwrap();
+        discovery.tracer.flush();
+
+        let names: Vec<_> = files
+            .iter()
+            .map(|file| file.file_name().unwrap().to_string_lossy().to_string())
+            .collect();
+        let mut sorted_names = names.clone();
+        sorted_names.sort();
+        sorted_names.dedup();
+        assert_eq!(sorted_names.len(), names.len(), "duplicates in {:?}", files);
+        assert_eq!(files.len(), 6);
+
+        // The nested directory isn't walked a second time
+        let trace = fs::read_to_string(&trace_path).unwrap();
+        let skipped = |reason: &str| {
+            trace
+                .lines()
+                .filter(|line| line.contains(&format!(r#""reason":"{}"#, reason)))
+                .count()
+        };
+        assert_eq!(skipped("covered by "), 1);
+        assert_eq!(
+            trace
+                .lines()
+                .filter(|line| line.contains("notes.md"))
+                .count(),
+            1
+        );
+        // The explicit files and the glob matches were already found by the walk
+        assert_eq!(skipped("already discovered"), 3);
+    }
+
     #[test]
     fn test_discover_single_file() {
         let temp_dir = TempDir::new().unwrap();
-        create_test_files(&temp_dir).unwrap();
+        create_test_files(&temp_dir).unwrap();
+
+        let config = FileDiscoveryConfig::default();
+        let discovery = FileDiscovery::new(config).unwrap();
+
+        let file_path = temp_dir.path().join("src/App.tsx");
+        let files = discovery
+            .discover_files(&[file_path.to_string_lossy().to_string()])
+            .unwrap();
+
+        assert_eq!(files.len(), 1);
+        assert!(files[0].ends_with("App.tsx"));
+    }
+
+    #[test]
+    fn test_custom_extensions() {
+        let temp_dir = TempDir::new().unwrap();
+        create_test_files(&temp_dir).unwrap();
+
+        let config = FileDiscoveryConfig {
+            extensions: vec!["tsx".to_string()], // Only TypeScript React files
+            ..Default::default()
+        };
+        let discovery = FileDiscovery::new(config).unwrap();
+
+        let files = discovery
+            .discover_files(&[temp_dir.path().to_string_lossy().to_string()])
+            .unwrap();
+
+        // Should only find .tsx files
+        for file in &files {
+            assert!(file.extension().unwrap() == "tsx");
+        }
+
+        let file_names: Vec<String> = files
+            .iter()
+            .filter_map(|p| p.file_name()?.to_str())
+            .map(|s| s.to_string())
+            .collect();
+
+        assert!(file_names.contains(&"App.tsx".to_string()));
+        assert!(file_names.contains(&"Button.tsx".to_string()));
+        assert!(!file_names.contains(&"App.jsx".to_string()));
+        assert!(!file_names.contains(&"Card.jsx".to_string()));
+    }
+
+    #[test]
+    fn test_glob_patterns() {
+        let temp_dir = TempDir::new().unwrap();
+        create_test_files(&temp_dir).unwrap();
+
+        let config = FileDiscoveryConfig::default();
+        let discovery = FileDiscovery::new(config).unwrap();
+
+        // Change to the temp directory for relative glob patterns
+        let original_dir = std::env::current_dir().unwrap();
+        std::env::set_current_dir(temp_dir.path()).unwrap();
+
+        let files = discovery
+            .discover_files(&["src/**/*.tsx".to_string()])
+            .unwrap();
+
+        // Restore original directory
+        std::env::set_current_dir(original_dir).unwrap();
+
+        // Should find only .tsx files in src/
+        assert!(!files.is_empty());
+        for file in &files {
+            assert_eq!(file.extension().unwrap(), "tsx");
+            assert!(file.to_string_lossy().contains("src"));
+        }
+    }
+
+    #[test]
+    fn test_glob_base_dir() {
+        assert_eq!(
+            FileDiscovery::glob_base_dir("packages/ui/src/**/*.tsx"),
+            PathBuf::from("packages/ui/src")
+        );
+        assert_eq!(
+            FileDiscovery::glob_base_dir("src/*.tsx"),
+            PathBuf::from("src")
+        );
+        assert_eq!(FileDiscovery::glob_base_dir("**/*.tsx"), PathBuf::from("."));
+        assert_eq!(FileDiscovery::glob_base_dir("*.tsx"), PathBuf::from("."));
+        assert_eq!(
+            FileDiscovery::glob_base_dir("src/{a,b}/*.tsx"),
+            PathBuf::from("src")
+        );
+        assert_eq!(
+            FileDiscovery::glob_base_dir("/abs/path/**/*.tsx"),
+            PathBuf::from("/abs/path")
+        );
+    }
+
+    #[test]
+    fn test_glob_walks_only_literal_prefix_in_deep_tree() {
+        let temp_dir = TempDir::new().unwrap();
+        let base = temp_dir.path();
+
+        // A deep tree of sibling packages that the glob should never enter
+        for pkg in 0..10 {
+            let mut dir = base.join(format!("packages/pkg{}", pkg));
+            for level in 0..8 {
+                dir = dir.join(format!("level{}", level));
+            }
+            fs::create_dir_all(&dir).unwrap();
+            fs::write(dir.join("Deep.tsx"), "// deep").unwrap();
+        }
+
+        fs::create_dir_all(base.join("packages/ui/src/components")).unwrap();
+        fs::write(base.join("packages/ui/src/Index.tsx"), "// index").unwrap();
+        fs::write(
+            base.join("packages/ui/src/components/Button.tsx"),
+            "// button",
+        )
+        .unwrap();
+        fs::write(base.join("packages/ui/src/helpers.ts"), "// helpers").unwrap();
+
+        let discovery = FileDiscovery::new(FileDiscoveryConfig::default()).unwrap();
+        let pattern = format!("{}/packages/ui/src/**/*.tsx", base.display());
+
+        let start = std::time::Instant::now();
+        let files = discovery.discover_files(&[pattern]).unwrap();
+        let elapsed = start.elapsed();
+
+        let file_names: Vec<String> = files
+            .iter()
+            .filter_map(|p| p.file_name()?.to_str())
+            .map(|s| s.to_string())
+            .collect();
+
+        assert_eq!(files.len(), 2);
+        assert!(file_names.contains(&"Index.tsx".to_string()));
+        assert!(file_names.contains(&"Button.tsx".to_string()));
+        assert!(!file_names.contains(&"Deep.tsx".to_string()));
+        assert!(elapsed.as_secs() < 5, "Glob discovery took {:?}", elapsed);
+    }
+
+    #[test]
+    fn test_nonexistent_path() {
+        let config = FileDiscoveryConfig::default();
+        let discovery = FileDiscovery::new(config).unwrap();
+
+        let result = discovery.discover_files(&["/nonexistent/path".to_string()]);
+        assert!(result.is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_symlink_cycle_is_skipped_when_following_links() {
+        let temp_dir = TempDir::new().unwrap();
+        let base = temp_dir.path();
+
+        fs::create_dir_all(base.join("a")).unwrap();
+        fs::create_dir_all(base.join("b")).unwrap();
+        fs::write(base.join("a/Button.tsx"), "// button").unwrap();
+        fs::write(base.join("b/Card.tsx"), "// card").unwrap();
+        // A link back to an ancestor, and a second link to an already visited directory
+        std::os::unix::fs::symlink("..", base.join("a/link")).unwrap();
+        std::os::unix::fs::symlink("../a", base.join("b/alias")).unwrap();
+
+        let config = FileDiscoveryConfig {
+            follow_links: true,
+            ..Default::default()
+        };
+        let discovery = FileDiscovery::new(config).unwrap();
+
+        let files = discovery
+            .discover_files(&[base.to_string_lossy().to_string()])
+            .unwrap();
+        let file_names: Vec<String> = files
+            .iter()
+            .filter_map(|p| p.file_name()?.to_str())
+            .map(|s| s.to_string())
+            .collect();
+
+        assert_eq!(file_names.len(), 2);
+        assert!(file_names.contains(&"Button.tsx".to_string()));
+        assert!(file_names.contains(&"Card.tsx".to_string()));
+
+        let warnings = discovery.take_warnings();
+        assert_eq!(warnings.len(), 2);
+        assert!(warnings.iter().all(|w| w.contains("already visited")));
+    }
+
+    #[test]
+    fn test_negated_exclude_reincludes_file_in_excluded_directory() {
+        let temp_dir = TempDir::new().unwrap();
+        let base = temp_dir.path();
+
+        fs::create_dir_all(base.join("src/generated")).unwrap();
+        fs::write(base.join("src/App.tsx"), "// app").unwrap();
+        fs::write(base.join("src/generated/api.tsx"), "// api").unwrap();
+        fs::write(base.join("src/generated/icons.tsx"), "// icons").unwrap();
+
+        let config = FileDiscoveryConfig {
+            exclude_patterns: vec![
+                "**/generated/**".to_string(),
+                "!**/generated/icons.tsx".to_string(),
+            ],
+            ..Default::default()
+        };
+        let discovery = FileDiscovery::new(config).unwrap();
+
+        let files = discovery
+            .discover_files(&[base.to_string_lossy().to_string()])
+            .unwrap();
+        let file_names: Vec<String> = files
+            .iter()
+            .filter_map(|p| p.file_name()?.to_str())
+            .map(|s| s.to_string())
+            .collect();
+
+        assert_eq!(files.len(), 2);
+        assert!(file_names.contains(&"App.tsx".to_string()));
+        assert!(file_names.contains(&"icons.tsx".to_string()));
+        assert!(!file_names.contains(&"api.tsx".to_string()));
+    }
+
+    #[test]
+    fn test_negated_exclude_reincludes_file_under_excluded_directory_name() {
+        let temp_dir = TempDir::new().unwrap();
+        let base = temp_dir.path();
 
-        let config = FileDiscoveryConfig::default();
+        fs::create_dir_all(base.join("src/generated")).unwrap();
+        fs::create_dir_all(base.join("node_modules/pkg")).unwrap();
+        fs::write(base.join("src/generated/api.tsx"), "// api").unwrap();
+        fs::write(base.join("src/generated/icons.tsx"), "// icons").unwrap();
+        fs::write(base.join("node_modules/pkg/index.js"), "// pkg").unwrap();
+
+        let config = FileDiscoveryConfig {
+            exclude_patterns: vec![
+                "node_modules/**".to_string(),
+                "generated/**".to_string(),
+                "!**/generated/icons.tsx".to_string(),
+            ],
+            ..Default::default()
+        };
         let discovery = FileDiscovery::new(config).unwrap();
 
-        let file_path = temp_dir.path().join("src/App.tsx");
         let files = discovery
-            .discover_files(&[file_path.to_string_lossy().to_string()])
+            .discover_files(&[base.to_string_lossy().to_string()])
             .unwrap();
 
         assert_eq!(files.len(), 1);
-        assert!(files[0].ends_with("App.tsx"));
+        assert!(files[0].ends_with("src/generated/icons.tsx"));
     }
 
     #[test]
-    fn test_custom_extensions() {
-        let temp_dir = TempDir::new().unwrap();
-        create_test_files(&temp_dir).unwrap();
-
+    fn test_exclude_last_matching_pattern_wins() {
         let config = FileDiscoveryConfig {
-            extensions: vec!["tsx".to_string()], // Only TypeScript React files
+            exclude_patterns: vec![
+                "**/generated/**".to_string(),
+                "!**/generated/icons.tsx".to_string(),
+                "**/generated/*.tsx".to_string(),
+            ],
             ..Default::default()
         };
         let discovery = FileDiscovery::new(config).unwrap();
 
-        let files = discovery
-            .discover_files(&[temp_dir.path().to_string_lossy().to_string()])
-            .unwrap();
+        // The final pattern excludes the file again
+        assert!(discovery.is_excluded(Path::new("src/generated/icons.tsx"), false));
+        assert!(discovery.is_excluded(Path::new("src/generated/api.tsx"), false));
+        assert!(!discovery.is_excluded(Path::new("src/App.tsx"), false));
 
-        // Should only find .tsx files
-        for file in &files {
-            assert!(file.extension().unwrap() == "tsx");
-        }
+        // Without a later negation naming it, an excluded directory is pruned
+        let config = FileDiscoveryConfig {
+            exclude_patterns: vec!["**/generated/**".to_string(), "generated".to_string()],
+            ..Default::default()
+        };
+        let discovery = FileDiscovery::new(config).unwrap();
+        assert!(discovery.is_excluded(Path::new("src/generated"), true));
+    }
 
-        let file_names: Vec<String> = files
-            .iter()
-            .filter_map(|p| p.file_name()?.to_str())
-            .map(|s| s.to_string())
-            .collect();
+    #[test]
+    fn test_exclude_patterns_expand_braces() {
+        let config = FileDiscoveryConfig {
+            exclude_patterns: vec![
+                "{generated,vendor}/**".to_string(),
+                "!**/generated/{icons,logos}.tsx".to_string(),
+            ],
+            ..Default::default()
+        };
+        let discovery = FileDiscovery::new(config).unwrap();
 
-        assert!(file_names.contains(&"App.tsx".to_string()));
-        assert!(file_names.contains(&"Button.tsx".to_string()));
-        assert!(!file_names.contains(&"App.jsx".to_string()));
-        assert!(!file_names.contains(&"Card.jsx".to_string()));
+        assert!(discovery.is_excluded(Path::new("src/vendor/lib.tsx"), false));
+        assert!(discovery.is_excluded(Path::new("src/generated/api.tsx"), false));
+        assert!(!discovery.is_excluded(Path::new("src/generated/logos.tsx"), false));
+        assert!(!discovery.is_excluded(Path::new("src/App.tsx"), false));
+
+        let config = FileDiscoveryConfig {
+            exclude_patterns: vec!["{generated,vendor/**".to_string()],
+            ..Default::default()
+        };
+        assert!(FileDiscovery::new(config).is_err());
     }
 
     #[test]
-    fn test_glob_patterns() {
+    fn test_exclude_dirs_match_any_path_component() {
         let temp_dir = TempDir::new().unwrap();
-        create_test_files(&temp_dir).unwrap();
+        let base = temp_dir.path();
+        fs::create_dir_all(base.join("src/__mocks__/api")).unwrap();
+        fs::create_dir_all(base.join("src/components")).unwrap();
+        fs::write(base.join("src/__mocks__/api/client.ts"), "").unwrap();
+        fs::write(base.join("src/components/__mocks__.tsx"), "").unwrap();
+        fs::write(base.join("src/components/Button.tsx"), "").unwrap();
 
-        let config = FileDiscoveryConfig::default();
+        let config = FileDiscoveryConfig {
+            exclude_dirs: vec!["__mocks__".to_string()],
+            // A negation cannot re-include files in an excluded directory
+            exclude_patterns: vec!["!**/client.ts".to_string()],
+            ..Default::default()
+        };
         let discovery = FileDiscovery::new(config).unwrap();
-
-        // Change to the temp directory for relative glob patterns
-        let original_dir = std::env::current_dir().unwrap();
-        std::env::set_current_dir(temp_dir.path()).unwrap();
-
         let files = discovery
-            .discover_files(&["src/**/*.tsx".to_string()])
+            .discover_files(&[base.to_string_lossy().to_string()])
             .unwrap();
 
-        // Restore original directory
-        std::env::set_current_dir(original_dir).unwrap();
+        let names: Vec<_> = files
+            .iter()
+            .filter_map(|f| f.file_name()?.to_str())
+            .collect();
+        // Only directories are matched, so a file named like the directory is kept
+        assert_eq!(names, vec!["Button.tsx", "__mocks__.tsx"]);
 
-        // Should find only .tsx files in src/
-        assert!(!files.is_empty());
-        for file in &files {
-            assert_eq!(file.extension().unwrap(), "tsx");
-            assert!(file.to_string_lossy().contains("src"));
-        }
+        let config = FileDiscoveryConfig {
+            exclude_dirs: vec!["src/__mocks__".to_string()],
+            ..Default::default()
+        };
+        assert!(FileDiscovery::new(config).is_err());
     }
 
     #[test]
-    fn test_nonexistent_path() {
-        let config = FileDiscoveryConfig::default();
+    fn test_include_patterns_restrict_discovered_files() {
+        let temp_dir = TempDir::new().unwrap();
+        let base = temp_dir.path();
+        fs::create_dir_all(base.join("src/pages/legacy")).unwrap();
+        fs::write(base.join("src/pages/home.page.tsx"), "").unwrap();
+        fs::write(base.join("src/pages/legacy/old.page.tsx"), "").unwrap();
+        fs::write(base.join("src/pages/Header.tsx"), "").unwrap();
+        fs::write(base.join("src/pages/notes.page.md"), "").unwrap();
+
+        let config = FileDiscoveryConfig {
+            include_patterns: vec!["**/*.page.tsx".to_string()],
+            exclude_patterns: vec!["**/legacy/**".to_string()],
+            ..Default::default()
+        };
         let discovery = FileDiscovery::new(config).unwrap();
+        let root = base.to_string_lossy().to_string();
 
-        let result = discovery.discover_files(&["/nonexistent/path".to_string()]);
-        assert!(result.is_err());
+        // Extensions are still required and excludes still apply
+        let files = discovery.discover_files(&[root]).unwrap();
+        assert_eq!(files, vec![base.join("src/pages/home.page.tsx")]);
+
+        // Explicitly named files are not filtered, as with excludes
+        let header = base.join("src/pages/Header.tsx");
+        let files = discovery
+            .discover_files(&[header.to_string_lossy().to_string()])
+            .unwrap();
+        assert_eq!(files, vec![header]);
+
+        let config = FileDiscoveryConfig {
+            include_patterns: vec!["[".to_string()],
+            ..Default::default()
+        };
+        assert!(FileDiscovery::new(config).is_err());
     }
 
     // ===== FILE PROCESSING PIPELINE TESTS =====
@@ -1000,6 +2416,56 @@ export const classes = "p-4 flex m-2";
         }
     }
 
+    #[test]
+    fn test_file_processing_pipeline_cache_skips_formatted_files() {
+        let temp_dir = TempDir::new().unwrap();
+        create_test_content_files(&temp_dir).unwrap();
+        let cache_path = temp_dir.path().join(".windwarden-cache");
+        let paths = [temp_dir.path().join("src").to_string_lossy().to_string()];
+        let config = Config::default();
+
+        let options = ProcessOptions {
+            dry_run: false,
+            write: false,
+            check_formatted: true,
+        };
+
+        let run = || {
+            let pipeline = FileProcessingPipeline::new_with_windwarden_config(
+                FileDiscoveryConfig::default(),
+                &config,
+                ProcessingMode::Sequential,
+            )
+            .unwrap()
+            .with_cache(ProcessingCache::load(&cache_path, &config));
+            let results = pipeline.process_files(&paths, options.clone()).unwrap();
+            pipeline.save_cache().unwrap();
+            results
+        };
+
+        let first = run();
+        assert_eq!(first.cache_hits, 0);
+
+        // Formatted files are skipped on the next run; files needing changes are not
+        let second = run();
+        assert_eq!(second.total_files, first.total_files);
+        assert_eq!(second.files_with_changes, first.files_with_changes);
+        assert_eq!(
+            second.cache_hits,
+            first.total_files - first.files_with_changes
+        );
+
+        // Editing a cached file invalidates its entry
+        fs::write(
+            temp_dir.path().join("src/pages/Home.ts"),
+            "const styles = \"p-4 flex\";",
+        )
+        .unwrap();
+        let third = run();
+        assert_eq!(third.cache_hits, second.cache_hits - 1);
+        assert_eq!(third.files_with_changes, second.files_with_changes + 1);
+    }
+
     #[test]
     fn test_file_processing_pipeline_single_file() {
         let temp_dir = TempDir::new().unwrap();
@@ -1101,19 +2567,31 @@ export const classes = "p-4 flex m-2";
         let mut results = BatchProcessingResults::new();
 
         // Add successful results
-        results.add_result(FileProcessingResult::success(
-            PathBuf::from("file1.tsx"),
-            true,
-            "original1".to_string(),
-            "processed1".to_string(),
-        ));
+        results.add_result(
+            FileProcessingResult::success(
+                PathBuf::from("file1.tsx"),
+                true,
+                "original1".to_string(),
+                "processed1".to_string(),
+            )
+            .with_class_counts(ClassCounts {
+                found: 5,
+                reordered: 2,
+            }),
+        );
 
-        results.add_result(FileProcessingResult::success(
-            PathBuf::from("file2.tsx"),
-            false, // no changes
-            "original2".to_string(),
-            "original2".to_string(),
-        ));
+        results.add_result(
+            FileProcessingResult::success(
+                PathBuf::from("file2.tsx"),
+                false, // no changes
+                "original2".to_string(),
+                "original2".to_string(),
+            )
+            .with_class_counts(ClassCounts {
+                found: 3,
+                reordered: 0,
+            }),
+        );
 
         // Add error result
         results.add_result(FileProcessingResult::error(
@@ -1125,7 +2603,35 @@ export const classes = "p-4 flex m-2";
         assert_eq!(results.processed_files, 2);
         assert_eq!(results.files_with_changes, 1);
         assert_eq!(results.failed_files, 1);
+        assert_eq!(results.classes_found, 8);
+        assert_eq!(results.classes_reordered, 2);
         assert!((results.success_rate() - 0.6666666666666666).abs() < f64::EPSILON);
+        assert_eq!(
+            results.files_per_second(std::time::Duration::from_millis(500)),
+            6.0
+        );
+        assert_eq!(results.files_per_second(std::time::Duration::ZERO), 0.0);
+        assert_eq!(
+            results.changed_files().collect::<Vec<_>>(),
+            vec![Path::new("file1.tsx")]
+        );
+    }
+
+    #[test]
+    fn test_sort_by_path() {
+        let mut results = BatchProcessingResults::new();
+        for name in ["b/C.tsx", "a/nested/C.tsx", "a/B.tsx"] {
+            results.add_result(FileProcessingResult::skipped_encoding(
+                PathBuf::from(name),
+                "invalid UTF-8".to_string(),
+            ));
+        }
+
+        results.sort_by_path();
+        let paths: Vec<_> = results.results.iter().map(|r| &r.file_path).collect();
+        assert_eq!(paths, ["a/B.tsx", "a/nested/C.tsx", "b/C.tsx"]);
+        assert!(results.warnings[0].starts_with("Skipped a/B.tsx"));
+        assert!(results.warnings[2].starts_with("Skipped b/C.tsx"));
     }
 
     // ===== PARALLEL PROCESSING TESTS =====
@@ -1237,7 +2743,7 @@ export const classes = "p-4 flex m-2";
         let path = temp_dir.path().to_string_lossy().to_string();
 
         let sequential_results = sequential_pipeline
-            .process_files(&[path.clone()], options.clone())
+            .process_files(std::slice::from_ref(&path), options.clone())
             .unwrap();
         let parallel_results = parallel_pipeline.process_files(&[path], options).unwrap();
 
@@ -1256,11 +2762,14 @@ export const classes = "p-4 flex m-2";
             parallel_results.failed_files
         );
 
-        // Sort results by file path for comparison
-        let mut seq_results = sequential_results.results;
-        let mut par_results = parallel_results.results;
-        seq_results.sort_by(|a, b| a.file_path.cmp(&b.file_path));
-        par_results.sort_by(|a, b| a.file_path.cmp(&b.file_path));
+        // Both list files in path order
+        let seq_results = sequential_results.results;
+        let par_results = parallel_results.results;
+        assert!(
+            seq_results
+                .windows(2)
+                .all(|pair| pair[0].file_path < pair[1].file_path)
+        );
 
         // Compare individual file results
         assert_eq!(seq_results.len(), par_results.len());
@@ -1273,6 +2782,107 @@ export const classes = "p-4 flex m-2";
         }
     }
 
+    #[test]
+    fn test_read_limiter_bounds_concurrency() {
+        use std::sync::atomic::AtomicUsize;
+
+        let limiter = ReadLimiter::new(2);
+        let active = AtomicUsize::new(0);
+        let peak = AtomicUsize::new(0);
+
+        std::thread::scope(|scope| {
+            for _ in 0..8 {
+                scope.spawn(|| {
+                    for _ in 0..10 {
+                        let _permit = limiter.acquire();
+                        let now = active.fetch_add(1, Ordering::SeqCst) + 1;
+                        peak.fetch_max(now, Ordering::SeqCst);
+                        std::thread::sleep(std::time::Duration::from_micros(200));
+                        active.fetch_sub(1, Ordering::SeqCst);
+                    }
+                });
+            }
+        });
+
+        assert!(peak.load(Ordering::SeqCst) <= 2);
+        assert_eq!(*limiter.available.lock().unwrap(), 2);
+    }
+
+    #[test]
+    fn test_parallel_with_max_concurrent_reads() {
+        let temp_dir = TempDir::new().unwrap();
+        create_test_content_files(&temp_dir).unwrap();
+        let paths = [temp_dir.path().to_string_lossy().to_string()];
+
+        let options = ProcessOptions {
+            dry_run: true,
+            write: false,
+            check_formatted: false,
+        };
+
+        let unbounded =
+            FileProcessingPipeline::parallel_with_threads(FileDiscoveryConfig::default(), 4)
+                .unwrap()
+                .process_files(&paths, options.clone())
+                .unwrap();
+        let bounded =
+            FileProcessingPipeline::parallel_with_threads(FileDiscoveryConfig::default(), 4)
+                .unwrap()
+                .with_max_concurrent_reads(Some(1))
+                .process_files(&paths, options)
+                .unwrap();
+
+        assert!(bounded.total_files > 0);
+        assert_eq!(bounded.total_files, unbounded.total_files);
+        assert_eq!(bounded.files_with_changes, unbounded.files_with_changes);
+        assert_eq!(bounded.failed_files, 0);
+    }
+
+    #[test]
+    fn test_resolve_thread_count() {
+        // A thread per core by default, but never more threads than files
+        assert_eq!(resolve_thread_count(None, None, 32, 1000), 32);
+        assert_eq!(resolve_thread_count(None, None, 32, 10), 10);
+        assert_eq!(resolve_thread_count(None, None, 8, 0), 1);
+
+        // Explicit counts are capped by the file count too
+        assert_eq!(resolve_thread_count(Some(4), None, 32, 1000), 4);
+        assert_eq!(resolve_thread_count(Some(64), None, 8, 1000), 64);
+        assert_eq!(resolve_thread_count(Some(16), None, 32, 3), 3);
+        assert_eq!(resolve_thread_count(Some(4), Some(0.25), 32, 1000), 4);
+
+        // Scaling takes a fraction of the cores, rounding up
+        assert_eq!(resolve_thread_count(None, Some(0.5), 32, 1000), 16);
+        assert_eq!(resolve_thread_count(None, Some(0.5), 3, 1000), 2);
+        assert_eq!(resolve_thread_count(None, Some(0.1), 4, 1000), 1);
+        assert_eq!(resolve_thread_count(None, Some(0.5), 32, 5), 5);
+        assert_eq!(resolve_thread_count(None, Some(1.0), 1, 1000), 1);
+    }
+
+    #[test]
+    fn test_thread_scaling_processes_all_files() {
+        let temp_dir = TempDir::new().unwrap();
+        for i in 0..6 {
+            fs::write(
+                temp_dir.path().join(format!("C{}.tsx", i)),
+                r#"export const C = () => <div className="p-4 flex">C</div>;"#,
+            )
+            .unwrap();
+        }
+
+        let results = FileProcessingPipeline::parallel(FileDiscoveryConfig::default())
+            .unwrap()
+            .with_thread_scaling(Some(0.5))
+            .process_files(
+                &[temp_dir.path().to_string_lossy().to_string()],
+                ProcessOptions::default(),
+            )
+            .unwrap();
+
+        assert_eq!(results.total_files, 6);
+        assert_eq!(results.files_with_changes, 6);
+    }
+
     #[test]
     fn test_processing_mode_setting() {
         let config = FileDiscoveryConfig::default();
@@ -1348,6 +2958,263 @@ export function Component{}() {{
         assert!(results.files_with_changes > 0);
     }
 
+    #[test]
+    fn test_pipeline_applies_config_overrides_per_file() {
+        use crate::config::{ConfigOverride, OverrideSettings};
+
+        let temp_dir = TempDir::new().unwrap();
+        let content = r#"export const styles = storyClasses("p-4 flex");"#;
+        let story = temp_dir.path().join("Button.stories.tsx");
+        let component = temp_dir.path().join("Button.tsx");
+        fs::write(&story, content).unwrap();
+        fs::write(&component, content).unwrap();
+
+        let config = Config {
+            overrides: vec![ConfigOverride {
+                files: vec!["**/*.stories.tsx".to_string()],
+                config: OverrideSettings {
+                    function_names: Some(vec!["storyClasses".to_string()]),
+                    ..Default::default()
+                },
+            }],
+            ..Default::default()
+        };
+        let paths = [temp_dir.path().to_string_lossy().to_string()];
+
+        for mode in [ProcessingMode::Sequential, ProcessingMode::Parallel] {
+            let pipeline = FileProcessingPipeline::new_with_windwarden_config(
+                FileDiscoveryConfig::default(),
+                &config,
+                mode,
+            )
+            .unwrap();
+            let results = pipeline
+                .process_files(&paths, ProcessOptions::default())
+                .unwrap();
+
+            let changed: Vec<&PathBuf> = results
+                .results
+                .iter()
+                .filter(|r| r.changes_made)
+                .map(|r| &r.file_path)
+                .collect();
+            assert_eq!(changed, [&story], "{:?}", mode);
+        }
+    }
+
+    #[test]
+    fn test_failures_are_categorized() {
+        let temp_dir = TempDir::new().unwrap();
+        let write = |name: &str, content: &str| {
+            let path = temp_dir.path().join(name);
+            fs::write(&path, content).unwrap();
+            path.to_string_lossy().to_string()
+        };
+        let paths = vec![
+            write("ok.tsx", r#"<div className="p-4 flex" />"#),
+            write("broken.tsx", "const = <div className=\"p-4 flex\" (;"),
+            write(
+                "big.tsx",
+                &format!(r#"<div className="{}" />"#, "p-4 ".repeat(64)),
+            ),
+        ];
+        let binary = temp_dir.path().join("binary.tsx");
+        fs::write(&binary, [0xff, 0xfe, 0x00]).unwrap();
+        let paths = [paths, vec![binary.to_string_lossy().to_string()]].concat();
+
+        let config = Config {
+            max_file_size: 200,
+            ..Config::default()
+        };
+        let results = FileProcessingPipeline::new_with_windwarden_config(
+            FileDiscoveryConfig::default(),
+            &config,
+            ProcessingMode::Sequential,
+        )
+        .unwrap()
+        .process_files(&paths, ProcessOptions::default())
+        .unwrap();
+
+        let kind_of = |name: &str| {
+            results
+                .results
+                .iter()
+                .find(|r| r.file_path.ends_with(name))
+                .and_then(|r| r.failure_kind)
+        };
+        assert_eq!(kind_of("ok.tsx"), None);
+        assert_eq!(kind_of("broken.tsx"), Some(FailureKind::Parse));
+        assert_eq!(kind_of("big.tsx"), Some(FailureKind::TooLarge));
+        assert_eq!(kind_of("binary.tsx"), None);
+        assert_eq!(results.failure_count(FailureKind::Parse), 1);
+        assert_eq!(results.failure_count(FailureKind::TooLarge), 1);
+        assert_eq!(results.failure_count(FailureKind::Other), 0);
+    }
+
+    #[test]
+    fn test_max_files_limit() {
+        let temp_dir = TempDir::new().unwrap();
+        for i in 0..3 {
+            fs::write(
+                temp_dir.path().join(format!("f{}.tsx", i)),
+                r#"<div className="flex" />"#,
+            )
+            .unwrap();
+        }
+        let paths = vec![temp_dir.path().to_string_lossy().to_string()];
+        let pipeline = |max| {
+            FileProcessingPipeline::sequential(FileDiscoveryConfig::default())
+                .unwrap()
+                .with_max_files(max)
+        };
+
+        let error = pipeline(Some(2))
+            .process_files(&paths, ProcessOptions::default())
+            .unwrap_err();
+        assert!(error.to_string().contains("Found 3 files to process"));
+
+        let results = pipeline(Some(3))
+            .process_files(&paths, ProcessOptions::default())
+            .unwrap();
+        assert_eq!(results.total_files, 3);
+    }
+
+    #[test]
+    fn test_invalid_utf8_is_skipped_unless_strict() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(
+            temp_dir.path().join("ok.tsx"),
+            r#"<div className="flex" />"#,
+        )
+        .unwrap();
+        fs::write(
+            temp_dir.path().join("latin1.tsx"),
+            b"<div title=\"caf\xe9\" />",
+        )
+        .unwrap();
+        let paths = vec![temp_dir.path().to_string_lossy().to_string()];
+
+        let results = FileProcessingPipeline::sequential(FileDiscoveryConfig::default())
+            .unwrap()
+            .process_files(&paths, ProcessOptions::default())
+            .unwrap();
+        assert_eq!(results.total_files, 2);
+        assert_eq!(results.failed_files, 0);
+        assert_eq!(results.skipped_encoding, 1);
+        assert!(results.success_rate() == 1.0);
+        assert!(results.results.iter().all(|r| !r.failed()));
+        assert_eq!(results.warnings.len(), 1);
+        assert!(results.warnings[0].contains("latin1.tsx: not valid UTF-8"));
+
+        let results = FileProcessingPipeline::sequential(FileDiscoveryConfig::default())
+            .unwrap()
+            .with_strict_encoding(true)
+            .process_files(&paths, ProcessOptions::default())
+            .unwrap();
+        assert_eq!(results.failed_files, 1);
+        assert_eq!(results.skipped_encoding, 0);
+        assert_eq!(results.failure_count(FailureKind::Io), 1);
+        assert!(results.warnings.is_empty());
+    }
+
+    #[test]
+    fn test_per_file_timeout() {
+        let temp_dir = TempDir::new().unwrap();
+        let small = temp_dir.path().join("small.tsx");
+        fs::write(&small, r#"<div className="p-4 flex" />"#).unwrap();
+        // Far too much to parse in no time at all
+        let slow = temp_dir.path().join("slow.tsx");
+        let slow_content = format!(
+            "export const A = () => <>{}</>;",
+            r#"<div className="p-4 flex" />"#.repeat(50_000)
+        );
+        fs::write(&slow, &slow_content).unwrap();
+
+        let pipeline = |timeout| {
+            FileProcessingPipeline::new_with_mode(
+                FileDiscoveryConfig::default(),
+                ProcessingMode::Parallel,
+            )
+            .unwrap()
+            .with_per_file_timeout(Some(timeout))
+        };
+        let options = ProcessOptions {
+            dry_run: false,
+            write: true,
+            check_formatted: false,
+        };
+
+        let results = pipeline(Duration::ZERO)
+            .process_files(&[slow.to_string_lossy().to_string()], options.clone())
+            .unwrap();
+        assert_eq!(results.failed_files, 1);
+        assert_eq!(results.failure_count(FailureKind::TimedOut), 1);
+        assert!(
+            results.results[0]
+                .error
+                .as_ref()
+                .unwrap()
+                .starts_with("Timed out after 0 ms")
+        );
+        // A file that timed out is never written
+        thread::sleep(Duration::from_millis(500));
+        assert_eq!(fs::read_to_string(&slow).unwrap(), slow_content);
+
+        let results = pipeline(Duration::from_secs(60))
+            .process_files(&[small.to_string_lossy().to_string()], options)
+            .unwrap();
+        assert_eq!(results.failed_files, 0);
+        assert_eq!(
+            fs::read_to_string(&small).unwrap(),
+            r#"<div className="flex p-4" />"#
+        );
+    }
+
+    #[test]
+    fn test_fail_fast_stops_at_first_unformatted_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let sorted = r#"export const A = () => <div className="flex p-4">A</div>;"#;
+        let unsorted = r#"export const B = () => <div className="p-4 flex">B</div>;"#;
+        let paths: Vec<String> = [
+            ("A.tsx", sorted),
+            ("B.tsx", unsorted),
+            ("C.tsx", sorted),
+            ("D.tsx", unsorted),
+        ]
+        .iter()
+        .map(|(name, content)| {
+            let path = temp_dir.path().join(name);
+            fs::write(&path, content).unwrap();
+            path.to_string_lossy().to_string()
+        })
+        .collect();
+
+        let options = ProcessOptions {
+            dry_run: false,
+            write: false,
+            check_formatted: true,
+        };
+
+        let results = FileProcessingPipeline::sequential(FileDiscoveryConfig::default())
+            .unwrap()
+            .with_fail_fast(true)
+            .process_files(&paths, options.clone())
+            .unwrap();
+        assert_eq!(results.total_files, 2);
+        assert_eq!(results.files_with_changes, 1);
+        assert_eq!(results.fail_fast_file, Some(temp_dir.path().join("B.tsx")));
+
+        // In parallel mode the first unformatted file to finish stops the run
+        let results = FileProcessingPipeline::parallel(FileDiscoveryConfig::default())
+            .unwrap()
+            .with_fail_fast(true)
+            .process_files(&paths, options)
+            .unwrap();
+        let stopped_at = results.fail_fast_file.unwrap();
+        assert!(stopped_at.ends_with("B.tsx") || stopped_at.ends_with("D.tsx"));
+        assert!(results.files_with_changes >= 1);
+    }
+
     #[test]
     fn test_parallel_error_handling() {
         let temp_dir = TempDir::new().unwrap();
@@ -0,0 +1,71 @@
+use crate::{Result, WindWardenError};
+use std::collections::HashSet;
+use std::fs;
+use std::path::PathBuf;
+use std::process::Command;
+
+/// Files that differ between a git ref and the working tree
+///
+/// Paths are canonical so they can be compared with discovered files.
+/// Deleted files are left out, and renamed files appear under their new
+/// name. Untracked files are not included.
+pub fn changed_files(since: &str) -> Result<HashSet<PathBuf>> {
+    if since.is_empty() || since.starts_with('-') {
+        return Err(WindWardenError::git_error(format!(
+            "Invalid git ref '{}'",
+            since
+        )));
+    }
+
+    run_git(&[
+        "rev-parse",
+        "--verify",
+        "--quiet",
+        &format!("{}^{{commit}}", since),
+    ])
+    .map_err(|_| WindWardenError::git_error(format!("Unknown git ref '{}'", since)))?;
+
+    let root = PathBuf::from(run_git(&["rev-parse", "--show-toplevel"])?.trim_end());
+    let output = run_git(&["diff", "--name-only", "-z", "--diff-filter=d", since, "--"])?;
+
+    Ok(output
+        .split('\0')
+        .filter(|path| !path.is_empty())
+        // Files deleted from the working tree since the diff have nothing to process
+        .filter_map(|path| fs::canonicalize(root.join(path)).ok())
+        .collect())
+}
+
+/// Run git in the current directory, returning its stdout
+fn run_git(args: &[&str]) -> Result<String> {
+    let output = Command::new("git")
+        .args(args)
+        .output()
+        .map_err(|e| WindWardenError::git_error(format!("Failed to run git: {}", e)))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(WindWardenError::git_error(format!(
+            "'git {}' failed: {}",
+            args.join(" "),
+            stderr.trim()
+        )));
+    }
+
+    String::from_utf8(output.stdout)
+        .map_err(|_| WindWardenError::git_error("git printed a path that is not valid UTF-8"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_option_like_refs_are_rejected() {
+        for since in ["", "--output=/tmp/x", "-p"] {
+            let error = changed_files(since).unwrap_err();
+            assert!(matches!(error, WindWardenError::Git { .. }));
+            assert!(error.to_string().contains("Invalid git ref"));
+        }
+    }
+}
@@ -1,13 +1,18 @@
 pub mod atomic;
+pub mod cache;
 #[path = "cli/mod.rs"]
 pub mod cli;
 pub mod config;
 pub mod diff;
 pub mod file_processor;
+pub mod git;
+pub mod logging;
+pub mod manifest;
 pub mod output;
 pub mod parser;
 pub mod processor;
 pub mod sorter;
+pub mod trace;
 pub mod utils;
 
 #[cfg(feature = "performance-profiling")]
@@ -18,6 +23,7 @@ use crate::file_processor::{
     BatchProcessingResults, FileDiscoveryConfig, FileProcessingPipeline, ProcessingMode,
 };
 use crate::processor::FileProcessor;
+pub use crate::processor::{ClassCounts, ProcessOutcome};
 use std::io::{self, Read};
 use thiserror::Error;
 
@@ -38,10 +44,12 @@ pub enum WindWardenError {
     #[error("File is not writable: {path}")]
     FileNotWritable { path: String },
 
-    #[error("Parse error in {file} at line {line}: {message}")]
+    #[error("Parse error in {file} at {}: {message}", parse_location(*.line, *.column))]
     ParseError {
         file: String,
         line: usize,
+        /// 1-based column, or None when only the line is known
+        column: Option<usize>,
         message: String,
     },
 
@@ -63,6 +71,9 @@ pub enum WindWardenError {
     #[error("Glob pattern error: {pattern} - {message}")]
     GlobPattern { pattern: String, message: String },
 
+    #[error("Git error: {message}")]
+    Git { message: String },
+
     #[error("Processing failed for {file_count} files: {summary}")]
     BatchProcessing { file_count: usize, summary: String },
 
@@ -76,6 +87,15 @@ pub enum WindWardenError {
     Internal { message: String },
 }
 
+/// Format a parse error position as "line N, column M", or "line N" when
+/// the column is unknown
+pub(crate) fn parse_location(line: usize, column: Option<usize>) -> String {
+    match column {
+        Some(column) => format!("line {}, column {}", line, column),
+        None => format!("line {}", line),
+    }
+}
+
 impl WindWardenError {
     /// Create a file not found error
     pub fn file_not_found(path: impl Into<String>) -> Self {
@@ -92,6 +112,22 @@ impl WindWardenError {
         Self::ParseError {
             file: file.into(),
             line,
+            column: None,
+            message: message.into(),
+        }
+    }
+
+    /// Create a parse error at a 1-based line and column
+    pub fn parse_error_at(
+        file: impl Into<String>,
+        line: usize,
+        column: usize,
+        message: impl Into<String>,
+    ) -> Self {
+        Self::ParseError {
+            file: file.into(),
+            line,
+            column: Some(column),
             message: message.into(),
         }
     }
@@ -126,6 +162,13 @@ impl WindWardenError {
         }
     }
 
+    /// Create a git error
+    pub fn git_error(message: impl Into<String>) -> Self {
+        Self::Git {
+            message: message.into(),
+        }
+    }
+
     /// Create an invalid UTF-8 error
     pub fn invalid_utf8(path: impl Into<String>) -> Self {
         Self::InvalidUtf8 { path: path.into() }
@@ -154,6 +197,7 @@ impl WindWardenError {
             Self::Config { .. }
             | Self::ThreadPool { .. }
             | Self::GlobPattern { .. }
+            | Self::Git { .. }
             | Self::BatchProcessing { .. }
             | Self::Cancelled
             | Self::Internal { .. }
@@ -179,11 +223,16 @@ impl WindWardenError {
             Self::ParseError {
                 file,
                 line,
+                column,
                 message,
             } => {
+                let position = match column {
+                    Some(column) => format!("{}:{}", line, column),
+                    None => line.to_string(),
+                };
                 format!(
-                    "❌ Parse error in {} at line {}: {}\n\n💡 Suggestions:\n   • Check the syntax around line {}\n   • Ensure proper quote matching\n   • Verify JSX/TSX syntax is valid",
-                    file, line, message, line
+                    "❌ Parse error in {}:{}: {}\n\n💡 Suggestions:\n   • Check the syntax around line {}\n   • Ensure proper quote matching\n   • Verify JSX/TSX syntax is valid",
+                    file, position, message, line
                 )
             }
             Self::UnsupportedFileType {
@@ -213,6 +262,12 @@ impl WindWardenError {
                     pattern, message
                 )
             }
+            Self::Git { message } => {
+                format!(
+                    "❌ Git error: {}\n\n💡 Suggestions:\n   • Run WindWarden inside a git repository\n   • Fetch the ref first (e.g. 'git fetch origin main')\n   • Check the ref with 'git rev-parse --verify <ref>'",
+                    message
+                )
+            }
             Self::BatchProcessing {
                 file_count,
                 summary,
@@ -284,12 +339,21 @@ pub fn process_file_with_config(
     processor.process_file(file_path, options)
 }
 
+/// File path assumed for stdin input when none is given
+pub const DEFAULT_STDIN_FILEPATH: &str = "stdin.tsx";
+
 pub fn process_stdin(options: ProcessOptions) -> Result<String> {
+    process_stdin_with_filepath(options, DEFAULT_STDIN_FILEPATH)
+}
+
+/// Process stdin as if it were the file at `file_path`, whose extension
+/// selects how the input is parsed
+pub fn process_stdin_with_filepath(options: ProcessOptions, file_path: &str) -> Result<String> {
     let mut input = String::new();
     io::stdin().read_to_string(&mut input)?;
 
     let processor = FileProcessor::new();
-    processor.process_content(&input, "stdin.tsx", options)
+    processor.process_content(&input, file_path, options)
 }
 
 pub fn process_stdin_with_config(
@@ -300,7 +364,62 @@ pub fn process_stdin_with_config(
     io::stdin().read_to_string(&mut input)?;
 
     let processor = FileProcessor::new_with_config(config);
-    processor.process_content(&input, "stdin.tsx", options)
+    processor.process_content(&input, DEFAULT_STDIN_FILEPATH, options)
+}
+
+/// Format a batch of snippets with one processor, as `--stdin-json` does
+///
+/// A snippet that fails to parse comes back unchanged with an `error`, so one
+/// bad buffer doesn't fail the whole batch.
+pub fn process_snippets(
+    requests: Vec<output::SnippetRequest>,
+    options: ProcessOptions,
+) -> Vec<output::SnippetResponse> {
+    let processor = FileProcessor::new();
+
+    requests
+        .into_iter()
+        .map(|request| {
+            let file_path = request
+                .filepath
+                .as_deref()
+                .unwrap_or(DEFAULT_STDIN_FILEPATH);
+            match processor.process_content(&request.content, file_path, options.clone()) {
+                Ok(content) => output::SnippetResponse {
+                    id: request.id,
+                    changed: content != request.content,
+                    content,
+                    error: None,
+                },
+                Err(e) => output::SnippetResponse {
+                    id: request.id,
+                    content: request.content,
+                    changed: false,
+                    error: Some(e.user_message()),
+                },
+            }
+        })
+        .collect()
+}
+
+/// Sort one class string the way WindWarden sorts class strings in files
+///
+/// Honors every sorter setting in `Config` (see `TailwindSorter::from_config`).
+///
+/// ```
+/// use windwarden::config::Config;
+///
+/// let config = Config::default();
+/// assert_eq!(
+///     windwarden::sort_class_string("p-4 flex hover:bg-blue-500", &config),
+///     "flex p-4 hover:bg-blue-500"
+/// );
+/// ```
+///
+/// Building a sorter is cheap but not free; to sort many strings with one
+/// configuration, build a [`sorter::TailwindSorter::from_config`] once.
+pub fn sort_class_string(classes: &str, config: &Config) -> String {
+    sorter::TailwindSorter::from_config(config).sort_classes(classes)
 }
 
 pub fn process_file_content(content: &str, file_path: &str) -> Result<String> {
@@ -308,6 +427,20 @@ pub fn process_file_content(content: &str, file_path: &str) -> Result<String> {
     processor.process_content(content, file_path, ProcessOptions::default())
 }
 
+/// Process content and describe each reordered class string
+///
+/// Returns the same content as `process_file_content` along with the line,
+/// column, original, and new text of every change and the class counts, for
+/// tools such as editor integrations or codemods built on WindWarden.
+pub fn process_content_detailed(
+    content: &str,
+    file_path: &str,
+    options: ProcessOptions,
+) -> Result<ProcessOutcome> {
+    let processor = FileProcessor::new();
+    processor.process_content_detailed(content, file_path, options)
+}
+
 /// Process multiple files or directories using the file processing pipeline
 pub fn process_files(paths: &[String], options: ProcessOptions) -> Result<BatchProcessingResults> {
     let config = FileDiscoveryConfig::default();
@@ -0,0 +1,168 @@
+use crate::cli::LogFormat;
+use log::kv::{Key, Value, VisitSource};
+use log::{Level, LevelFilter, Log, Metadata, Record};
+use serde_json::{Map, Value as JsonValue};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Writes diagnostic log records to stderr, as text or JSON lines
+///
+/// Result output never goes through the logger, so `--log-format` and
+/// `--format` are independent.
+pub struct Logger {
+    format: LogFormat,
+    level: LevelFilter,
+}
+
+impl Logger {
+    /// Log warnings and errors, and informational records too when verbose
+    pub fn new(format: LogFormat, verbose: bool) -> Self {
+        Self {
+            format,
+            level: if verbose {
+                LevelFilter::Info
+            } else {
+                LevelFilter::Warn
+            },
+        }
+    }
+
+    /// Install this logger as the global `log` backend
+    pub fn init(self) {
+        let level = self.level;
+        if log::set_boxed_logger(Box::new(self)).is_ok() {
+            log::set_max_level(level);
+        }
+    }
+
+    /// Format a record as one line (text records may span several)
+    pub fn format_record(&self, record: &Record) -> String {
+        match self.format {
+            LogFormat::Text => match record.level() {
+                Level::Error => record.args().to_string(),
+                Level::Warn => format!("Warning: {}", record.args()),
+                _ => format!("[windwarden] {}", record.args()),
+            },
+            LogFormat::Json => {
+                let mut fields = Map::new();
+                fields.insert(
+                    "timestamp_ms".to_string(),
+                    JsonValue::from(
+                        SystemTime::now()
+                            .duration_since(UNIX_EPOCH)
+                            .unwrap_or_default()
+                            .as_millis() as u64,
+                    ),
+                );
+                fields.insert(
+                    "level".to_string(),
+                    JsonValue::from(record.level().as_str().to_lowercase()),
+                );
+                let _ = record.key_values().visit(&mut JsonFields(&mut fields));
+                fields.insert(
+                    "message".to_string(),
+                    JsonValue::from(record.args().to_string()),
+                );
+                JsonValue::Object(fields).to_string()
+            }
+        }
+    }
+}
+
+impl Log for Logger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= self.level
+    }
+
+    fn log(&self, record: &Record) {
+        if self.enabled(record.metadata()) {
+            eprintln!("{}", self.format_record(record));
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+/// Collects a record's key-values as JSON fields
+struct JsonFields<'a>(&'a mut Map<String, JsonValue>);
+
+impl<'kvs> VisitSource<'kvs> for JsonFields<'_> {
+    fn visit_pair(&mut self, key: Key<'kvs>, value: Value<'kvs>) -> Result<(), log::kv::Error> {
+        let value = if let Some(number) = value.to_u64() {
+            JsonValue::from(number)
+        } else if let Some(flag) = value.to_bool() {
+            JsonValue::from(flag)
+        } else {
+            JsonValue::from(value.to_string())
+        };
+        self.0.insert(key.as_str().to_string(), value);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn format(logger: &Logger, level: Level, kvs: &[(&str, Value)], message: &str) -> String {
+        let kvs: Vec<(&str, Value)> = kvs.to_vec();
+        logger.format_record(
+            &Record::builder()
+                .level(level)
+                .key_values(&kvs)
+                .args(format_args!("{}", message))
+                .build(),
+        )
+    }
+
+    #[test]
+    fn test_text_records() {
+        let logger = Logger::new(LogFormat::Text, true);
+        assert_eq!(
+            format(&logger, Level::Info, &[], "src/App.tsx: changed"),
+            "[windwarden] src/App.tsx: changed"
+        );
+        assert_eq!(
+            format(&logger, Level::Warn, &[], "symlink loop"),
+            "Warning: symlink loop"
+        );
+        assert_eq!(format(&logger, Level::Error, &[], "❌ Failed"), "❌ Failed");
+    }
+
+    #[test]
+    fn test_json_records() {
+        let logger = Logger::new(LogFormat::Json, true);
+        let line = format(
+            &logger,
+            Level::Info,
+            &[
+                ("event", Value::from("processed")),
+                ("file", Value::from("src/App.tsx")),
+                ("duration_ms", Value::from(12u64)),
+                ("changed", Value::from(true)),
+            ],
+            "src/App.tsx: changed",
+        );
+
+        let record: JsonValue = serde_json::from_str(&line).unwrap();
+        assert_eq!(record["level"], "info");
+        assert_eq!(record["event"], "processed");
+        assert_eq!(record["file"], "src/App.tsx");
+        assert_eq!(record["duration_ms"], 12);
+        assert_eq!(record["changed"], true);
+        assert_eq!(record["message"], "src/App.tsx: changed");
+        assert!(record["timestamp_ms"].is_u64());
+    }
+
+    #[test]
+    fn test_level_filter() {
+        let metadata = |level| Metadata::builder().level(level).build();
+
+        let quiet = Logger::new(LogFormat::Text, false);
+        assert!(quiet.enabled(&metadata(Level::Warn)));
+        assert!(!quiet.enabled(&metadata(Level::Info)));
+
+        let verbose = Logger::new(LogFormat::Json, true);
+        assert!(verbose.enabled(&metadata(Level::Info)));
+        assert!(!verbose.enabled(&metadata(Level::Debug)));
+    }
+}
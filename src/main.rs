@@ -1,86 +1,280 @@
 use clap::{CommandFactory, Parser};
-use std::io;
+use std::collections::BTreeSet;
+use std::io::{self, IsTerminal, Read, Write};
+use std::path::{Path, PathBuf};
 use std::process;
-use std::time::Instant;
-use windwarden::cli::{Cli, Commands, ConfigAction, OperationMode, ProcessingMode, Shell};
-use windwarden::config::ConfigManager;
-use windwarden::file_processor::{FileDiscovery, FileDiscoveryConfig, FileProcessingPipeline};
-use windwarden::output::{OutputFormatter, ProgressReporter, ProgressTracker};
-use windwarden::{ProcessOptions, WindWardenError, process_stdin};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+use windwarden::cache::{DEFAULT_CACHE_FILE, ProcessingCache};
+use windwarden::cli::{
+    Cli, Commands, ConfigAction, DiffStyle, OperationMode, ProcessingMode, ProgressFormat,
+    ReportFormat, Shell,
+};
+use windwarden::config::{
+    CONFIG_ENV_VAR, Config, ConfigManager, ConfigOverrides, validate_verify_exit_code,
+};
+use windwarden::diff::{DEFAULT_DIFF_WIDTH, DiffFormatter, FileDiff, terminal_width};
+use windwarden::file_processor::{
+    BatchProcessingResults, FileDiscovery, FileDiscoveryConfig, FileProcessingPipeline,
+};
+use windwarden::logging::Logger;
+use windwarden::manifest::Manifest;
+use windwarden::output::formats::junit;
+use windwarden::output::reporters::Reporter;
+use windwarden::output::{
+    BatchInputError, CheckReport, DiagnosticReport, ERROR_EXIT_CODE, OutputFormat, OutputFormatter,
+    ProgressReporter, ProgressTracker, SnippetRequest, SummaryReport,
+};
+use windwarden::processor::{FileProcessor, UnknownClass};
+use windwarden::sorter::UnknownClassSeverity;
+use windwarden::trace::Tracer;
+use windwarden::utils::glob::build_glob_set;
+use windwarden::{DEFAULT_STDIN_FILEPATH, ProcessOptions, WindWardenError};
 
 #[derive(Debug, Clone)]
 struct CommandOptions {
     processing_mode: ProcessingMode,
     threads: Option<usize>,
+    thread_scaling: Option<f64>,
+    max_concurrent_reads: Option<usize>,
+    max_files: Option<usize>,
+    force: bool,
+    per_file_timeout: Option<u64>,
+    verify_exit_code: Option<i32>,
     extensions: Option<Vec<String>>,
+    since: Option<String>,
+    manifest: Option<PathBuf>,
+    include: Option<Vec<String>>,
     exclude: Option<Vec<String>>,
+    exclude_dirs: Option<Vec<String>>,
     max_depth: Option<usize>,
     follow_links: bool,
     show_stats: bool,
     show_progress: bool,
+    progress_format: ProgressFormat,
     show_diff: bool,
+    diff_context: usize,
+    diff_style: DiffStyle,
+    format: ReportFormat,
+    output: Option<PathBuf>,
+    strict_encoding: bool,
+    fail_fast: bool,
+    summary_line: bool,
+    dry_run: bool,
+    explain: bool,
+    max_in_memory: Option<usize>,
+    trace_file: Option<PathBuf>,
+    verbose: bool,
+    cache_file: Option<PathBuf>,
+    report_unused_functions: bool,
+    print_config: bool,
+    interactive: bool,
+    on_change: Option<String>,
+    stdin_filepath: Option<String>,
 }
 
 fn main() {
     let cli = Cli::parse();
+    Logger::new(cli.log_format, cli.verbose).init();
+
+    let explains = matches!(cli.command, Some(Commands::Format { explain: true, .. }));
+    if cli.stdin_filepath.is_some() && !cli.stdin && !explains {
+        log::error!("--stdin-filepath can only be used with --stdin or --explain -");
+        process::exit(ERROR_EXIT_CODE);
+    }
+
+    // Doctor reports configuration errors as a failed check instead of exiting
+    if matches!(cli.command, Some(Commands::Doctor)) {
+        process::exit(handle_doctor_command(&cli));
+    }
 
     // Load configuration
-    let config_manager = match load_configuration(&cli) {
+    let mut config_manager = match load_configuration(&cli) {
         Ok(manager) => manager,
         Err(e) => {
-            eprintln!("{}", e.user_message());
-            process::exit(1);
+            log::error!("{}", e.user_message());
+            process::exit(ERROR_EXIT_CODE);
         }
     };
+    config_manager.merge_with_cli_args(&cli);
 
     let result = match &cli.command {
         Some(Commands::Format {
             paths,
             mode,
+            fix,
+            check,
             processing,
             threads,
+            thread_scaling,
+            max_concurrent_reads,
+            max_files,
+            force,
+            per_file_timeout,
+            verify_exit_code,
+            no_parallel_io,
             extensions,
+            since,
+            manifest,
+            include,
             exclude,
+            exclude_dir,
             max_depth,
             follow_links,
             stats,
             progress,
+            progress_format,
             diff,
+            diff_context,
+            diff_style,
+            format,
+            output,
+            strict_encoding,
+            fail_fast,
+            summary_line,
+            dry_run,
+            explain,
+            interactive,
+            on_change,
+            max_in_memory,
+            cache,
+            cache_file,
+            report_unused_functions,
+            print_config,
         }) => {
             let options = CommandOptions {
                 processing_mode: *processing,
                 threads: *threads,
+                thread_scaling: *thread_scaling,
+                max_concurrent_reads: if *no_parallel_io {
+                    Some(1)
+                } else {
+                    *max_concurrent_reads
+                },
+                max_files: *max_files,
+                force: *force,
+                per_file_timeout: *per_file_timeout,
+                verify_exit_code: *verify_exit_code,
                 extensions: extensions.clone(),
+                since: since.clone(),
+                manifest: manifest.clone(),
+                include: include.clone(),
                 exclude: exclude.clone(),
+                exclude_dirs: exclude_dir.clone(),
                 max_depth: *max_depth,
                 follow_links: *follow_links,
                 show_stats: *stats,
                 show_progress: *progress,
+                progress_format: *progress_format,
                 show_diff: *diff,
+                diff_context: *diff_context,
+                diff_style: *diff_style,
+                format: *format,
+                output: output.clone(),
+                strict_encoding: *strict_encoding,
+                fail_fast: *fail_fast,
+                summary_line: *summary_line,
+                dry_run: *dry_run,
+                explain: *explain,
+                max_in_memory: *max_in_memory,
+                trace_file: cli.trace_file.clone(),
+                verbose: cli.verbose,
+                cache_file: cache_file_path(*cache, cache_file),
+                report_unused_functions: *report_unused_functions,
+                print_config: *print_config,
+                interactive: *interactive,
+                on_change: on_change.clone(),
+                stdin_filepath: cli.stdin_filepath.clone(),
             };
-            handle_format_command(&config_manager, paths, *mode, &options)
+            let mode = OperationMode::from_flags(*mode, *fix, *check);
+            match mode.map_or_else(|| config_manager.default_mode(), |mode| Ok(Some(mode))) {
+                Ok(mode) => handle_format_command(
+                    &config_manager,
+                    paths,
+                    mode.unwrap_or(OperationMode::Check),
+                    &options,
+                ),
+                Err(e) => Err(e.into()),
+            }
         }
 
         Some(Commands::Check {
             paths,
             processing,
             threads,
+            thread_scaling,
+            max_concurrent_reads,
+            max_files,
+            force,
+            per_file_timeout,
+            verify_exit_code,
+            no_parallel_io,
             extensions,
+            since,
+            manifest,
+            include,
             exclude,
+            exclude_dir,
             stats,
             progress,
+            progress_format,
             diff,
+            diff_context,
+            diff_style,
+            format,
+            output,
+            strict_encoding,
+            fail_fast,
+            summary_line,
+            max_in_memory,
+            cache,
+            cache_file,
+            report_unused_functions,
+            print_config,
         }) => {
             let options = CommandOptions {
                 processing_mode: *processing,
                 threads: *threads,
+                thread_scaling: *thread_scaling,
+                max_concurrent_reads: if *no_parallel_io {
+                    Some(1)
+                } else {
+                    *max_concurrent_reads
+                },
+                max_files: *max_files,
+                force: *force,
+                per_file_timeout: *per_file_timeout,
+                verify_exit_code: *verify_exit_code,
                 extensions: extensions.clone(),
+                since: since.clone(),
+                manifest: manifest.clone(),
+                include: include.clone(),
                 exclude: exclude.clone(),
+                exclude_dirs: exclude_dir.clone(),
                 max_depth: None,
                 follow_links: false,
                 show_stats: *stats,
                 show_progress: *progress,
+                progress_format: *progress_format,
                 show_diff: *diff,
+                diff_context: *diff_context,
+                diff_style: *diff_style,
+                format: *format,
+                output: output.clone(),
+                strict_encoding: *strict_encoding,
+                fail_fast: *fail_fast,
+                summary_line: *summary_line,
+                dry_run: false,
+                explain: false,
+                max_in_memory: *max_in_memory,
+                trace_file: cli.trace_file.clone(),
+                verbose: cli.verbose,
+                cache_file: cache_file_path(*cache, cache_file),
+                report_unused_functions: *report_unused_functions,
+                print_config: *print_config,
+                interactive: false,
+                on_change: None,
+                stdin_filepath: None,
             };
             handle_check_command(&config_manager, paths, &options)
         }
@@ -89,26 +283,13 @@ fn main() {
 
         Some(Commands::Completions { shell }) => handle_completions_command(*shell),
 
+        Some(Commands::Doctor) => unreachable!("doctor is handled before loading configuration"),
+
         None => {
-            if cli.stdin {
-                // Stdin processing
-                let options = ProcessOptions {
-                    dry_run: false,
-                    write: false, // stdin always outputs to stdout
-                    check_formatted: false,
-                };
-                match process_stdin(options) {
-                    Ok(output) => {
-                        if !output.is_empty() {
-                            println!("{}", output);
-                        }
-                        Ok(0)
-                    }
-                    Err(e) => {
-                        eprintln!("{}", e.user_message());
-                        Ok(1)
-                    }
-                }
+            if cli.stdin_json {
+                handle_stdin_json(&cli)
+            } else if cli.stdin {
+                handle_stdin(&cli)
             } else {
                 eprintln!("Error: Must specify a command or use --stdin");
                 eprintln!("Try 'windwarden --help' for more information.");
@@ -122,11 +303,11 @@ fn main() {
         Err(e) => {
             // Try to downcast to WindWardenError to get user-friendly message
             if let Some(ww_error) = e.downcast_ref::<WindWardenError>() {
-                eprintln!("{}", ww_error.user_message());
+                log::error!("{}", ww_error.user_message());
             } else {
-                eprintln!("Error: {}", e);
+                log::error!("Error: {}", e);
             }
-            process::exit(1);
+            process::exit(ERROR_EXIT_CODE);
         }
     }
 }
@@ -137,6 +318,16 @@ fn handle_format_command(
     mode: OperationMode,
     options: &CommandOptions,
 ) -> Result<i32, Box<dyn std::error::Error>> {
+    if options.print_config {
+        let resolved = resolve_command_config(config_manager, options).resolved();
+        println!("{}", serde_json::to_string_pretty(&resolved)?);
+        return Ok(0);
+    }
+
+    if options.explain {
+        return explain_classes(config_manager, paths, options);
+    }
+
     let start_time = Instant::now();
 
     // Build file discovery config
@@ -144,25 +335,80 @@ fn handle_format_command(
 
     if let Some(exts) = &options.extensions {
         config.extensions = exts.clone();
+    } else {
+        if config_manager.config().html {
+            config
+                .extensions
+                .extend(["html".to_string(), "htm".to_string()]);
+        }
+        if config_manager.config().css {
+            config
+                .extensions
+                .extend(["css".to_string(), "scss".to_string()]);
+        }
     }
 
-    if let Some(patterns) = &options.exclude {
-        config.exclude_patterns.extend(patterns.clone());
+    config.include_patterns = options
+        .include
+        .clone()
+        .unwrap_or_else(|| config_manager.config().include_paths.clone());
+
+    if let Some(dirs) = &options.exclude_dirs {
+        config.exclude_dirs.extend(dirs.clone());
     }
 
     // Add ignore patterns from JSON configuration
-    config.exclude_patterns.extend(
-        config_manager
-            .get_ignore_patterns()
-            .iter()
-            .map(|p| format!("{}/**", p)), // Convert directory names to glob patterns
-    );
+    config
+        .exclude_patterns
+        .extend(config_manager.get_ignore_patterns().iter().flat_map(
+            |p| match p.strip_prefix('!') {
+                // Negations re-include the path itself as well as anything beneath it
+                Some(rest) => vec![p.clone(), format!("!{}/**", rest)],
+                None => vec![format!("{}/**", p)], // Convert directory names to glob patterns
+            },
+        ));
+
+    // CLI patterns come last so their negations can override the configuration
+    if let Some(patterns) = &options.exclude {
+        config.exclude_patterns.extend(patterns.clone());
+    }
 
     config.max_depth = options.max_depth;
     config.follow_links = options.follow_links;
 
+    if let Some(since) = &options.since {
+        config.changed_files = Some(windwarden::git::changed_files(since)?);
+    }
+
+    // Without paths, a manifest names the files to process itself
+    let manifest_paths: Vec<String>;
+    let mut paths = paths;
+    if let Some(manifest_path) = &options.manifest {
+        let manifest = Manifest::load(manifest_path)?;
+        for path in manifest.unknown_paths() {
+            log::warn!(
+                "Manifest {} lists a file that does not exist: {}",
+                manifest_path.display(),
+                path.display()
+            );
+        }
+
+        config.selected_files = Some(manifest.selected_files());
+
+        if paths.is_empty() {
+            manifest_paths = manifest
+                .files_to_sort()
+                .filter(|path| path.is_file())
+                .map(|path| path.display().to_string())
+                .collect();
+            paths = &manifest_paths;
+        }
+    }
+
     // Create processing pipeline
+    // Interactive runs prompt between files, so files are processed in order
     let pipeline_mode = match (options.processing_mode, options.threads) {
+        _ if options.interactive => windwarden::file_processor::ProcessingMode::Sequential,
         (_, Some(n)) => windwarden::file_processor::ProcessingMode::ParallelWithThreads(n),
         (ProcessingMode::Sequential, None) => {
             windwarden::file_processor::ProcessingMode::Sequential
@@ -170,19 +416,103 @@ fn handle_format_command(
         (ProcessingMode::Parallel, None) => windwarden::file_processor::ProcessingMode::Parallel,
     };
 
-    let pipeline = FileProcessingPipeline::new_with_windwarden_config(
+    let tracer = match &options.trace_file {
+        Some(path) => Tracer::to_file(path)?,
+        None => Tracer::disabled(),
+    }
+    .with_verbose(options.verbose);
+
+    let mut pipeline = FileProcessingPipeline::new_with_windwarden_config(
         config.clone(),
         config_manager.config(),
         pipeline_mode,
-    )?;
+    )?
+    .with_config_dir(config_manager.config_dir())
+    .with_tracer(tracer.clone())
+    .with_max_in_memory(options.max_in_memory)
+    .with_fail_fast(options.fail_fast)
+    .with_strict_encoding(options.strict_encoding)
+    .with_max_files(if options.force {
+        None
+    } else {
+        options.max_files.or(config_manager.config().max_files)
+    })
+    .with_thread_scaling(options.thread_scaling)
+    .with_max_concurrent_reads(
+        options
+            .max_concurrent_reads
+            .or(config_manager.config().max_concurrent_reads),
+    )
+    .with_per_file_timeout(
+        Some(
+            options
+                .per_file_timeout
+                .unwrap_or(config_manager.config().per_file_timeout_ms),
+        )
+        .filter(|&ms| ms > 0)
+        .map(Duration::from_millis),
+    );
+
+    if let Some(cache_file) = &options.cache_file {
+        pipeline = pipeline.with_cache(ProcessingCache::load(cache_file, config_manager.config()));
+    }
 
     // Validate inputs
-    if paths.is_empty() {
+    if paths.is_empty() && options.manifest.is_none() {
         return Err(Box::new(WindWardenError::config_error(
             "No paths specified",
         )));
     }
 
+    if options.fail_fast && !matches!(mode, OperationMode::Verify) {
+        return Err(Box::new(WindWardenError::config_error(
+            "--fail-fast can only be used with --mode verify",
+        )));
+    }
+
+    if options.summary_line
+        && !(matches!(mode, OperationMode::Verify) && matches!(options.format, ReportFormat::Text))
+    {
+        return Err(Box::new(WindWardenError::config_error(
+            "--summary-line can only be used with --mode verify and --format text",
+        )));
+    }
+
+    if matches!(
+        options.format,
+        ReportFormat::Check | ReportFormat::CheckJson
+    ) && matches!(mode, OperationMode::Write)
+    {
+        return Err(Box::new(WindWardenError::config_error(
+            "--format check and check-json list files that need formatting, so they can't be used with --mode write",
+        )));
+    }
+
+    if options.dry_run && !matches!(mode, OperationMode::Write) {
+        return Err(Box::new(WindWardenError::config_error(
+            "--dry-run can only be used with --mode write",
+        )));
+    }
+
+    if options.interactive {
+        if !matches!(mode, OperationMode::Write) {
+            return Err(Box::new(WindWardenError::config_error(
+                "--interactive can only be used with --mode write",
+            )));
+        }
+        if !io::stdin().is_terminal() {
+            return Err(Box::new(WindWardenError::config_error(
+                "--interactive needs a terminal to read answers from; use --mode write without --interactive to write every file",
+            )));
+        }
+    }
+
+    if options.on_change.is_some() && !matches!(mode, OperationMode::Write) {
+        return Err(Box::new(WindWardenError::config_error(
+            "--on-change can only be used with --mode write",
+        )));
+    }
+
     if let Some(thread_count) = options.threads {
         if thread_count == 0 {
             return Err(Box::new(WindWardenError::config_error(
@@ -196,24 +526,40 @@ fn handle_format_command(
         }
     }
 
-    // Set up process options based on operation mode
-    let process_options = match mode {
-        OperationMode::Check => ProcessOptions {
-            dry_run: true,
-            write: false,
-            check_formatted: false,
-        },
-        OperationMode::Write => ProcessOptions {
-            dry_run: false,
-            write: true,
-            check_formatted: false,
-        },
-        OperationMode::Verify => ProcessOptions {
-            dry_run: false,
-            write: false,
-            check_formatted: true,
-        },
-    };
+    if options.max_concurrent_reads == Some(0) {
+        return Err(Box::new(WindWardenError::config_error(
+            "--max-concurrent-reads must be at least 1",
+        )));
+    }
+
+    if options.max_files == Some(0) {
+        return Err(Box::new(WindWardenError::config_error(
+            "--max-files must be at least 1",
+        )));
+    }
+
+    if let Some(code) = options.verify_exit_code {
+        validate_verify_exit_code(code)
+            .map_err(|msg| WindWardenError::config_error(format!("--verify-exit-code {}", msg)))?;
+    }
+
+    if let Some(fraction) = options.thread_scaling {
+        if !(fraction > 0.0 && fraction <= 1.0) {
+            return Err(Box::new(WindWardenError::config_error(format!(
+                "--thread-scaling must be greater than 0 and at most 1, got {}",
+                fraction
+            ))));
+        }
+        if matches!(options.processing_mode, ProcessingMode::Sequential) {
+            return Err(Box::new(WindWardenError::config_error(
+                "--thread-scaling cannot be used with --processing sequential",
+            )));
+        }
+    }
+
+    // Set up process options based on operation mode. Interactive runs
+    // process like a dry run and write confirmed files afterwards
+    let process_options = mode.process_options(options.dry_run || options.interactive);
 
     // Set up progress reporting if requested
     let (results, duration) = if options.show_progress {
@@ -223,18 +569,24 @@ fn handle_format_command(
             temp_discovery.discover_files(paths)?
         };
 
-        if discovered_files.len() > 5 {
-            // Show progress for larger file counts
-            let progress_reporter = ProgressReporter::new(discovered_files.len(), true);
+        let json_progress = options.progress_format == ProgressFormat::Json;
+        if discovered_files.len() > 5 || (json_progress && !discovered_files.is_empty()) {
+            // Show progress for larger file counts, and always for consumers
+            // reading JSON progress
+            let mut progress_reporter = ProgressReporter::new(discovered_files.len(), true)
+                .with_format(options.progress_format);
             let progress_tracker = ProgressTracker::new(progress_reporter.get_counter());
 
-            eprintln!("Processing {} files...", discovered_files.len());
-
-            let results = pipeline.process_files_with_progress(
-                paths,
-                process_options,
-                Some(progress_tracker),
-            )?;
+            let process = || {
+                pipeline.process_files_with_progress(paths, process_options, Some(progress_tracker))
+            };
+            let results = if json_progress {
+                progress_reporter.display_progress();
+                with_periodic_progress(&mut progress_reporter, process)?
+            } else {
+                eprintln!("Processing {} files...", discovered_files.len());
+                process()?
+            };
             let duration = start_time.elapsed();
 
             // Show final progress
@@ -252,17 +604,617 @@ fn handle_format_command(
         (results, duration)
     };
 
+    tracer.flush();
+    pipeline.save_cache()?;
+
+    for warning in &results.warnings {
+        log::warn!("{}", warning);
+    }
+
+    // JSON reports list unknown classes as issues of their own
+    if !matches!(options.format, ReportFormat::Json) {
+        report_unknown_classes(&results);
+    }
+
+    if let Some(file) = &results.fail_fast_file {
+        log::warn!(
+            "Stopped at first unformatted file: {} (--fail-fast)",
+            file.display()
+        );
+    }
+
+    if options.report_unused_functions {
+        eprintln!(
+            "{}",
+            unused_functions_report(
+                config_manager.config(),
+                &results.called_functions,
+                results.files_with_incomplete_calls
+            )
+        );
+    }
+
+    if options.interactive {
+        return write_interactively(config_manager.config(), &results, options);
+    }
+
+    // Hooks run once every file has been written
+    let hooks_failed = match &options.on_change {
+        Some(command) if matches!(mode, OperationMode::Write) && !options.dry_run => {
+            !run_on_change(command, results.changed_files())
+        }
+        _ => false,
+    };
+
+    // Results written to a file are plain text
+    let to_file = options.output.is_some();
+    if to_file {
+        colored::control::set_override(false);
+    }
+    let mut writer = result_writer(options.output.as_deref())?;
+
     // Format and display results
-    let formatter = OutputFormatter::new(options.show_stats).with_diff(options.show_diff);
-    let output = match mode {
-        OperationMode::Check => formatter.format_check_results(&results, Some(duration)),
-        OperationMode::Write => formatter.format_write_results(&results, Some(duration)),
-        OperationMode::Verify => formatter.format_verify_results(&results, Some(duration)),
+    let formatter = OutputFormatter::new(options.show_stats)
+        .with_diff(options.show_diff || options.dry_run)
+        .with_diff_context(options.diff_context)
+        .with_diff_style(options.diff_style)
+        .with_colors(config_manager.config().colored_output && !to_file)
+        .with_summary_line(options.summary_line)
+        .with_verify_exit_code(
+            options
+                .verify_exit_code
+                .unwrap_or(config_manager.config().verify_exit_code),
+        );
+    let exit_code = if hooks_failed {
+        ERROR_EXIT_CODE
+    } else {
+        formatter.get_exit_code(&mode, &results)
+    };
+    let output = match (options.format, mode) {
+        (ReportFormat::Summary | ReportFormat::SummaryJson, _) => {
+            let output_format = match options.format {
+                ReportFormat::SummaryJson => OutputFormat::Summary,
+                _ => OutputFormat::Text,
+            };
+            Reporter::new(output_format, writer)
+                .report_summary(&SummaryReport::from_results(&results))?;
+            return Ok(exit_code);
+        }
+        (ReportFormat::Check | ReportFormat::CheckJson, _) => {
+            let output_format = match options.format {
+                ReportFormat::CheckJson => OutputFormat::Json,
+                _ => OutputFormat::Check,
+            };
+            Reporter::new(output_format, writer)
+                .report_check(&CheckReport::from_results(&results))?;
+            return Ok(exit_code);
+        }
+        (ReportFormat::Json, _) => {
+            let report = DiagnosticReport::from_results(&results, options.dry_run);
+            Reporter::new(OutputFormat::Json, writer).report_diagnostics(&report)?;
+            return Ok(exit_code);
+        }
+        (ReportFormat::Junit, mode) => junit::format_junit(
+            &results,
+            Some(duration),
+            !matches!(mode, OperationMode::Write),
+        ),
+        (ReportFormat::Text, OperationMode::Check) => {
+            formatter.format_check_results(&results, Some(duration))
+        }
+        // Dry runs show the diff of what would have been written
+        (ReportFormat::Text, OperationMode::Write) if options.dry_run => {
+            formatter.format_check_results(&results, Some(duration))
+        }
+        (ReportFormat::Text, OperationMode::Write) => {
+            formatter.format_write_results(&results, Some(duration))
+        }
+        (ReportFormat::Text, OperationMode::Verify) => {
+            formatter.format_verify_results(&results, Some(duration))
+        }
+    };
+
+    writeln!(writer, "{}", output)?;
+    writer.flush()?;
+
+    Ok(exit_code)
+}
+
+/// Log the classes that match no utility, with where their class strings are
+fn report_unknown_classes(results: &BatchProcessingResults) {
+    for result in &results.results {
+        for unknown in &result.unknown_classes {
+            log_unknown_class(result.file_path.display(), unknown);
+        }
+    }
+}
+
+fn log_unknown_class(file: impl std::fmt::Display, unknown: &UnknownClass) {
+    let message = format!(
+        "{}:{}:{}: unknown class '{}'",
+        file, unknown.line, unknown.column, unknown.class
+    );
+    match unknown.severity {
+        UnknownClassSeverity::Error => log::error!("{}", message),
+        UnknownClassSeverity::Warn | UnknownClassSeverity::Ignore => log::warn!("{}", message),
+    }
+}
+
+/// Run the `--on-change` command once per written file, one file at a time
+///
+/// The file path is appended to the command as its last argument. The
+/// command's output goes to stderr so reports on stdout stay parseable.
+/// Returns whether every run exited successfully.
+fn run_on_change<'a>(command: &str, files: impl Iterator<Item = &'a Path>) -> bool {
+    let mut succeeded = true;
+
+    for file in files {
+        match shell_command(command, file).stdout(io::stderr()).status() {
+            Ok(status) if status.success() => {
+                log::debug!("--on-change `{}` succeeded for {}", command, file.display());
+            }
+            Ok(status) => {
+                log::error!(
+                    "--on-change `{}` failed for {} ({})",
+                    command,
+                    file.display(),
+                    status
+                );
+                succeeded = false;
+            }
+            Err(e) => {
+                log::error!(
+                    "Failed to run --on-change `{}` for {}: {}",
+                    command,
+                    file.display(),
+                    e
+                );
+                succeeded = false;
+            }
+        }
+    }
+
+    succeeded
+}
+
+/// Build a shell invocation of `command` with `file` appended as an argument
+fn shell_command(command: &str, file: &Path) -> process::Command {
+    #[cfg(windows)]
+    {
+        let mut shell = process::Command::new("cmd");
+        shell
+            .arg("/C")
+            .arg(format!("{} \"{}\"", command, file.display()));
+        shell
+    }
+    #[cfg(not(windows))]
+    {
+        // "$@" passes the path through without the shell splitting it
+        let mut shell = process::Command::new("sh");
+        shell
+            .arg("-c")
+            .arg(format!("{} \"$@\"", command))
+            .arg("sh")
+            .arg(file);
+        shell
+    }
+}
+
+/// Run `work` while another thread shows the reporter's progress at its
+/// update interval
+fn with_periodic_progress<T>(reporter: &mut ProgressReporter, work: impl FnOnce() -> T) -> T {
+    let done = AtomicBool::new(false);
+    let interval = reporter.update_interval();
+
+    std::thread::scope(|scope| {
+        scope.spawn(|| {
+            loop {
+                std::thread::sleep(interval);
+                if done.load(Ordering::Relaxed) {
+                    break;
+                }
+                reporter.update();
+            }
+        });
+
+        let result = work();
+        done.store(true, Ordering::Relaxed);
+        result
+    })
+}
+
+/// Where result output goes: the `--output` file, created along with its
+/// parent directories, or stdout
+fn result_writer(output: Option<&Path>) -> Result<Box<dyn Write>, WindWardenError> {
+    let Some(path) = output else {
+        return Ok(Box::new(io::stdout()));
+    };
+
+    if let Some(parent) = path
+        .parent()
+        .filter(|parent| !parent.as_os_str().is_empty())
+    {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| WindWardenError::from_io_error(e, Some(&parent.display().to_string())))?;
+    }
+    let file = std::fs::File::create(path)
+        .map_err(|e| WindWardenError::from_io_error(e, Some(&path.display().to_string())))?;
+    Ok(Box::new(io::BufWriter::new(file)))
+}
+
+/// Answer to the `--interactive` prompt
+enum PromptAnswer {
+    Apply,
+    Skip,
+    ApplyAll,
+    Quit,
+}
+
+impl PromptAnswer {
+    /// Parse an answer; an empty answer takes the default, skipping the file
+    fn parse(input: &str) -> Option<Self> {
+        match input.trim().to_lowercase().as_str() {
+            "y" | "yes" => Some(Self::Apply),
+            "" | "n" | "no" => Some(Self::Skip),
+            "a" | "all" => Some(Self::ApplyAll),
+            "q" | "quit" => Some(Self::Quit),
+            _ => None,
+        }
+    }
+}
+
+/// Show the diff of each changed file and write the ones the user accepts
+///
+/// `results` come from a dry run, so every file is written with the content
+/// shown in its diff. Returns the exit code: 2 when any file failed to
+/// process or write or an `--on-change` command failed, 0 otherwise.
+fn write_interactively(
+    config: &Config,
+    results: &BatchProcessingResults,
+    options: &CommandOptions,
+) -> Result<i32, Box<dyn std::error::Error>> {
+    let processor = FileProcessor::new_with_config(config);
+    let diff_formatter = DiffFormatter::new()
+        .with_context(options.diff_context)
+        .with_style(options.diff_style)
+        .with_width(terminal_width().unwrap_or(DEFAULT_DIFF_WIDTH))
+        .with_colors(config.colored_output);
+
+    let changed: Vec<_> = results
+        .results
+        .iter()
+        .filter(|result| result.success && result.changes_made)
+        .collect();
+    let mut failed = results.failed_files > 0;
+    let mut written = Vec::new();
+    let mut apply_all = false;
+
+    for (index, result) in changed.iter().enumerate() {
+        let (Some(original), Some(processed)) =
+            (&result.original_content, &result.processed_content)
+        else {
+            continue;
+        };
+        let path = result.file_path.to_string_lossy();
+
+        if !apply_all {
+            let diff = FileDiff::new(path.to_string(), original.clone(), processed.clone());
+            println!("{}", diff_formatter.format_diff(&diff));
+
+            let answer = loop {
+                print!(
+                    "Apply changes to {} ({}/{})? [y/N/a/q] ",
+                    path,
+                    index + 1,
+                    changed.len()
+                );
+                io::stdout().flush()?;
+
+                let mut line = String::new();
+                if io::stdin().read_line(&mut line)? == 0 {
+                    // End of input quits rather than skipping every remaining file
+                    println!();
+                    break PromptAnswer::Quit;
+                }
+                match PromptAnswer::parse(&line) {
+                    Some(answer) => break answer,
+                    None => {
+                        println!("Please answer y (apply), n (skip), a (apply all), or q (quit)")
+                    }
+                }
+            };
+
+            match answer {
+                PromptAnswer::Apply => {}
+                PromptAnswer::Skip => continue,
+                PromptAnswer::ApplyAll => apply_all = true,
+                PromptAnswer::Quit => break,
+            }
+        }
+
+        match processor.write_processed(&path, original, processed) {
+            Ok(()) => written.push(result.file_path.as_path()),
+            Err(e) => {
+                log::error!("Failed to write {}: {}", path, e.user_message());
+                failed = true;
+            }
+        }
+    }
+
+    println!(
+        "Applied changes to {} of {} changed files",
+        written.len(),
+        changed.len()
+    );
+
+    if let Some(command) = &options.on_change
+        && !run_on_change(command, written.into_iter())
+    {
+        failed = true;
+    }
+    Ok(if failed { ERROR_EXIT_CODE } else { 0 })
+}
+
+/// Describe which configured functions were never called
+///
+/// Only functions added through `functionNames` and `functionArgIndices`
+/// are checked; the built-in ones are always available. When some files
+/// couldn't be searched for calls, uncalled functions aren't claimed to be
+/// unused.
+fn unused_functions_report(
+    config: &Config,
+    called: &BTreeSet<String>,
+    incomplete_files: usize,
+) -> String {
+    let configured: BTreeSet<&String> = config
+        .function_names
+        .iter()
+        .chain(config.function_arg_indices.keys())
+        .collect();
+
+    if configured.is_empty() {
+        return "Unused functions: none configured".to_string();
+    }
+
+    let unused: Vec<&str> = configured
+        .into_iter()
+        .filter(|name| !called.contains(*name))
+        .map(String::as_str)
+        .collect();
+
+    if unused.is_empty() {
+        "Unused functions: none (all configured functions were called)".to_string()
+    } else if incomplete_files > 0 {
+        format!(
+            "Unused functions: incomplete, {} file{} could not be searched for calls; not called elsewhere: {}",
+            incomplete_files,
+            if incomplete_files == 1 { "" } else { "s" },
+            unused.join(", ")
+        )
+    } else {
+        format!("Unused functions: {}", unused.join(", "))
+    }
+}
+
+/// The processor for `--stdin`: the defaults, with the sorting flags applied
+fn stdin_processor(cli: &Cli) -> FileProcessor {
+    let mut config = Config::default();
+    if cli.no_dedup {
+        config.preserve_duplicates = true;
+    }
+    if cli.merge {
+        config.merge_conflicts = true;
+    }
+    if cli.strict_unknown {
+        config.unknown_classes = "error".to_string();
+    }
+    FileProcessor::new_with_config(&config)
+}
+
+/// Format a JSON array of snippets from stdin, writing a JSON array of results
+///
+/// Input that isn't a valid request array produces an `{"error": ...}` object
+/// and exit code 2; snippets that fail to parse carry their own `error`.
+fn handle_stdin_json(cli: &Cli) -> Result<i32, Box<dyn std::error::Error>> {
+    if cli.no_dedup || cli.merge || cli.strict_unknown {
+        return Err(Box::new(WindWardenError::config_error(
+            "--no-dedup, --merge, and --strict-unknown cannot be used with --stdin-json",
+        )));
+    }
+
+    let mut input = String::new();
+    io::stdin().read_to_string(&mut input)?;
+
+    let requests: Vec<SnippetRequest> = match serde_json::from_str(&input) {
+        Ok(requests) => requests,
+        Err(e) => {
+            let error = BatchInputError {
+                error: format!("Invalid --stdin-json input: {}", e),
+            };
+            println!("{}", serde_json::to_string(&error)?);
+            return Ok(ERROR_EXIT_CODE);
+        }
+    };
+
+    let options = ProcessOptions {
+        dry_run: false,
+        write: false,
+        check_formatted: false,
+    };
+    let responses = windwarden::process_snippets(requests, options);
+    println!("{}", serde_json::to_string(&responses)?);
+    Ok(0)
+}
+
+/// Format stdin to stdout
+///
+/// With `--stdin-report-changed`, the output is printed exactly as formatted and
+/// a `changed: <bool>` line on stderr tells editors whether to update the buffer.
+/// With `--strict-unknown`, unknown classes are logged on stderr and the exit
+/// code is 2, though the formatted output is still printed.
+fn handle_stdin(cli: &Cli) -> Result<i32, Box<dyn std::error::Error>> {
+    let options = ProcessOptions {
+        dry_run: false,
+        write: false, // stdin always outputs to stdout
+        check_formatted: false,
+    };
+    let file_path = cli
+        .stdin_filepath
+        .as_deref()
+        .unwrap_or(DEFAULT_STDIN_FILEPATH);
+
+    let mut input = String::new();
+    io::stdin().read_to_string(&mut input)?;
+
+    let outcome = match stdin_processor(cli).process_content_detailed(&input, file_path, options) {
+        Ok(outcome) => outcome,
+        Err(e) => {
+            log::error!("{}", e.user_message());
+            return Ok(ERROR_EXIT_CODE);
+        }
     };
+    for unknown in &outcome.unknown_classes {
+        log_unknown_class(file_path, unknown);
+    }
 
-    println!("{}", output);
+    if cli.stdin_report_changed {
+        print!("{}", outcome.content);
+        io::stdout().flush()?;
+        eprintln!("changed: {}", outcome.content != input);
+    } else if !outcome.content.is_empty() {
+        println!("{}", outcome.content);
+    }
 
-    Ok(formatter.get_exit_code(&mode, &results))
+    Ok(if outcome.unknown_classes.is_empty() {
+        0
+    } else {
+        ERROR_EXIT_CODE
+    })
+}
+
+/// Print the category of every class in one file, or stdin for `-`, in the
+/// order the classes sort
+fn explain_classes(
+    config_manager: &ConfigManager,
+    paths: &[String],
+    options: &CommandOptions,
+) -> Result<i32, Box<dyn std::error::Error>> {
+    let format = options.format;
+    let [path] = paths else {
+        return Err(Box::new(WindWardenError::config_error(
+            "--explain takes a single file, or - for stdin",
+        )));
+    };
+    if !matches!(format, ReportFormat::Text | ReportFormat::Json) {
+        return Err(Box::new(WindWardenError::config_error(
+            "--explain can only be used with --format text or json",
+        )));
+    }
+
+    let (content, file_path) = if path == "-" {
+        let mut input = String::new();
+        io::stdin().read_to_string(&mut input)?;
+        let file_path = options
+            .stdin_filepath
+            .as_deref()
+            .unwrap_or(DEFAULT_STDIN_FILEPATH);
+        (input, file_path)
+    } else {
+        let content = std::fs::read_to_string(path).map_err(WindWardenError::Io)?;
+        (content, path.as_str())
+    };
+
+    let config = config_manager.config_for_path(Path::new(file_path))?;
+    if config.prettier_compat {
+        log::warn!(
+            "prettierCompat orders classes by CSS property, so categories don't decide their order"
+        );
+    }
+    let explanations =
+        FileProcessor::new_with_config(&config).explain_content(&content, file_path)?;
+
+    if matches!(format, ReportFormat::Json) {
+        println!("{}", serde_json::to_string_pretty(&explanations)?);
+        return Ok(0);
+    }
+
+    for (index, explanation) in explanations.iter().enumerate() {
+        if index > 0 {
+            println!();
+        }
+        println!(
+            "{}:{}:{}: \"{}\" → \"{}\"",
+            file_path,
+            explanation.line,
+            explanation.column,
+            explanation.original,
+            explanation.sorted
+        );
+        for class in &explanation.classes {
+            let position = class.category_index.map_or_else(
+                || "not in order, sorted last".to_string(),
+                |index| format!("#{}", index),
+            );
+            print!("  {} → {} ({})", class.class, class.category, position);
+            if !class.variants.is_empty() {
+                print!(" [variants: {}]", class.variants.join(", "));
+            }
+            println!();
+        }
+    }
+    Ok(0)
+}
+
+/// Resolve the cache file from `--cache` and `--cache-file`
+fn cache_file_path(cache: bool, cache_file: &Option<PathBuf>) -> Option<PathBuf> {
+    match cache_file {
+        Some(path) => Some(path.clone()),
+        None if cache => Some(PathBuf::from(DEFAULT_CACHE_FILE)),
+        None => None,
+    }
+}
+
+/// The configuration with this command's flags applied over it, as the run
+/// will see it
+fn resolve_command_config(
+    config_manager: &ConfigManager,
+    options: &CommandOptions,
+) -> ConfigManager {
+    let mut resolved = config_manager.clone();
+    if let Some(extensions) = &options.extensions {
+        resolved.override_from_cli("fileExtensions", |config| {
+            config.file_extensions = extensions.clone()
+        });
+    }
+    // Like the run, CLI patterns follow the configuration's
+    if let Some(exclude) = &options.exclude {
+        resolved.override_from_cli("ignorePaths", |config| {
+            config.ignore_paths.extend(exclude.iter().cloned())
+        });
+    }
+    if let Some(include) = &options.include {
+        resolved.override_from_cli("includePaths", |config| {
+            config.include_paths = include.clone()
+        });
+    }
+    if let Some(threads) = options.threads {
+        resolved.override_from_cli("threads", |config| config.threads = threads);
+    }
+    if let Some(reads) = options.max_concurrent_reads {
+        resolved.override_from_cli("maxConcurrentReads", |config| {
+            config.max_concurrent_reads = Some(reads)
+        });
+    }
+    if let Some(max) = options.max_files {
+        resolved.override_from_cli("maxFiles", |config| config.max_files = Some(max));
+    }
+    if let Some(ms) = options.per_file_timeout {
+        resolved.override_from_cli("perFileTimeoutMs", |config| config.per_file_timeout_ms = ms);
+    }
+    if let Some(code) = options.verify_exit_code {
+        resolved.override_from_cli("verifyExitCode", |config| config.verify_exit_code = code);
+    }
+    resolved
 }
 
 fn handle_check_command(
@@ -295,6 +1247,11 @@ fn load_configuration(cli: &Cli) -> Result<ConfigManager, WindWardenError> {
             Ok(manager)
         }
         None => {
+            // Inline configuration takes precedence over configuration files
+            if let Some(content) = env_config()? {
+                return ConfigManager::from_env_json(&content);
+            }
+
             // Search for config file in current directory and parents
             let current_dir =
                 std::env::current_dir().map_err(|e| WindWardenError::from_io_error(e, None))?;
@@ -303,18 +1260,47 @@ fn load_configuration(cli: &Cli) -> Result<ConfigManager, WindWardenError> {
     }
 }
 
+/// The inline configuration JSON in `WINDWARDEN_CONFIG`, unless it's unset
+/// or empty
+fn env_config() -> Result<Option<String>, WindWardenError> {
+    match std::env::var(CONFIG_ENV_VAR) {
+        Ok(content) if content.trim().is_empty() => Ok(None),
+        Ok(content) => Ok(Some(content)),
+        Err(std::env::VarError::NotPresent) => Ok(None),
+        Err(std::env::VarError::NotUnicode(_)) => Err(WindWardenError::config_error(format!(
+            "The {} environment variable is not valid UTF-8",
+            CONFIG_ENV_VAR
+        ))),
+    }
+}
+
 fn handle_config_command(
     action: &ConfigAction,
     config_manager: &ConfigManager,
 ) -> Result<i32, Box<dyn std::error::Error>> {
     match action {
-        ConfigAction::Init { path } => {
+        ConfigAction::Init { path, interactive } => {
             if path.exists() {
                 eprintln!("Configuration file already exists: {}", path.display());
                 eprintln!("Use --force to overwrite (not implemented yet)");
                 return Ok(1);
             }
 
+            if *interactive {
+                if io::stdin().is_terminal() {
+                    let config = ConfigManager::prompt_for_config(
+                        &mut io::stdin().lock(),
+                        &mut io::stdout(),
+                    )?;
+                    ConfigManager::write_config(path, &config)?;
+                    println!("\nCreated configuration file: {}", path.display());
+                    return Ok(0);
+                }
+                log::warn!(
+                    "--interactive needs a terminal to read answers from; writing the default configuration"
+                );
+            }
+
             ConfigManager::create_default_config(path)?;
             println!("Created default configuration file: {}", path.display());
             println!("\nTo customize your configuration, edit the file and modify settings like:");
@@ -338,6 +1324,8 @@ fn handle_config_command(
             println!("Current configuration:");
             if let Some(path) = config_manager.config_path() {
                 println!("Loaded from: {}", path.display());
+            } else if config_manager.loaded_from_env() {
+                println!("Loaded from: {} environment variable", CONFIG_ENV_VAR);
             } else {
                 println!("Using default configuration (no config file found)");
             }
@@ -345,12 +1333,36 @@ fn handle_config_command(
             Ok(0)
         }
 
-        ConfigAction::Validate { path } => {
+        ConfigAction::Validate { stdin: true, .. } => {
+            let mut input = String::new();
+            io::stdin().read_to_string(&mut input)?;
+
+            match ConfigManager::parse_config(&input, "stdin") {
+                Ok(_) => {
+                    println!("✓ Configuration is valid");
+                    Ok(0)
+                }
+                Err(e) => {
+                    eprintln!("✗ Configuration is invalid");
+                    eprintln!("{}", e.user_message());
+                    Ok(1)
+                }
+            }
+        }
+
+        ConfigAction::Validate { path, .. } => {
             let config_path = match path {
                 Some(p) => p.clone(),
                 None => {
                     if let Some(p) = config_manager.config_path() {
                         p.clone()
+                    } else if config_manager.loaded_from_env() {
+                        // It was validated when it was loaded
+                        println!(
+                            "✓ Configuration is valid: {} environment variable",
+                            CONFIG_ENV_VAR
+                        );
+                        return Ok(0);
                     } else {
                         return Err(Box::new(WindWardenError::config_error(
                             "No configuration file specified and none found",
@@ -374,6 +1386,189 @@ fn handle_config_command(
     }
 }
 
+/// Outcome of a single `doctor` check
+enum CheckStatus {
+    Pass,
+    Fail,
+    Skip,
+}
+
+/// Check the configuration and environment, printing a pass/fail checklist
+///
+/// Returns 1 if any check failed. Checks that depend on the configuration
+/// are skipped when it can't be loaded.
+fn handle_doctor_command(cli: &Cli) -> i32 {
+    let mut checks = Vec::new();
+
+    match load_configuration(cli) {
+        Ok(manager) => {
+            let source = match manager.config_path() {
+                Some(path) => path.display().to_string(),
+                None if manager.loaded_from_env() => {
+                    format!("{} environment variable", CONFIG_ENV_VAR)
+                }
+                None => "no configuration file found, using defaults".to_string(),
+            };
+            checks.push((
+                CheckStatus::Pass,
+                format!("Configuration is valid ({})", source),
+            ));
+
+            let config = manager.config();
+            checks.push(check_custom_order(config));
+            checks.push(check_glob_patterns(config));
+            checks.push(check_thread_count(config));
+        }
+        Err(e) => {
+            checks.push((
+                CheckStatus::Fail,
+                format!("Configuration is invalid\n    {}", e),
+            ));
+            checks.push((
+                CheckStatus::Skip,
+                "Configuration checks skipped until the configuration loads".to_string(),
+            ));
+        }
+    }
+    checks.push(check_git_repository());
+
+    let mut failed = 0;
+    for (status, message) in &checks {
+        let mark = match status {
+            CheckStatus::Pass => "✓",
+            CheckStatus::Fail => {
+                failed += 1;
+                "✗"
+            }
+            CheckStatus::Skip => "-",
+        };
+        println!("{} {}", mark, message);
+    }
+
+    if failed == 0 {
+        println!("\nAll checks passed");
+        0
+    } else {
+        println!(
+            "\n{} check{} failed",
+            failed,
+            if failed == 1 { "" } else { "s" }
+        );
+        1
+    }
+}
+
+/// Check that every `customOrder` category, including in overrides, is known
+///
+/// Loading only validates `customOrder` when `sortOrder` is `"custom"`, so
+/// this also catches typos in orders that are not in use yet.
+fn check_custom_order(config: &Config) -> (CheckStatus, String) {
+    let orders: Vec<&Vec<String>> = std::iter::once(&config.custom_order)
+        .chain(
+            config
+                .overrides
+                .iter()
+                .filter_map(|entry| entry.config.custom_order.as_ref()),
+        )
+        .filter(|order| !order.is_empty())
+        .collect();
+    if orders.is_empty() {
+        return (CheckStatus::Skip, "No customOrder configured".to_string());
+    }
+
+    let available = ConfigManager::get_available_categories();
+    let unknown: BTreeSet<&str> = orders
+        .iter()
+        .flat_map(|order| order.iter())
+        .filter(|category| !available.contains(category))
+        .map(String::as_str)
+        .collect();
+
+    if unknown.is_empty() {
+        (
+            CheckStatus::Pass,
+            "customOrder categories are known".to_string(),
+        )
+    } else {
+        (
+            CheckStatus::Fail,
+            format!(
+                "Unknown customOrder categories: {}\n    Available categories: {}",
+                unknown.into_iter().collect::<Vec<_>>().join(", "),
+                available.join(", ")
+            ),
+        )
+    }
+}
+
+/// Check that `includePaths`, `ignorePaths`, and override globs compile
+fn check_glob_patterns(config: &Config) -> (CheckStatus, String) {
+    let mut errors: Vec<String> = config
+        .include_paths
+        .iter()
+        .chain(&config.ignore_paths)
+        .filter_map(|pattern| build_glob_set([pattern.as_str()]).err())
+        .map(|e| e.to_string())
+        .collect();
+    if let Err(e) = ConfigOverrides::new(config) {
+        errors.push(e.to_string());
+    }
+
+    if errors.is_empty() {
+        (CheckStatus::Pass, "Glob patterns compile".to_string())
+    } else {
+        (
+            CheckStatus::Fail,
+            format!("Invalid glob patterns\n    {}", errors.join("\n    ")),
+        )
+    }
+}
+
+/// Check that the configured thread count fits the machine
+fn check_thread_count(config: &Config) -> (CheckStatus, String) {
+    let cores = std::thread::available_parallelism().map_or(1, |n| n.get());
+
+    match config.threads {
+        0 => (
+            CheckStatus::Pass,
+            format!("Thread count: {} (auto-detected)", cores),
+        ),
+        threads if threads > cores => (
+            CheckStatus::Fail,
+            format!(
+                "Thread count {} exceeds the {} available CPU cores; set threads to 0 to auto-detect",
+                threads, cores
+            ),
+        ),
+        threads => (
+            CheckStatus::Pass,
+            format!("Thread count: {} ({} CPU cores available)", threads, cores),
+        ),
+    }
+}
+
+/// Report whether the working directory is inside a git repository
+///
+/// Only `--since` needs git, so a missing repository is not a failure.
+fn check_git_repository() -> (CheckStatus, String) {
+    let root = std::env::current_dir().ok().and_then(|dir| {
+        dir.ancestors()
+            .find(|dir| dir.join(".git").exists())
+            .map(Path::to_path_buf)
+    });
+
+    match root {
+        Some(root) => (
+            CheckStatus::Pass,
+            format!("Git repository: {}", root.display()),
+        ),
+        None => (
+            CheckStatus::Skip,
+            "Not a git repository (only needed for --since)".to_string(),
+        ),
+    }
+}
+
 fn handle_completions_command(shell: Shell) -> Result<i32, Box<dyn std::error::Error>> {
     let mut cmd = Cli::command();
     let app_name = cmd.get_name().to_string();
@@ -0,0 +1,156 @@
+use crate::{Result, WindWardenError};
+use serde::Deserialize;
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// One file listed in a `--manifest` file
+///
+/// Other fields, such as build metadata, are allowed and ignored.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct ManifestEntry {
+    /// Path to the file, relative to the manifest's directory unless absolute
+    pub path: PathBuf,
+    /// Whether WindWarden should process the file
+    pub sort: bool,
+}
+
+/// A JSON list of files with a per-file `sort` toggle, for `--manifest`
+///
+/// ```json
+/// [
+///   { "path": "src/Button.tsx", "sort": true },
+///   { "path": "src/generated/Icons.tsx", "sort": false }
+/// ]
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Manifest {
+    entries: Vec<ManifestEntry>,
+}
+
+impl Manifest {
+    /// Read and validate a manifest file
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = fs::read_to_string(path)
+            .map_err(|e| WindWardenError::from_io_error(e, Some(&path.display().to_string())))?;
+        let base_dir = path.parent().unwrap_or(Path::new(""));
+        Self::parse(&content, &path.display().to_string(), base_dir)
+    }
+
+    /// Parse a manifest, resolving relative paths against `base_dir`
+    pub fn parse(content: &str, source: &str, base_dir: &Path) -> Result<Self> {
+        let entries: Vec<ManifestEntry> = serde_json::from_str(content).map_err(|e| {
+            WindWardenError::config_error(format!(
+                "Invalid manifest {}: {} (expected a JSON array of {{\"path\": string, \"sort\": boolean}})",
+                source, e
+            ))
+        })?;
+
+        let entries = entries
+            .into_iter()
+            .enumerate()
+            .map(|(index, entry)| {
+                if entry.path.as_os_str().is_empty() {
+                    return Err(WindWardenError::config_error(format!(
+                        "Invalid manifest {}: entry {} has an empty path",
+                        source, index
+                    )));
+                }
+                Ok(ManifestEntry {
+                    path: base_dir.join(entry.path),
+                    sort: entry.sort,
+                })
+            })
+            .collect::<Result<_>>()?;
+
+        Ok(Self { entries })
+    }
+
+    /// All entries, with paths resolved
+    pub fn entries(&self) -> &[ManifestEntry] {
+        &self.entries
+    }
+
+    /// Entries marked `"sort": true`
+    pub fn files_to_sort(&self) -> impl Iterator<Item = &Path> {
+        self.entries
+            .iter()
+            .filter(|entry| entry.sort)
+            .map(|entry| entry.path.as_path())
+    }
+
+    /// Canonical paths of the files to sort, to compare with discovered files
+    pub fn selected_files(&self) -> HashSet<PathBuf> {
+        self.files_to_sort()
+            .filter_map(|path| fs::canonicalize(path).ok())
+            .collect()
+    }
+
+    /// Entries whose file doesn't exist
+    pub fn unknown_paths(&self) -> Vec<&Path> {
+        self.entries
+            .iter()
+            .map(|entry| entry.path.as_path())
+            .filter(|path| !path.is_file())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_parse_resolves_paths_and_toggles() {
+        let manifest = Manifest::parse(
+            r#"[
+                {"path": "a.tsx", "sort": true, "chunk": "main"},
+                {"path": "b.tsx", "sort": false},
+                {"path": "/abs/c.tsx", "sort": true}
+            ]"#,
+            "manifest.json",
+            Path::new("build"),
+        )
+        .unwrap();
+
+        let files: Vec<&Path> = manifest.files_to_sort().collect();
+        assert_eq!(
+            files,
+            vec![Path::new("build/a.tsx"), Path::new("/abs/c.tsx")]
+        );
+        assert_eq!(manifest.entries().len(), 3);
+    }
+
+    #[test]
+    fn test_parse_rejects_invalid_manifests() {
+        for content in [
+            r#"{"path": "a.tsx", "sort": true}"#,
+            r#"[{"path": "a.tsx"}]"#,
+            r#"[{"path": "a.tsx", "sort": "yes"}]"#,
+            r#"[{"path": "", "sort": true}]"#,
+        ] {
+            let error = Manifest::parse(content, "manifest.json", Path::new("")).unwrap_err();
+            assert!(matches!(error, WindWardenError::Config { .. }));
+            assert!(error.to_string().contains("Invalid manifest manifest.json"));
+        }
+    }
+
+    #[test]
+    fn test_unknown_paths() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(temp_dir.path().join("a.tsx"), "").unwrap();
+        let manifest = Manifest::parse(
+            r#"[{"path": "a.tsx", "sort": true}, {"path": "missing.tsx", "sort": false}]"#,
+            "manifest.json",
+            temp_dir.path(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            manifest.unknown_paths(),
+            vec![temp_dir.path().join("missing.tsx")]
+        );
+        assert_eq!(manifest.selected_files().len(), 1);
+    }
+}
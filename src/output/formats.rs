@@ -1,5 +1,11 @@
+use super::position::LineIndex;
 use super::*;
+use crate::file_processor::{FailureKind, FileProcessingResult};
 use crate::parser::ClassMatch;
+use crate::processor::UnknownClass;
+use crate::sorter::UnknownClassSeverity;
+
+pub mod junit;
 
 /// Utility functions for creating output format structures
 /// Create an issue from a class match that needs sorting
@@ -16,7 +22,7 @@ pub fn create_sorting_issue(
         line,
         column,
         end_line: line,
-        end_column: column + class_match.original.len(),
+        end_column: column + class_match.original.chars().count(),
         source: class_match.original.clone(),
         suggestions: vec![Suggestion {
             desc: "Sort classes according to Tailwind order".to_string(),
@@ -28,6 +34,112 @@ pub fn create_sorting_issue(
     }
 }
 
+/// Create an issue covering everything sorting changes in a file
+///
+/// The fix replaces the span between the first and last differing bytes.
+pub fn create_file_change_issue(original: &str, processed: &str, reordered: usize) -> Issue {
+    let prefix = common_prefix_len(original, processed);
+    let suffix = common_suffix_len(&original[prefix..], &processed[prefix..]);
+    let end = original.len() - suffix;
+
+    let index = LineIndex::new(original);
+    let (line, column) = index.line_col(prefix);
+    let (end_line, end_column) = index.line_col(end);
+    let message = match reordered {
+        0 | 1 => "Classes are not sorted according to Tailwind order".to_string(),
+        n => format!(
+            "{} class strings are not sorted according to Tailwind order",
+            n
+        ),
+    };
+
+    Issue {
+        rule_id: "class-order".to_string(),
+        severity: Severity::Warning,
+        message,
+        line,
+        column,
+        end_line,
+        end_column,
+        source: original[prefix..end].to_string(),
+        suggestions: vec![Suggestion {
+            desc: "Sort classes according to Tailwind order".to_string(),
+            fix: Fix {
+                range: [prefix, end],
+                text: processed[prefix..processed.len() - suffix].to_string(),
+            },
+        }],
+    }
+}
+
+/// Create an error issue for a file that could not be processed
+///
+/// Errors without a location, such as unreadable files, are reported at 1:1.
+pub fn create_failure_issue(result: &FileProcessingResult) -> Issue {
+    let (line, column) = result.error_location.unwrap_or((1, 1));
+    let rule_id = match result.failure_kind {
+        Some(FailureKind::Parse) => "parse-error",
+        Some(FailureKind::Io) => "io-error",
+        Some(FailureKind::UnsupportedType) => "unsupported-file-type",
+        Some(FailureKind::TooLarge) => "file-too-large",
+        Some(FailureKind::TimedOut) => "timeout",
+        Some(FailureKind::Other) | None => "processing-error",
+    };
+
+    Issue {
+        rule_id: rule_id.to_string(),
+        severity: Severity::Error,
+        message: result
+            .error
+            .clone()
+            .unwrap_or_else(|| "Processing failed".to_string()),
+        line,
+        column,
+        end_line: line,
+        end_column: column,
+        source: String::new(),
+        suggestions: Vec::new(),
+    }
+}
+
+/// Create an issue for a class that matches no utility, at its class string
+pub fn create_unknown_class_issue(unknown: &UnknownClass) -> Issue {
+    let severity = match unknown.severity {
+        UnknownClassSeverity::Error => Severity::Error,
+        UnknownClassSeverity::Warn | UnknownClassSeverity::Ignore => Severity::Warning,
+    };
+
+    Issue {
+        rule_id: "unknown-class".to_string(),
+        severity,
+        message: format!("Unknown class '{}'", unknown.class),
+        line: unknown.line,
+        column: unknown.column,
+        end_line: unknown.line,
+        end_column: unknown.column,
+        source: unknown.class.clone(),
+        suggestions: Vec::new(),
+    }
+}
+
+/// Length in bytes of the longest common prefix, on a char boundary
+fn common_prefix_len(a: &str, b: &str) -> usize {
+    a.char_indices()
+        .zip(b.chars())
+        .find(|((_, x), y)| x != y)
+        .map_or(a.len().min(b.len()), |((i, _), _)| i)
+}
+
+/// Length in bytes of the longest common suffix, on a char boundary
+fn common_suffix_len(a: &str, b: &str) -> usize {
+    a.chars()
+        .rev()
+        .zip(b.chars().rev())
+        .take_while(|(x, y)| x == y)
+        .map(|(x, _)| x.len_utf8())
+        .sum()
+}
+
 /// Create a modification entry for diff reports
 pub fn create_modification(
     line: usize,
@@ -44,27 +156,6 @@ pub fn create_modification(
     }
 }
 
-/// Convert byte position to line and column numbers
-pub fn position_to_line_col(content: &str, pos: usize) -> (usize, usize) {
-    let mut line = 1;
-    let mut col = 1;
-
-    for (i, ch) in content.char_indices() {
-        if i >= pos {
-            break;
-        }
-
-        if ch == '\n' {
-            line += 1;
-            col = 1;
-        } else {
-            col += 1;
-        }
-    }
-
-    (line, col)
-}
-
 /// Extract file name from path for display
 pub fn extract_file_name(path: &str) -> &str {
     path.split('/').next_back().unwrap_or(path)
@@ -109,6 +200,19 @@ pub fn format_issue_count(errors: usize, warnings: usize) -> String {
     }
 }
 
+/// Format a count with thousands separators, e.g. `1,284`
+pub fn format_count(count: usize) -> String {
+    let digits = count.to_string();
+    let mut formatted = String::with_capacity(digits.len() + digits.len() / 3);
+    for (i, digit) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i).is_multiple_of(3) {
+            formatted.push(',');
+        }
+        formatted.push(digit);
+    }
+    formatted
+}
+
 /// Create a progress summary for processing operations
 pub fn create_processing_summary(
     files_processed: usize,
@@ -129,16 +233,6 @@ mod tests {
     use super::*;
     use crate::parser::{PatternType, QuoteStyle};
 
-    #[test]
-    fn test_position_to_line_col() {
-        let content = "line 1\nline 2\nline 3";
-
-        assert_eq!(position_to_line_col(content, 0), (1, 1));
-        assert_eq!(position_to_line_col(content, 6), (1, 7)); // End of line 1
-        assert_eq!(position_to_line_col(content, 7), (2, 1)); // Start of line 2
-        assert_eq!(position_to_line_col(content, 14), (3, 1)); // Start of line 3
-    }
-
     #[test]
     fn test_extract_file_name() {
         assert_eq!(extract_file_name("/path/to/file.tsx"), "file.tsx");
@@ -158,6 +252,44 @@ mod tests {
         assert_eq!(format_issue_count(2, 3), "2 errors, 3 warnings");
     }
 
+    #[test]
+    fn test_create_file_change_issue() {
+        let original = "<div className=\"p-4 flex\">\n<p className=\"m-2 block\" />";
+        let processed = "<div className=\"flex p-4\">\n<p className=\"block m-2\" />";
+
+        let issue = create_file_change_issue(original, processed, 2);
+        assert_eq!((issue.line, issue.column), (1, 17));
+        assert_eq!((issue.end_line, issue.end_column), (2, 24));
+        assert_eq!(
+            issue.message,
+            "2 class strings are not sorted according to Tailwind order"
+        );
+
+        let fix = &issue.suggestions[0].fix;
+        let mut fixed = original.to_string();
+        fixed.replace_range(fix.range[0]..fix.range[1], &fix.text);
+        assert_eq!(fixed, processed);
+    }
+
+    #[test]
+    fn test_create_file_change_issue_after_multibyte_text() {
+        let original = "<p>日本語</p><div className=\"p-4 flex\">";
+        let processed = "<p>日本語</p><div className=\"flex p-4\">";
+
+        let issue = create_file_change_issue(original, processed, 1);
+        assert_eq!((issue.line, issue.column), (1, 27));
+        assert_eq!((issue.end_line, issue.end_column), (1, 35));
+        assert_eq!(issue.suggestions[0].fix.range, [32, 40]);
+    }
+
+    #[test]
+    fn test_format_count() {
+        assert_eq!(format_count(0), "0");
+        assert_eq!(format_count(312), "312");
+        assert_eq!(format_count(1284), "1,284");
+        assert_eq!(format_count(1_000_000), "1,000,000");
+    }
+
     #[test]
     fn test_create_sorting_issue() {
         let class_match = ClassMatch {
@@ -0,0 +1,172 @@
+use crate::diff::{DiffFormatter, FileDiff};
+use crate::file_processor::{BatchProcessingResults, FileProcessingResult};
+use std::time::Duration;
+
+/// Format batch results as a JUnit XML test-suite document
+///
+/// Each processed file is a `<testcase>`. When `changes_are_failures` is set
+/// (check and verify modes), files that need formatting become failures with
+/// the diff as the failure body; files that could not be read or parsed
+/// become errors.
+pub fn format_junit(
+    results: &BatchProcessingResults,
+    duration: Option<Duration>,
+    changes_are_failures: bool,
+) -> String {
+    let failures = if changes_are_failures {
+        results.files_with_changes
+    } else {
+        0
+    };
+    let time = duration.map_or(0.0, |d| d.as_secs_f64());
+    let diff_formatter = DiffFormatter::new().with_colors(false);
+
+    let mut xml = Vec::new();
+    xml.push(r#"<?xml version="1.0" encoding="UTF-8"?>"#.to_string());
+    xml.push(format!(
+        r#"<testsuites name="windwarden" tests="{}" failures="{}" errors="{}" time="{:.3}">"#,
+        results.total_files, failures, results.failed_files, time
+    ));
+    xml.push(format!(
+        r#"  <testsuite name="windwarden" tests="{}" failures="{}" errors="{}" skipped="{}" time="{:.3}">"#,
+        results.total_files, failures, results.failed_files, results.skipped_encoding, time
+    ));
+
+    for result in &results.results {
+        let name = escape_xml(&result.file_path.display().to_string());
+        let open = format!(r#"    <testcase name="{}" classname="windwarden">"#, name);
+
+        if result.failed() {
+            let error = escape_xml(result.error.as_deref().unwrap_or("Processing failed"));
+            xml.push(open);
+            xml.push(format!(
+                r#"      <error message="{}" type="error">{}</error>"#,
+                error, error
+            ));
+            xml.push("    </testcase>".to_string());
+        } else if result.skipped_encoding {
+            let message = escape_xml(result.error.as_deref().unwrap_or("Not valid UTF-8"));
+            xml.push(open);
+            xml.push(format!(r#"      <skipped message="{}"/>"#, message));
+            xml.push("    </testcase>".to_string());
+        } else if changes_are_failures && result.changes_made {
+            xml.push(open);
+            xml.push(format!(
+                r#"      <failure message="Classes are not sorted" type="unformatted">{}</failure>"#,
+                escape_xml(&file_diff(&diff_formatter, result))
+            ));
+            xml.push("    </testcase>".to_string());
+        } else {
+            xml.push(format!(
+                r#"    <testcase name="{}" classname="windwarden"/>"#,
+                name
+            ));
+        }
+    }
+
+    xml.push("  </testsuite>".to_string());
+    xml.push("</testsuites>".to_string());
+    xml.join("\n")
+}
+
+fn file_diff(formatter: &DiffFormatter, result: &FileProcessingResult) -> String {
+    match (&result.original_content, &result.processed_content) {
+        (Some(original), Some(processed)) => formatter.format_diff(&FileDiff::new(
+            result.file_path.display().to_string(),
+            original.clone(),
+            processed.clone(),
+        )),
+        _ => String::new(),
+    }
+}
+
+fn escape_xml(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for ch in text.chars() {
+        match ch {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            // Other control characters are not allowed in XML 1.0
+            '\t' | '\n' | '\r' => escaped.push(ch),
+            c if c.is_control() => {}
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn sample_results() -> BatchProcessingResults {
+        let mut results = BatchProcessingResults::new();
+        results.add_result(FileProcessingResult::success(
+            PathBuf::from("src/Sorted.tsx"),
+            false,
+            "a".to_string(),
+            "a".to_string(),
+        ));
+        results.add_result(FileProcessingResult::success(
+            PathBuf::from("src/Unsorted.tsx"),
+            true,
+            r#"<div className="p-4 flex" />"#.to_string(),
+            r#"<div className="flex p-4" />"#.to_string(),
+        ));
+        results.add_result(FileProcessingResult::error(
+            PathBuf::from("src/Broken.tsx"),
+            "Parse error in src/Broken.tsx at line 1, column 7: Unexpected token".to_string(),
+        ));
+        results
+    }
+
+    #[test]
+    fn test_junit_report_counts_and_cases() {
+        let xml = format_junit(&sample_results(), Some(Duration::from_millis(1500)), true);
+
+        assert!(xml.starts_with(r#"<?xml version="1.0" encoding="UTF-8"?>"#));
+        assert!(xml.contains(r#"tests="3" failures="1" errors="1" skipped="0" time="1.500""#));
+        assert!(xml.contains(r#"<testcase name="src/Sorted.tsx" classname="windwarden"/>"#));
+        assert!(xml.contains(r#"<failure message="Classes are not sorted" type="unformatted">"#));
+        assert!(xml.contains("+&lt;div className=&quot;flex p-4&quot; /&gt;"));
+        assert!(xml.contains(r#"<error message="Parse error in src/Broken.tsx"#));
+    }
+
+    #[test]
+    fn test_junit_report_skips_invalid_utf8() {
+        let mut results = sample_results();
+        results.add_result(FileProcessingResult::skipped_encoding(
+            PathBuf::from("src/Latin1.tsx"),
+            "File contains invalid UTF-8".to_string(),
+        ));
+        let xml = format_junit(&results, None, true);
+
+        assert!(xml.contains(r#"errors="1" skipped="1""#));
+        assert!(xml.contains(
+            r#"<testcase name="src/Latin1.tsx" classname="windwarden">
+      <skipped message="File contains invalid UTF-8"/>"#
+        ));
+        assert!(!xml.contains(r#"<error message="File contains invalid UTF-8"#));
+    }
+
+    #[test]
+    fn test_junit_report_changes_pass_in_write_mode() {
+        let xml = format_junit(&sample_results(), None, false);
+
+        assert!(xml.contains(r#"failures="0" errors="1""#));
+        assert!(!xml.contains("<failure"));
+    }
+
+    #[test]
+    fn test_escape_xml() {
+        assert_eq!(
+            escape_xml(r#"<a href="x">'&'</a>"#),
+            "&lt;a href=&quot;x&quot;&gt;&apos;&amp;&apos;&lt;/a&gt;"
+        );
+        assert_eq!(escape_xml("a\u{1b}b\n"), "ab\n");
+    }
+}
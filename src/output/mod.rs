@@ -1,12 +1,16 @@
+use crate::file_processor::BatchProcessingResults;
 use serde::{Deserialize, Serialize};
 use std::path::Path;
 
 pub mod formats;
+pub mod position;
 pub mod reporters;
 pub mod text_formatter;
 
 // Re-export the text formatter for backward compatibility
-pub use text_formatter::{OutputFormatter, ProgressReporter, ProgressTracker};
+pub use text_formatter::{
+    DEFAULT_VERIFY_EXIT_CODE, ERROR_EXIT_CODE, OutputFormatter, ProgressReporter, ProgressTracker,
+};
 
 /// Output format for machine-readable results
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -110,6 +114,9 @@ pub struct FileResult {
     pub fixable_error_count: usize,
     /// Number of fixable warnings
     pub fixable_warning_count: usize,
+    /// Content after sorting, included for dry runs of write mode
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub output: Option<String>,
 }
 
 /// Complete diagnostic report (ESLint-style)
@@ -144,6 +151,9 @@ pub struct CheckReport {
     pub summary: CheckSummary,
     /// Files that need formatting
     pub unformatted_files: Vec<UnformattedFile>,
+    /// Files that could not be read or parsed
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub failed_files: Vec<FailedFile>,
 }
 
 /// Summary for check mode
@@ -168,6 +178,16 @@ pub struct UnformattedFile {
     pub issues: Vec<String>,
 }
 
+/// File that could not be checked
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FailedFile {
+    /// File path
+    pub file_path: String,
+    /// Why the file could not be checked
+    pub error: String,
+}
+
 /// Modification made to a file
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -194,6 +214,38 @@ pub struct FileChanges {
     pub modifications: Vec<Modification>,
 }
 
+/// A snippet to format in `--stdin-json` batch mode
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SnippetRequest {
+    /// Caller-chosen identifier, echoed back in the response
+    pub id: serde_json::Value,
+    /// File path whose extension selects the parser (default: stdin.tsx)
+    #[serde(default)]
+    pub filepath: Option<String>,
+    /// Source to format
+    pub content: String,
+}
+
+/// A formatted snippet in `--stdin-json` batch mode
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SnippetResponse {
+    /// Identifier of the request
+    pub id: serde_json::Value,
+    /// Formatted source, or the original source if formatting failed
+    pub content: String,
+    /// Whether formatting changed the source
+    pub changed: bool,
+    /// Why the snippet could not be formatted
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Written instead of responses when `--stdin-json` input can't be read
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatchInputError {
+    pub error: String,
+}
+
 /// Diff report showing changes
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -243,6 +295,7 @@ impl FileResult {
             warning_count: 0,
             fixable_error_count: 0,
             fixable_warning_count: 0,
+            output: None,
         }
     }
 
@@ -294,6 +347,42 @@ impl DiagnosticReport {
         self.fixable_warning_count += file_result.fixable_warning_count;
         self.results.push(file_result);
     }
+
+    /// Build a report from batch processing results
+    ///
+    /// Each file that needs sorting gets one fixable warning, and each file
+    /// that failed gets one error. With `include_output`, changed files also
+    /// carry their sorted content.
+    pub fn from_results(results: &BatchProcessingResults, include_output: bool) -> Self {
+        let mut report = Self::new();
+
+        for result in results.results.iter().filter(|r| !r.skipped_encoding) {
+            let mut file_result = FileResult::new(&result.file_path);
+
+            if result.failed() {
+                file_result.add_issue(formats::create_failure_issue(result));
+            } else if result.changes_made
+                && let (Some(original), Some(processed)) =
+                    (&result.original_content, &result.processed_content)
+            {
+                file_result.add_issue(formats::create_file_change_issue(
+                    original,
+                    processed,
+                    result.classes_reordered,
+                ));
+                if include_output {
+                    file_result.output = Some(processed.clone());
+                }
+            }
+            for unknown in &result.unknown_classes {
+                file_result.add_issue(formats::create_unknown_class_issue(unknown));
+            }
+
+            report.add_file_result(file_result);
+        }
+
+        report
+    }
 }
 
 impl Default for CheckReport {
@@ -313,8 +402,41 @@ impl CheckReport {
                 unformatted_files: 0,
             },
             unformatted_files: Vec::new(),
+            failed_files: Vec::new(),
         }
     }
+
+    /// Build a check report from batch processing results
+    ///
+    /// Files that need sorting are listed as unformatted; files that failed
+    /// are listed separately and counted as neither formatted nor unformatted.
+    pub fn from_results(results: &BatchProcessingResults) -> Self {
+        let mut report = Self::new();
+
+        for result in &results.results {
+            let file_path = result.file_path.display().to_string();
+            if result.failed() {
+                report.failed_files.push(FailedFile {
+                    file_path,
+                    error: result.error.clone().unwrap_or_default(),
+                });
+            } else if result.changes_made {
+                report
+                    .unformatted_files
+                    .push(formats::create_unformatted_file(
+                        &file_path,
+                        result.classes_reordered,
+                    ));
+            }
+        }
+
+        report.summary = CheckSummary {
+            total_files: results.total_files,
+            formatted_files: results.processed_files - report.unformatted_files.len(),
+            unformatted_files: report.unformatted_files.len(),
+        };
+        report
+    }
 }
 
 impl Default for DiffReport {
@@ -358,4 +480,17 @@ impl SummaryReport {
             },
         }
     }
+
+    /// Build a summary from batch processing results
+    pub fn from_results(results: &BatchProcessingResults) -> Self {
+        Self {
+            summary: formats::create_processing_summary(
+                results.processed_files,
+                results.files_with_changes,
+                results.classes_found,
+                results.classes_reordered,
+            ),
+            ..Self::new()
+        }
+    }
 }
@@ -0,0 +1,93 @@
+/// Convert a byte offset to a 1-based line and column
+///
+/// Columns count UTF-8 characters rather than bytes, so positions stay
+/// correct after non-ASCII text. Offsets past the end of the source map to
+/// the end, and offsets inside a character to that character.
+pub fn offset_to_line_col(source: &str, offset: usize) -> (usize, usize) {
+    LineIndex::new(source).line_col(offset)
+}
+
+/// Line start offsets of a source, for converting many offsets without
+/// rescanning the source for each one
+pub struct LineIndex<'a> {
+    source: &'a str,
+    line_starts: Vec<usize>,
+}
+
+impl<'a> LineIndex<'a> {
+    pub fn new(source: &'a str) -> Self {
+        let line_starts = std::iter::once(0)
+            .chain(source.match_indices('\n').map(|(i, _)| i + 1))
+            .collect();
+
+        Self {
+            source,
+            line_starts,
+        }
+    }
+
+    /// The 1-based line and column of a byte offset, as [`offset_to_line_col`]
+    pub fn line_col(&self, offset: usize) -> (usize, usize) {
+        let offset = offset.min(self.source.len());
+        let line = self.line_starts.partition_point(|&start| start <= offset) - 1;
+        let line_start = self.line_starts[line];
+
+        let column = self.source[line_start..]
+            .char_indices()
+            .take_while(|&(i, ch)| line_start + i + ch.len_utf8() <= offset)
+            .count();
+
+        (line + 1, column + 1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_offset_to_line_col() {
+        let content = "line 1\nline 2\nline 3";
+
+        assert_eq!(offset_to_line_col(content, 0), (1, 1));
+        assert_eq!(offset_to_line_col(content, 6), (1, 7)); // End of line 1
+        assert_eq!(offset_to_line_col(content, 7), (2, 1)); // Start of line 2
+        assert_eq!(offset_to_line_col(content, 14), (3, 1)); // Start of line 3
+        assert_eq!(offset_to_line_col(content, 100), (3, 7)); // Past the end
+        assert_eq!(offset_to_line_col("", 0), (1, 1));
+    }
+
+    #[test]
+    fn test_offset_to_line_col_counts_characters() {
+        let content = "<p>Café 日本 🎉</p>\n<p>Ünïcödé</p><div className=\"p-4 flex\">";
+
+        let offset = content.find("className").unwrap();
+        assert_eq!(offset_to_line_col(content, offset), (2, 20));
+
+        let first_line_end = content.find('\n').unwrap();
+        assert_eq!(offset_to_line_col(content, first_line_end), (1, 17));
+
+        // An offset inside a multibyte character points at that character
+        let emoji = content.find('🎉').unwrap();
+        assert_eq!(offset_to_line_col(content, emoji), (1, 12));
+        assert_eq!(offset_to_line_col(content, emoji + 2), (1, 12));
+    }
+
+    #[test]
+    fn test_line_index_matches_a_character_scan() {
+        let content = "const a = \"日本\";\n\nconst b = 'ä';\r\nconst c = 1;";
+        let index = LineIndex::new(content);
+
+        let (mut line, mut column) = (1, 1);
+        for (offset, ch) in content.char_indices() {
+            assert_eq!(index.line_col(offset), (line, column), "offset {}", offset);
+            if ch == '\n' {
+                line += 1;
+                column = 1;
+            } else {
+                column += 1;
+            }
+        }
+        assert_eq!(index.line_col(content.len()), (4, 13));
+    }
+}
@@ -53,7 +53,7 @@ impl Reporter {
     /// Report check results
     pub fn report_check(&mut self, report: &CheckReport) -> Result<()> {
         match self.format {
-            OutputFormat::Json | OutputFormat::Check => {
+            OutputFormat::Json => {
                 let json = serde_json::to_string_pretty(report).map_err(|e| {
                     WindWardenError::internal_error(format!(
                         "Failed to serialize check report: {}",
@@ -64,6 +64,9 @@ impl Reporter {
                     WindWardenError::internal_error(format!("Failed to write output: {}", e))
                 })?;
             }
+            OutputFormat::Check => {
+                self.write_prettier_check(report)?;
+            }
             OutputFormat::Text => {
                 self.write_text_check(report)?;
             }
@@ -195,6 +198,46 @@ impl Reporter {
         Ok(())
     }
 
+    /// Write check report in the format of `prettier --check`
+    ///
+    /// Each unformatted file gets a `[warn]` line and each failed file an
+    /// `[error]` line, followed by a one-line summary.
+    fn write_prettier_check(&mut self, report: &CheckReport) -> Result<()> {
+        let mut lines = vec!["Checking formatting...".to_string()];
+        lines.extend(
+            report
+                .unformatted_files
+                .iter()
+                .map(|file| format!("[warn] {}", file.file_path)),
+        );
+        lines.extend(
+            report
+                .failed_files
+                .iter()
+                .map(|file| format!("[error] {}: {}", file.file_path, file.error)),
+        );
+
+        lines.push(match report.unformatted_files.len() {
+            0 if report.failed_files.is_empty() => {
+                "All matched files use WindWarden code style!".to_string()
+            }
+            0 => "[error] Some files could not be checked.".to_string(),
+            1 => "[warn] Code style issues found in the above file. Run WindWarden with --mode write to fix."
+                .to_string(),
+            count => format!(
+                "[warn] Code style issues found in {} files. Run WindWarden with --mode write to fix.",
+                count
+            ),
+        });
+
+        for line in lines {
+            writeln!(self.writer, "{}", line).map_err(|e| {
+                WindWardenError::internal_error(format!("Failed to write output: {}", e))
+            })?;
+        }
+        Ok(())
+    }
+
     /// Write check report in human-readable text format
     fn write_text_check(&mut self, report: &CheckReport) -> Result<()> {
         if report.unformatted_files.is_empty() {
@@ -332,6 +375,117 @@ mod tests {
         // In real usage, the output would go to the writer
     }
 
+    #[test]
+    fn test_summary_from_results() {
+        use crate::file_processor::{BatchProcessingResults, FileProcessingResult};
+        use crate::processor::ClassCounts;
+        use std::path::PathBuf;
+
+        let mut results = BatchProcessingResults::new();
+        results.add_result(
+            FileProcessingResult::success(
+                PathBuf::from("a.tsx"),
+                true,
+                "a".to_string(),
+                "b".to_string(),
+            )
+            .with_class_counts(ClassCounts {
+                found: 4,
+                reordered: 3,
+            }),
+        );
+        results.add_result(FileProcessingResult::error(
+            PathBuf::from("b.tsx"),
+            "error".to_string(),
+        ));
+
+        let report = SummaryReport::from_results(&results);
+        assert_eq!(report.summary.files_processed, 1);
+        assert_eq!(report.summary.files_changed, 1);
+        assert_eq!(report.summary.classes_processed, 4);
+        assert_eq!(report.summary.classes_sorted, 3);
+    }
+
+    #[test]
+    fn test_check_report_from_results() {
+        use crate::file_processor::{BatchProcessingResults, FileProcessingResult};
+        use crate::processor::ClassCounts;
+        use std::path::PathBuf;
+
+        let mut results = BatchProcessingResults::new();
+        results.add_result(
+            FileProcessingResult::success(
+                PathBuf::from("a.tsx"),
+                true,
+                "a".to_string(),
+                "b".to_string(),
+            )
+            .with_class_counts(ClassCounts {
+                found: 2,
+                reordered: 2,
+            }),
+        );
+        results.add_result(FileProcessingResult::success(
+            PathBuf::from("b.tsx"),
+            false,
+            "b".to_string(),
+            "b".to_string(),
+        ));
+        results.add_result(FileProcessingResult::error(
+            PathBuf::from("c.tsx"),
+            "Parse error".to_string(),
+        ));
+
+        let report = CheckReport::from_results(&results);
+        assert_eq!(report.summary.total_files, 3);
+        assert_eq!(report.summary.formatted_files, 1);
+        assert_eq!(report.summary.unformatted_files, 1);
+        assert_eq!(report.unformatted_files[0].file_path, "a.tsx");
+        assert_eq!(
+            report.unformatted_files[0].issues,
+            ["2 class ordering issues"]
+        );
+        assert_eq!(report.failed_files[0].file_path, "c.tsx");
+        assert_eq!(report.failed_files[0].error, "Parse error");
+    }
+
+    #[test]
+    fn test_diagnostic_report_includes_failures() {
+        use crate::file_processor::{BatchProcessingResults, FailureKind, FileProcessingResult};
+        use std::path::PathBuf;
+
+        let mut results = BatchProcessingResults::new();
+        results.add_result(FileProcessingResult::success(
+            PathBuf::from("a.tsx"),
+            true,
+            "a".to_string(),
+            "b".to_string(),
+        ));
+        results.add_result(
+            FileProcessingResult::error(PathBuf::from("b.tsx"), "Parse error".to_string())
+                .with_failure_kind(FailureKind::Parse)
+                .with_error_location(3, 7),
+        );
+        results.add_result(
+            FileProcessingResult::error(PathBuf::from("c.tsx"), "Permission denied".to_string())
+                .with_failure_kind(FailureKind::Io),
+        );
+
+        let report = DiagnosticReport::from_results(&results, false);
+        assert_eq!(report.results.len(), 3);
+        assert_eq!(report.error_count, 2);
+        assert_eq!(report.warning_count, 1);
+
+        let parse_error = &report.results[1].messages[0];
+        assert_eq!(parse_error.rule_id, "parse-error");
+        assert_eq!((parse_error.line, parse_error.column), (3, 7));
+        assert_eq!(parse_error.message, "Parse error");
+
+        let io_error = &report.results[2].messages[0];
+        assert_eq!(io_error.rule_id, "io-error");
+        assert_eq!((io_error.line, io_error.column), (1, 1));
+    }
+
     #[test]
     fn test_text_summary_output() {
         let mut report = SummaryReport::new();
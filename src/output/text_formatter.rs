@@ -1,14 +1,24 @@
 // This file contains the text formatting functionality from the original output.rs
-use crate::diff::{DiffFormatter, FileDiff};
-use crate::file_processor::BatchProcessingResults;
+use crate::cli::{DiffStyle, ProgressFormat};
+use crate::diff::{DEFAULT_DIFF_WIDTH, DiffFormatter, FileDiff, terminal_width};
+use crate::file_processor::{BatchProcessingResults, FailureKind};
+use crate::output::formats::format_count;
 use colored::Colorize;
 use std::time::Duration;
 
+/// Exit code for runs where files could not be processed or that failed outright
+pub const ERROR_EXIT_CODE: i32 = 2;
+
+/// Exit code for verify runs that find unformatted files, unless configured
+pub const DEFAULT_VERIFY_EXIT_CODE: i32 = 1;
+
 /// Output formatting for CLI results
 pub struct OutputFormatter {
     show_stats: bool,
     show_diff: bool,
     diff_formatter: DiffFormatter,
+    verify_exit_code: i32,
+    summary_line: bool,
 }
 
 impl OutputFormatter {
@@ -17,6 +27,8 @@ impl OutputFormatter {
             show_stats,
             show_diff: false,
             diff_formatter: DiffFormatter::new(),
+            verify_exit_code: DEFAULT_VERIFY_EXIT_CODE,
+            summary_line: false,
         }
     }
 
@@ -25,6 +37,39 @@ impl OutputFormatter {
         self
     }
 
+    /// Set how many unchanged lines surround each change in diffs
+    pub fn with_diff_context(mut self, lines: usize) -> Self {
+        self.diff_formatter = self.diff_formatter.with_context(lines);
+        self
+    }
+
+    /// Set the diff layout, fitting side-by-side diffs to the terminal width
+    pub fn with_diff_style(mut self, style: DiffStyle) -> Self {
+        self.diff_formatter = self
+            .diff_formatter
+            .with_style(style)
+            .with_width(terminal_width().unwrap_or(DEFAULT_DIFF_WIDTH));
+        self
+    }
+
+    /// Enable or disable colors in diffs
+    pub fn with_colors(mut self, use_colors: bool) -> Self {
+        self.diff_formatter = self.diff_formatter.with_colors(use_colors);
+        self
+    }
+
+    /// End verify results with a `unformatted=N errors=N total=N` line
+    pub fn with_summary_line(mut self, summary_line: bool) -> Self {
+        self.summary_line = summary_line;
+        self
+    }
+
+    /// Set the exit code for verify runs that find unformatted files
+    pub fn with_verify_exit_code(mut self, code: i32) -> Self {
+        self.verify_exit_code = code;
+        self
+    }
+
     /// Format results for check mode (preview)
     pub fn format_check_results(
         &self,
@@ -77,7 +122,7 @@ impl OutputFormatter {
         }
 
         // Show failed files
-        let failed_files: Vec<_> = results.results.iter().filter(|r| !r.success).collect();
+        let failed_files: Vec<_> = results.results.iter().filter(|r| r.failed()).collect();
 
         if !failed_files.is_empty() {
             output.push("Failed to process:".red().bold().to_string());
@@ -141,7 +186,7 @@ impl OutputFormatter {
         }
 
         // Show failed files
-        let failed_files: Vec<_> = results.results.iter().filter(|r| !r.success).collect();
+        let failed_files: Vec<_> = results.results.iter().filter(|r| r.failed()).collect();
 
         if !failed_files.is_empty() {
             output.push("Failed to process:".red().bold().to_string());
@@ -205,7 +250,7 @@ impl OutputFormatter {
         }
 
         // Show failed files
-        let failed_files: Vec<_> = results.results.iter().filter(|r| !r.success).collect();
+        let failed_files: Vec<_> = results.results.iter().filter(|r| r.failed()).collect();
 
         if !failed_files.is_empty() {
             output.push("Failed to process:".red().bold().to_string());
@@ -241,6 +286,13 @@ impl OutputFormatter {
             output.push(self.format_statistics(results, duration));
         }
 
+        if self.summary_line {
+            output.push(format!(
+                "unformatted={} errors={} total={}",
+                results.files_with_changes, results.failed_files, results.total_files
+            ));
+        }
+
         output.join("\n")
     }
 
@@ -257,6 +309,26 @@ impl OutputFormatter {
         stats.push(format!("  Processed: {}", results.processed_files));
         stats.push(format!("  Changed: {}", results.files_with_changes));
         stats.push(format!("  Failed: {}", results.failed_files));
+        for kind in FailureKind::ALL {
+            let count = results.failure_count(kind);
+            if count > 0 {
+                stats.push(format!("    {}: {}", kind.label(), count));
+            }
+        }
+        if results.skipped_encoding > 0 {
+            stats.push(format!(
+                "  Skipped (encoding): {}",
+                results.skipped_encoding
+            ));
+        }
+        stats.push(format!(
+            "  Classes: {} processed, {} reordered",
+            format_count(results.classes_found),
+            format_count(results.classes_reordered)
+        ));
+        if results.cache_hits > 0 {
+            stats.push(format!("  Cache hits: {}", results.cache_hits));
+        }
         stats.push(format!(
             "  Success rate: {:.1}%",
             results.success_rate() * 100.0
@@ -266,8 +338,10 @@ impl OutputFormatter {
             stats.push(format!("  Duration: {:.2}s", duration.as_secs_f64()));
 
             if results.total_files > 0 {
-                let files_per_sec = results.total_files as f64 / duration.as_secs_f64();
-                stats.push(format!("  Files/sec: {:.1}", files_per_sec));
+                stats.push(format!(
+                    "  Files/sec: {:.1}",
+                    results.files_per_second(duration)
+                ));
             }
         }
 
@@ -275,24 +349,26 @@ impl OutputFormatter {
     }
 
     /// Determine exit code based on operation mode and results
+    ///
+    /// Files that failed to process, and unknown classes reported as errors,
+    /// exit with [`ERROR_EXIT_CODE`] in every mode, taking precedence over
+    /// unformatted files, which exit with the verify exit code in verify
+    /// mode only.
     pub fn get_exit_code(
         &self,
         operation_mode: &crate::cli::OperationMode,
         results: &BatchProcessingResults,
     ) -> i32 {
+        if results.failed_files > 0 || results.has_unknown_class_errors() {
+            return ERROR_EXIT_CODE;
+        }
+
         match operation_mode {
-            crate::cli::OperationMode::Check | crate::cli::OperationMode::Write => {
-                // For check and write modes, exit with error only if there were failures
-                if results.failed_files > 0 { 1 } else { 0 }
-            }
-            crate::cli::OperationMode::Verify => {
-                // For verify mode, exit with error if files need formatting or there were failures
-                if results.files_with_changes > 0 || results.failed_files > 0 {
-                    1
-                } else {
-                    0
-                }
+            crate::cli::OperationMode::Check | crate::cli::OperationMode::Write => 0,
+            crate::cli::OperationMode::Verify if results.files_with_changes > 0 => {
+                self.verify_exit_code
             }
+            crate::cli::OperationMode::Verify => 0,
         }
     }
 }
@@ -307,6 +383,7 @@ pub struct ProgressReporter {
     processed: Arc<AtomicUsize>,
     start_time: Instant,
     show_progress: bool,
+    format: ProgressFormat,
     last_update: std::time::Instant,
     update_interval: Duration,
 }
@@ -318,11 +395,23 @@ impl ProgressReporter {
             processed: Arc::new(AtomicUsize::new(0)),
             start_time: Instant::now(),
             show_progress,
+            format: ProgressFormat::default(),
             last_update: Instant::now(),
             update_interval: Duration::from_millis(100), // Update every 100ms
         }
     }
 
+    /// Set whether progress is drawn as a bar or written as JSON lines
+    pub fn with_format(mut self, format: ProgressFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// How often `update` shows progress
+    pub fn update_interval(&self) -> Duration {
+        self.update_interval
+    }
+
     /// Get a thread-safe counter for tracking progress
     pub fn get_counter(&self) -> Arc<AtomicUsize> {
         self.processed.clone()
@@ -354,6 +443,14 @@ impl ProgressReporter {
         let current = self.get_current();
         let elapsed = self.start_time.elapsed();
 
+        if self.format == ProgressFormat::Json {
+            eprintln!(
+                "{}",
+                serde_json::json!({ "processed": current, "total": self.total })
+            );
+            return;
+        }
+
         if self.total < 10 {
             // For small file counts, just show simple progress
             eprint!("\rProcessing files: {}/{}", current, self.total);
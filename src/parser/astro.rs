@@ -0,0 +1,50 @@
+/// Byte offset where an Astro component's template starts
+///
+/// Astro components may begin with a frontmatter script fenced by `---`
+/// lines; everything up to and including the closing fence is skipped.
+pub fn template_start(source: &str) -> usize {
+    let leading_ws = source.len() - source.trim_start().len();
+    let rest = &source[leading_ws..];
+
+    let Some(after_open) = rest.strip_prefix("---") else {
+        return 0;
+    };
+    let Some(open_line_end) = after_open.find('\n') else {
+        return 0;
+    };
+    if !after_open[..open_line_end].trim().is_empty() {
+        return 0;
+    }
+
+    let body_start = leading_ws + 3 + open_line_end + 1;
+    let mut line_start = body_start;
+    for line in source[body_start..].split_inclusive('\n') {
+        if line.trim_end() == "---" {
+            return line_start + line.len();
+        }
+        line_start += line.len();
+    }
+
+    // Unterminated frontmatter: treat the whole file as script
+    source.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_template_start_skips_frontmatter() {
+        let source = "---\nconst a = \"p-4 flex\";\n---\n<div class=\"p-4\"></div>";
+        assert_eq!(
+            &source[template_start(source)..],
+            "<div class=\"p-4\"></div>"
+        );
+
+        assert_eq!(template_start("<div></div>"), 0);
+        assert_eq!(
+            template_start("---\nunterminated"),
+            "---\nunterminated".len()
+        );
+    }
+}
@@ -0,0 +1,176 @@
+const APPLY: &str = "@apply";
+
+/// Byte ranges of the utility lists of a stylesheet's `@apply` directives
+///
+/// Each range covers the utilities only, so indentation, a trailing
+/// `!important`, and the closing `;` are left in place. Directives are
+/// found at any nesting depth, such as inside `@layer` blocks or nested
+/// rules, and a `;` or `}` inside an arbitrary value (`content-['a;b']`)
+/// doesn't end the directive. Comments and strings are skipped, as are `//`
+/// line comments when `line_comments` is set (SCSS). Directives with SCSS
+/// `#{...}` interpolation are skipped.
+pub fn apply_directives(source: &str, line_comments: bool) -> Vec<(usize, usize)> {
+    let bytes = source.as_bytes();
+    let mut directives = Vec::new();
+    let mut pos = 0;
+
+    while pos < bytes.len() {
+        match bytes[pos] {
+            b'/' if bytes.get(pos + 1) == Some(&b'*') => {
+                pos = find_from(source, pos + 2, "*/").map_or(source.len(), |end| end + 2);
+            }
+            b'/' if line_comments && bytes.get(pos + 1) == Some(&b'/') => {
+                pos = find_from(source, pos, "\n").unwrap_or(source.len());
+            }
+            quote @ (b'"' | b'\'') => {
+                pos += 1;
+                while pos < bytes.len() && bytes[pos] != quote {
+                    pos += if bytes[pos] == b'\\' { 2 } else { 1 };
+                }
+                pos += 1;
+            }
+            b'@' if source[pos..].starts_with(APPLY)
+                && bytes
+                    .get(pos + APPLY.len())
+                    .is_some_and(u8::is_ascii_whitespace) =>
+            {
+                let start = pos + APPLY.len();
+                let end = directive_end(source, start);
+                if let Some(range) = utility_range(source, start, end) {
+                    directives.push(range);
+                }
+                pos = end;
+            }
+            _ => pos += 1,
+        }
+    }
+
+    directives
+}
+
+/// The end of an `@apply` utility list starting at `start`: the first `;`
+/// or `}` outside brackets, parentheses, and quotes
+///
+/// Lists with unbalanced brackets or quotes, or with SCSS interpolation,
+/// end at their first `;` or `}` instead.
+fn directive_end(source: &str, start: usize) -> usize {
+    let bytes = source.as_bytes();
+    let first_stop = source[start..]
+        .find([';', '}'])
+        .map_or(source.len(), |i| start + i);
+    let mut depth = 0usize;
+    let mut quote = None;
+    let mut pos = start;
+
+    while pos < bytes.len() {
+        match (bytes[pos], quote) {
+            (b'\\', _) => pos += 1,
+            (byte, Some(open)) if byte == open => quote = None,
+            (_, Some(_)) => {}
+            (byte @ (b'"' | b'\''), None) => quote = Some(byte),
+            (b'[' | b'(', None) => depth += 1,
+            (b']' | b')', None) => depth = depth.saturating_sub(1),
+            (b';' | b'}', None) if depth == 0 => return pos,
+            (b'{' | b'}', None) => return first_stop,
+            _ => {}
+        }
+        pos += 1;
+    }
+
+    first_stop
+}
+
+/// The utilities in `source[start..end]`, without surrounding whitespace or
+/// a trailing `!important`
+fn utility_range(source: &str, start: usize, end: usize) -> Option<(usize, usize)> {
+    let mut value = source[start..end].trim_end();
+    if let Some(rest) = value.strip_suffix("!important") {
+        value = rest.trim_end();
+    }
+    let leading = value.len() - value.trim_start().len();
+
+    let utilities = &value[leading..];
+    if utilities.is_empty() || utilities.contains("#{") {
+        return None;
+    }
+    Some((start + leading, start + value.len()))
+}
+
+fn find_from(source: &str, pos: usize, needle: &str) -> Option<usize> {
+    source[pos..].find(needle).map(|i| pos + i)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn utilities(source: &str, line_comments: bool) -> Vec<&str> {
+        apply_directives(source, line_comments)
+            .into_iter()
+            .map(|(start, end)| &source[start..end])
+            .collect()
+    }
+
+    #[test]
+    fn test_apply_directives() {
+        let source = r#".btn {
+  @apply p-4 flex items-center;
+  color: red;
+}
+.card { @apply rounded  shadow }
+.alert {
+  @apply font-bold p-2 !important;
+}
+"#;
+        assert_eq!(
+            utilities(source, false),
+            ["p-4 flex items-center", "rounded  shadow", "font-bold p-2"]
+        );
+    }
+
+    #[test]
+    fn test_comments_and_strings_are_skipped() {
+        let source = r#"/* @apply p-4 flex; */
+.a::before { content: "@apply m-2 block;"; }
+// @apply text-lg font-bold;
+.b { @apply m-2 block; }
+"#;
+        assert_eq!(utilities(source, true), ["m-2 block"]);
+        // Plain CSS has no line comments
+        assert_eq!(utilities(source, false), ["text-lg font-bold", "m-2 block"]);
+    }
+
+    #[test]
+    fn test_nested_layers_and_arbitrary_values() {
+        let source = r#"@layer components {
+  .btn {
+    @apply px-4 content-['a;b'] grid-cols-[repeat(2,minmax(0,1fr))] flex;
+
+    &:hover {
+      @apply bg-[url('/img/x}y.png')] underline;
+    }
+  }
+}
+@variants hover, focus {
+  .link { @apply text-blue-500 font-bold }
+}
+.broken { @apply p-4 w-[calc(100%; }
+"#;
+        assert_eq!(
+            utilities(source, false),
+            [
+                "px-4 content-['a;b'] grid-cols-[repeat(2,minmax(0,1fr))] flex",
+                "bg-[url('/img/x}y.png')] underline",
+                "text-blue-500 font-bold",
+                "p-4 w-[calc(100%",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_dynamic_and_empty_directives_are_skipped() {
+        assert!(utilities(".a { @apply p-4 #{$size}; }", true).is_empty());
+        assert!(utilities(".a { @apply ; }", false).is_empty());
+        assert!(utilities(".a { @applyx p-4; }", false).is_empty());
+    }
+}
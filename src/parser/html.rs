@@ -0,0 +1,299 @@
+use super::QuoteStyle;
+
+/// Value of an attribute found in a markup tag, as byte offsets into the source
+#[derive(Debug, Clone, PartialEq)]
+pub enum AttributeValue {
+    /// `name`, with no value
+    Empty,
+    /// `name="..."` or `name='...'`; the span includes the quotes
+    Quoted {
+        start: usize,
+        end: usize,
+        quote_style: QuoteStyle,
+    },
+    /// `name={...}` in templates with expressions; the span excludes the braces
+    Expression { start: usize, end: usize },
+    /// `name=value`
+    Unquoted { start: usize, end: usize },
+}
+
+/// An attribute found in a markup tag
+#[derive(Debug, Clone, PartialEq)]
+pub struct MarkupAttribute<'s> {
+    pub name: &'s str,
+    pub value: AttributeValue,
+}
+
+/// Call `on_attribute` for every attribute of every tag in `source[start..]`,
+/// and `on_script` with the start and end offsets of every `<script>` body
+///
+/// This is a tolerant tokenizer for HTML and HTML-like templates rather than
+/// a full parser: comments and doctypes are skipped, `<script>` and `<style>`
+/// bodies are not scanned, and tags and attribute values may span lines.
+/// With `expressions`, `{...}` attribute values (as in Astro) are matched by
+/// brace depth while ignoring braces inside string literals.
+pub fn scan_markup<'s>(
+    source: &'s str,
+    start: usize,
+    expressions: bool,
+    mut on_attribute: impl FnMut(MarkupAttribute<'s>),
+    mut on_script: impl FnMut(usize, usize),
+) {
+    let bytes = source.as_bytes();
+    let mut pos = start;
+
+    while let Some(offset) = source[pos..].find('<') {
+        pos += offset;
+
+        if source[pos..].starts_with("<!--") {
+            pos = find_from(source, pos + 4, "-->").map_or(source.len(), |end| end + 3);
+            continue;
+        }
+
+        let name_start = pos + 1;
+        if !bytes.get(name_start).is_some_and(u8::is_ascii_alphabetic) {
+            pos += 1;
+            continue;
+        }
+
+        let name_end = scan_while(bytes, name_start, |b| {
+            !b.is_ascii_whitespace() && b != b'>' && b != b'/'
+        });
+        let tag_name = &source[name_start..name_end];
+
+        pos = scan_tag_attributes(source, name_end, expressions, &mut on_attribute);
+
+        // Raw text elements can contain `<` and quotes that aren't markup
+        if tag_name.eq_ignore_ascii_case("script") || tag_name.eq_ignore_ascii_case("style") {
+            let closing = format!("</{}", tag_name.to_ascii_lowercase());
+            let body_start = pos;
+            pos = find_from_ignore_case(source, pos, &closing).unwrap_or(source.len());
+            if tag_name.eq_ignore_ascii_case("script") {
+                on_script(body_start, pos);
+            }
+        }
+    }
+}
+
+/// Scan the attributes of a tag whose name ends at `pos`, returning the
+/// offset just past the closing `>`
+fn scan_tag_attributes<'s>(
+    source: &'s str,
+    mut pos: usize,
+    expressions: bool,
+    on_attribute: &mut impl FnMut(MarkupAttribute<'s>),
+) -> usize {
+    let bytes = source.as_bytes();
+
+    loop {
+        pos = scan_while(bytes, pos, |b| b.is_ascii_whitespace());
+
+        match bytes.get(pos) {
+            None => return source.len(),
+            Some(b'>') => return pos + 1,
+            Some(b'/') if bytes.get(pos + 1) == Some(&b'>') => return pos + 2,
+            Some(b'/') => {
+                pos += 1;
+                continue;
+            }
+            // Spread attributes like `{...props}`
+            Some(b'{') if expressions => {
+                pos = matching_brace(source, pos).map_or(source.len(), |end| end + 1);
+                continue;
+            }
+            _ => {}
+        }
+
+        let name_start = pos;
+        pos = scan_while(bytes, pos, |b| {
+            !b.is_ascii_whitespace() && b != b'=' && b != b'>' && b != b'/'
+        });
+        let name = &source[name_start..pos];
+
+        let after_name = scan_while(bytes, pos, |b| b.is_ascii_whitespace());
+        if bytes.get(after_name) != Some(&b'=') {
+            on_attribute(MarkupAttribute {
+                name,
+                value: AttributeValue::Empty,
+            });
+            continue;
+        }
+
+        pos = scan_while(bytes, after_name + 1, |b| b.is_ascii_whitespace());
+        let value = match bytes.get(pos) {
+            Some(&quote @ (b'"' | b'\'')) => {
+                let Some(close) = source[pos + 1..].find(quote as char) else {
+                    return source.len();
+                };
+                let end = pos + 1 + close + 1;
+                let quote_style = if quote == b'"' {
+                    QuoteStyle::Double
+                } else {
+                    QuoteStyle::Single
+                };
+                let value = AttributeValue::Quoted {
+                    start: pos,
+                    end,
+                    quote_style,
+                };
+                pos = end;
+                value
+            }
+            Some(b'{') if expressions => {
+                let Some(close) = matching_brace(source, pos) else {
+                    return source.len();
+                };
+                let value = AttributeValue::Expression {
+                    start: pos + 1,
+                    end: close,
+                };
+                pos = close + 1;
+                value
+            }
+            _ => {
+                let start = pos;
+                pos = scan_while(bytes, pos, |b| !b.is_ascii_whitespace() && b != b'>');
+                AttributeValue::Unquoted { start, end: pos }
+            }
+        };
+
+        on_attribute(MarkupAttribute { name, value });
+    }
+}
+
+/// Find the `}` matching the `{` at `open`, skipping string and template literals
+fn matching_brace(source: &str, open: usize) -> Option<usize> {
+    let bytes = source.as_bytes();
+    let mut depth = 0usize;
+    let mut pos = open;
+
+    while pos < bytes.len() {
+        match bytes[pos] {
+            b'{' => depth += 1,
+            b'}' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(pos);
+                }
+            }
+            quote @ (b'"' | b'\'' | b'`') => {
+                pos += 1;
+                while pos < bytes.len() && bytes[pos] != quote {
+                    if bytes[pos] == b'\\' {
+                        pos += 1;
+                    }
+                    pos += 1;
+                }
+            }
+            _ => {}
+        }
+        pos += 1;
+    }
+
+    None
+}
+
+fn scan_while(bytes: &[u8], mut pos: usize, predicate: impl Fn(u8) -> bool) -> usize {
+    while pos < bytes.len() && predicate(bytes[pos]) {
+        pos += 1;
+    }
+    pos
+}
+
+fn find_from(source: &str, pos: usize, needle: &str) -> Option<usize> {
+    source[pos..].find(needle).map(|offset| pos + offset)
+}
+
+fn find_from_ignore_case(source: &str, pos: usize, needle: &str) -> Option<usize> {
+    source[pos..]
+        .to_ascii_lowercase()
+        .find(needle)
+        .map(|offset| pos + offset)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn collect(source: &str, expressions: bool) -> Vec<(String, AttributeValue)> {
+        let mut attributes = Vec::new();
+        scan_markup(
+            source,
+            0,
+            expressions,
+            |attr| attributes.push((attr.name.to_string(), attr.value)),
+            |_, _| {},
+        );
+        attributes
+    }
+
+    #[test]
+    fn test_scan_attribute_values() {
+        let source =
+            r#"<a class="p-4 flex" data-x='y' hidden class:list={["a", { b: c }]} href=/x>"#;
+        let attributes = collect(source, true);
+
+        let names: Vec<&str> = attributes.iter().map(|(n, _)| n.as_str()).collect();
+        assert_eq!(names, ["class", "data-x", "hidden", "class:list", "href"]);
+
+        let AttributeValue::Quoted { start, end, .. } = attributes[0].1 else {
+            panic!("class should be quoted");
+        };
+        assert_eq!(&source[start..end], "\"p-4 flex\"");
+
+        let AttributeValue::Expression { start, end } = attributes[3].1 else {
+            panic!("class:list should be an expression");
+        };
+        assert_eq!(&source[start..end], "[\"a\", { b: c }]");
+
+        assert_eq!(attributes[2].1, AttributeValue::Empty);
+    }
+
+    #[test]
+    fn test_braces_are_plain_values_without_expressions() {
+        let attributes = collect("<div data-x={a} class='p-4'>", false);
+
+        assert_eq!(attributes.len(), 2);
+        assert!(matches!(attributes[0].1, AttributeValue::Unquoted { .. }));
+        assert_eq!(attributes[1].0, "class");
+    }
+
+    #[test]
+    fn test_scan_multiline_and_self_closing_tags() {
+        let source = "<!DOCTYPE html>\n<img\n  src=\"a.png\"\n  class=\"p-4\n    flex\"\n/><br/><input class='m-2'>";
+        let attributes = collect(source, false);
+
+        let names: Vec<&str> = attributes.iter().map(|(n, _)| n.as_str()).collect();
+        assert_eq!(names, ["src", "class", "class"]);
+
+        let AttributeValue::Quoted { start, end, .. } = attributes[1].1 else {
+            panic!("class should be quoted");
+        };
+        assert_eq!(&source[start..end], "\"p-4\n    flex\"");
+    }
+
+    #[test]
+    fn test_scan_skips_comments_and_raw_text() {
+        let source = r#"<!-- <p class="a"> --><style>.x { }</style><script>if (a <b) {}</script><p class="b"/>"#;
+        let attributes = collect(source, false);
+
+        assert_eq!(attributes.len(), 1);
+        assert_eq!(attributes[0].0, "class");
+    }
+
+    #[test]
+    fn test_scan_markup_reports_script_bodies() {
+        let source =
+            r#"<script lang="ts">const a = cn("p-4");</script><p class="b"/><SCRIPT>b()</SCRIPT>"#;
+        let mut scripts = Vec::new();
+        scan_markup(
+            source,
+            0,
+            true,
+            |_| {},
+            |start, end| scripts.push(&source[start..end]),
+        );
+
+        assert_eq!(scripts, [r#"const a = cn("p-4");"#, "b()"]);
+    }
+}
@@ -0,0 +1,210 @@
+/// Kinds of JavaScript found in an MDX document
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BlockKind {
+    /// `import`/`export` statements
+    Esm,
+    /// A JSX element or `{expression}` starting a line, parsed as the
+    /// children of a fragment
+    Jsx,
+    /// The body of a ```` ```jsx ```` or ```` ```tsx ```` code fence
+    Code,
+}
+
+/// A region of an MDX document that holds JavaScript
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Block {
+    pub kind: BlockKind,
+    pub start: usize,
+    /// Possible ends of the region, shortest first. JSX elements and exports
+    /// may contain blank lines, so the end of every paragraph up to the next
+    /// code fence is a candidate; the first one that parses wins.
+    pub ends: Vec<usize>,
+}
+
+/// Paragraphs a block may span before it is given up on
+const MAX_BLOCK_PARAGRAPHS: usize = 64;
+
+/// Find the regions of an MDX document that can be parsed as JavaScript
+///
+/// Blocks start at lines beginning with `import`, `export`, a JSX tag, or
+/// `{`. Markdown prose and code fences in other languages are never part of
+/// a block. Blocks may overlap when an earlier block spans several
+/// paragraphs; callers skip blocks starting inside one they parsed.
+pub fn code_blocks(source: &str) -> Vec<Block> {
+    let lines = lines_with_offsets(source);
+    let mut blocks = Vec::new();
+    let mut index = 0;
+
+    while index < lines.len() {
+        let (start, line) = lines[index];
+
+        if let Some((fence, info)) = fence_open(line) {
+            let body_start = start + line.len();
+            let close = lines[index + 1..]
+                .iter()
+                .position(|(_, line)| is_fence_close(line, fence));
+            let Some(close) = close.map(|offset| index + 1 + offset) else {
+                // An unclosed fence runs to the end of the document
+                break;
+            };
+
+            let language = info.split_whitespace().next().unwrap_or("");
+            if matches!(language, "jsx" | "tsx") {
+                blocks.push(Block {
+                    kind: BlockKind::Code,
+                    start: body_start,
+                    ends: vec![lines[close].0],
+                });
+            }
+            index = close + 1;
+            continue;
+        }
+
+        if let Some(kind) = block_start(line) {
+            blocks.push(Block {
+                kind,
+                start: start + (line.len() - line.trim_start().len()),
+                ends: paragraph_ends(&lines[index..]),
+            });
+        }
+        index += 1;
+    }
+
+    blocks
+}
+
+/// Each line of `source` with its byte offset, line endings included
+fn lines_with_offsets(source: &str) -> Vec<(usize, &str)> {
+    let mut offset = 0;
+    source
+        .split_inclusive('\n')
+        .map(|line| {
+            let start = offset;
+            offset += line.len();
+            (start, line)
+        })
+        .collect()
+}
+
+/// The kind of block a line starts, if any
+fn block_start(line: &str) -> Option<BlockKind> {
+    let indent = line.len() - line.trim_start_matches(' ').len();
+    // Lines indented four spaces or more are Markdown code
+    if indent >= 4 {
+        return None;
+    }
+
+    let trimmed = line.trim();
+    if trimmed.starts_with("import ") || trimmed.starts_with("export ") {
+        return Some(BlockKind::Esm);
+    }
+
+    let mut chars = trimmed.chars();
+    match (chars.next(), chars.next()) {
+        (Some('<'), Some(next)) if next.is_ascii_alphabetic() || next == '>' => {
+            Some(BlockKind::Jsx)
+        }
+        (Some('{'), _) => Some(BlockKind::Jsx),
+        _ => None,
+    }
+}
+
+/// Ends of the paragraphs starting at `lines[0]`, stopping at a code fence
+fn paragraph_ends(lines: &[(usize, &str)]) -> Vec<usize> {
+    let mut ends = Vec::new();
+    let mut last_content_end = None;
+
+    for &(start, line) in lines {
+        if fence_open(line).is_some() {
+            break;
+        }
+
+        if line.trim().is_empty() {
+            if let Some(end) = last_content_end.take() {
+                ends.push(end);
+                if ends.len() == MAX_BLOCK_PARAGRAPHS {
+                    return ends;
+                }
+            }
+        } else {
+            last_content_end = Some(start + line.trim_end().len());
+        }
+    }
+
+    ends.extend(last_content_end);
+    ends
+}
+
+/// The fence marker (such as "```") and info string of a line opening a
+/// code fence
+fn fence_open(line: &str) -> Option<(&str, &str)> {
+    let trimmed = line.trim_start_matches(' ');
+    if line.len() - trimmed.len() >= 4 {
+        return None;
+    }
+
+    let marker = trimmed.chars().next().filter(|c| matches!(c, '`' | '~'))?;
+    let fence_len = trimmed.len() - trimmed.trim_start_matches(marker).len();
+    if fence_len < 3 {
+        return None;
+    }
+
+    Some((&trimmed[..fence_len], trimmed[fence_len..].trim()))
+}
+
+/// Whether a line closes a fence opened with `fence`
+fn is_fence_close(line: &str, fence: &str) -> bool {
+    let trimmed = line.trim();
+    let marker = fence.as_bytes()[0] as char;
+    trimmed.len() >= fence.len() && trimmed.chars().all(|c| c == marker)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn block_texts(source: &str) -> Vec<(BlockKind, &str)> {
+        code_blocks(source)
+            .into_iter()
+            .map(|block| (block.kind, &source[block.start..block.ends[0]]))
+            .collect()
+    }
+
+    #[test]
+    fn test_finds_esm_jsx_and_jsx_fences() {
+        let source = "import { Card } from './card'\n\n# Title\n\nSome <b>prose</b> text.\n\n<Card className=\"p-4 flex\">\n  Hello\n</Card>\n\n```jsx\n<div className=\"p-4 flex\" />\n```\n\n```html\n<div class=\"p-4 flex\"></div>\n```\n\n{cn('p-4 flex')}\n";
+
+        assert_eq!(
+            block_texts(source),
+            [
+                (BlockKind::Esm, "import { Card } from './card'"),
+                (
+                    BlockKind::Jsx,
+                    "<Card className=\"p-4 flex\">\n  Hello\n</Card>"
+                ),
+                (BlockKind::Code, "<div className=\"p-4 flex\" />\n"),
+                (BlockKind::Jsx, "{cn('p-4 flex')}"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_blocks_end_at_any_later_paragraph_before_a_fence() {
+        let source = "<Tabs>\n\nFirst\n\n</Tabs>\n\n```js\nconst a = 1\n```\n\n<Note />";
+        let blocks = code_blocks(source);
+
+        assert_eq!(blocks.len(), 2);
+        let ends: Vec<&str> = blocks[0].ends.iter().map(|&end| &source[..end]).collect();
+        assert_eq!(
+            ends,
+            ["<Tabs>", "<Tabs>\n\nFirst", "<Tabs>\n\nFirst\n\n</Tabs>"]
+        );
+        assert_eq!(&source[blocks[1].start..blocks[1].ends[0]], "<Note />");
+    }
+
+    #[test]
+    fn test_ignores_indented_code_and_unclosed_fences() {
+        assert!(code_blocks("    <div className=\"p-4 flex\" />\n").is_empty());
+        assert!(code_blocks("```jsx\n<div className=\"p-4 flex\" />\n").is_empty());
+    }
+}
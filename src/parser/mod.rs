@@ -2,37 +2,300 @@ use oxc_allocator::Allocator;
 use oxc_ast::Visit;
 use oxc_parser::{Parser, ParserReturn};
 use oxc_span::SourceType;
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
 use std::path::Path;
 
+use crate::config::TailwindDetection;
+use crate::output::position::offset_to_line_col;
+use crate::sorter::KnownCustomClasses;
+use crate::trace::{TraceEvent, Tracer};
 use crate::{Result, WindWardenError};
 
 pub use visitor::ClassExtractor;
 
+/// Tags whose tagged templates are sorted unless configured otherwise
+pub const DEFAULT_TEMPLATE_TAGS: &[&str] = &["tw"];
+
+fn default_template_tags() -> HashSet<String> {
+    DEFAULT_TEMPLATE_TAGS
+        .iter()
+        .map(|tag| tag.to_string())
+        .collect()
+}
+
+mod astro;
+mod css;
+mod html;
+mod mdx;
 mod visitor;
 
 pub struct FileParser {
-    allocator: Allocator,
+    /// Arena for the AST of the file being parsed, reset before each parse so
+    /// its memory is reused across files
+    allocator: RefCell<Allocator>,
     custom_functions: Option<Vec<String>>,
+    function_arg_indices: HashMap<String, Vec<usize>>,
+    /// Contexts whose matches are kept (every context when `None`)
+    sort_contexts: Option<HashSet<SortContext>>,
+    /// Tags whose tagged templates are sorted
+    template_tags: HashSet<String>,
+    /// Whether template literals without a tag are sorted
+    sort_untagged_templates: bool,
+    /// Props like `classNames` whose object values are all class strings
+    class_object_props: Vec<String>,
+    /// Non-Tailwind classes that count as classes when detecting class strings
+    known_custom_classes: KnownCustomClasses,
+    detection: TailwindDetection,
+    tracer: Tracer,
+}
+
+/// Syntax errors reported by the parser, described by the first one
+struct SyntaxErrors {
+    count: usize,
+    message: String,
+    /// Byte offset of the first error in the parsed source, if known
+    offset: Option<usize>,
+}
+
+/// Class matches of a file along with the supported functions it calls
+#[derive(Debug, Clone, Default)]
+pub struct ParsedFile {
+    pub matches: Vec<ClassMatch>,
+    /// Supported functions (such as `cn`) called anywhere in the file
+    pub called_functions: HashSet<String>,
+    /// Whether some of the file's code didn't parse, so `called_functions`
+    /// may be missing calls
+    pub calls_incomplete: bool,
+}
+
+/// Markup languages that are scanned for class attributes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Markup {
+    Html,
+    Astro,
+    Svelte,
+}
+
+/// A clone has the same settings and an arena of its own
+impl Clone for FileParser {
+    fn clone(&self) -> Self {
+        Self {
+            allocator: RefCell::new(Allocator::default()),
+            custom_functions: self.custom_functions.clone(),
+            function_arg_indices: self.function_arg_indices.clone(),
+            sort_contexts: self.sort_contexts.clone(),
+            template_tags: self.template_tags.clone(),
+            sort_untagged_templates: self.sort_untagged_templates,
+            class_object_props: self.class_object_props.clone(),
+            known_custom_classes: self.known_custom_classes.clone(),
+            detection: self.detection,
+            tracer: self.tracer.clone(),
+        }
+    }
 }
 
 impl FileParser {
     pub fn new() -> Self {
         Self {
-            allocator: Allocator::default(),
+            allocator: RefCell::new(Allocator::default()),
             custom_functions: None,
+            function_arg_indices: HashMap::new(),
+            sort_contexts: None,
+            template_tags: default_template_tags(),
+            sort_untagged_templates: true,
+            class_object_props: Vec::new(),
+            known_custom_classes: KnownCustomClasses::default(),
+            detection: TailwindDetection::default(),
+            tracer: Tracer::disabled(),
         }
     }
 
     pub fn new_with_custom_functions(custom_functions: Vec<String>) -> Self {
         Self {
-            allocator: Allocator::default(),
+            allocator: RefCell::new(Allocator::default()),
             custom_functions: Some(custom_functions),
+            function_arg_indices: HashMap::new(),
+            sort_contexts: None,
+            template_tags: default_template_tags(),
+            sort_untagged_templates: true,
+            class_object_props: Vec::new(),
+            known_custom_classes: KnownCustomClasses::default(),
+            detection: TailwindDetection::default(),
+            tracer: Tracer::disabled(),
         }
     }
 
+    /// Restrict sorting to specific argument indices for the given functions
+    pub fn with_function_arg_indices(
+        mut self,
+        function_arg_indices: HashMap<String, Vec<usize>>,
+    ) -> Self {
+        self.function_arg_indices = function_arg_indices;
+        self
+    }
+
+    /// Only keep matches found in the given contexts
+    pub fn with_sort_contexts(mut self, contexts: impl IntoIterator<Item = SortContext>) -> Self {
+        self.sort_contexts = Some(contexts.into_iter().collect());
+        self
+    }
+
+    /// Only sort tagged templates whose tag is one of these, such as `tw`
+    ///
+    /// Member and call tags like `tw.div` and `styled(Link)` match by the name
+    /// they start with.
+    pub fn with_template_tags(mut self, tags: impl IntoIterator<Item = String>) -> Self {
+        self.template_tags = tags.into_iter().collect();
+        self
+    }
+
+    /// Whether to sort template literals that have no tag
+    pub fn with_untagged_templates(mut self, sort: bool) -> Self {
+        self.sort_untagged_templates = sort;
+        self
+    }
+
+    /// Sort every value in these props' object literals, such as the slot map
+    /// in `classNames={{ root: "p-4 flex" }}`, whatever the keys are
+    pub fn with_class_object_props(mut self, props: impl IntoIterator<Item = String>) -> Self {
+        self.class_object_props = props.into_iter().collect();
+        self
+    }
+
+    /// Set how many tokens of a string must look like Tailwind classes for it
+    /// to be sorted
+    pub fn with_detection(mut self, detection: TailwindDetection) -> Self {
+        self.detection = detection;
+        self
+    }
+
+    /// The thresholds for recognizing strings of Tailwind classes
+    pub fn detection(&self) -> TailwindDetection {
+        self.detection
+    }
+
+    /// Count these classes, or prefixes ending in `*`, as Tailwind classes
+    /// when deciding whether a string holds classes
+    pub fn with_known_custom_classes(mut self, patterns: &[String]) -> Self {
+        self.known_custom_classes = KnownCustomClasses::new(patterns);
+        self
+    }
+
+    /// An extractor for checking whether strings look like class lists, with
+    /// this parser's detection settings
+    pub(crate) fn class_checker<'a>(&self, source_text: &'a str) -> ClassExtractor<'a> {
+        ClassExtractor::new(source_text)
+            .with_detection(self.detection)
+            .with_known_custom_classes(self.known_custom_classes.clone())
+    }
+
+    /// Record parse results to the given tracer
+    pub fn with_tracer(mut self, tracer: Tracer) -> Self {
+        self.tracer = tracer;
+        self
+    }
+
+    /// Replace the tracer used to record parse results
+    pub fn set_tracer(&mut self, tracer: Tracer) {
+        self.tracer = tracer;
+    }
+
     pub fn parse_file(&self, file_path: &str, source_text: &str) -> Result<Vec<ClassMatch>> {
+        self.parse_file_detailed(file_path, source_text)
+            .map(|parsed| parsed.matches)
+    }
+
+    /// Parse a file, also reporting which supported functions it calls
+    ///
+    /// Matches outside the enabled sort contexts are dropped.
+    pub fn parse_file_detailed(&self, file_path: &str, source_text: &str) -> Result<ParsedFile> {
+        let mut parsed = self.parse_file_all_contexts(file_path, source_text)?;
+        parsed
+            .matches
+            .retain(|class_match| self.keeps_match(class_match));
+        Ok(parsed)
+    }
+
+    /// Whether a match is in an enabled sort context and, for template
+    /// literals, has a sorted tag
+    pub(crate) fn keeps_match(&self, class_match: &ClassMatch) -> bool {
+        if self
+            .sort_contexts
+            .as_ref()
+            .is_some_and(|contexts| !contexts.contains(&class_match.pattern_type.context()))
+        {
+            return false;
+        }
+        match &class_match.pattern_type {
+            PatternType::TemplateLiteral { tag } => self.is_sorted_template(tag.as_deref()),
+            _ => true,
+        }
+    }
+
+    /// Whether `name` is a function whose arguments are sorted
+    pub(crate) fn is_supported_function(&self, name: &str) -> bool {
+        visitor::DEFAULT_SUPPORTED_FUNCTIONS.contains(&name)
+            || self
+                .custom_functions
+                .as_ref()
+                .is_some_and(|functions| functions.iter().any(|function| function == name))
+            || self.function_arg_indices.contains_key(name)
+    }
+
+    /// Byte ranges of a file that may hold code, or `None` when all of it may
+    ///
+    /// In MDX, only imports, exports, JSX, and jsx/tsx code fences are code;
+    /// the Markdown around them isn't.
+    pub(crate) fn code_regions(
+        &self,
+        file_path: &str,
+        source_text: &str,
+    ) -> Option<Vec<(usize, usize)>> {
+        if !Self::has_extension(file_path, "mdx") {
+            return None;
+        }
+        Some(
+            mdx::code_blocks(source_text)
+                .into_iter()
+                .filter_map(|block| block.ends.last().map(|&end| (block.start, end)))
+                .collect(),
+        )
+    }
+
+    /// Whether a template literal with this tag, if any, is sorted
+    fn is_sorted_template(&self, tag: Option<&str>) -> bool {
+        let Some(tag) = tag else {
+            return self.sort_untagged_templates;
+        };
+        let name = tag.split(['.', '(']).next().unwrap_or(tag).trim();
+        self.template_tags.contains(tag) || self.template_tags.contains(name)
+    }
+
+    fn parse_file_all_contexts(&self, file_path: &str, source_text: &str) -> Result<ParsedFile> {
+        if Self::has_extension(file_path, "astro") {
+            let template_start = astro::template_start(source_text);
+            return Ok(self.parse_markup(file_path, source_text, template_start, Markup::Astro));
+        }
+
+        if Self::has_extension(file_path, "svelte") {
+            return Ok(self.parse_markup(file_path, source_text, 0, Markup::Svelte));
+        }
+
+        if Self::has_extension(file_path, "mdx") {
+            return Ok(self.parse_mdx(file_path, source_text));
+        }
+
+        if Self::has_extension(file_path, "html") || Self::has_extension(file_path, "htm") {
+            return Ok(self.parse_markup(file_path, source_text, 0, Markup::Html));
+        }
+
+        if Self::has_extension(file_path, "css") || Self::has_extension(file_path, "scss") {
+            return Ok(self.parse_stylesheet(file_path, source_text));
+        }
+
         let source_type = self.detect_source_type(file_path);
-        self.parse_source_with_path(source_text, source_type, file_path)
+        self.parse_source_detailed(source_text, source_type, file_path)
     }
 
     pub fn parse_source(
@@ -49,49 +312,350 @@ impl FileParser {
         source_type: SourceType,
         file_path: &str,
     ) -> Result<Vec<ClassMatch>> {
+        self.parse_source_detailed(source_text, source_type, file_path)
+            .map(|parsed| parsed.matches)
+    }
+
+    fn parse_source_detailed(
+        &self,
+        source_text: &str,
+        source_type: SourceType,
+        file_path: &str,
+    ) -> Result<ParsedFile> {
         // Wrap incomplete JSX in a component for parsing
         let (wrapped_source, offset) = self.wrap_jsx_if_needed(source_text);
+        // Wrapped sources are trimmed, so wrapper offsets map to just after leading whitespace
+        let leading_ws = if offset > 0 {
+            source_text.len() - source_text.trim_start().len()
+        } else {
+            0
+        };
+
+        let mut parsed = match self.extract_matches(&wrapped_source, source_type) {
+            Ok(parsed) => parsed,
+            Err(errors) => {
+                let position = errors
+                    .offset
+                    .map(|pos| (pos.saturating_sub(offset) + leading_ws).min(source_text.len()))
+                    .unwrap_or(0);
+                let (line, column) = offset_to_line_col(source_text, position);
+
+                let message = if errors.count == 1 {
+                    errors.message
+                } else {
+                    format!("{} (and {} more errors)", errors.message, errors.count - 1)
+                };
+
+                self.tracer.emit(|| TraceEvent::ParseFailed {
+                    file: file_path.to_string(),
+                    message: message.clone(),
+                });
+
+                return Err(WindWardenError::parse_error_at(
+                    file_path, line, column, message,
+                ));
+            }
+        };
+
+        // Adjust spans back to original source if we wrapped it
+        if offset > 0 {
+            for class_match in &mut parsed.matches {
+                if class_match.start >= offset && class_match.end >= offset {
+                    class_match.shift(leading_ws as isize - offset as isize);
+                }
+            }
+        }
+
+        self.tracer.emit(|| TraceEvent::Parsed {
+            file: file_path.to_string(),
+            matches: parsed.matches.len(),
+        });
+
+        Ok(parsed)
+    }
+
+    /// Parse source and collect class matches, or describe its syntax errors
+    fn extract_matches(
+        &self,
+        source_text: &str,
+        source_type: SourceType,
+    ) -> std::result::Result<ParsedFile, SyntaxErrors> {
+        // Nothing allocated in the arena outlives this call: matches own their
+        // strings, so the previous file's AST can be freed for reuse
+        let mut allocator = self.allocator.borrow_mut();
+        allocator.reset();
 
         let ParserReturn {
             program, errors, ..
-        } = Parser::new(&self.allocator, &wrapped_source, source_type).parse();
-
-        if !errors.is_empty() {
-            // For now, provide a simplified error message since extracting line numbers
-            // from Oxc diagnostics is complex and would require more dependencies
-            let error_count = errors.len();
-            let message = if error_count == 1 {
-                "Syntax error in file".to_string()
-            } else {
-                format!("{} syntax errors in file", error_count)
-            };
+        } = Parser::new(&allocator, source_text, source_type).parse();
 
-            return Err(WindWardenError::parse_error(
-                file_path, 1, // Default to line 1 for now
-                message,
-            ));
+        if let Some(first) = errors.first() {
+            return Err(SyntaxErrors {
+                count: errors.len(),
+                message: first.message.to_string(),
+                offset: first
+                    .labels
+                    .as_ref()
+                    .and_then(|labels| labels.first())
+                    .map(|label| label.offset()),
+            });
         }
 
         let mut extractor = if let Some(ref custom_functions) = self.custom_functions {
-            ClassExtractor::new_with_custom_functions(&wrapped_source, custom_functions)
+            ClassExtractor::new_with_custom_functions(source_text, custom_functions)
         } else {
-            ClassExtractor::new(&wrapped_source)
-        };
+            ClassExtractor::new(source_text)
+        }
+        .with_detection(self.detection)
+        .with_known_custom_classes(self.known_custom_classes.clone())
+        .with_class_object_props(self.class_object_props.iter().cloned());
+        extractor.set_function_arg_indices(&self.function_arg_indices);
         extractor.visit_program(&program);
 
-        let mut matches = extractor.into_matches();
+        let called_functions = extractor.called_functions().clone();
+        Ok(ParsedFile {
+            matches: extractor.into_matches(),
+            called_functions,
+            calls_incomplete: false,
+        })
+    }
 
-        // Adjust spans back to original source if we wrapped it
-        if offset > 0 {
-            for class_match in &mut matches {
-                if class_match.start >= offset && class_match.end >= offset {
-                    class_match.start -= offset;
-                    class_match.end -= offset;
+    /// Extract class matches from HTML or an Astro or Svelte component's markup
+    ///
+    /// Only `source_text[start..]` is scanned, so Astro frontmatter is left
+    /// untouched, and `<script>`/`<style>` bodies are skipped. Static `class`
+    /// attributes are sorted in place. In Astro and Svelte, `class`/`class:list`
+    /// expressions are parsed as JavaScript, so dynamic templates inside them
+    /// are skipped as usual; Svelte's `class:name` directives are left alone.
+    /// In HTML, the string keys of Angular `[ngClass]` object literals are
+    /// sorted, while `{{ }}` interpolations and `[class]` bindings are skipped.
+    ///
+    /// Supported function calls are collected from the attribute expressions,
+    /// the `<script>` bodies, and Astro frontmatter.
+    fn parse_markup(
+        &self,
+        file_path: &str,
+        source_text: &str,
+        start: usize,
+        markup: Markup,
+    ) -> ParsedFile {
+        let class_checker = self.class_checker(source_text);
+        let mut matches = Vec::new();
+        let mut called_functions = HashSet::new();
+        let mut calls_incomplete = false;
+        let mut scripts = Vec::new();
+        let expressions = markup != Markup::Html;
+
+        if markup == Markup::Astro && start > 0 {
+            let frontmatter = source_text[..start].trim();
+            let frontmatter = frontmatter.strip_prefix("---").unwrap_or(frontmatter);
+            scripts.push(frontmatter.strip_suffix("---").unwrap_or(frontmatter));
+        }
+
+        let on_script = |body_start, body_end| scripts.push(&source_text[body_start..body_end]);
+        html::scan_markup(
+            source_text,
+            start,
+            expressions,
+            |attr| {
+                if markup == Markup::Html && attr.name == "[ngClass]" {
+                    if let html::AttributeValue::Quoted { start, end, .. } = attr.value {
+                        // Angular's `{'p-4 flex': cond}` takes the same shape as Solid's classList
+                        let prefix = "<x classList={";
+                        let expression =
+                            format!("{}{}}} />", prefix, &source_text[start + 1..end - 1]);
+                        let source_type =
+                            SourceType::default().with_typescript(true).with_jsx(true);
+
+                        match self.extract_matches(&expression, source_type) {
+                            Ok(parsed) => {
+                                matches.extend(parsed.matches.into_iter().map(|mut m| {
+                                    m.shift(start as isize + 1 - prefix.len() as isize);
+                                    m
+                                }));
+                                called_functions.extend(parsed.called_functions);
+                            }
+                            Err(_) => calls_incomplete = true,
+                        }
+                    }
+                    return;
                 }
+
+                if !matches!(attr.name, "class" | "className" | "class:list") {
+                    return;
+                }
+
+                match attr.value {
+                    html::AttributeValue::Quoted {
+                        start,
+                        end,
+                        quote_style,
+                    } => {
+                        let content = &source_text[start + 1..end - 1];
+                        // Svelte interpolates `{...}` inside quoted attribute values, and
+                        // Angular templates `{{...}}`
+                        let dynamic = match markup {
+                            Markup::Svelte => content.contains('{'),
+                            Markup::Html => content.contains("{{"),
+                            Markup::Astro => false,
+                        };
+                        if !content.trim().is_empty()
+                            && !dynamic
+                            && class_checker.looks_like_tailwind_classes(content)
+                        {
+                            matches.push(ClassMatch::new(
+                                start,
+                                end,
+                                content.to_string(),
+                                quote_style,
+                                PatternType::MarkupAttribute,
+                            ));
+                        }
+                    }
+                    html::AttributeValue::Expression { start, end } => {
+                        // Parenthesize so object literals parse as expressions. `class:list`
+                        // goes through a JSX attribute so its object keys are sorted too.
+                        let prefix = if attr.name == "class:list" {
+                            "<x class:list={"
+                        } else {
+                            "("
+                        };
+                        let suffix = if attr.name == "class:list" {
+                            "} />"
+                        } else {
+                            ")"
+                        };
+                        let expression =
+                            format!("{}{}{}", prefix, &source_text[start..end], suffix);
+                        let source_type =
+                            SourceType::default().with_typescript(true).with_jsx(true);
+
+                        // Expressions that don't parse are treated as dynamic and skipped
+                        match self.extract_matches(&expression, source_type) {
+                            Ok(parsed) => {
+                                matches.extend(parsed.matches.into_iter().map(|mut m| {
+                                    m.shift(start as isize - prefix.len() as isize);
+                                    m
+                                }));
+                                called_functions.extend(parsed.called_functions);
+                            }
+                            Err(_) => calls_incomplete = true,
+                        }
+                    }
+                    html::AttributeValue::Empty | html::AttributeValue::Unquoted { .. } => {}
+                }
+            },
+            on_script,
+        );
+
+        // Scripts are only searched for calls; their strings aren't sorted
+        let script_type = SourceType::default().with_typescript(true);
+        for script in scripts {
+            match self.extract_matches(script, script_type) {
+                Ok(parsed) => called_functions.extend(parsed.called_functions),
+                Err(_) => calls_incomplete = true,
             }
         }
 
-        Ok(matches)
+        self.tracer.emit(|| TraceEvent::Parsed {
+            file: file_path.to_string(),
+            matches: matches.len(),
+        });
+
+        ParsedFile {
+            matches,
+            called_functions,
+            calls_incomplete,
+        }
+    }
+
+    /// Extract class matches from the imports, exports, JSX, and jsx/tsx code
+    /// fences of an MDX document
+    ///
+    /// Each block is parsed on its own, JSX blocks as the children of a
+    /// fragment, so Markdown between them is never parsed. A block spanning
+    /// blank lines is parsed up to the first paragraph end where it is
+    /// complete; blocks that never parse are left untouched.
+    fn parse_mdx(&self, file_path: &str, source_text: &str) -> ParsedFile {
+        let source_type = SourceType::default().with_typescript(true).with_jsx(true);
+        let mut matches = Vec::new();
+        let mut called_functions = HashSet::new();
+        let mut calls_incomplete = false;
+        let mut parsed_until = 0;
+
+        for block in mdx::code_blocks(source_text) {
+            if block.start < parsed_until {
+                continue;
+            }
+
+            let (prefix, suffix) = match block.kind {
+                mdx::BlockKind::Jsx => ("<>", "</>"),
+                mdx::BlockKind::Esm | mdx::BlockKind::Code => ("", ""),
+            };
+            let parsed = block.ends.iter().find_map(|&end| {
+                let code = format!("{}{}{}", prefix, &source_text[block.start..end], suffix);
+                self.extract_matches(&code, source_type)
+                    .ok()
+                    .map(|parsed| (parsed, end))
+            });
+            match parsed {
+                Some((parsed, end)) => {
+                    matches.extend(parsed.matches.into_iter().map(|mut m| {
+                        m.shift(block.start as isize - prefix.len() as isize);
+                        m
+                    }));
+                    called_functions.extend(parsed.called_functions);
+                    parsed_until = end;
+                }
+                None => calls_incomplete = true,
+            }
+        }
+
+        self.tracer.emit(|| TraceEvent::Parsed {
+            file: file_path.to_string(),
+            matches: matches.len(),
+        });
+
+        ParsedFile {
+            matches,
+            called_functions,
+            calls_incomplete,
+        }
+    }
+
+    /// Extract the utility lists of a stylesheet's `@apply` directives
+    ///
+    /// Everything else in the stylesheet is left untouched.
+    fn parse_stylesheet(&self, file_path: &str, source_text: &str) -> ParsedFile {
+        let line_comments = Self::has_extension(file_path, "scss");
+        let matches: Vec<ClassMatch> = css::apply_directives(source_text, line_comments)
+            .into_iter()
+            .map(|(start, end)| {
+                ClassMatch::new(
+                    start,
+                    end,
+                    source_text[start..end].to_string(),
+                    QuoteStyle::Double,
+                    PatternType::CssApply,
+                )
+            })
+            .collect();
+
+        self.tracer.emit(|| TraceEvent::Parsed {
+            file: file_path.to_string(),
+            matches: matches.len(),
+        });
+
+        ParsedFile {
+            matches,
+            ..Default::default()
+        }
+    }
+
+    fn has_extension(file_path: &str, extension: &str) -> bool {
+        Path::new(file_path)
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case(extension))
     }
 
     fn wrap_jsx_if_needed(&self, source_text: &str) -> (String, usize) {
@@ -156,6 +720,188 @@ pub enum QuoteStyle {
     Backtick,
 }
 
+/// The quotes sorted class strings are written with, set by `quoteStyle`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum QuotePreference {
+    /// Keep each string's own quotes
+    #[default]
+    Preserve,
+    Single,
+    Double,
+}
+
+impl QuotePreference {
+    /// Names accepted by `quoteStyle`
+    pub const NAMES: &'static [&'static str] = &["preserve", "single", "double"];
+
+    /// The quote every string is written with, or `None` to keep their own
+    pub fn quote(self) -> Option<QuoteStyle> {
+        match self {
+            QuotePreference::Preserve => None,
+            QuotePreference::Single => Some(QuoteStyle::Single),
+            QuotePreference::Double => Some(QuoteStyle::Double),
+        }
+    }
+}
+
+impl std::str::FromStr for QuotePreference {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "preserve" => Ok(QuotePreference::Preserve),
+            "single" => Ok(QuotePreference::Single),
+            "double" => Ok(QuotePreference::Double),
+            _ => Err(format!(
+                "Invalid quote style '{}'. Valid options: {}",
+                s,
+                Self::NAMES.join(", ")
+            )),
+        }
+    }
+}
+
+impl QuoteStyle {
+    pub fn as_char(self) -> char {
+        match self {
+            QuoteStyle::Single => '\'',
+            QuoteStyle::Double => '"',
+            QuoteStyle::Backtick => '`',
+        }
+    }
+
+    /// The quote to wrap raw literal `content` in, keeping `self` when possible
+    ///
+    /// Falls back to the other plain quote when `self` appears unescaped in
+    /// the content, e.g. `content-['x']` taken from a double-quoted literal.
+    /// Returns `self` when both quotes appear; see [`QuoteStyle::wrap`].
+    pub fn for_content(self, content: &str) -> QuoteStyle {
+        let fallback = match self {
+            QuoteStyle::Double => QuoteStyle::Single,
+            _ => QuoteStyle::Double,
+        };
+
+        if !has_unescaped(content, self.as_char()) {
+            self
+        } else if !has_unescaped(content, fallback.as_char()) {
+            fallback
+        } else {
+            self
+        }
+    }
+
+    /// Wrap raw literal `content` in this quote
+    ///
+    /// Existing escape sequences are kept as they are; only unescaped
+    /// occurrences of the quote itself get a backslash.
+    pub fn wrap(self, content: &str) -> String {
+        let quote = self.as_char();
+        let mut wrapped = String::with_capacity(content.len() + 2);
+        wrapped.push(quote);
+
+        let mut chars = content.chars();
+        while let Some(ch) = chars.next() {
+            if ch == '\\' {
+                wrapped.push(ch);
+                if let Some(escaped) = chars.next() {
+                    wrapped.push(escaped);
+                }
+                continue;
+            }
+            if ch == quote {
+                wrapped.push('\\');
+            }
+            wrapped.push(ch);
+        }
+
+        wrapped.push(quote);
+        wrapped
+    }
+}
+
+/// Whether `quote` appears in raw literal content without a preceding backslash
+fn has_unescaped(content: &str, quote: char) -> bool {
+    let mut chars = content.chars();
+    while let Some(ch) = chars.next() {
+        if ch == '\\' {
+            chars.next();
+        } else if ch == quote {
+            return true;
+        }
+    }
+    false
+}
+
+/// The kind of code a class string was found in, for limiting sorting to
+/// some kinds with `sortContexts`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SortContext {
+    /// `className="..."` and other class attributes, in JSX and markup
+    JsxAttribute,
+    /// Arguments of supported functions such as `cn(...)`
+    SupportedFunction,
+    /// Other string literals that look like classes
+    StringLiteral,
+    /// Static and tagged template literals
+    TemplateLiteral,
+    /// Arrays of class strings
+    Array,
+    /// String branches of `cond ? "..." : "..."`
+    Conditional,
+    /// String keys of `classList`-style objects
+    ObjectKey,
+    /// Concatenated strings (`"..." + "..."`)
+    Concatenation,
+    /// CSS `@apply` directives
+    CssApply,
+}
+
+impl SortContext {
+    /// Every context, in the order of their names
+    pub const ALL: &'static [SortContext] = &[
+        SortContext::JsxAttribute,
+        SortContext::SupportedFunction,
+        SortContext::StringLiteral,
+        SortContext::TemplateLiteral,
+        SortContext::Array,
+        SortContext::Conditional,
+        SortContext::ObjectKey,
+        SortContext::Concatenation,
+        SortContext::CssApply,
+    ];
+
+    /// Names accepted by `sortContexts`
+    pub const NAMES: &'static [&'static str] = &[
+        "jsxAttribute",
+        "supportedFunction",
+        "stringLiteral",
+        "templateLiteral",
+        "array",
+        "conditional",
+        "objectKey",
+        "concatenation",
+        "cssApply",
+    ];
+}
+
+impl std::str::FromStr for SortContext {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        Self::NAMES
+            .iter()
+            .position(|name| *name == s)
+            .map(|index| Self::ALL[index])
+            .ok_or_else(|| {
+                format!(
+                    "Invalid sort context '{}'. Valid options: {}",
+                    s,
+                    Self::NAMES.join(", ")
+                )
+            })
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum PatternType {
     JSXAttribute,
@@ -171,11 +917,44 @@ pub enum PatternType {
     },
     Array {
         elements: Vec<String>, // All the string elements from the array
+        /// Spans of the elements' string literals, including quotes
+        element_spans: Vec<(usize, usize)>,
     },
+    /// A string literal branch of a `cond ? "..." : "..."` expression
+    ConditionalBranch,
+    /// A string key of a `classList={{ "...": cond }}` object
+    ObjectKey,
     BinaryExpression {
         left_content: String,
         right_content: String,
+        /// Spans of the left and right string literals, including quotes
+        left_span: (usize, usize),
+        right_span: (usize, usize),
     },
+    /// A quoted attribute in a non-JSX template, such as Astro's `class="..."`
+    MarkupAttribute,
+    /// The utilities of a CSS `@apply` directive, without quotes
+    CssApply,
+}
+
+impl PatternType {
+    /// The sort context a match of this kind belongs to
+    pub fn context(&self) -> SortContext {
+        match self {
+            PatternType::JSXAttribute | PatternType::MarkupAttribute => SortContext::JsxAttribute,
+            // Strings outside supported calls are recorded as calls to "general"
+            PatternType::FunctionCall { function_name, .. } if function_name == "general" => {
+                SortContext::StringLiteral
+            }
+            PatternType::FunctionCall { .. } => SortContext::SupportedFunction,
+            PatternType::TemplateLiteral { .. } => SortContext::TemplateLiteral,
+            PatternType::ArrayElement { .. } | PatternType::Array { .. } => SortContext::Array,
+            PatternType::ConditionalBranch => SortContext::Conditional,
+            PatternType::ObjectKey => SortContext::ObjectKey,
+            PatternType::BinaryExpression { .. } => SortContext::Concatenation,
+            PatternType::CssApply => SortContext::CssApply,
+        }
+    }
 }
 
 impl ClassMatch {
@@ -194,4 +973,169 @@ impl ClassMatch {
             pattern_type,
         }
     }
+
+    /// Move the match, and any spans inside its pattern, by `delta` bytes
+    pub(crate) fn shift(&mut self, delta: isize) {
+        let move_by = |offset: &mut usize| *offset = offset.saturating_add_signed(delta);
+
+        move_by(&mut self.start);
+        move_by(&mut self.end);
+        if let PatternType::BinaryExpression {
+            left_span,
+            right_span,
+            ..
+        } = &mut self.pattern_type
+        {
+            for span in [left_span, right_span] {
+                move_by(&mut span.0);
+                move_by(&mut span.1);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_error_position(file_path: &str, source: &str) -> (usize, usize, String) {
+        match FileParser::new().parse_file(file_path, source) {
+            Err(WindWardenError::ParseError {
+                line,
+                column,
+                message,
+                ..
+            }) => (line, column.expect("parse errors carry a column"), message),
+            other => panic!("expected a parse error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_parse_error_reports_line_and_column() {
+        let source = "const a = 1;\nconst b = ;\n";
+        let (line, column, message) = parse_error_position("test.ts", source);
+
+        assert_eq!((line, column), (2, 11));
+        assert!(!message.is_empty());
+        assert_ne!(message, "Syntax error in file");
+    }
+
+    #[test]
+    fn test_parse_error_position_ignores_jsx_wrapper() {
+        // Bare JSX is wrapped in a component before parsing
+        let source = "  <div className=\"p-4\">{ )}</div>";
+        let (line, column, _) = parse_error_position("test.tsx", source);
+
+        assert_eq!((line, column), (1, 26));
+    }
+
+    #[test]
+    fn test_parse_error_column_is_optional() {
+        let without_column = WindWardenError::parse_error("a.tsx", 3, "Unexpected token");
+        assert_eq!(
+            without_column.to_string(),
+            "Parse error in a.tsx at line 3: Unexpected token"
+        );
+
+        let with_column = WindWardenError::parse_error_at("a.tsx", 3, 7, "Unexpected token");
+        assert_eq!(
+            with_column.to_string(),
+            "Parse error in a.tsx at line 3, column 7: Unexpected token"
+        );
+    }
+
+    #[test]
+    fn test_parse_file_detailed_reports_called_functions() {
+        let parser =
+            FileParser::new_with_custom_functions(vec!["tw".to_string(), "styles".to_string()]);
+        let source = r#"const a = cn(base);
+const b = tw("p-4 flex");
+const c = other("p-4 flex");"#;
+
+        let parsed = parser.parse_file_detailed("test.ts", source).unwrap();
+        let mut called: Vec<_> = parsed.called_functions.into_iter().collect();
+        called.sort();
+        assert_eq!(called, vec!["cn", "tw"]);
+
+        // Calls inside markup expressions and scripts count too
+        let source = "<script>styles('p-4')</script>\n<p class={tw('p-4 flex')}></p>";
+        let parsed = parser
+            .parse_file_detailed("Component.svelte", source)
+            .unwrap();
+        assert_eq!(
+            parsed.called_functions,
+            HashSet::from(["tw".to_string(), "styles".to_string()])
+        );
+        assert!(!parsed.calls_incomplete);
+
+        let source = "---\nconst a = styles(size);\n---\n<p class=\"p-4\"></p>";
+        let parsed = parser.parse_file_detailed("Card.astro", source).unwrap();
+        assert_eq!(
+            parsed.called_functions,
+            HashSet::from(["styles".to_string()])
+        );
+
+        // Scripts that don't parse may hide calls
+        let parsed = parser
+            .parse_file_detailed("Broken.svelte", "<script>tw(</script>")
+            .unwrap();
+        assert!(parsed.calls_incomplete);
+    }
+
+    #[test]
+    fn test_parser_reuses_allocator_across_files() {
+        let parser = FileParser::new();
+
+        for i in 0..50 {
+            let source = format!("const a{} = cn(\"p-{} flex\");", i, i % 8);
+            let matches = parser.parse_file("test.ts", &source).unwrap();
+            assert_eq!(matches.len(), 1);
+            assert_eq!(matches[0].original, format!("p-{} flex", i % 8));
+        }
+
+        // A failed parse leaves the parser usable
+        assert!(parser.parse_file("test.ts", "const = ;").is_err());
+        let matches = parser
+            .parse_file("test.ts", "const b = cn('m-2 block');")
+            .unwrap();
+        assert_eq!(matches[0].original, "m-2 block");
+    }
+
+    #[test]
+    fn test_quote_style_for_content() {
+        assert_eq!(
+            QuoteStyle::Single.for_content("p-4 flex"),
+            QuoteStyle::Single
+        );
+        assert_eq!(
+            QuoteStyle::Single.for_content("content-['x'] p-4"),
+            QuoteStyle::Double
+        );
+        assert_eq!(
+            QuoteStyle::Double.for_content(r#"content-["x"] p-4"#),
+            QuoteStyle::Single
+        );
+        // Escaped quotes are already valid inside the preferred quote
+        assert_eq!(
+            QuoteStyle::Single.for_content(r"content-[\'x\'] p-4"),
+            QuoteStyle::Single
+        );
+        assert_eq!(
+            QuoteStyle::Double.for_content(r#"content-['it'] after:content-["x"]"#),
+            QuoteStyle::Double
+        );
+    }
+
+    #[test]
+    fn test_quote_style_wrap() {
+        assert_eq!(QuoteStyle::Double.wrap("flex p-4"), r#""flex p-4""#);
+        assert_eq!(
+            QuoteStyle::Double.wrap(r"content-[\'x\'] flex"),
+            r#""content-[\'x\'] flex""#
+        );
+        assert_eq!(
+            QuoteStyle::Single.wrap(r"content-['\\'] flex"),
+            r"'content-[\'\\\'] flex'"
+        );
+    }
 }
@@ -1,17 +1,178 @@
 use oxc_ast::Visit;
 use oxc_ast::ast::*;
-use oxc_span::Span;
+use oxc_ast::visit::walk;
+use oxc_span::{GetSpan, Span};
+use std::collections::HashMap;
 
 use super::{ClassMatch, PatternType, QuoteStyle};
-
-const DEFAULT_SUPPORTED_FUNCTIONS: &[&str] =
-    &["cn", "twMerge", "clsx", "classNames", "classList", "cva"];
+use crate::config::TailwindDetection;
+use crate::sorter::KnownCustomClasses;
+
+pub(super) const DEFAULT_SUPPORTED_FUNCTIONS: &[&str] = &[
+    "cn",
+    "twMerge",
+    "clsx",
+    "classNames",
+    "classList",
+    "cva",
+    "cx",
+    "tv",
+];
+
+/// Class names, or class name prefixes ending in `-`, recognized as Tailwind
+/// utilities once variants are stripped
+const TAILWIND_PREFIXES: &[&str] = &[
+    // Layout
+    "block",
+    "inline",
+    "flex",
+    "grid",
+    "table",
+    "hidden",
+    "relative",
+    "absolute",
+    "fixed",
+    "sticky",
+    "static",
+    "inset-",
+    "top-",
+    "right-",
+    "bottom-",
+    "left-",
+    "z-",
+    "float-",
+    "clear-",
+    "object-",
+    "overflow-",
+    "overscroll-",
+    "position-",
+    "visible",
+    "invisible",
+    "collapse",
+    // Container Queries
+    "@container",
+    "@apply",
+    "@screen",
+    "@layer",
+    // Flexbox & Grid
+    "items-",
+    "justify-",
+    "gap-",
+    "grid-",
+    "col-",
+    "row-",
+    "flex-",
+    "order-",
+    "justify-self-",
+    "justify-items-",
+    "content-",
+    "items-",
+    "self-",
+    // Spacing
+    "p-",
+    "px-",
+    "py-",
+    "pt-",
+    "pr-",
+    "pb-",
+    "pl-",
+    "m-",
+    "mx-",
+    "my-",
+    "mt-",
+    "mr-",
+    "mb-",
+    "ml-",
+    "space-",
+    "-space-",
+    // Sizing
+    "w-",
+    "h-",
+    "min-w-",
+    "min-h-",
+    "max-w-",
+    "max-h-",
+    "size-",
+    // Typography
+    "text-",
+    "font-",
+    "leading-",
+    "tracking-",
+    "line-",
+    "list-",
+    "placeholder-",
+    "decoration-",
+    "underline",
+    "overline",
+    "line-through",
+    "no-underline",
+    // Backgrounds
+    "bg-",
+    "from-",
+    "via-",
+    "to-",
+    "gradient-",
+    // Borders
+    "border",
+    "border-",
+    "rounded",
+    "rounded-",
+    "divide-",
+    "outline-",
+    // Effects
+    "shadow",
+    "shadow-",
+    "opacity-",
+    "ring-",
+    "ring-",
+    "drop-shadow-",
+    // Filters
+    "blur-",
+    "brightness-",
+    "contrast-",
+    "grayscale",
+    "invert",
+    "saturate-",
+    "sepia",
+    "hue-rotate-",
+    "filter",
+    "backdrop-",
+    // Transforms
+    "transform",
+    "rotate-",
+    "scale-",
+    "translate-",
+    "skew-",
+    "origin-",
+    // Transitions
+    "transition",
+    "duration-",
+    "ease-",
+    "delay-",
+    "animate-",
+    // Interactivity
+    "cursor-",
+    "select-",
+    "pointer-events-",
+    "resize",
+    "scroll-",
+    "snap-",
+    "touch-",
+    "will-change-",
+];
 
 pub struct ClassExtractor<'a> {
     source_text: &'a str,
     matches: Vec<ClassMatch>,
     processed_spans: std::collections::HashSet<(usize, usize)>,
     supported_functions: std::collections::HashSet<String>,
+    function_arg_indices: HashMap<String, Vec<usize>>,
+    called_functions: std::collections::HashSet<String>,
+    /// Props like `classNames` whose object values are all class strings
+    class_object_props: std::collections::HashSet<String>,
+    /// Non-Tailwind classes that count as classes when detecting class strings
+    known_custom_classes: KnownCustomClasses,
+    detection: TailwindDetection,
 }
 
 impl<'a> ClassExtractor<'a> {
@@ -26,6 +187,11 @@ impl<'a> ClassExtractor<'a> {
             matches: Vec::new(),
             processed_spans: std::collections::HashSet::new(),
             supported_functions,
+            function_arg_indices: HashMap::new(),
+            called_functions: std::collections::HashSet::new(),
+            class_object_props: std::collections::HashSet::new(),
+            known_custom_classes: KnownCustomClasses::default(),
+            detection: TailwindDetection::default(),
         }
     }
 
@@ -47,13 +213,51 @@ impl<'a> ClassExtractor<'a> {
             matches: Vec::new(),
             processed_spans: std::collections::HashSet::new(),
             supported_functions,
+            function_arg_indices: HashMap::new(),
+            called_functions: std::collections::HashSet::new(),
+            class_object_props: std::collections::HashSet::new(),
+            known_custom_classes: KnownCustomClasses::default(),
+            detection: TailwindDetection::default(),
+        }
+    }
+
+    /// Limit which call arguments are treated as class lists for specific functions
+    pub fn set_function_arg_indices(&mut self, function_arg_indices: &HashMap<String, Vec<usize>>) {
+        for (func, indices) in function_arg_indices {
+            self.supported_functions.insert(func.clone());
+            self.function_arg_indices
+                .insert(func.clone(), indices.clone());
         }
     }
 
+    /// Set how many tokens of a string must look like Tailwind classes
+    pub fn with_detection(mut self, detection: TailwindDetection) -> Self {
+        self.detection = detection;
+        self
+    }
+
+    /// Count these classes as Tailwind-like when detecting class strings
+    pub(crate) fn with_known_custom_classes(mut self, known: KnownCustomClasses) -> Self {
+        self.known_custom_classes = known;
+        self
+    }
+
+    /// Treat every value in these props' object literals as a class string,
+    /// whatever its key, as in `classNames={{ root: "p-4 flex" }}`
+    pub fn with_class_object_props(mut self, props: impl IntoIterator<Item = String>) -> Self {
+        self.class_object_props = props.into_iter().collect();
+        self
+    }
+
     pub fn into_matches(self) -> Vec<ClassMatch> {
         self.matches
     }
 
+    /// Supported functions that were called in the visited source
+    pub fn called_functions(&self) -> &std::collections::HashSet<String> {
+        &self.called_functions
+    }
+
     fn extract_string_value(&self, span: Span) -> String {
         let start = span.start as usize;
         let end = span.end as usize;
@@ -114,7 +318,7 @@ impl<'a> ClassExtractor<'a> {
         }
     }
 
-    fn looks_like_tailwind_classes(&self, content: &str) -> bool {
+    pub(crate) fn looks_like_tailwind_classes(&self, content: &str) -> bool {
         let trimmed = content.trim();
 
         // Exclude obvious non-class strings
@@ -123,7 +327,7 @@ impl<'a> ClassExtractor<'a> {
         }
 
         // Check if string contains potential Tailwind classes
-        if trimmed.contains(' ') {
+        if trimmed.contains(char::is_whitespace) {
             // Multiple words - check if they look like CSS classes
             return self.contains_tailwind_like_tokens(trimmed);
         }
@@ -171,19 +375,18 @@ impl<'a> ClassExtractor<'a> {
         let tokens: Vec<&str> = content.split_whitespace().collect();
 
         // For multi-token strings, require a higher threshold to avoid false positives
-        // At least 50% of tokens should look like Tailwind classes, and at least 2 tokens total
         if tokens.len() >= 2 {
             let tailwind_tokens = tokens
                 .iter()
                 .filter(|token| self.matches_tailwind_pattern(token))
                 .count();
 
-            // For short strings (2-4 tokens), require at least 2 Tailwind tokens
-            // For longer strings, require at least 50% to be Tailwind tokens
+            // For short strings (2-4 tokens), require a minimum number of Tailwind
+            // tokens (2 by default); for longer strings, a minimum share (50%)
             if tokens.len() <= 4 {
-                tailwind_tokens >= 2
+                tailwind_tokens >= self.detection.min_tokens
             } else {
-                tailwind_tokens as f32 / tokens.len() as f32 >= 0.5
+                tailwind_tokens as f32 / tokens.len() as f32 >= self.detection.ratio
             }
         } else {
             // Single token - check if it looks like a Tailwind class
@@ -194,6 +397,10 @@ impl<'a> ClassExtractor<'a> {
     }
 
     fn matches_tailwind_pattern(&self, mut token: &str) -> bool {
+        if self.known_custom_classes.matches(token) {
+            return true;
+        }
+
         // Handle modifiers iteratively to avoid recursion warnings
         loop {
             // Check for responsive prefixes (sm:, md:, lg:, xl:, 2xl:)
@@ -226,150 +433,8 @@ impl<'a> ClassExtractor<'a> {
             break;
         }
 
-        // Now check the core token
-        // Known Tailwind prefixes
-        let common_prefixes = [
-            // Layout
-            "block",
-            "inline",
-            "flex",
-            "grid",
-            "table",
-            "hidden",
-            "relative",
-            "absolute",
-            "fixed",
-            "sticky",
-            "static",
-            "inset-",
-            "top-",
-            "right-",
-            "bottom-",
-            "left-",
-            "z-",
-            "float-",
-            "clear-",
-            "object-",
-            "overflow-",
-            "overscroll-",
-            "position-",
-            "visible",
-            "invisible",
-            "collapse",
-            // Container Queries
-            "@container",
-            "@apply",
-            "@screen",
-            "@layer",
-            // Flexbox & Grid
-            "items-",
-            "justify-",
-            "gap-",
-            "grid-",
-            "col-",
-            "row-",
-            "flex-",
-            "order-",
-            "justify-self-",
-            "justify-items-",
-            "content-",
-            "items-",
-            "self-",
-            // Spacing
-            "p-",
-            "px-",
-            "py-",
-            "pt-",
-            "pr-",
-            "pb-",
-            "pl-",
-            "m-",
-            "mx-",
-            "my-",
-            "mt-",
-            "mr-",
-            "mb-",
-            "ml-",
-            "space-",
-            "-space-",
-            // Sizing
-            "w-",
-            "h-",
-            "min-w-",
-            "min-h-",
-            "max-w-",
-            "max-h-",
-            "size-",
-            // Typography
-            "text-",
-            "font-",
-            "leading-",
-            "tracking-",
-            "line-",
-            "list-",
-            "placeholder-",
-            "decoration-",
-            "underline",
-            "overline",
-            "line-through",
-            "no-underline",
-            // Backgrounds
-            "bg-",
-            "from-",
-            "via-",
-            "to-",
-            "gradient-",
-            // Borders
-            "border",
-            "border-",
-            "rounded",
-            "rounded-",
-            "divide-",
-            "outline-",
-            // Effects
-            "shadow",
-            "shadow-",
-            "opacity-",
-            "ring-",
-            "ring-",
-            "drop-shadow-",
-            // Filters
-            "blur-",
-            "brightness-",
-            "contrast-",
-            "grayscale",
-            "invert",
-            "saturate-",
-            "sepia",
-            "hue-rotate-",
-            "filter",
-            "backdrop-",
-            // Transforms
-            "transform",
-            "rotate-",
-            "scale-",
-            "translate-",
-            "skew-",
-            "origin-",
-            // Transitions
-            "transition",
-            "duration-",
-            "ease-",
-            "delay-",
-            "animate-",
-            // Interactivity
-            "cursor-",
-            "select-",
-            "pointer-events-",
-            "resize",
-            "scroll-",
-            "snap-",
-            "touch-",
-            "will-change-",
-        ];
-
         // Check for exact matches or prefix matches
-        common_prefixes.iter().any(|prefix| {
+        TAILWIND_PREFIXES.iter().any(|prefix| {
             if prefix.ends_with('-') {
                 token.starts_with(prefix)
             } else {
@@ -398,7 +463,17 @@ impl<'a> ClassExtractor<'a> {
     }
 
     fn process_function_arguments(&mut self, function_name: &str, arguments: &[Argument<'a>]) {
+        let allowed_indices = self.function_arg_indices.get(function_name).cloned();
+
         for (arg_index, arg) in arguments.iter().enumerate() {
+            // Skip arguments that aren't configured as class lists for this function
+            if allowed_indices
+                .as_ref()
+                .is_some_and(|indices| !indices.contains(&arg_index))
+            {
+                continue;
+            }
+
             match arg {
                 Argument::StringLiteral(string_lit) => {
                     // Direct string literal argument
@@ -410,6 +485,15 @@ impl<'a> ClassExtractor<'a> {
                         },
                     );
                 }
+                Argument::ObjectExpression(object) if function_name == "cva" => {
+                    self.process_compound_variants(function_name, arg_index, object);
+                    self.visit_argument(arg);
+                }
+                Argument::ObjectExpression(object) if function_name == "tv" => {
+                    self.process_tv_config(function_name, arg_index, object);
+                    self.process_compound_variants(function_name, arg_index, object);
+                    self.visit_argument(arg);
+                }
                 _ => {
                     // For non-string arguments (conditionals, objects, etc.),
                     // we still need to visit them to find nested string literals
@@ -419,6 +503,299 @@ impl<'a> ClassExtractor<'a> {
         }
     }
 
+    /// Sort the `class`/`className` values of cva's `compoundVariants` entries
+    ///
+    /// These values are always class lists, so unlike arbitrary strings and
+    /// arrays they are sorted even when they don't look like Tailwind classes.
+    /// Other properties of the entries are left to normal visiting.
+    fn process_compound_variants(
+        &mut self,
+        function_name: &str,
+        arg_index: usize,
+        config: &ObjectExpression<'a>,
+    ) {
+        let Some(Expression::ArrayExpression(entries)) =
+            static_property(config, "compoundVariants")
+        else {
+            return;
+        };
+
+        for entry in &entries.elements {
+            let ArrayExpressionElement::ObjectExpression(entry) = entry else {
+                continue;
+            };
+
+            for property in &entry.properties {
+                let ObjectPropertyKind::ObjectProperty(prop) = property else {
+                    continue;
+                };
+                if prop.computed
+                    || !prop
+                        .key
+                        .static_name()
+                        .is_some_and(|name| self.is_class_attribute(&name))
+                {
+                    continue;
+                }
+
+                self.push_class_list(function_name, arg_index, &prop.value);
+            }
+        }
+    }
+
+    /// Sort the class lists of a tailwind-variants `tv` config: `base`,
+    /// `slots` and the values of each variant (`variants.size.sm`)
+    ///
+    /// Like cva's `compoundVariants` class values, these are always sorted.
+    /// A variant value may also be an object of slot classes, whose values
+    /// are sorted the same way.
+    fn process_tv_config(
+        &mut self,
+        function_name: &str,
+        arg_index: usize,
+        config: &ObjectExpression<'a>,
+    ) {
+        if let Some(base) = static_property(config, "base") {
+            self.push_class_list(function_name, arg_index, base);
+        }
+        if let Some(Expression::ObjectExpression(slots)) = static_property(config, "slots") {
+            for slot in static_values(slots) {
+                self.push_class_list(function_name, arg_index, slot);
+            }
+        }
+
+        let Some(Expression::ObjectExpression(variants)) = static_property(config, "variants")
+        else {
+            return;
+        };
+
+        for variant in static_values(variants) {
+            let Expression::ObjectExpression(values) = variant else {
+                continue;
+            };
+
+            for value in static_values(values) {
+                match value {
+                    Expression::ObjectExpression(slots) => {
+                        for slot in static_values(slots) {
+                            self.push_class_list(function_name, arg_index, slot);
+                        }
+                    }
+                    value => self.push_class_list(function_name, arg_index, value),
+                }
+            }
+        }
+    }
+
+    /// Record a string or an array of strings known to hold classes, whether
+    /// or not they look like Tailwind classes
+    ///
+    /// Other expressions, and arrays mixing strings with other elements, are
+    /// left to normal visiting.
+    fn push_class_list(&mut self, function_name: &str, arg_index: usize, value: &Expression<'a>) {
+        match value {
+            Expression::StringLiteral(string_lit) => {
+                let span_key = (string_lit.span.start as usize, string_lit.span.end as usize);
+                let content = self.extract_class_string_content(string_lit.span);
+                if content.trim().is_empty() || !self.processed_spans.insert(span_key) {
+                    return;
+                }
+
+                self.matches.push(ClassMatch::new(
+                    span_key.0,
+                    span_key.1,
+                    content,
+                    self.detect_quote_style(string_lit.span),
+                    PatternType::FunctionCall {
+                        function_name: function_name.to_string(),
+                        arg_index,
+                    },
+                ));
+            }
+            Expression::ArrayExpression(array) => {
+                let mut elements = Vec::new();
+                for element in &array.elements {
+                    match element {
+                        ArrayExpressionElement::StringLiteral(string_lit) => {
+                            elements.push(string_lit.as_ref());
+                        }
+                        // Mixed arrays are visited normally
+                        _ => return,
+                    }
+                }
+
+                if !elements.is_empty() {
+                    self.push_array_match(array, &elements);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Record an array of class strings as one match, sorted as a whole
+    ///
+    /// The quote style of the first element is used for the rebuilt array.
+    fn push_array_match(&mut self, array: &ArrayExpression<'a>, literals: &[&StringLiteral<'a>]) {
+        let span_key = (array.span.start as usize, array.span.end as usize);
+
+        // Skip if already processed
+        if !self.processed_spans.insert(span_key) {
+            return;
+        }
+
+        let elements: Vec<String> = literals
+            .iter()
+            .map(|lit| self.extract_class_string_content(lit.span))
+            .collect();
+        let element_spans = literals
+            .iter()
+            .map(|lit| (lit.span.start as usize, lit.span.end as usize))
+            .collect();
+
+        // Join all elements for sorting (like a single class string)
+        let combined_classes = elements.join(" ");
+
+        let class_match = ClassMatch::new(
+            span_key.0,
+            span_key.1,
+            combined_classes,
+            self.detect_quote_style(literals[0].span),
+            PatternType::Array {
+                elements,
+                element_spans,
+            },
+        );
+        self.matches.push(class_match);
+    }
+
+    /// Sort the string keys of objects in a class list value, either the
+    /// object itself or objects directly inside an array
+    fn process_class_list_value(&mut self, expression: &Expression<'a>) {
+        match expression {
+            Expression::ObjectExpression(object) => {
+                for property in &object.properties {
+                    if let ObjectPropertyKind::ObjectProperty(prop) = property
+                        && let PropertyKey::StringLiteral(key) = &prop.key
+                    {
+                        self.process_string_literal(key, PatternType::ObjectKey);
+                    }
+                }
+            }
+            Expression::ArrayExpression(array) => {
+                for element in &array.elements {
+                    if let Some(element) = element.as_expression() {
+                        self.process_class_list_value(element);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Sort the string literal branches of a conditional expression, including
+    /// nested conditionals, without treating its test as a class list
+    fn process_conditional_branches(&mut self, conditional: &ConditionalExpression<'a>) {
+        for branch in [&conditional.consequent, &conditional.alternate] {
+            match branch {
+                Expression::StringLiteral(string_lit) => {
+                    self.process_string_literal(string_lit, PatternType::ConditionalBranch);
+                }
+                Expression::ConditionalExpression(nested) => {
+                    self.process_conditional_branches(nested);
+                }
+                _ => self.visit_expression(branch),
+            }
+        }
+    }
+
+    /// Sort the static parts of a `className={...}` expression
+    ///
+    /// Strings and static template literals are sorted directly, supported
+    /// function calls and conditionals are descended into, and dynamic
+    /// values like `className={styles}` are left alone.
+    fn process_class_expression(&mut self, expression: &Expression<'a>) {
+        match expression {
+            Expression::StringLiteral(string_lit) => {
+                self.process_string_literal(string_lit, PatternType::JSXAttribute);
+            }
+            Expression::TemplateLiteral(template) => self.visit_template_literal(template),
+            Expression::ConditionalExpression(conditional) => {
+                self.visit_conditional_expression(conditional);
+            }
+            Expression::ParenthesizedExpression(parenthesized) => {
+                self.process_class_expression(&parenthesized.expression);
+            }
+            Expression::Identifier(_)
+            | Expression::StaticMemberExpression(_)
+            | Expression::ComputedMemberExpression(_) => {}
+            _ => self.visit_expression(expression),
+        }
+    }
+
+    /// Sort the values of a slot map passed to a `classObjectProps` prop
+    ///
+    /// Values are handled like `className={...}` expressions, and nested
+    /// objects are treated as slot maps too.
+    fn process_class_object(&mut self, object: &ObjectExpression<'a>) {
+        for property in &object.properties {
+            match property {
+                ObjectPropertyKind::ObjectProperty(prop) => {
+                    match prop.value.without_parentheses() {
+                        Expression::ObjectExpression(nested) => self.process_class_object(nested),
+                        value => self.process_class_expression(value),
+                    }
+                }
+                ObjectPropertyKind::SpreadProperty(spread) => self.visit_spread_element(spread),
+            }
+        }
+    }
+
+    /// The slot map passed to a `classObjectProps` prop, if `value` is one
+    fn class_object<'b>(
+        &self,
+        name: &str,
+        value: &'b Expression<'a>,
+    ) -> Option<&'b ObjectExpression<'a>> {
+        match value.without_parentheses() {
+            Expression::ObjectExpression(object) if self.class_object_props.contains(name) => {
+                Some(object)
+            }
+            _ => None,
+        }
+    }
+
+    /// Find tagged templates and supported function calls interpolated into a
+    /// template literal, without treating its other strings as classes
+    fn visit_template_interpolations(&mut self, template: &TemplateLiteral<'a>) {
+        for expression in &template.expressions {
+            self.visit_interpolation(expression);
+        }
+    }
+
+    fn visit_interpolation(&mut self, expression: &Expression<'a>) {
+        match expression.without_parentheses() {
+            Expression::TaggedTemplateExpression(tagged) => {
+                self.visit_tagged_template_expression(tagged);
+            }
+            Expression::CallExpression(call) => self.visit_call_expression(call),
+            Expression::TemplateLiteral(template) => self.visit_template_interpolations(template),
+            Expression::LogicalExpression(logical) => {
+                self.visit_interpolation(&logical.left);
+                self.visit_interpolation(&logical.right);
+            }
+            Expression::ConditionalExpression(conditional) => {
+                self.visit_interpolation(&conditional.consequent);
+                self.visit_interpolation(&conditional.alternate);
+            }
+            Expression::ArrowFunctionExpression(arrow) => {
+                if let Some(body) = arrow.get_expression() {
+                    self.visit_interpolation(body);
+                }
+            }
+            _ => {}
+        }
+    }
+
     fn process_string_literal(
         &mut self,
         string_lit: &StringLiteral<'a>,
@@ -452,12 +829,46 @@ impl<'a> ClassExtractor<'a> {
 
 impl<'a> Visit<'a> for ClassExtractor<'a> {
     fn visit_jsx_attribute(&mut self, attr: &JSXAttribute<'a>) {
-        if let JSXAttributeName::Identifier(ident) = &attr.name {
-            if self.is_class_attribute(&ident.name) {
-                if let Some(JSXAttributeValue::StringLiteral(string_lit)) = &attr.value {
+        if let JSXAttributeName::Identifier(ident) = &attr.name
+            && self.is_class_attribute(&ident.name)
+        {
+            match &attr.value {
+                Some(JSXAttributeValue::StringLiteral(string_lit)) => {
                     self.process_string_literal(string_lit, PatternType::JSXAttribute);
                 }
+                Some(JSXAttributeValue::ExpressionContainer(container)) => {
+                    if let Some(expression) = container.expression.as_expression() {
+                        self.process_class_expression(expression);
+                    }
+                }
+                _ => {}
             }
+            return;
+        }
+
+        if let JSXAttributeName::Identifier(ident) = &attr.name
+            && let Some(JSXAttributeValue::ExpressionContainer(container)) = &attr.value
+            && let Some(object) = container
+                .expression
+                .as_expression()
+                .and_then(|expression| self.class_object(&ident.name, expression))
+        {
+            self.process_class_object(object);
+            return;
+        }
+
+        // Solid's `classList={{ ... }}` and Astro's `class:list={...}` use object keys as classes
+        let is_class_list = match &attr.name {
+            JSXAttributeName::Identifier(ident) => ident.name == "classList",
+            JSXAttributeName::NamespacedName(name) => {
+                name.namespace.name == "class" && name.property.name == "list"
+            }
+        };
+        if is_class_list
+            && let Some(JSXAttributeValue::ExpressionContainer(container)) = &attr.value
+            && let Some(expression) = container.expression.as_expression()
+        {
+            self.process_class_list_value(expression);
         }
 
         // Continue visiting child nodes
@@ -471,14 +882,33 @@ impl<'a> Visit<'a> for ClassExtractor<'a> {
         // Check if this is a supported function call
         if let Some(function_name) = self.extract_function_name(call) {
             if self.is_supported_function(&function_name) {
+                self.called_functions.insert(function_name.clone());
+
                 // For supported function calls, we DON'T continue normal visiting
                 // Instead, we manually process arguments to avoid duplicates
                 self.process_function_arguments(&function_name, &call.arguments);
                 return;
             } else {
                 // For unsupported function calls, don't visit arguments at all
-                // This prevents string literals inside them from being processed
+                // This prevents string literals inside them from being processed.
+                // Conditional branches are the exception: they are checked on their own.
+                // Tagged templates, such as a styled component passed to `memo`, and the
+                // interpolations of template literals are searched for nested class sources.
                 self.visit_expression(&call.callee);
+                for arg in &call.arguments {
+                    match arg {
+                        Argument::ConditionalExpression(conditional) => {
+                            self.process_conditional_branches(conditional);
+                        }
+                        Argument::TaggedTemplateExpression(tagged) => {
+                            self.visit_tagged_template_expression(tagged);
+                        }
+                        Argument::TemplateLiteral(template) => {
+                            self.visit_template_interpolations(template);
+                        }
+                        _ => {}
+                    }
+                }
                 return;
             }
         }
@@ -490,6 +920,11 @@ impl<'a> Visit<'a> for ClassExtractor<'a> {
         }
     }
 
+    fn visit_conditional_expression(&mut self, expr: &ConditionalExpression<'a>) {
+        self.visit_expression(&expr.test);
+        self.process_conditional_branches(expr);
+    }
+
     fn visit_string_literal(&mut self, lit: &StringLiteral<'a>) {
         // This catches string literals that are NOT in supported function calls
         // (e.g., standalone strings, strings in conditionals within non-supported functions)
@@ -504,23 +939,24 @@ impl<'a> Visit<'a> for ClassExtractor<'a> {
 
     fn visit_template_literal(&mut self, template: &TemplateLiteral<'a>) {
         // Only process static template literals (no interpolations)
-        if let Some(content) = self.extract_template_content(template) {
-            if !content.trim().is_empty() && self.looks_like_tailwind_classes(&content) {
-                let span_key = (template.span.start as usize, template.span.end as usize);
+        if let Some(content) = self.extract_template_content(template)
+            && !content.trim().is_empty()
+            && self.looks_like_tailwind_classes(&content)
+        {
+            let span_key = (template.span.start as usize, template.span.end as usize);
 
-                // Skip if already processed
-                if !self.processed_spans.contains(&span_key) {
-                    self.processed_spans.insert(span_key);
+            // Skip if already processed
+            if !self.processed_spans.contains(&span_key) {
+                self.processed_spans.insert(span_key);
 
-                    let class_match = ClassMatch::new(
-                        template.span.start as usize,
-                        template.span.end as usize,
-                        content,
-                        QuoteStyle::Backtick, // Template literals always use backticks
-                        PatternType::TemplateLiteral { tag: None },
-                    );
-                    self.matches.push(class_match);
-                }
+                let class_match = ClassMatch::new(
+                    template.span.start as usize,
+                    template.span.end as usize,
+                    content,
+                    QuoteStyle::Backtick, // Template literals always use backticks
+                    PatternType::TemplateLiteral { tag: None },
+                );
+                self.matches.push(class_match);
             }
         }
 
@@ -531,33 +967,39 @@ impl<'a> Visit<'a> for ClassExtractor<'a> {
     }
 
     fn visit_tagged_template_expression(&mut self, tagged: &TaggedTemplateExpression<'a>) {
-        // Extract tag name if it's a simple identifier
+        // The tag as written, such as `tw`, `tw.div`, or `styled(Link)`
         let tag_name = match &tagged.tag {
             Expression::Identifier(ident) => Some(ident.name.to_string()),
-            _ => None,
+            tag => {
+                let span = tag.span();
+                self.source_text
+                    .get(span.start as usize..span.end as usize)
+                    .map(str::to_string)
+            }
         };
 
         // Process the template part
-        if let Some(content) = self.extract_template_content(&tagged.quasi) {
-            if !content.trim().is_empty() && self.looks_like_tailwind_classes(&content) {
-                let span_key = (
+        if let Some(content) = self.extract_template_content(&tagged.quasi)
+            && !content.trim().is_empty()
+            && self.looks_like_tailwind_classes(&content)
+        {
+            let span_key = (
+                tagged.quasi.span.start as usize,
+                tagged.quasi.span.end as usize,
+            );
+
+            // Skip if already processed
+            if !self.processed_spans.contains(&span_key) {
+                self.processed_spans.insert(span_key);
+
+                let class_match = ClassMatch::new(
                     tagged.quasi.span.start as usize,
                     tagged.quasi.span.end as usize,
+                    content,
+                    QuoteStyle::Backtick,
+                    PatternType::TemplateLiteral { tag: tag_name },
                 );
-
-                // Skip if already processed
-                if !self.processed_spans.contains(&span_key) {
-                    self.processed_spans.insert(span_key);
-
-                    let class_match = ClassMatch::new(
-                        tagged.quasi.span.start as usize,
-                        tagged.quasi.span.end as usize,
-                        content,
-                        QuoteStyle::Backtick,
-                        PatternType::TemplateLiteral { tag: tag_name },
-                    );
-                    self.matches.push(class_match);
-                }
+                self.matches.push(class_match);
             }
         }
 
@@ -570,21 +1012,15 @@ impl<'a> Visit<'a> for ClassExtractor<'a> {
         // Collect string literals from the array and check if they look like classes
         let mut string_elements = Vec::new();
         let mut tailwind_elements = Vec::new();
-        let mut quote_style = QuoteStyle::Double; // Default
         let mut total_strings = 0;
 
         for element in &array.elements {
             match element {
                 ArrayExpressionElement::StringLiteral(string_lit) => {
                     let content = self.extract_class_string_content(string_lit.span);
-                    string_elements.push(content.clone());
+                    string_elements.push(string_lit.as_ref());
                     total_strings += 1;
 
-                    // Use the quote style from the first element for consistency
-                    if total_strings == 1 {
-                        quote_style = self.detect_quote_style(string_lit.span);
-                    }
-
                     // Check if this specific element looks like Tailwind classes
                     if self.looks_like_tailwind_classes(&content) {
                         tailwind_elements.push(content);
@@ -612,54 +1048,43 @@ impl<'a> Visit<'a> for ClassExtractor<'a> {
         };
 
         if total_strings > 0 && (tailwind_ratio >= 0.5 || string_elements.len() == 1) {
-            let span_key = (array.span.start as usize, array.span.end as usize);
-
-            // Skip if already processed
-            if !self.processed_spans.contains(&span_key) {
-                self.processed_spans.insert(span_key);
-
-                // Join all elements for sorting (like a single class string)
-                let combined_classes = string_elements.join(" ");
-
-                let class_match = ClassMatch::new(
-                    array.span.start as usize,
-                    array.span.end as usize,
-                    combined_classes,
-                    quote_style,
-                    PatternType::Array {
-                        elements: string_elements,
-                    },
-                );
-                self.matches.push(class_match);
-            }
+            self.push_array_match(array, &string_elements);
         }
     }
 
     fn visit_object_property(&mut self, prop: &ObjectProperty<'a>) {
+        // Slot maps nested in objects, such as props spread onto a component
+        if let PropertyKey::StaticIdentifier(ident) = &prop.key
+            && let Some(object) = self.class_object(&ident.name, &prop.value)
+        {
+            self.process_class_object(object);
+            return;
+        }
+
         // Check if this is a className or class property
-        if let PropertyKey::StaticIdentifier(ident) = &prop.key {
-            if self.is_class_attribute(&ident.name) {
-                // Process the value if it's a string literal
-                if let Expression::StringLiteral(string_lit) = &prop.value {
-                    let span_key = (string_lit.span.start as usize, string_lit.span.end as usize);
-
-                    // Skip if already processed
-                    if !self.processed_spans.contains(&span_key) {
-                        self.processed_spans.insert(span_key);
-
-                        let content = self.extract_class_string_content(string_lit.span);
-                        if self.looks_like_tailwind_classes(&content) {
-                            let quote_style = self.detect_quote_style(string_lit.span);
-
-                            let class_match = ClassMatch::new(
-                                string_lit.span.start as usize,
-                                string_lit.span.end as usize,
-                                content,
-                                quote_style,
-                                PatternType::JSXAttribute, // Treat similar to JSX attribute
-                            );
-                            self.matches.push(class_match);
-                        }
+        if let PropertyKey::StaticIdentifier(ident) = &prop.key
+            && self.is_class_attribute(&ident.name)
+        {
+            // Process the value if it's a string literal
+            if let Expression::StringLiteral(string_lit) = &prop.value {
+                let span_key = (string_lit.span.start as usize, string_lit.span.end as usize);
+
+                // Skip if already processed
+                if !self.processed_spans.contains(&span_key) {
+                    self.processed_spans.insert(span_key);
+
+                    let content = self.extract_class_string_content(string_lit.span);
+                    if self.looks_like_tailwind_classes(&content) {
+                        let quote_style = self.detect_quote_style(string_lit.span);
+
+                        let class_match = ClassMatch::new(
+                            string_lit.span.start as usize,
+                            string_lit.span.end as usize,
+                            content,
+                            quote_style,
+                            PatternType::JSXAttribute, // Treat similar to JSX attribute
+                        );
+                        self.matches.push(class_match);
                     }
                 }
             }
@@ -670,48 +1095,75 @@ impl<'a> Visit<'a> for ClassExtractor<'a> {
         self.visit_expression(&prop.value);
     }
 
+    fn visit_binding_property(&mut self, property: &BindingProperty<'a>) {
+        // Renamed destructured defaults, such as `{ className: cls = "..." }`
+        if let PropertyKey::StaticIdentifier(ident) = &property.key
+            && self.is_class_attribute(&ident.name)
+            && let BindingPatternKind::AssignmentPattern(default) = &property.value.kind
+        {
+            self.process_class_expression(&default.right);
+            return;
+        }
+
+        walk::walk_binding_property(self, property);
+    }
+
+    fn visit_assignment_pattern(&mut self, pattern: &AssignmentPattern<'a>) {
+        // Defaults of class parameters and props, such as
+        // `function Button({ className = "..." })`
+        if let BindingPatternKind::BindingIdentifier(ident) = &pattern.left.kind
+            && self.is_class_attribute(&ident.name)
+        {
+            self.process_class_expression(&pattern.right);
+            return;
+        }
+
+        walk::walk_assignment_pattern(self, pattern);
+    }
+
     fn visit_binary_expression(&mut self, expr: &BinaryExpression<'a>) {
         // Handle string concatenation (e.g., "classes " + "more classes")
-        if matches!(expr.operator, BinaryOperator::Addition) {
-            if let (Expression::StringLiteral(left), Expression::StringLiteral(right)) =
+        if matches!(expr.operator, BinaryOperator::Addition)
+            && let (Expression::StringLiteral(left), Expression::StringLiteral(right)) =
                 (&expr.left, &expr.right)
+        {
+            // Check if both parts look like Tailwind classes
+            let left_content = self.extract_class_string_content(left.span);
+            let right_content = self.extract_class_string_content(right.span);
+
+            if self.looks_like_tailwind_classes(&left_content)
+                && self.looks_like_tailwind_classes(&right_content)
             {
-                // Check if both parts look like Tailwind classes
-                let left_content = self.extract_class_string_content(left.span);
-                let right_content = self.extract_class_string_content(right.span);
+                // Create a combined span covering both strings and the operator
+                let span_key = (expr.span.start as usize, expr.span.end as usize);
 
-                if self.looks_like_tailwind_classes(&left_content)
-                    && self.looks_like_tailwind_classes(&right_content)
-                {
-                    // Create a combined span covering both strings and the operator
-                    let span_key = (expr.span.start as usize, expr.span.end as usize);
-
-                    // Skip if already processed
-                    if !self.processed_spans.contains(&span_key) {
-                        self.processed_spans.insert(span_key);
-
-                        // Combine the class strings
-                        let combined_classes =
-                            format!("{} {}", left_content.trim(), right_content.trim());
-
-                        if self.looks_like_tailwind_classes(&combined_classes) {
-                            let quote_style = self.detect_quote_style(left.span);
-
-                            let class_match = ClassMatch::new(
-                                expr.span.start as usize,
-                                expr.span.end as usize,
-                                combined_classes,
-                                quote_style,
-                                PatternType::BinaryExpression {
-                                    left_content: left_content.clone(),
-                                    right_content: right_content.clone(),
-                                },
-                            );
-                            self.matches.push(class_match);
-                        }
+                // Skip if already processed
+                if !self.processed_spans.contains(&span_key) {
+                    self.processed_spans.insert(span_key);
+
+                    // Combine the class strings
+                    let combined_classes =
+                        format!("{} {}", left_content.trim(), right_content.trim());
+
+                    if self.looks_like_tailwind_classes(&combined_classes) {
+                        let quote_style = self.detect_quote_style(left.span);
+
+                        let class_match = ClassMatch::new(
+                            expr.span.start as usize,
+                            expr.span.end as usize,
+                            combined_classes,
+                            quote_style,
+                            PatternType::BinaryExpression {
+                                left_content: left_content.clone(),
+                                right_content: right_content.clone(),
+                                left_span: (left.span.start as usize, left.span.end as usize),
+                                right_span: (right.span.start as usize, right.span.end as usize),
+                            },
+                        );
+                        self.matches.push(class_match);
                     }
-                    return; // Don't visit children if we processed this concatenation
                 }
+                return; // Don't visit children if we processed this concatenation
             }
         }
 
@@ -721,10 +1173,41 @@ impl<'a> Visit<'a> for ClassExtractor<'a> {
     }
 }
 
+/// The value of an object's property with a static `name`
+fn static_property<'b, 'a>(
+    object: &'b ObjectExpression<'a>,
+    name: &str,
+) -> Option<&'b Expression<'a>> {
+    object
+        .properties
+        .iter()
+        .find_map(|property| match property {
+            ObjectPropertyKind::ObjectProperty(prop)
+                if !prop.computed && prop.key.is_specific_static_name(name) =>
+            {
+                Some(&prop.value)
+            }
+            _ => None,
+        })
+}
+
+/// The values of an object's properties with static names
+fn static_values<'b, 'a>(
+    object: &'b ObjectExpression<'a>,
+) -> impl Iterator<Item = &'b Expression<'a>> {
+    object
+        .properties
+        .iter()
+        .filter_map(|property| match property {
+            ObjectPropertyKind::ObjectProperty(prop) if !prop.computed => Some(&prop.value),
+            _ => None,
+        })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::parser::FileParser;
+    use crate::parser::{FileParser, SortContext};
     use oxc_span::SourceType;
 
     fn parse_and_extract(source: &str) -> Vec<ClassMatch> {
@@ -763,6 +1246,52 @@ mod tests {
         assert_eq!(matches[0].quote_style, QuoteStyle::Single);
     }
 
+    #[test]
+    fn test_escaped_quotes_are_kept_raw() {
+        let source = r#"const c = cn('p-4 before:content-[\'x\'] flex');"#;
+        let matches = parse_and_extract(source);
+
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].original, r"p-4 before:content-[\'x\'] flex");
+        assert_eq!(matches[0].quote_style, QuoteStyle::Single);
+    }
+
+    #[test]
+    fn test_jsx_classname_expression_containers() {
+        let matches = parse_and_extract(r#"<div className={"p-4 flex"}>"#);
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].original, "p-4 flex");
+        assert_eq!(matches[0].pattern_type, PatternType::JSXAttribute);
+
+        let matches =
+            parse_and_extract(r#"<div className={cn("p-4 flex", active && "m-2 block")}>"#);
+        let originals: Vec<&str> = matches.iter().map(|m| m.original.as_str()).collect();
+        assert_eq!(originals, ["p-4 flex", "m-2 block"]);
+
+        let matches = parse_and_extract("<div className={`p-4 flex m-2`}>");
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].original, "p-4 flex m-2");
+        assert_eq!(matches[0].quote_style, QuoteStyle::Backtick);
+
+        let matches = parse_and_extract(r#"<div className={(open ? "flex p-4" : "hidden")}>"#);
+        let originals: Vec<&str> = matches.iter().map(|m| m.original.as_str()).collect();
+        assert_eq!(originals, ["flex p-4", "hidden"]);
+    }
+
+    #[test]
+    fn test_jsx_classname_dynamic_values_are_ignored() {
+        for source in [
+            "<div className={dynamic}>",
+            "<div className={styles.card}>",
+            "<div className={styles[variant]}>",
+            "<div {...props} className={dynamic}>",
+            "<div className={`p-4 ${size}`}>",
+            r#"<div className={format("p-4 flex")}>"#,
+        ] {
+            assert!(parse_and_extract(source).is_empty(), "{}", source);
+        }
+    }
+
     #[test]
     fn test_object_property_classname() {
         let source = r#"const props = { className: "p-4 flex m-2 items-center" }"#;
@@ -783,6 +1312,92 @@ mod tests {
         assert_eq!(matches[0].original, "p-4 flex m-2 items-center");
     }
 
+    #[test]
+    fn test_destructured_class_defaults() {
+        let source = r#"function Button({ className = "p-4 flex m-2", title = "p-4 flex" }) {}
+function Card({ class: cls = `mt-2 block` }, [className = "m-2 p-4"]) {}"#;
+        let matches = parse_and_extract(source);
+
+        let originals: Vec<&str> = matches.iter().map(|m| m.original.as_str()).collect();
+        assert_eq!(
+            originals,
+            vec!["p-4 flex m-2", "p-4 flex", "mt-2 block", "m-2 p-4"]
+        );
+        assert_eq!(matches[0].pattern_type, PatternType::JSXAttribute);
+        // Other defaults are only sorted as plain strings
+        assert_eq!(
+            matches[1].pattern_type.context(),
+            SortContext::StringLiteral
+        );
+        assert_eq!(
+            matches[2].pattern_type,
+            PatternType::TemplateLiteral { tag: None }
+        );
+        assert_eq!(matches[3].pattern_type, PatternType::JSXAttribute);
+    }
+
+    #[test]
+    fn test_class_list_object_keys() {
+        let source =
+            r#"<div classList={{ "p-4 flex": true, "bg-red-500": active, plain: true }} />"#;
+        let matches = parse_and_extract(source);
+
+        let originals: Vec<&str> = matches.iter().map(|m| m.original.as_str()).collect();
+        assert_eq!(originals, vec!["p-4 flex", "bg-red-500"]);
+        assert!(
+            matches
+                .iter()
+                .all(|m| m.pattern_type == PatternType::ObjectKey)
+        );
+    }
+
+    #[test]
+    fn test_class_object_prop_values() {
+        let source = r#"<Card
+  classNames={{ root: "p-4 flex", icon: active ? "m-2 text-sm" : "hidden", label: cn("font-bold text-lg"), nested: { title: "mt-2 block" }, id: styles.id }}
+/>"#;
+        let parser = FileParser::new().with_class_object_props(["classNames".to_string()]);
+        let source_type = SourceType::default().with_jsx(true).with_typescript(true);
+        let matches = parser.parse_source(source, source_type).unwrap();
+
+        let originals: Vec<&str> = matches.iter().map(|m| m.original.as_str()).collect();
+        assert_eq!(
+            originals,
+            vec![
+                "p-4 flex",
+                "m-2 text-sm",
+                "hidden",
+                "font-bold text-lg",
+                "mt-2 block"
+            ]
+        );
+        assert_eq!(matches[0].pattern_type, PatternType::JSXAttribute);
+        assert_eq!(matches[4].pattern_type, PatternType::JSXAttribute);
+    }
+
+    #[test]
+    fn test_conditional_branches() {
+        let source = r#"const x = isActive ? "bg-blue-500 text-white p-4" : "bg-gray-200 p-2";"#;
+        let matches = parse_and_extract(source);
+
+        assert_eq!(matches.len(), 2);
+        assert!(
+            matches
+                .iter()
+                .all(|m| m.pattern_type == PatternType::ConditionalBranch)
+        );
+    }
+
+    #[test]
+    fn test_conditional_branches_in_unsupported_call() {
+        // Other arguments of unknown functions are still left alone
+        let source = r#"format("p-4 flex", big ? "p-8 flex" : a ? "p-2 flex" : "Not classes");"#;
+        let matches = parse_and_extract(source);
+
+        let originals: Vec<&str> = matches.iter().map(|m| m.original.as_str()).collect();
+        assert_eq!(originals, vec!["p-8 flex", "p-2 flex"]);
+    }
+
     #[test]
     fn test_string_concatenation() {
         let source = r#""p-4 flex m-2" + "items-center bg-white""#;
@@ -885,6 +1500,21 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_nested_tagged_template_in_unsupported_call() {
+        let source = r#"const A = memo(styled.div`color: red; ${tw`p-4 flex`}`)"#;
+        let matches = parse_and_extract(source);
+
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].original, "p-4 flex");
+        assert_eq!(
+            matches[0].pattern_type,
+            PatternType::TemplateLiteral {
+                tag: Some("tw".to_string())
+            }
+        );
+    }
+
     #[test]
     fn test_tagged_template_literal() {
         let source = r#"const styles = tw`p-4 flex m-2`"#;
@@ -910,7 +1540,7 @@ mod tests {
         assert_eq!(matches[0].original, "p-4 flex m-2 items-center");
 
         // Check that it's detected as an Array pattern
-        if let PatternType::Array { elements } = &matches[0].pattern_type {
+        if let PatternType::Array { elements, .. } = &matches[0].pattern_type {
             assert_eq!(elements.len(), 4);
             assert_eq!(elements[0], "p-4");
             assert_eq!(elements[1], "flex");
@@ -931,7 +1561,7 @@ mod tests {
         assert_eq!(matches[0].original, "p-4 flex");
 
         // Check that it's detected as an Array pattern
-        if let PatternType::Array { elements } = &matches[0].pattern_type {
+        if let PatternType::Array { elements, .. } = &matches[0].pattern_type {
             assert_eq!(elements.len(), 2);
             assert_eq!(elements[0], "p-4");
             assert_eq!(elements[1], "flex");
@@ -940,6 +1570,119 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_cva_compound_variants_class_values() {
+        let source = r#"cva('flex p-4', {
+  compoundVariants: [
+    { intent: 'primary', size: 'lg', class: 'btn-primary uppercase' },
+    { intent: 'ghost', className: ['btn-ghost', 'underline'] },
+    { intent: 'link', label: 'Read more here' }
+  ]
+})"#;
+        let matches = parse_and_extract(source);
+        let originals: Vec<&str> = matches.iter().map(|m| m.original.as_str()).collect();
+
+        // Class values are sorted even though they fail the Tailwind heuristics,
+        // while other properties of the entries are left alone
+        assert_eq!(
+            originals,
+            ["flex p-4", "btn-primary uppercase", "btn-ghost underline"]
+        );
+        assert!(matches!(
+            matches[1].pattern_type,
+            PatternType::FunctionCall { ref function_name, arg_index: 1 } if function_name == "cva"
+        ));
+        assert!(matches!(matches[2].pattern_type, PatternType::Array { .. }));
+    }
+
+    #[test]
+    fn test_compound_variants_outside_cva_use_heuristics() {
+        let source = r#"config({ compoundVariants: [{ class: 'btn-primary uppercase' }] })"#;
+        let matches = parse_and_extract(source);
+
+        assert!(matches.is_empty());
+    }
+
+    #[test]
+    fn test_tv_config_class_values() {
+        let source = r#"tv({
+  base: 'btn-base uppercase',
+  slots: { icon: 'icon-md shrink-0' },
+  variants: {
+    size: { sm: ['btn-sm', 'text-sm'] },
+    color: { primary: { base: 'btn-primary', icon: 'fill-current icon-primary' } }
+  },
+  compoundVariants: [{ size: 'sm', color: 'primary', class: 'btn-tight px-2' }],
+  defaultVariants: { size: 'sm' }
+})"#;
+        let matches = parse_and_extract(source);
+        let originals: Vec<&str> = matches.iter().map(|m| m.original.as_str()).collect();
+
+        // Every class value is sorted, slot objects included, while variant
+        // selections such as defaultVariants are left alone
+        assert_eq!(
+            originals,
+            [
+                "btn-base uppercase",
+                "icon-md shrink-0",
+                "btn-sm text-sm",
+                "btn-primary",
+                "fill-current icon-primary",
+                "btn-tight px-2"
+            ]
+        );
+        assert!(matches!(
+            matches[0].pattern_type,
+            PatternType::FunctionCall { ref function_name, arg_index: 0 } if function_name == "tv"
+        ));
+    }
+
+    #[test]
+    fn test_cx_function() {
+        let matches = parse_and_extract(r#"cx("p-4 flex", isActive && "bg-blue-500")"#);
+        let originals: Vec<&str> = matches.iter().map(|m| m.original.as_str()).collect();
+
+        assert_eq!(originals, ["p-4 flex", "bg-blue-500"]);
+    }
+
+    #[test]
+    fn test_configured_detection_thresholds() {
+        // Two of five tokens look like Tailwind classes
+        let borderline = "flex p-4 card title wrapper";
+        assert!(!ClassExtractor::new("").looks_like_tailwind_classes(borderline));
+
+        let lax = TailwindDetection {
+            ratio: 0.4,
+            ..Default::default()
+        };
+        assert!(
+            ClassExtractor::new("")
+                .with_detection(lax)
+                .looks_like_tailwind_classes(borderline)
+        );
+
+        // Short strings are judged by their count of Tailwind tokens instead
+        assert!(!ClassExtractor::new("").looks_like_tailwind_classes("flex wrapper"));
+        let lax = TailwindDetection {
+            min_tokens: 1,
+            ..Default::default()
+        };
+        assert!(
+            ClassExtractor::new("")
+                .with_detection(lax)
+                .looks_like_tailwind_classes("flex wrapper")
+        );
+
+        let strict = TailwindDetection {
+            min_tokens: 3,
+            ratio: 0.9,
+        };
+        let extractor = ClassExtractor::new("").with_detection(strict);
+        assert!(!extractor.looks_like_tailwind_classes("flex p-4 card"));
+        assert!(!extractor.looks_like_tailwind_classes("flex p-4 m-2 gap-2 card"));
+        assert!(extractor.looks_like_tailwind_classes("flex p-4 m-2 gap-2 grid"));
+    }
+
     #[test]
     fn test_regular_text_not_processed() {
         // Test cases that should NOT be processed as Tailwind classes
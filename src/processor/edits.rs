@@ -0,0 +1,120 @@
+use crate::{Result, WindWardenError};
+
+/// A replacement of the bytes `start..end` of a source
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Edit {
+    pub start: usize,
+    pub end: usize,
+    pub replacement: String,
+}
+
+impl Edit {
+    pub fn new(start: usize, end: usize, replacement: impl Into<String>) -> Self {
+        Self {
+            start,
+            end,
+            replacement: replacement.into(),
+        }
+    }
+}
+
+/// Apply edits to a source in a single pass, building a new string
+///
+/// Every edit refers to offsets in the original source, so edits can be
+/// collected in any order; they are left sorted by position. Duplicate
+/// edits are applied once. Edits that overlap or fall outside the source
+/// are an error rather than being applied on top of each other.
+pub fn apply_edits(source: &str, edits: &mut Vec<Edit>) -> Result<String> {
+    edits.sort_by_key(|edit| (edit.start, edit.end));
+    edits.dedup();
+
+    let mut result = String::with_capacity(source.len());
+    let mut copied = 0;
+    let mut previous: Option<&Edit> = None;
+
+    for edit in edits.iter() {
+        if let Some(previous) = previous
+            && edit.start < previous.end
+        {
+            return Err(WindWardenError::sort_error(
+                "replacement",
+                format!(
+                    "overlapping edits at bytes {}..{} and {}..{}",
+                    previous.start, previous.end, edit.start, edit.end
+                ),
+            ));
+        }
+
+        let (Some(before), Some(_)) = (
+            source.get(copied..edit.start),
+            source.get(edit.start..edit.end),
+        ) else {
+            return Err(WindWardenError::sort_error(
+                "replacement",
+                format!(
+                    "edit at bytes {}..{} is outside the source",
+                    edit.start, edit.end
+                ),
+            ));
+        };
+
+        result.push_str(before);
+        result.push_str(&edit.replacement);
+        copied = edit.end;
+        previous = Some(edit);
+    }
+
+    result.push_str(&source[copied..]);
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_apply_edits_in_any_order() {
+        let source = r#"<a class="p-4 flex"><b class="m-2 block">"#;
+        let second = source.find("\"m-2").unwrap();
+        let mut edits = vec![
+            Edit::new(second, second + 11, "\"block m-2\""),
+            Edit::new(9, 19, "\"flex p-4\""),
+        ];
+
+        let result = apply_edits(source, &mut edits).unwrap();
+        assert_eq!(result, r#"<a class="flex p-4"><b class="block m-2">"#);
+        assert_eq!(edits[0].start, 9);
+    }
+
+    #[test]
+    fn test_apply_edits_changes_lengths() {
+        let source = "a bb ccc";
+        let mut edits = vec![
+            Edit::new(0, 1, "aaa"),
+            Edit::new(2, 4, ""),
+            Edit::new(5, 8, "c"),
+        ];
+
+        assert_eq!(apply_edits(source, &mut edits).unwrap(), "aaa  c");
+        assert_eq!(apply_edits("", &mut Vec::new()).unwrap(), "");
+    }
+
+    #[test]
+    fn test_duplicate_edits_apply_once() {
+        let mut edits = vec![Edit::new(0, 1, "x"), Edit::new(0, 1, "x")];
+        assert_eq!(apply_edits("ab", &mut edits).unwrap(), "xb");
+        assert_eq!(edits.len(), 1);
+    }
+
+    #[test]
+    fn test_overlapping_edits_are_rejected() {
+        let mut edits = vec![Edit::new(0, 4, "x"), Edit::new(2, 6, "y")];
+        assert!(apply_edits("abcdefgh", &mut edits).is_err());
+
+        let mut edits = vec![Edit::new(0, 1, "x"), Edit::new(0, 1, "y")];
+        assert!(apply_edits("ab", &mut edits).is_err());
+
+        let mut edits = vec![Edit::new(2, 10, "x")];
+        assert!(apply_edits("abc", &mut edits).is_err());
+    }
+}
@@ -0,0 +1,165 @@
+use std::ops::Range;
+
+const IGNORE_DIRECTIVE: &str = "windwarden-ignore";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Directive {
+    /// `windwarden-ignore`: skip the next line
+    NextLine,
+    /// `windwarden-ignore-start`: skip everything up to the matching end marker
+    Start,
+    /// `windwarden-ignore-end`
+    End,
+}
+
+/// Byte ranges of a source excluded from sorting by ignore comments
+///
+/// A `// windwarden-ignore` comment (or `/* */`, `{/* */}`, `<!-- -->`)
+/// excludes the line after it. `windwarden-ignore-start` and
+/// `windwarden-ignore-end` comments exclude everything between them; a start
+/// marker without an end marker excludes the rest of the file.
+#[derive(Debug, Default)]
+pub struct IgnoredRegions {
+    ranges: Vec<Range<usize>>,
+}
+
+impl IgnoredRegions {
+    pub fn scan(source: &str) -> Self {
+        if !source.contains(IGNORE_DIRECTIVE) {
+            return Self::default();
+        }
+
+        let mut ranges = Vec::new();
+        let mut block_start = None;
+        let mut ignore_next_line = false;
+        let mut line_start = 0;
+
+        for line in source.split_inclusive('\n') {
+            let line_end = line_start + line.len();
+            if std::mem::take(&mut ignore_next_line) {
+                ranges.push(line_start..line_end);
+            }
+
+            match directive(line) {
+                Some((Directive::NextLine, _)) => ignore_next_line = true,
+                Some((Directive::Start, index)) => {
+                    block_start.get_or_insert(line_start + index);
+                }
+                Some((Directive::End, index)) => {
+                    if let Some(start) = block_start.take() {
+                        ranges.push(start..line_start + index);
+                    }
+                }
+                None => {}
+            }
+
+            line_start = line_end;
+        }
+
+        if let Some(start) = block_start {
+            ranges.push(start..source.len());
+        }
+
+        Self { ranges }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    /// Whether a byte offset lies in an ignored region
+    pub fn contains(&self, offset: usize) -> bool {
+        self.ranges.iter().any(|range| range.contains(&offset))
+    }
+}
+
+/// The ignore directive in a line, if any, with its byte index in the line
+///
+/// The directive must be the first word of a comment, so string literals
+/// and prose that mention it are not mistaken for one.
+fn directive(line: &str) -> Option<(Directive, usize)> {
+    let index = line.find(IGNORE_DIRECTIVE)?;
+    let before = line[..index].trim_end();
+    if !["//", "/*", "<!--"]
+        .iter()
+        .any(|open| before.ends_with(open))
+    {
+        return None;
+    }
+
+    let rest = &line[index + IGNORE_DIRECTIVE.len()..];
+    let directive = if let Some(suffix) = rest.strip_prefix("-start") {
+        (!suffix.starts_with(is_word_char)).then_some(Directive::Start)
+    } else if let Some(suffix) = rest.strip_prefix("-end") {
+        (!suffix.starts_with(is_word_char)).then_some(Directive::End)
+    } else {
+        (!rest.starts_with(is_word_char)).then_some(Directive::NextLine)
+    };
+
+    directive.map(|directive| (directive, index))
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '-' || c == '_'
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_directive() {
+        assert_eq!(
+            directive("// windwarden-ignore\n"),
+            Some((Directive::NextLine, 3))
+        );
+        assert_eq!(
+            directive("  {/* windwarden-ignore */}"),
+            Some((Directive::NextLine, 6))
+        );
+        assert_eq!(
+            directive("/* windwarden-ignore-start */"),
+            Some((Directive::Start, 3))
+        );
+        assert_eq!(
+            directive("<!-- windwarden-ignore-end -->"),
+            Some((Directive::End, 5))
+        );
+
+        assert_eq!(directive("const a = 'windwarden-ignore';"), None);
+        assert_eq!(directive("// see windwarden-ignore"), None);
+        assert_eq!(directive("// windwarden-ignored"), None);
+        assert_eq!(directive("// windwarden-ignore-starts"), None);
+    }
+
+    #[test]
+    fn test_next_line_region() {
+        let source = "a\n// windwarden-ignore\nb\nc\n";
+        let regions = IgnoredRegions::scan(source);
+
+        let b = source.find('b').unwrap();
+        let c = source.find('c').unwrap();
+        assert!(!regions.contains(0));
+        assert!(regions.contains(b));
+        assert!(!regions.contains(c));
+    }
+
+    #[test]
+    fn test_block_regions() {
+        let source = "a\n/* windwarden-ignore-start */\nb\nc\n/* windwarden-ignore-end */\nd\n";
+        let regions = IgnoredRegions::scan(source);
+
+        assert!(!regions.contains(source.find('a').unwrap()));
+        assert!(regions.contains(source.find('b').unwrap()));
+        assert!(regions.contains(source.find('c').unwrap()));
+        assert!(!regions.contains(source.find("\nd").unwrap() + 1));
+
+        // An unterminated block runs to the end of the file
+        let source = "a\n// windwarden-ignore-start\nb\n";
+        let regions = IgnoredRegions::scan(source);
+        assert!(regions.contains(source.find('b').unwrap()));
+
+        // A stray end marker ignores nothing
+        assert!(IgnoredRegions::scan("// windwarden-ignore-end\na\n").is_empty());
+    }
+}
@@ -1,15 +1,89 @@
+mod edits;
+mod ignore;
+
+use serde::Serialize;
+use std::collections::HashSet;
 use std::fs;
 
 use crate::atomic;
 use crate::config::Config;
-use crate::parser::{FileParser, PatternType, QuoteStyle};
-use crate::sorter::TailwindSorter;
+use crate::output::Modification;
+use crate::output::formats::create_modification;
+use crate::output::position::LineIndex;
+use crate::parser::{ClassMatch, FileParser, PatternType, QuotePreference, QuoteStyle};
+use crate::sorter::{ClassExplanation, TailwindSorter, UnknownClassSeverity};
+use crate::trace::{TraceEvent, Tracer};
 use crate::{ProcessOptions, Result, WindWardenError};
+use edits::Edit;
+use ignore::IgnoredRegions;
+
+/// How many class strings a file contained and how many of them were reordered
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ClassCounts {
+    pub found: usize,
+    pub reordered: usize,
+}
+
+/// Processed content along with what sorting changed
+#[derive(Debug, Clone, Default)]
+pub struct ProcessOutcome {
+    /// The processed content, as returned by `process_content`
+    pub content: String,
+    /// Class strings that were reordered, in source order
+    pub modifications: Vec<Modification>,
+    pub counts: ClassCounts,
+    /// Supported functions called in the content
+    pub called_functions: HashSet<String>,
+    /// Whether `called_functions` may be missing calls, because the content
+    /// was scanned line by line or some of its code didn't parse
+    pub calls_incomplete: bool,
+    /// Classes that match no utility, unless `unknownClasses` ignores them
+    pub unknown_classes: Vec<UnknownClass>,
+}
+
+/// A class that matches no Tailwind utility and no known custom class
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnknownClass {
+    /// Line number of the class string the class is in (1-based)
+    pub line: usize,
+    /// Column number of the class string the class is in (1-based), not of
+    /// the class itself
+    pub column: usize,
+    pub class: String,
+    pub severity: UnknownClassSeverity,
+}
+
+impl ProcessOutcome {
+    /// Whether sorting changed anything
+    pub fn changed(&self) -> bool {
+        !self.modifications.is_empty()
+    }
+}
+
+/// A class string found in a file, with why each of its classes sorts where
+/// it does
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StringExplanation {
+    /// Line number (1-based)
+    pub line: usize,
+    /// Column number (1-based)
+    pub column: usize,
+    pub original: String,
+    pub sorted: String,
+    /// The sorted classes, in order
+    pub classes: Vec<ClassExplanation>,
+}
 
+#[derive(Clone)]
 pub struct FileProcessor {
     parser: FileParser,
     sorter: TailwindSorter,
     config: Option<Config>,
+    tracer: Tracer,
+    max_in_memory: Option<usize>,
+    quotes: QuotePreference,
+    unknown_classes: UnknownClassSeverity,
 }
 
 impl FileProcessor {
@@ -18,6 +92,10 @@ impl FileProcessor {
             parser: FileParser::new(),
             sorter: TailwindSorter::new(),
             config: None,
+            tracer: Tracer::disabled(),
+            max_in_memory: None,
+            quotes: QuotePreference::default(),
+            unknown_classes: UnknownClassSeverity::default(),
         }
     }
 
@@ -26,22 +104,56 @@ impl FileProcessor {
         let temp_manager = crate::config::ConfigManager::new_with_config(config.clone(), None);
         let all_functions = temp_manager.get_function_names();
 
-        let parser = FileParser::new_with_custom_functions(all_functions);
-
-        // Create sorter with custom order if specified
-        let sorter = if config.sort_order == "custom" && !config.custom_order.is_empty() {
-            TailwindSorter::new_with_custom_order(Some(config.custom_order.clone()))
-        } else {
-            TailwindSorter::new()
-        };
+        let parser = FileParser::new_with_custom_functions(all_functions)
+            .with_function_arg_indices(temp_manager.get_function_arg_indices())
+            .with_sort_contexts(
+                config
+                    .sort_contexts
+                    .iter()
+                    .filter_map(|context| context.parse().ok()),
+            )
+            .with_template_tags(config.template_tags.iter().cloned())
+            .with_untagged_templates(config.sort_untagged_templates)
+            .with_class_object_props(config.class_object_props.iter().cloned())
+            .with_known_custom_classes(&config.known_custom_classes)
+            .with_detection(config.tailwind_detection);
+
+        let sorter = TailwindSorter::from_config(config);
 
         Self {
             parser,
             sorter,
             config: Some(config.clone()),
+            tracer: Tracer::disabled(),
+            max_in_memory: None,
+            quotes: config.quote_style.parse().unwrap_or_default(),
+            unknown_classes: config.unknown_classes.parse().unwrap_or_default(),
         }
     }
 
+    /// Record parse results, matches, and writes to the given tracer
+    pub fn with_tracer(mut self, tracer: Tracer) -> Self {
+        self.parser.set_tracer(tracer.clone());
+        self.tracer = tracer;
+        self
+    }
+
+    /// Process content larger than `bytes` with a line scan instead of a full parse
+    pub fn with_max_in_memory(mut self, bytes: Option<usize>) -> Self {
+        self.max_in_memory = bytes;
+        self
+    }
+
+    /// The largest file, in bytes, that the configuration allows processing
+    pub fn max_file_size(&self) -> Option<usize> {
+        self.config.as_ref().map(|config| config.max_file_size)
+    }
+
+    /// Get the tracer decisions are recorded to
+    pub fn tracer(&self) -> &Tracer {
+        &self.tracer
+    }
+
     pub fn process_file(&self, file_path: &str, options: ProcessOptions) -> Result<String> {
         let content = fs::read_to_string(file_path).map_err(WindWardenError::Io)?;
 
@@ -54,144 +166,457 @@ impl FileProcessor {
         file_path: &str,
         options: ProcessOptions,
     ) -> Result<String> {
-        // Parse the file to find class matches
-        let matches = self.parser.parse_file(file_path, content)?;
+        self.process_content_with_counts(content, file_path, options)
+            .map(|(processed, _)| processed)
+    }
+
+    /// Process content and also report how many class strings were found and reordered
+    pub fn process_content_with_counts(
+        &self,
+        content: &str,
+        file_path: &str,
+        options: ProcessOptions,
+    ) -> Result<(String, ClassCounts)> {
+        self.process_content_detailed(content, file_path, options)
+            .map(|outcome| (outcome.content, outcome.counts))
+    }
+
+    /// Process content and describe each class string that was reordered
+    pub fn process_content_detailed(
+        &self,
+        content: &str,
+        file_path: &str,
+        options: ProcessOptions,
+    ) -> Result<ProcessOutcome> {
+        if self
+            .max_in_memory
+            .is_some_and(|limit| content.len() > limit)
+        {
+            return self.sort_lines(content, file_path, options);
+        }
+
+        // Parse the file to find class matches, leaving out ignored regions
+        let parsed = self.parser.parse_file_detailed(file_path, content)?;
+        let called_functions = parsed.called_functions;
+        let calls_incomplete = parsed.calls_incomplete;
+        let mut matches = parsed.matches;
+        let ignored = IgnoredRegions::scan(content);
+        if !ignored.is_empty() {
+            matches.retain(|class_match| !ignored.contains(class_match.start));
+        }
 
         if matches.is_empty() {
             // No classes found, return original content or empty based on mode
-            return if options.dry_run || !options.write {
-                Ok(content.to_string())
+            let processed = if options.dry_run || !options.write {
+                content.to_string()
             } else {
-                Ok(String::new())
+                String::new()
             };
+            return Ok(ProcessOutcome {
+                content: processed,
+                called_functions,
+                calls_incomplete,
+                ..Default::default()
+            });
         }
 
-        // Sort matches by position (start offset) in reverse order
-        // This allows us to replace from end to beginning without affecting positions
-        let mut sorted_matches = matches;
-        sorted_matches.sort_by(|a, b| b.start.cmp(&a.start));
-
-        let mut result = content.to_string();
         let mut changes_made = false;
+        let mut edits = Vec::new();
+        let mut counts = ClassCounts {
+            found: matches.len(),
+            reordered: 0,
+        };
+
+        let collapse_concatenation = self
+            .config
+            .as_ref()
+            .is_some_and(|config| config.collapse_concatenation);
+        let lines = self
+            .reports_unknown_classes()
+            .then(|| LineIndex::new(content));
+        let mut unknown_classes = Vec::new();
+
+        for class_match in matches {
+            if let Some(lines) = &lines {
+                unknown_classes.extend(self.unknown_classes(
+                    lines,
+                    class_match.start,
+                    &class_match.original,
+                ));
+            }
 
-        for class_match in sorted_matches {
-            let sorted_classes = self.sorter.sort_classes(&class_match.original);
+            let sorted_classes = match &class_match.pattern_type {
+                // Operands are sorted on their own unless they get collapsed
+                PatternType::BinaryExpression {
+                    left_content,
+                    right_content,
+                    ..
+                } if !collapse_concatenation => format!(
+                    "{} {}",
+                    self.sorter.sort_classes(left_content).trim(),
+                    self.sorter.sort_classes(right_content).trim()
+                ),
+                // twMerge resolves conflicts at runtime, so they are left for it
+                PatternType::FunctionCall { function_name, .. } if function_name == "twMerge" => {
+                    self.sorter.sort_classes_unmerged(&class_match.original)
+                }
+                _ => self.sorter.sort_classes(&class_match.original),
+            };
 
-            // Check if sorting actually changed anything
-            if sorted_classes != class_match.original {
+            self.tracer.emit(|| TraceEvent::Match {
+                file: file_path.to_string(),
+                start: class_match.start,
+                end: class_match.end,
+                original: class_match.original.clone(),
+                sorted: sorted_classes.clone(),
+                changed: sorted_classes != class_match.original,
+            });
+
+            // Check if sorting or the configured quotes change anything
+            let reordered = sorted_classes != class_match.original;
+            if reordered || self.needs_requote(content, &class_match) {
                 changes_made = true;
+                if reordered {
+                    counts.reordered += 1;
+                }
+                if let Some(edit) = self.match_edit(
+                    content,
+                    &class_match,
+                    &sorted_classes,
+                    collapse_concatenation,
+                ) && content.get(edit.start..edit.end) != Some(edit.replacement.as_str())
+                {
+                    edits.push(edit);
+                }
+            }
+        }
+
+        // Every edit refers to the original content, so they apply in one pass
+        let result = edits::apply_edits(content, &mut edits)?;
+        self.finish_processing(content, result, changes_made, file_path, options)
+            .map(|processed| ProcessOutcome {
+                content: processed,
+                modifications: modifications(content, &edits),
+                counts,
+                called_functions,
+                calls_incomplete,
+                unknown_classes,
+            })
+    }
+
+    /// Explain how each class string in the content would be sorted
+    pub fn explain_content(
+        &self,
+        content: &str,
+        file_path: &str,
+    ) -> Result<Vec<StringExplanation>> {
+        let mut matches = self.parser.parse_file(file_path, content)?;
+        let ignored = IgnoredRegions::scan(content);
+        matches.retain(|class_match| !ignored.contains(class_match.start));
+
+        let lines = LineIndex::new(content);
+        Ok(matches
+            .into_iter()
+            .map(|class_match| {
+                let (line, column) = lines.line_col(class_match.start);
+                StringExplanation {
+                    line,
+                    column,
+                    sorted: self.sorter.sort_classes(&class_match.original),
+                    classes: self.sorter.explain(&class_match.original),
+                    original: class_match.original,
+                }
+            })
+            .collect())
+    }
+
+    /// The edit that replaces a match with its sorted classes, or `None` when
+    /// the match can't be located in the content
+    fn match_edit(
+        &self,
+        content: &str,
+        class_match: &ClassMatch,
+        sorted_classes: &str,
+        collapse_concatenation: bool,
+    ) -> Option<Edit> {
+        let (start, end) = (class_match.start, class_match.end);
+        let quote_char = class_match.quote_style.as_char();
+        let replacement = self
+            .output_quote(class_match, sorted_classes)
+            .wrap(sorted_classes);
+
+        match &class_match.pattern_type {
+            // The span must hold the quoted classes; searching for them could
+            // land on an identical string elsewhere
+            PatternType::JSXAttribute => {
+                let quoted = format!("{}{}{}", quote_char, class_match.original, quote_char);
+                (content.get(start..end) == Some(quoted.as_str()))
+                    .then(|| Edit::new(start, end, replacement))
+            }
+            // `@apply` utilities aren't quoted
+            PatternType::CssApply => Some(Edit::new(start, end, sorted_classes)),
+            // Template literals keep their backticks
+            PatternType::TemplateLiteral { .. } => {
+                Some(Edit::new(start, end, format!("`{}`", sorted_classes)))
+            }
+            // These come from the AST or the markup scanner with exact spans
+            PatternType::FunctionCall { .. }
+            | PatternType::MarkupAttribute
+            | PatternType::ConditionalBranch
+            | PatternType::ObjectKey
+            | PatternType::ArrayElement { .. } => Some(Edit::new(start, end, replacement)),
+            PatternType::Array {
+                elements,
+                element_spans,
+            } if self.config.as_ref().is_some_and(|c| c.preserve_formatting) => {
+                reorder_array_elements(content, start, end, elements, element_spans, sorted_classes)
+                    .map(|replacement| Edit::new(start, end, replacement))
+            }
+            PatternType::Array { element_spans, .. } => {
+                // For arrays, sort the combined classes and rebuild the array.
+                // Elements may come from literals with different quotes, so pick
+                // one that none of them needs escaping for
+                let element_quote = self
+                    .quotes
+                    .quote()
+                    .unwrap_or_else(|| class_match.quote_style.for_content(sorted_classes));
+
+                let sorted_elements: Vec<String> = sorted_classes
+                    .split_whitespace()
+                    .map(|s| element_quote.wrap(s))
+                    .collect();
+                let rebuilt = multiline_array(content, start, end, element_spans, &sorted_elements)
+                    .unwrap_or_else(|| format!("[{}]", sorted_elements.join(", ")));
+                Some(Edit::new(start, end, rebuilt))
+            }
+            PatternType::BinaryExpression {
+                left_content,
+                right_content,
+                left_span,
+                right_span,
+            } => {
+                if collapse_concatenation {
+                    // Replace the whole concatenation with a single literal; the
+                    // operands may use different quotes
+                    let collapsed = self
+                        .quotes
+                        .quote()
+                        .unwrap_or_else(|| class_match.quote_style.for_content(sorted_classes))
+                        .wrap(sorted_classes);
+                    return Some(Edit::new(start, end, collapsed));
+                }
 
-                // Create the replacement string with appropriate quotes
-                let quote_char = match class_match.quote_style {
-                    QuoteStyle::Single => '\'',
-                    QuoteStyle::Double => '"',
-                    QuoteStyle::Backtick => '`',
+                // Sort each operand inside its own quotes, unless a quote style
+                // is configured; words never move across the `+`
+                let operand = |span: &(usize, usize), classes: &str| -> Option<String> {
+                    let sorted = self.sort_operand(classes);
+                    Some(match self.quotes.quote() {
+                        Some(quote) => quote.wrap(&sorted),
+                        None => format!(
+                            "{}{}{}",
+                            content.get(span.0..span.0 + 1)?,
+                            sorted,
+                            content.get(span.1 - 1..span.1)?
+                        ),
+                    })
                 };
+                let replacement = format!(
+                    "{}{}{}{}{}",
+                    content.get(start..left_span.0)?,
+                    operand(left_span, left_content)?,
+                    content.get(left_span.1..right_span.0)?,
+                    operand(right_span, right_content)?,
+                    content.get(right_span.1..end)?,
+                );
+                Some(Edit::new(start, end, replacement))
+            }
+        }
+    }
 
-                let replacement = format!("{}{}{}", quote_char, sorted_classes, quote_char);
-
-                // Handle different pattern types differently
-                match &class_match.pattern_type {
-                    PatternType::JSXAttribute => {
-                        // For JSX attributes, use string search as before
-                        let search_pattern =
-                            format!("{}{}{}", quote_char, class_match.original, quote_char);
-                        if let Some(start_pos) = result.find(&search_pattern) {
-                            let end_pos = start_pos + search_pattern.len();
-                            result.replace_range(start_pos..end_pos, &replacement);
-                        }
-                    }
-                    PatternType::FunctionCall { .. } => {
-                        // For function calls, use span positions since they're more accurate
-                        // The spans should be correct from the AST parser
-                        if class_match.start < result.len() && class_match.end <= result.len() {
-                            result.replace_range(class_match.start..class_match.end, &replacement);
-                        }
-                    }
-                    PatternType::TemplateLiteral { .. } => {
-                        // For template literals, we need to replace just the content, preserving backticks
-                        let template_replacement = format!("`{}`", sorted_classes);
-
-                        // Use span positions for template literals
-                        if class_match.start < result.len() && class_match.end <= result.len() {
-                            result.replace_range(
-                                class_match.start..class_match.end,
-                                &template_replacement,
-                            );
-                        }
-                    }
-                    PatternType::ArrayElement { .. } => {
-                        // For array elements, use span positions
-                        if class_match.start < result.len() && class_match.end <= result.len() {
-                            result.replace_range(class_match.start..class_match.end, &replacement);
-                        }
-                    }
-                    PatternType::Array { elements: _ } => {
-                        // For arrays, sort the combined classes and rebuild the array
-                        let element_quote_char = match class_match.quote_style {
-                            QuoteStyle::Single => '\'',
-                            QuoteStyle::Double => '"',
-                            QuoteStyle::Backtick => '`', // Though arrays shouldn't use backticks
-                        };
-
-                        let sorted_elements: Vec<String> = sorted_classes
-                            .split_whitespace()
-                            .map(|s| format!("{}{}{}", element_quote_char, s, element_quote_char))
-                            .collect();
-                        let array_replacement = format!("[{}]", sorted_elements.join(", "));
-
-                        // Use span positions for arrays
-                        if class_match.start < result.len() && class_match.end <= result.len() {
-                            result.replace_range(
-                                class_match.start..class_match.end,
-                                &array_replacement,
-                            );
-                        }
+    /// The quote a single-literal match is written back with
+    ///
+    /// Template literals keep their backticks, and attribute values keep
+    /// their quotes when the classes contain the configured one, since
+    /// attribute values can't escape it.
+    fn output_quote(&self, class_match: &ClassMatch, sorted_classes: &str) -> QuoteStyle {
+        let original = class_match.quote_style;
+        match self.quotes.quote() {
+            Some(_) if original == QuoteStyle::Backtick => original,
+            Some(quote)
+                if matches!(
+                    class_match.pattern_type,
+                    PatternType::JSXAttribute | PatternType::MarkupAttribute
+                ) && sorted_classes.contains(quote.as_char()) =>
+            {
+                original
+            }
+            Some(quote) => quote,
+            None => original,
+        }
+    }
+
+    /// Whether a match is written with other quotes than `quoteStyle` asks for
+    fn needs_requote(&self, content: &str, class_match: &ClassMatch) -> bool {
+        let Some(quote) = self.quotes.quote() else {
+            return false;
+        };
+        let quoted = |span: &(usize, usize)| {
+            content.as_bytes().get(span.0) == Some(&(quote.as_char() as u8))
+        };
+
+        match &class_match.pattern_type {
+            PatternType::TemplateLiteral { .. } | PatternType::CssApply => false,
+            // Arrays keep each element's quotes when their formatting is preserved
+            PatternType::Array { element_spans, .. } => {
+                !self.config.as_ref().is_some_and(|c| c.preserve_formatting)
+                    && !element_spans.iter().all(quoted)
+            }
+            PatternType::BinaryExpression {
+                left_span,
+                right_span,
+                ..
+            } => !(quoted(left_span) && quoted(right_span)),
+            _ => self.output_quote(class_match, &class_match.original) != class_match.quote_style,
+        }
+    }
+
+    /// Process content with a line-by-line scan instead of building an AST
+    ///
+    /// Used for files above the `max_in_memory` limit. Every quoted string or
+    /// static template literal that fits on one line and looks like Tailwind
+    /// classes is sorted, unless the sort contexts or template tags leave out
+    /// the kind of string it seems to be; in MDX, only code is scanned. This
+    /// is less accurate than the parser: strings spanning lines are skipped,
+    /// while class-like text in comments may be sorted.
+    pub fn process_content_by_lines(
+        &self,
+        content: &str,
+        file_path: &str,
+        options: ProcessOptions,
+    ) -> Result<String> {
+        self.sort_lines(content, file_path, options)
+            .map(|outcome| outcome.content)
+    }
+
+    fn sort_lines(
+        &self,
+        content: &str,
+        file_path: &str,
+        options: ProcessOptions,
+    ) -> Result<ProcessOutcome> {
+        let class_checker = self.parser.class_checker("");
+        let ignored = IgnoredRegions::scan(content);
+        let code_regions = self.parser.code_regions(file_path, content);
+        let mut changes_made = false;
+        let mut edits = Vec::new();
+        let mut counts = ClassCounts::default();
+        let lines = self
+            .reports_unknown_classes()
+            .then(|| LineIndex::new(content));
+        let mut unknown_classes = Vec::new();
+        let mut line_start = 0;
+
+        for line in content.split_inclusive('\n') {
+            for (start, end) in quoted_strings(line) {
+                let original = &line[start + 1..end - 1];
+                let in_code = code_regions.as_ref().is_none_or(|regions| {
+                    regions
+                        .iter()
+                        .any(|&(from, to)| from <= line_start + start && line_start + end <= to)
+                });
+                if original.trim().is_empty()
+                    || !in_code
+                    || ignored.contains(line_start + start)
+                    || (line.as_bytes()[start] == b'`' && original.contains("${"))
+                    || !class_checker.looks_like_tailwind_classes(original)
+                {
+                    continue;
+                }
+
+                // The same sort contexts and template tags apply as when parsing
+                let class_match = scanned_match(&self.parser, line, line_start, start, end);
+                if !self.parser.keeps_match(&class_match) {
+                    continue;
+                }
+
+                if let Some(lines) = &lines {
+                    unknown_classes.extend(self.unknown_classes(
+                        lines,
+                        line_start + start,
+                        original,
+                    ));
+                }
+
+                let sorted = self.sorter.sort_classes(original);
+                counts.found += 1;
+                self.tracer.emit(|| TraceEvent::Match {
+                    file: file_path.to_string(),
+                    start: line_start + start,
+                    end: line_start + end,
+                    original: original.to_string(),
+                    sorted: sorted.clone(),
+                    changed: sorted != original,
+                });
+
+                // Quotes follow `quoteStyle` as they do for parsed matches
+                let reordered = sorted != original;
+                if reordered || self.needs_requote(content, &class_match) {
+                    changes_made = true;
+                    if reordered {
+                        counts.reordered += 1;
                     }
-                    PatternType::BinaryExpression {
-                        left_content,
-                        right_content: _,
-                    } => {
-                        // For binary expressions (string concatenation), rebuild with sorted classes
-                        let quote_char = match class_match.quote_style {
-                            QuoteStyle::Single => '\'',
-                            QuoteStyle::Double => '"',
-                            QuoteStyle::Backtick => '`',
-                        };
-
-                        // Split the sorted classes back into reasonable chunks
-                        // Try to preserve the original split pattern as much as possible
-                        let sorted_words: Vec<&str> = sorted_classes.split_whitespace().collect();
-                        let left_word_count = left_content.split_whitespace().count();
-
-                        let (left_words, right_words) = if left_word_count <= sorted_words.len() {
-                            sorted_words.split_at(left_word_count)
-                        } else {
-                            // If original left had more words, split roughly in half
-                            let split_point = sorted_words.len() / 2;
-                            sorted_words.split_at(split_point)
-                        };
-
-                        let new_left = left_words.join(" ");
-                        let new_right = right_words.join(" ");
-
-                        let binary_replacement = format!(
-                            "{}{}{} + {}{}{}",
-                            quote_char, new_left, quote_char, quote_char, new_right, quote_char
-                        );
-
-                        // Use span positions for binary expressions
-                        if class_match.start < result.len() && class_match.end <= result.len() {
-                            result.replace_range(
-                                class_match.start..class_match.end,
-                                &binary_replacement,
-                            );
-                        }
+
+                    let replacement = self.output_quote(&class_match, &sorted).wrap(&sorted);
+                    if content.get(class_match.start..class_match.end) != Some(replacement.as_str())
+                    {
+                        edits.push(Edit::new(class_match.start, class_match.end, replacement));
                     }
                 }
             }
+            line_start += line.len();
         }
 
+        let result = edits::apply_edits(content, &mut edits)?;
+        self.finish_processing(content, result, changes_made, file_path, options)
+            .map(|processed| ProcessOutcome {
+                content: processed,
+                modifications: modifications(content, &edits),
+                counts,
+                // Calls aren't collected when scanning line by line
+                calls_incomplete: true,
+                unknown_classes,
+                ..Default::default()
+            })
+    }
+
+    fn reports_unknown_classes(&self) -> bool {
+        self.unknown_classes != UnknownClassSeverity::Ignore
+    }
+
+    /// The unknown classes of the class string at byte offset `start`
+    fn unknown_classes(&self, lines: &LineIndex, start: usize, classes: &str) -> Vec<UnknownClass> {
+        let (line, column) = lines.line_col(start);
+        self.sorter
+            .unknown_classes(classes)
+            .into_iter()
+            .map(|class| UnknownClass {
+                line,
+                column,
+                class: class.to_string(),
+                severity: self.unknown_classes,
+            })
+            .collect()
+    }
+
+    /// Apply the processing mode to sorted content: write it, or return it
+    fn finish_processing(
+        &self,
+        content: &str,
+        result: String,
+        changes_made: bool,
+        file_path: &str,
+        options: ProcessOptions,
+    ) -> Result<String> {
         // Handle different processing modes
         if options.check_formatted {
             // For check_formatted mode, we don't return an error for unsorted classes
@@ -199,16 +624,60 @@ impl FileProcessor {
             return Ok(content.to_string());
         }
 
-        if options.write && changes_made {
-            self.write_file_safely(file_path, &result)?;
+        if options.write && changes_made && options.dry_run {
+            // Run the write path's checks without touching the file
+            self.check_write(file_path, &result)?;
+        } else if options.write && changes_made {
+            self.write_processed(file_path, content, &result)?;
         }
 
         // Always return the processed content so that changes_made detection works correctly
         Ok(result)
     }
 
+    /// Write content processed earlier, such as by a dry run, using the
+    /// configured safety settings
+    ///
+    /// `original` is the content the file was processed from.
+    pub fn write_processed(&self, file_path: &str, original: &str, content: &str) -> Result<()> {
+        self.write_file_safely(file_path, original, content)?;
+        self.tracer.emit(|| TraceEvent::Written {
+            file: file_path.to_string(),
+            bytes: content.len(),
+        });
+        Ok(())
+    }
+
+    /// Sort one operand of a string concatenation, keeping the leading and
+    /// trailing whitespace that separates it from its neighbours
+    fn sort_operand(&self, operand: &str) -> String {
+        let trimmed = operand.trim();
+        if trimmed.is_empty() {
+            return operand.to_string();
+        }
+
+        let leading = &operand[..operand.len() - operand.trim_start().len()];
+        let trailing = &operand[operand.trim_end().len()..];
+        format!(
+            "{}{}{}",
+            leading,
+            self.sorter.sort_classes(trimmed),
+            trailing
+        )
+    }
+
+    /// Check that a write would succeed: the file can be replaced and the
+    /// new content still parses
+    fn check_write(&self, file_path: &str, content: &str) -> Result<()> {
+        atomic::operations::check_writable(file_path)?;
+        self.parser.parse_file(file_path, content)?;
+        Ok(())
+    }
+
     /// Write file content using the configured safety settings
-    fn write_file_safely(&self, file_path: &str, content: &str) -> Result<()> {
+    ///
+    /// `original` is the content the file was read with.
+    fn write_file_safely(&self, file_path: &str, original: &str, content: &str) -> Result<()> {
         // Use configuration if available, otherwise use defaults
         let safety_config = self
             .config
@@ -217,24 +686,22 @@ impl FileProcessor {
             .cloned()
             .unwrap_or_default();
 
-        if safety_config.atomic_writes {
-            if safety_config.create_backups {
-                atomic::operations::write_file_with_backup(file_path, content)?;
-            } else {
-                atomic::operations::write_file(file_path, content)?;
+        let options = atomic::WriteOptions::from(&safety_config);
+        let edited_in_place = safety_config.in_place_edit
+            && atomic::operations::write_changed_ranges(file_path, original, content, &options)?;
+
+        if edited_in_place || safety_config.atomic_writes {
+            if !edited_in_place {
+                atomic::operations::write_file_with_options(file_path, content, &options)?;
             }
 
             // Optionally verify the write
             if safety_config.verify_writes {
-                let written_content = fs::read_to_string(file_path)
-                    .map_err(|e| WindWardenError::from_io_error(e, Some(file_path)))?;
-
-                if written_content != content {
-                    return Err(WindWardenError::internal_error(format!(
-                        "File verification failed for {}: content mismatch",
-                        file_path
-                    )));
-                }
+                let strategy = safety_config
+                    .verify_strategy
+                    .parse()
+                    .map_err(WindWardenError::config_error)?;
+                atomic::operations::verify_written(file_path, content, strategy)?;
             }
         } else {
             // Fall back to direct write if atomic writes are disabled
@@ -246,6 +713,206 @@ impl FileProcessor {
     }
 }
 
+/// Rebuild the array at `content[start..end]` with its elements in sorted
+/// order, keeping each element's literal text and the text between elements
+///
+/// Each element must hold a single class; `None` otherwise, leaving the
+/// array as written. Elements dropped by deduplication or conflict merging
+/// are removed along with the separator after them.
+fn reorder_array_elements(
+    content: &str,
+    start: usize,
+    end: usize,
+    elements: &[String],
+    element_spans: &[(usize, usize)],
+    sorted_classes: &str,
+) -> Option<String> {
+    if elements.iter().any(|e| e.split_whitespace().count() != 1) {
+        return None;
+    }
+    let (first, last) = (element_spans.first()?, element_spans.last()?);
+
+    let mut unused: Vec<Option<&str>> = elements.iter().map(|e| Some(e.trim())).collect();
+    let mut ordered = Vec::new();
+    for class in sorted_classes.split_whitespace() {
+        let index = unused.iter().position(|e| *e == Some(class))?;
+        unused[index] = None;
+        let (element_start, element_end) = element_spans[index];
+        ordered.push(content.get(element_start..element_end)?);
+    }
+
+    let separators = element_spans
+        .windows(2)
+        .map(|pair| content.get(pair[0].1..pair[1].0))
+        .collect::<Option<Vec<_>>>()?;
+    let mut rebuilt = content.get(start..first.0)?.to_string();
+    for (index, element) in ordered.iter().enumerate() {
+        if index > 0 {
+            rebuilt.push_str(separators[index - 1]);
+        }
+        rebuilt.push_str(element);
+    }
+    rebuilt.push_str(content.get(last.1..end)?);
+    Some(rebuilt)
+}
+
+/// Rebuild an array that spans several lines with one element per line
+///
+/// The text after `[` and before `]`, such as a trailing comma and the
+/// closing bracket's indent, is kept as written, and elements are separated
+/// like the first two original elements. Returns `None` for arrays on one
+/// line, or when comments between elements would have to be repeated.
+fn multiline_array(
+    content: &str,
+    start: usize,
+    end: usize,
+    element_spans: &[(usize, usize)],
+    elements: &[String],
+) -> Option<String> {
+    let (first, last) = (element_spans.first()?, element_spans.last()?);
+    let open = content.get(start..first.0)?;
+    let close = content.get(last.1..end)?;
+    if !content.get(start..end)?.contains('\n') {
+        return None;
+    }
+
+    // Separate elements like the original ones, or like the first element
+    // is set off from the bracket
+    let separator = match element_spans.get(1) {
+        Some(second) => content.get(first.1..second.0)?.to_string(),
+        None => format!(",{}", open.strip_prefix('[')?),
+    };
+    let is_layout = |text: &str| text.trim_start_matches(',').trim().is_empty();
+    if !is_layout(&separator) || !separator.contains(',') {
+        return None;
+    }
+
+    Some(format!("{}{}{}", open, elements.join(&separator), close))
+}
+
+/// Describe edits, given in source order, with 1-based lines and columns
+fn modifications(content: &str, edits: &[Edit]) -> Vec<Modification> {
+    let index = LineIndex::new(content);
+
+    edits
+        .iter()
+        .map(|edit| {
+            let (line, column) = index.line_col(edit.start);
+            create_modification(
+                line,
+                column,
+                &content[edit.start..edit.end],
+                &edit.replacement,
+            )
+        })
+        .collect()
+}
+
+/// A string found by the line scan, as a match spanning `start..end` of
+/// `line`, which begins at byte `line_start` of the content
+///
+/// Its kind is guessed from the text before it: a backtick string is a
+/// template literal tagged by the name before it, a string after
+/// `className=` or `class=` is an attribute value, and a string right after
+/// `name(` is the first argument of `name` when it is a supported function.
+/// Anything else is a plain string literal.
+fn scanned_match(
+    parser: &FileParser,
+    line: &str,
+    line_start: usize,
+    start: usize,
+    end: usize,
+) -> ClassMatch {
+    let before = &line[..start];
+    let quote_style = match line.as_bytes()[start] {
+        b'\'' => QuoteStyle::Single,
+        b'`' => QuoteStyle::Backtick,
+        _ => QuoteStyle::Double,
+    };
+    let called = before
+        .trim_end()
+        .strip_suffix('(')
+        .map(|callee| identifier_suffix(callee.trim_end()))
+        .filter(|name| parser.is_supported_function(name));
+    let pattern_type = if quote_style == QuoteStyle::Backtick {
+        PatternType::TemplateLiteral {
+            tag: template_tag(before).map(str::to_string),
+        }
+    } else if before.ends_with("className=") || before.ends_with("class=") {
+        PatternType::JSXAttribute
+    } else if let Some(name) = called {
+        PatternType::FunctionCall {
+            function_name: name.to_string(),
+            arg_index: 0,
+        }
+    } else {
+        PatternType::FunctionCall {
+            function_name: "general".to_string(),
+            arg_index: 0,
+        }
+    };
+
+    ClassMatch::new(
+        line_start + start,
+        line_start + end,
+        line[start + 1..end - 1].to_string(),
+        quote_style,
+        pattern_type,
+    )
+}
+
+/// The identifier `text` ends with, or `""`
+fn identifier_suffix(text: &str) -> &str {
+    let start = text
+        .rfind(|c: char| !(c.is_alphanumeric() || c == '_' || c == '$'))
+        .map_or(0, |index| index + 1);
+    &text[start..]
+}
+
+/// The tag written right before a template literal, such as `tw`, `tw.div`,
+/// or `styled(Link)`, if there is one
+fn template_tag(before: &str) -> Option<&str> {
+    let mut end = before.len();
+    if before.ends_with(')') {
+        end = before.rfind('(')?;
+    }
+    let start = before[..end]
+        .rfind(|c: char| !(c.is_alphanumeric() || matches!(c, '_' | '$' | '.')))
+        .map_or(0, |index| index + 1);
+    let tag = &before[start..];
+    (start < end && !tag.starts_with(|c: char| c.is_ascii_digit() || c == '.')).then_some(tag)
+}
+
+/// Byte ranges of the quoted strings on a single line, including quotes
+///
+/// Scanning stops at a `//` comment outside of a string; an unterminated
+/// quote ends the scan.
+fn quoted_strings(line: &str) -> Vec<(usize, usize)> {
+    let bytes = line.as_bytes();
+    let mut strings = Vec::new();
+    let mut i = 0;
+
+    while i < bytes.len() {
+        match bytes[i] {
+            b'/' if bytes.get(i + 1) == Some(&b'/') => break,
+            quote @ (b'"' | b'\'' | b'`') => {
+                let mut j = i + 1;
+                while j < bytes.len() && bytes[j] != quote {
+                    j += if bytes[j] == b'\\' { 2 } else { 1 };
+                }
+                if j >= bytes.len() {
+                    break;
+                }
+                strings.push((i, j + 1));
+                i = j + 1;
+            }
+            _ => i += 1,
+        }
+    }
+
+    strings
+}
+
 impl Default for FileProcessor {
     fn default() -> Self {
         Self::new()
@@ -314,10 +981,22 @@ mod tests {
     }
 
     #[test]
-    fn test_basic_processing() {
-        let processor = FileProcessor::new();
-        let input = r#"<div className="p-4 flex m-2">"#;
-        let expected = r#"<div className="flex m-2 p-4">"#;
+    fn test_sort_contexts_skip_template_literals() {
+        let config = crate::config::Config {
+            sort_contexts: crate::parser::SortContext::NAMES
+                .iter()
+                .filter(|&&name| name != "templateLiteral")
+                .map(|name| name.to_string())
+                .collect(),
+            ..Default::default()
+        };
+        let processor = FileProcessor::new_with_config(&config);
+        let input = r#"const base = `p-4 flex`;
+const card = tw`p-4 flex`;
+<div className={`p-4 flex`}><span className="p-4 flex" /></div>"#;
+        let expected = r#"const base = `p-4 flex`;
+const card = tw`p-4 flex`;
+<div className={`p-4 flex`}><span className="flex p-4" /></div>"#;
 
         let result = processor
             .process_content(input, "test.tsx", ProcessOptions::default())
@@ -326,22 +1005,33 @@ mod tests {
     }
 
     #[test]
-    fn test_no_changes_needed() {
-        let processor = FileProcessor::new();
-        let input = r#"<div className="flex m-2 p-4">"#;
+    fn test_sort_contexts_skip_bare_strings() {
+        let config = crate::config::Config {
+            sort_contexts: vec!["jsxAttribute".to_string(), "supportedFunction".to_string()],
+            ..Default::default()
+        };
+        let processor = FileProcessor::new_with_config(&config);
+        let input = r#"const words = "flex grid block";
+<div className={cn("p-4 flex")}><span className="p-4 flex" /></div>"#;
+        let expected = r#"const words = "flex grid block";
+<div className={cn("flex p-4")}><span className="flex p-4" /></div>"#;
 
         let result = processor
             .process_content(input, "test.tsx", ProcessOptions::default())
             .unwrap();
-        assert_eq!(result, input);
+        assert_eq!(result, expected);
     }
 
     #[test]
-    fn test_preserve_quote_style() {
-        let processor = FileProcessor::new();
-        let input = r#"<div className='p-4 flex m-2'>"#;
-        let expected = r#"<div className='flex m-2 p-4'>"#;
+    fn test_destructured_class_defaults_sort_as_attributes() {
+        let input = r#"function Button({ className = "p-4 flex m-2", label = "p-4 flex" }) {}"#;
+        let expected = r#"function Button({ className = "flex m-2 p-4", label = "p-4 flex" }) {}"#;
 
+        let config = crate::config::Config {
+            sort_contexts: vec!["jsxAttribute".to_string()],
+            ..Default::default()
+        };
+        let processor = FileProcessor::new_with_config(&config);
         let result = processor
             .process_content(input, "test.tsx", ProcessOptions::default())
             .unwrap();
@@ -349,11 +1039,28 @@ mod tests {
     }
 
     #[test]
-    fn test_multiple_attributes() {
-        let processor = FileProcessor::new();
-        let input = r#"<div className="p-4 flex" id="test"><span className="m-2 text-sm">Text</span></div>"#;
-        let expected = r#"<div className="flex p-4" id="test"><span className="m-2 text-sm">Text</span></div>"#;
+    fn test_class_object_props_sort_slot_values() {
+        let input = r#"<Card classNames={{ root: "p-4 flex", icon: "text-sm m-2" }} />
+const props = { classNames: { base: "p-4 flex" } };"#;
+        let expected = r#"<Card classNames={{ root: "flex p-4", icon: "m-2 text-sm" }} />
+const props = { classNames: { base: "flex p-4" } };"#;
+
+        // Slot values are sorted as class attributes, not as bare strings
+        let config = crate::config::Config {
+            sort_contexts: vec!["jsxAttribute".to_string()],
+            ..Default::default()
+        };
+        let processor = FileProcessor::new_with_config(&config);
+        let result = processor
+            .process_content(input, "test.tsx", ProcessOptions::default())
+            .unwrap();
+        assert_eq!(result, input);
 
+        let config = crate::config::Config {
+            class_object_props: vec!["classNames".to_string()],
+            ..config
+        };
+        let processor = FileProcessor::new_with_config(&config);
         let result = processor
             .process_content(input, "test.tsx", ProcessOptions::default())
             .unwrap();
@@ -361,47 +1068,93 @@ mod tests {
     }
 
     #[test]
-    fn test_basic_cn_function() {
-        let processor = FileProcessor::new();
-        let input = r#"cn("p-4 flex m-2")"#;
-        let expected = r#"cn("flex m-2 p-4")"#;
+    fn test_unknown_classes_are_reported() {
+        let input = "const a = <div className=\"flexx p-4 m-2\" />;\nconst b = \"u-card p4 m-2\";";
+        let options = ProcessOptions {
+            dry_run: true,
+            write: false,
+            check_formatted: false,
+        };
 
-        let result = processor
-            .process_content(input, "test.tsx", ProcessOptions::default())
+        // Ignored by default
+        let outcome = FileProcessor::new()
+            .process_content_detailed(input, "test.tsx", options.clone())
             .unwrap();
-        assert_eq!(result, expected);
+        assert!(outcome.unknown_classes.is_empty());
+
+        let config = crate::config::Config {
+            unknown_classes: "warn".to_string(),
+            known_custom_classes: vec!["u-*".to_string()],
+            ..Default::default()
+        };
+        let outcome = FileProcessor::new_with_config(&config)
+            .process_content_detailed(input, "test.tsx", options)
+            .unwrap();
+        let unknown: Vec<(usize, usize, &str)> = outcome
+            .unknown_classes
+            .iter()
+            .map(|unknown| (unknown.line, unknown.column, unknown.class.as_str()))
+            .collect();
+        assert_eq!(unknown, vec![(1, 26, "flexx"), (2, 11, "p4")]);
+        assert!(
+            outcome
+                .unknown_classes
+                .iter()
+                .all(|unknown| unknown.severity == UnknownClassSeverity::Warn)
+        );
     }
 
     #[test]
-    fn test_multiple_cn_args() {
-        let processor = FileProcessor::new();
-        let input = r#"cn("p-4 flex", "m-2 items-center")"#;
-        let expected = r#"cn("flex p-4", "items-center m-2")"#;
+    fn test_known_custom_classes_count_as_classes() {
+        let input = r#"<div className="p-4 stack" />;
+const slots = "p-4 u-hidden widget u-card";"#;
 
+        // One Tailwind-like token isn't enough to treat a string as classes
+        let processor = FileProcessor::new_with_config(&crate::config::Config::default());
         let result = processor
             .process_content(input, "test.tsx", ProcessOptions::default())
             .unwrap();
-        assert_eq!(result, expected);
+        assert_eq!(result, input);
+
+        let config = crate::config::Config {
+            known_custom_classes: vec!["stack".to_string(), "u-*".to_string()],
+            ..Default::default()
+        };
+        let processor = FileProcessor::new_with_config(&config);
+        let result = processor
+            .process_content(input, "test.tsx", ProcessOptions::default())
+            .unwrap();
+        assert_eq!(
+            result,
+            r#"<div className="stack p-4" />;
+const slots = "u-card u-hidden p-4 widget";"#
+        );
     }
 
     #[test]
-    fn test_tw_merge_function() {
-        // Test multiline JSX with twMerge
-        let processor = FileProcessor::new();
-        let input = r#"twMerge("p-4 flex m-2", "p-2 items-center")"#;
-        let expected = r#"twMerge("flex m-2 p-4", "items-center p-2")"#;
+    fn test_tailwind_detection_ratio_from_config() {
+        let input = r#"const label = "p-4 flex card title wrapper";"#;
 
+        let processor = FileProcessor::new_with_config(&crate::config::Config::default());
         let result = processor
-            .process_content(input, "test.tsx", ProcessOptions::default())
+            .process_content(input, "test.ts", ProcessOptions::default())
             .unwrap();
-        assert_eq!(result, expected);
+        assert_eq!(result, input);
+
+        let mut config = crate::config::Config::default();
+        config.tailwind_detection.ratio = 0.4;
+        let processor = FileProcessor::new_with_config(&config);
+        let result = processor
+            .process_content(input, "test.ts", ProcessOptions::default())
+            .unwrap();
+        assert_eq!(result, r#"const label = "flex p-4 card title wrapper";"#);
     }
 
     #[test]
-    fn test_clsx_function() {
+    fn test_basic_processing() {
         let processor = FileProcessor::new();
-        let input = r#"clsx("p-4 flex m-2 items-center")"#;
-        let expected = r#"clsx("flex items-center m-2 p-4")"#;
+        let input = r#"<div className="p-4 flex m-2">"#;
+        let expected = r#"<div className="flex m-2 p-4">"#;
 
         let result = processor
             .process_content(input, "test.tsx", ProcessOptions::default())
@@ -410,24 +1163,21 @@ mod tests {
     }
 
     #[test]
-    fn test_class_names_function() {
-        // Test multiline JSX with classNames
+    fn test_no_changes_needed() {
         let processor = FileProcessor::new();
-        let input = r#"classNames("p-4 flex m-2 items-center")"#;
-        let expected = r#"classNames("flex items-center m-2 p-4")"#;
+        let input = r#"<div className="flex m-2 p-4">"#;
 
         let result = processor
             .process_content(input, "test.tsx", ProcessOptions::default())
             .unwrap();
-        assert_eq!(result, expected);
+        assert_eq!(result, input);
     }
 
     #[test]
-    fn test_class_list_function() {
-        // Test multiline JSX with classList
+    fn test_preserve_quote_style() {
         let processor = FileProcessor::new();
-        let input = r#"classList("p-4 flex m-2 items-center")"#;
-        let expected = r#"classList("flex items-center m-2 p-4")"#;
+        let input = r#"<div className='p-4 flex m-2'>"#;
+        let expected = r#"<div className='flex m-2 p-4'>"#;
 
         let result = processor
             .process_content(input, "test.tsx", ProcessOptions::default())
@@ -436,23 +1186,229 @@ mod tests {
     }
 
     #[test]
-    fn test_cn_with_conditionals() {
-        let processor = FileProcessor::new();
-        let input = r#"cn("p-4 flex", isActive && "bg-blue-500 text-white", "m-2")"#;
-        // Note: String inside conditional should ALSO be sorted
-        let expected = r#"cn("flex p-4", isActive && "text-white bg-blue-500", "m-2")"#;
+    fn test_tab_separated_classes() {
+        let input = "export const A = () => (\n  <div className=\"p-4\tflex\t\tm-2\">\n    <span className={cn(\"text-sm\tblock\")}>A</span>\n  </div>\n);\n";
 
+        // Tabs are replaced along with the order, and later matches still
+        // land on their own spans
+        let result = FileProcessor::new()
+            .process_content(input, "A.tsx", ProcessOptions::default())
+            .unwrap();
+        assert_eq!(
+            result,
+            "export const A = () => (\n  <div className=\"flex m-2 p-4\">\n    <span className={cn(\"block text-sm\")}>A</span>\n  </div>\n);\n"
+        );
+
+        // Already sorted classes are still rewritten with spaces
+        let result = FileProcessor::new()
+            .process_content("cn(\"flex\tp-4\")", "a.ts", ProcessOptions::default())
+            .unwrap();
+        assert_eq!(result, r#"cn("flex p-4")"#);
+
+        let processor = FileProcessor::new_with_config(&Config {
+            whitespace_separator: "\t".to_string(),
+            ..Config::default()
+        });
         let result = processor
-            .process_content(input, "test.tsx", ProcessOptions::default())
+            .process_content(r#"cn("p-4 flex m-2")"#, "a.ts", ProcessOptions::default())
             .unwrap();
-        assert_eq!(result, expected);
+        assert_eq!(result, "cn(\"flex\tm-2\tp-4\")");
     }
 
     #[test]
-    fn test_cn_with_objects() {
-        let processor = FileProcessor::new();
-        let input = r#"cn("p-4 flex", { "bg-blue-500": isActive }, "m-2")"#;
-        let expected = r#"cn("flex p-4", { "bg-blue-500": isActive }, "m-2")"#;
+    fn test_quote_style_double() {
+        let config = crate::config::Config {
+            quote_style: "double".to_string(),
+            ..Default::default()
+        };
+        let processor = FileProcessor::new_with_config(&config);
+        let input = r#"const a = cn('p-4 flex', 'block m-2', isActive ? 'text-white bg-blue-500' : "flex p-4");
+const b = ['p-4', "flex"];
+const c = 'p-4 ' + 'flex m-2';
+const d = tw`p-4 flex`;
+const e = cn('p-4 after:content-["x"] flex');
+<div className='flex p-4'><span className='before:content-["x"] p-4 block' /></div>"#;
+        let expected = r#"const a = cn("flex p-4", "block m-2", isActive ? "text-white bg-blue-500" : "flex p-4");
+const b = ["flex", "p-4"];
+const c = "p-4 " + "flex m-2";
+const d = tw`flex p-4`;
+const e = cn("after:content-[\"x\"] flex p-4");
+<div className="flex p-4"><span className='block before:content-["x"] p-4' /></div>"#;
+
+        let result = processor
+            .process_content(input, "test.tsx", ProcessOptions::default())
+            .unwrap();
+        assert_eq!(result, expected);
+
+        // Normalized output is stable
+        let again = processor
+            .process_content(&result, "test.tsx", ProcessOptions::default())
+            .unwrap();
+        assert_eq!(again, expected);
+    }
+
+    #[test]
+    fn test_quote_style_single() {
+        let config = crate::config::Config {
+            quote_style: "single".to_string(),
+            ..Default::default()
+        };
+        let processor = FileProcessor::new_with_config(&config);
+        let input = r#"<div className="flex p-4" />;
+const a = clsx("m-2 block");"#;
+        let expected = r#"<div className='flex p-4' />;
+const a = clsx('block m-2');"#;
+
+        let result = processor
+            .process_content(input, "test.tsx", ProcessOptions::default())
+            .unwrap();
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn test_identical_attribute_strings_edit_their_own_span() {
+        let processor = FileProcessor::new();
+        // The first string is in a comment, so only the second one is sorted
+        let input = r#"// <div className="p-4 flex" />
+const A = () => <div className="p-4 flex" />;"#;
+        let expected = r#"// <div className="p-4 flex" />
+const A = () => <div className="flex p-4" />;"#;
+
+        let result = processor
+            .process_content(input, "test.tsx", ProcessOptions::default())
+            .unwrap();
+        assert_eq!(result, expected);
+
+        // A match whose span doesn't hold its classes gets no edit
+        let class_match = ClassMatch::new(
+            input.rfind('"').unwrap() - 9,
+            input.rfind('"').unwrap() + 1,
+            "p-4 flex".to_string(),
+            QuoteStyle::Double,
+            PatternType::JSXAttribute,
+        );
+        assert!(
+            processor
+                .match_edit(input, &class_match, "flex p-4", false)
+                .is_some()
+        );
+        let shifted = ClassMatch {
+            start: class_match.start + 1,
+            end: class_match.end + 1,
+            ..class_match
+        };
+        assert!(
+            processor
+                .match_edit(input, &shifted, "flex p-4", false)
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_multiple_attributes() {
+        let processor = FileProcessor::new();
+        let input = r#"<div className="p-4 flex" id="test"><span className="m-2 text-sm">Text</span></div>"#;
+        let expected = r#"<div className="flex p-4" id="test"><span className="m-2 text-sm">Text</span></div>"#;
+
+        let result = processor
+            .process_content(input, "test.tsx", ProcessOptions::default())
+            .unwrap();
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn test_basic_cn_function() {
+        let processor = FileProcessor::new();
+        let input = r#"cn("p-4 flex m-2")"#;
+        let expected = r#"cn("flex m-2 p-4")"#;
+
+        let result = processor
+            .process_content(input, "test.tsx", ProcessOptions::default())
+            .unwrap();
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn test_multiple_cn_args() {
+        let processor = FileProcessor::new();
+        let input = r#"cn("p-4 flex", "m-2 items-center")"#;
+        let expected = r#"cn("flex p-4", "items-center m-2")"#;
+
+        let result = processor
+            .process_content(input, "test.tsx", ProcessOptions::default())
+            .unwrap();
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn test_tw_merge_function() {
+        // Test multiline JSX with twMerge
+        let processor = FileProcessor::new();
+        let input = r#"twMerge("p-4 flex m-2", "p-2 items-center")"#;
+        let expected = r#"twMerge("flex m-2 p-4", "items-center p-2")"#;
+
+        let result = processor
+            .process_content(input, "test.tsx", ProcessOptions::default())
+            .unwrap();
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn test_clsx_function() {
+        let processor = FileProcessor::new();
+        let input = r#"clsx("p-4 flex m-2 items-center")"#;
+        let expected = r#"clsx("flex items-center m-2 p-4")"#;
+
+        let result = processor
+            .process_content(input, "test.tsx", ProcessOptions::default())
+            .unwrap();
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn test_class_names_function() {
+        // Test multiline JSX with classNames
+        let processor = FileProcessor::new();
+        let input = r#"classNames("p-4 flex m-2 items-center")"#;
+        let expected = r#"classNames("flex items-center m-2 p-4")"#;
+
+        let result = processor
+            .process_content(input, "test.tsx", ProcessOptions::default())
+            .unwrap();
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn test_class_list_function() {
+        // Test multiline JSX with classList
+        let processor = FileProcessor::new();
+        let input = r#"classList("p-4 flex m-2 items-center")"#;
+        let expected = r#"classList("flex items-center m-2 p-4")"#;
+
+        let result = processor
+            .process_content(input, "test.tsx", ProcessOptions::default())
+            .unwrap();
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn test_cn_with_conditionals() {
+        let processor = FileProcessor::new();
+        let input = r#"cn("p-4 flex", isActive && "bg-blue-500 text-white", "m-2")"#;
+        // Note: String inside conditional should ALSO be sorted
+        let expected = r#"cn("flex p-4", isActive && "text-white bg-blue-500", "m-2")"#;
+
+        let result = processor
+            .process_content(input, "test.tsx", ProcessOptions::default())
+            .unwrap();
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn test_cn_with_objects() {
+        let processor = FileProcessor::new();
+        let input = r#"cn("p-4 flex", { "bg-blue-500": isActive }, "m-2")"#;
+        let expected = r#"cn("flex p-4", { "bg-blue-500": isActive }, "m-2")"#;
 
         let result = processor
             .process_content(input, "test.tsx", ProcessOptions::default())
@@ -554,14 +1510,81 @@ const styles2 = `p-4 text-lg bg-white border-2 rounded-lg`;
 
     #[test]
     fn test_tagged_templates_multiple_tags() {
-        let processor = FileProcessor::new();
         let input = r#"
 const tailwind = tw`p-4 flex m-2 items-center`;
+const button = tw.button`p-4 flex`;
 const styles = css`p-4 flex m-2 items-center`;
+const plain = `p-4 flex`;
 "#;
-        let expected = r#"
+
+        // Only `tw` templates are sorted by default, along with untagged ones
+        let result = FileProcessor::new()
+            .process_content(input, "test.tsx", ProcessOptions::default())
+            .unwrap();
+        assert_eq!(
+            result,
+            r#"
 const tailwind = tw`flex items-center m-2 p-4`;
+const button = tw.button`flex p-4`;
+const styles = css`p-4 flex m-2 items-center`;
+const plain = `flex p-4`;
+"#
+        );
+
+        let config = Config {
+            template_tags: vec!["css".to_string()],
+            sort_untagged_templates: false,
+            ..Config::default()
+        };
+        let result = FileProcessor::new_with_config(&config)
+            .process_content(input, "test.tsx", ProcessOptions::default())
+            .unwrap();
+        assert_eq!(
+            result,
+            r#"
+const tailwind = tw`p-4 flex m-2 items-center`;
+const button = tw.button`p-4 flex`;
 const styles = css`flex items-center m-2 p-4`;
+const plain = `p-4 flex`;
+"#
+        );
+    }
+
+    #[test]
+    fn test_unlisted_tag_keeps_nested_tw_sorted() {
+        let processor = FileProcessor::new();
+        let input = "const B = styled.div`\n  block\n  flex\n`;\nconst C = css`${tw`p-4 flex`}`;";
+        let expected =
+            "const B = styled.div`\n  block\n  flex\n`;\nconst C = css`${tw`flex p-4`}`;";
+
+        let result = processor
+            .process_content(input, "test.tsx", ProcessOptions::default())
+            .unwrap();
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn test_tw_nested_in_styled_component_templates() {
+        let processor = FileProcessor::new();
+        let input = r#"
+const Button = styled.div`
+  color: red;
+  ${tw`p-4 flex`}
+  ${({ active }) => active && tw`text-white p-2 flex`}
+`;
+const Wrapped = memo(styled.div`${tw`p-4 flex`} margin: 0;`);
+const Other = withTheme(styled(Link)`${(p) => (p.big ? tw`p-4 flex` : cx("p-2 flex"))}`);
+const Plain = foo(`${"p-4 flex"}`);
+"#;
+        let expected = r#"
+const Button = styled.div`
+  color: red;
+  ${tw`flex p-4`}
+  ${({ active }) => active && tw`flex p-2 text-white`}
+`;
+const Wrapped = memo(styled.div`${tw`flex p-4`} margin: 0;`);
+const Other = withTheme(styled(Link)`${(p) => (p.big ? tw`flex p-4` : cx("flex p-2"))}`);
+const Plain = foo(`${"p-4 flex"}`);
 "#;
 
         let result = processor
@@ -805,6 +1828,98 @@ const absolute = '/home/user/file';"#;
         assert_eq!(result, expected);
     }
 
+    #[test]
+    fn test_merge_conflicts() {
+        let config = Config {
+            merge_conflicts: true,
+            ..Default::default()
+        };
+        let processor = FileProcessor::new_with_config(&config);
+
+        let input = r#"export const A = () => <div className="block p-2 text-red-500 flex p-4 text-blue-500">A</div>;
+const b = cn("px-2 bg-white p-4 bg-black", "m-2 m-1");
+const c = twMerge("p-2 flex p-4", "block flex");"#;
+        let expected = r#"export const A = () => <div className="flex p-4 text-blue-500">A</div>;
+const b = cn("p-4 bg-black", "m-1");
+const c = twMerge("flex p-2 p-4", "block flex");"#;
+
+        let result = processor
+            .process_content(input, "test.tsx", ProcessOptions::default())
+            .unwrap();
+        assert_eq!(result, expected);
+
+        // Conflicts are kept unless merging is enabled
+        let result = FileProcessor::new()
+            .process_content(input, "test.tsx", ProcessOptions::default())
+            .unwrap();
+        assert!(result.contains(r#"className="block flex p-2 p-4 text-blue-500 text-red-500""#));
+    }
+
+    #[test]
+    fn test_ignore_comment_skips_next_line() {
+        let processor = FileProcessor::new();
+        let input = r#"export const A = () => (
+  <div className="p-4 flex">
+    {/* windwarden-ignore */}
+    <span className="p-4 flex">A</span>
+    <span className="p-4 flex">B</span>
+  </div>
+);
+const b = cn(
+  // windwarden-ignore
+  "p-4 flex",
+  "m-2 block"
+);"#;
+        let expected = r#"export const A = () => (
+  <div className="flex p-4">
+    {/* windwarden-ignore */}
+    <span className="p-4 flex">A</span>
+    <span className="flex p-4">B</span>
+  </div>
+);
+const b = cn(
+  // windwarden-ignore
+  "p-4 flex",
+  "block m-2"
+);"#;
+
+        let outcome = processor
+            .process_content_detailed(input, "test.tsx", ProcessOptions::default())
+            .unwrap();
+        assert_eq!(outcome.content, expected);
+        assert_eq!(outcome.counts.found, 3);
+    }
+
+    #[test]
+    fn test_ignore_block_skips_all_matches_inside() {
+        let processor = FileProcessor::new();
+        let input = r#"const a = cn("p-4 flex");
+/* windwarden-ignore-start */
+const b = cn("p-4 flex");
+export const B = () => <div className="m-2 block">B</div>;
+const c = tw`p-4 flex`;
+/* windwarden-ignore-end */
+const d = cn("p-4 flex");"#;
+        let expected = r#"const a = cn("flex p-4");
+/* windwarden-ignore-start */
+const b = cn("p-4 flex");
+export const B = () => <div className="m-2 block">B</div>;
+const c = tw`p-4 flex`;
+/* windwarden-ignore-end */
+const d = cn("flex p-4");"#;
+
+        let result = processor
+            .process_content(input, "test.tsx", ProcessOptions::default())
+            .unwrap();
+        assert_eq!(result, expected);
+
+        // The line-based scan honors the same comments
+        let result = processor
+            .process_content_by_lines(input, "test.tsx", ProcessOptions::default())
+            .unwrap();
+        assert_eq!(result, expected);
+    }
+
     #[test]
     fn test_whitespace_normalization() {
         let processor = FileProcessor::new();
@@ -1020,34 +2135,22 @@ const absolute = '/home/user/file';"#;
     }
 
     #[test]
-    fn test_multiple_cva_definitions() {
+    fn test_cva_compound_variants() {
         let processor = FileProcessor::new();
-        let input = r#"
-const buttonVariants = cva(['p-4', 'flex'], {
-  variants: {
-    size: { sm: ['text-sm', 'p-2'] }
-  }
-});
-
-const cardVariants = cva(['bg-white', 'rounded', 'shadow'], {
-  variants: {
-    padding: { lg: ['p-6', 'gap-4'] }
-  }
-});
-"#;
-        let expected = r#"
-const buttonVariants = cva(['flex', 'p-4'], {
-  variants: {
-    size: { sm: ['p-2', 'text-sm'] }
-  }
-});
-
-const cardVariants = cva(['bg-white', 'rounded', 'shadow'], {
-  variants: {
-    padding: { lg: ['gap-4', 'p-6'] }
-  }
-});
-"#;
+        let input = r#"const button = cva("p-4 flex", {
+  variants: { size: { sm: "text-sm p-2" } },
+  compoundVariants: [
+    { size: "sm", intent: "primary", class: "btn-primary uppercase" },
+    { size: "lg", className: ["shadow-sm", "btn-lg", "font-bold"] }
+  ]
+});"#;
+        let expected = r#"const button = cva("flex p-4", {
+  variants: { size: { sm: "p-2 text-sm" } },
+  compoundVariants: [
+    { size: "sm", intent: "primary", class: "uppercase btn-primary" },
+    { size: "lg", className: ["font-bold", "shadow-sm", "btn-lg"] }
+  ]
+});"#;
 
         let result = processor
             .process_content(input, "test.tsx", ProcessOptions::default())
@@ -1056,24 +2159,32 @@ const cardVariants = cva(['bg-white', 'rounded', 'shadow'], {
     }
 
     #[test]
-    fn test_cva_with_mixed_quote_styles() {
+    fn test_tv_with_nested_variants() {
         let processor = FileProcessor::new();
-        let input = r#"cva(["p-4", "flex"], {
+        let input = r#"const button = tv({
+  base: ['p-4', 'flex'],
   variants: {
     size: {
-      sm: ['text-sm', 'p-2'],
-      lg: ["text-lg", "p-6"]
+      sm: ['text-sm', 'p-2', 'gap-1'],
+      lg: 'text-lg p-6 gap-4'
+    },
+    color: {
+      primary: ['bg-blue-500', 'text-white', 'hover:bg-blue-600']
     }
   }
-})"#;
-        let expected = r#"cva(["flex", "p-4"], {
+});"#;
+        let expected = r#"const button = tv({
+  base: ['flex', 'p-4'],
   variants: {
     size: {
-      sm: ['p-2', 'text-sm'],
-      lg: ["p-6", "text-lg"]
+      sm: ['gap-1', 'p-2', 'text-sm'],
+      lg: 'gap-4 p-6 text-lg'
+    },
+    color: {
+      primary: ['text-white', 'bg-blue-500', 'hover:bg-blue-600']
     }
   }
-})"#;
+});"#;
 
         let result = processor
             .process_content(input, "test.tsx", ProcessOptions::default())
@@ -1082,11 +2193,113 @@ const cardVariants = cva(['bg-white', 'rounded', 'shadow'], {
     }
 
     #[test]
-    fn test_cva_with_empty_and_mixed_arrays() {
+    fn test_tv_slots_and_compound_variants() {
         let processor = FileProcessor::new();
-        let input = r#"cva(['p-4', 'flex'], {
+        let input = r#"const card = tv({
+  slots: { base: "rounded p-4 flex", title: "font-bold text-lg" },
   variants: {
-    size: {
+    size: { sm: { base: "p-2 gap-1", title: "text-sm" } }
+  },
+  compoundVariants: [
+    { size: "sm", class: "btn-sm uppercase" }
+  ]
+});"#;
+        let expected = r#"const card = tv({
+  slots: { base: "flex p-4 rounded", title: "font-bold text-lg" },
+  variants: {
+    size: { sm: { base: "gap-1 p-2", title: "text-sm" } }
+  },
+  compoundVariants: [
+    { size: "sm", class: "uppercase btn-sm" }
+  ]
+});"#;
+
+        let result = processor
+            .process_content(input, "test.tsx", ProcessOptions::default())
+            .unwrap();
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn test_cx_function_sorting() {
+        let processor = FileProcessor::new();
+        let input = r#"<div className={cx("p-4 flex", active && "text-white bg-blue-500")} />"#;
+        let expected = r#"<div className={cx("flex p-4", active && "text-white bg-blue-500")} />"#;
+
+        let result = processor
+            .process_content(input, "test.tsx", ProcessOptions::default())
+            .unwrap();
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn test_multiple_cva_definitions() {
+        let processor = FileProcessor::new();
+        let input = r#"
+const buttonVariants = cva(['p-4', 'flex'], {
+  variants: {
+    size: { sm: ['text-sm', 'p-2'] }
+  }
+});
+
+const cardVariants = cva(['bg-white', 'rounded', 'shadow'], {
+  variants: {
+    padding: { lg: ['p-6', 'gap-4'] }
+  }
+});
+"#;
+        let expected = r#"
+const buttonVariants = cva(['flex', 'p-4'], {
+  variants: {
+    size: { sm: ['p-2', 'text-sm'] }
+  }
+});
+
+const cardVariants = cva(['bg-white', 'rounded', 'shadow'], {
+  variants: {
+    padding: { lg: ['gap-4', 'p-6'] }
+  }
+});
+"#;
+
+        let result = processor
+            .process_content(input, "test.tsx", ProcessOptions::default())
+            .unwrap();
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn test_cva_with_mixed_quote_styles() {
+        let processor = FileProcessor::new();
+        let input = r#"cva(["p-4", "flex"], {
+  variants: {
+    size: {
+      sm: ['text-sm', 'p-2'],
+      lg: ["text-lg", "p-6"]
+    }
+  }
+})"#;
+        let expected = r#"cva(["flex", "p-4"], {
+  variants: {
+    size: {
+      sm: ['p-2', 'text-sm'],
+      lg: ["p-6", "text-lg"]
+    }
+  }
+})"#;
+
+        let result = processor
+            .process_content(input, "test.tsx", ProcessOptions::default())
+            .unwrap();
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn test_cva_with_empty_and_mixed_arrays() {
+        let processor = FileProcessor::new();
+        let input = r#"cva(['p-4', 'flex'], {
+  variants: {
+    size: {
       sm: ['text-sm', 'p-2'],
       md: [],
       lg: ['text-lg', someVariable, 'p-6']
@@ -1196,18 +2409,955 @@ const cardVariants = cva(['bg-white', 'rounded', 'shadow'], {
         assert_eq!(result, expected);
     }
 
+    #[test]
+    fn test_function_arg_indices_limit_sorted_arguments() {
+        let mut config = crate::config::Config::default();
+        config
+            .function_arg_indices
+            .insert("classesFor".to_string(), vec![0]);
+        let processor = FileProcessor::new_with_config(&config);
+
+        // Only the first argument of classesFor is a class list
+        let input = r#"classesFor("p-4 flex m-2", "p-4 flex m-2")"#;
+        let expected = r#"classesFor("flex m-2 p-4", "p-4 flex m-2")"#;
+
+        let result = processor
+            .process_content(input, "test.tsx", ProcessOptions::default())
+            .unwrap();
+        assert_eq!(result, expected);
+
+        // Functions without restrictions still sort every argument
+        let input = r#"cn("p-4 flex m-2", "p-4 flex m-2")"#;
+        let expected = r#"cn("flex m-2 p-4", "flex m-2 p-4")"#;
+
+        let result = processor
+            .process_content(input, "test.tsx", ProcessOptions::default())
+            .unwrap();
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn test_many_matches_in_one_file() {
+        let processor = FileProcessor::new();
+
+        // Every pattern type, repeated so later matches sit far from the start
+        // and earlier replacements change the length of the file
+        let component = |i: usize, classes: [&str; 5]| {
+            format!(
+                r#"export const C{i} = ({{ active }}) => (
+  <div className="{}" data-i="{i}">
+    <span className={{cn("{}", active ? "{}" : "m-2")}} />
+    <p className={{clsx([{}], {{ "{}": active }})}}>{i}</p>
+  </div>
+);
+"#,
+                classes[0], classes[1], classes[2], classes[3], classes[4]
+            )
+        };
+
+        let mut input = String::new();
+        let mut expected = String::new();
+        for i in 0..400 {
+            input.push_str(&component(
+                i,
+                [
+                    "p-4 flex",
+                    "text-lg font-bold p-2",
+                    "bg-blue-500 m-2 flex",
+                    r#""m-2", "block""#,
+                    "text-white p-2",
+                ],
+            ));
+            expected.push_str(&component(
+                i,
+                [
+                    "flex p-4",
+                    "p-2 font-bold text-lg",
+                    "flex m-2 bg-blue-500",
+                    r#""block", "m-2""#,
+                    "p-2 text-white",
+                ],
+            ));
+        }
+
+        let outcome = processor
+            .process_content_detailed(&input, "Many.tsx", ProcessOptions::default())
+            .unwrap();
+        assert_eq!(outcome.content, expected);
+        assert_eq!(outcome.counts.found, 400 * 6);
+        assert_eq!(outcome.counts.reordered, 400 * 5);
+        assert_eq!(outcome.modifications.len(), 400 * 5);
+
+        // The edits are reported in source order
+        assert!(
+            outcome
+                .modifications
+                .windows(2)
+                .all(|pair| (pair[0].line, pair[0].column) < (pair[1].line, pair[1].column))
+        );
+    }
+
+    #[test]
+    fn test_html_file() {
+        let processor = FileProcessor::new();
+
+        let input = r#"<!DOCTYPE html>
+<html>
+  <body class="p-4 flex">
+    <img src="logo.png"
+         class='m-2 block' />
+    <button
+      type="button"
+      class="text-white p-2
+             bg-blue-500"
+    >Go</button>
+    <my-card className="p-4 flex"></my-card>
+    <script>const a = "p-4 flex";</script>
+  </body>
+</html>
+"#;
+        let expected = r#"<!DOCTYPE html>
+<html>
+  <body class="flex p-4">
+    <img src="logo.png"
+         class='block m-2' />
+    <button
+      type="button"
+      class="p-2 text-white bg-blue-500"
+    >Go</button>
+    <my-card className="flex p-4"></my-card>
+    <script>const a = "p-4 flex";</script>
+  </body>
+</html>
+"#;
+
+        let result = processor
+            .process_content(input, "index.html", ProcessOptions::default())
+            .unwrap();
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn test_css_apply_in_nested_layer_rules() {
+        let processor = FileProcessor::new();
+
+        let input = r#"@layer components {
+  .btn {
+    @apply p-4 grid-cols-[repeat(2,minmax(0,1fr))] grid;
+
+    &:hover {
+      @apply underline bg-[url('/img/a;b.png')] font-bold;
+    }
+  }
+}
+"#;
+        let expected = r#"@layer components {
+  .btn {
+    @apply grid grid-cols-[repeat(2,minmax(0,1fr))] p-4;
+
+    &:hover {
+      @apply font-bold underline bg-[url('/img/a;b.png')];
+    }
+  }
+}
+"#;
+
+        let result = processor
+            .process_content(input, "components.scss", ProcessOptions::default())
+            .unwrap();
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn test_css_apply_directives() {
+        let processor = FileProcessor::new();
+
+        let input = r#"@tailwind base;
+
+/* @apply p-4 flex; */
+.btn {
+  @apply p-4 flex items-center;
+  color: red;
+}
+
+.card { @apply shadow rounded p-2 !important }
+
+@media (min-width: 640px) {
+  .card {
+      @apply text-lg
+        font-bold;
+  }
+}
+"#;
+        let expected = r#"@tailwind base;
+
+/* @apply p-4 flex; */
+.btn {
+  @apply flex items-center p-4;
+  color: red;
+}
+
+.card { @apply p-2 rounded shadow !important }
+
+@media (min-width: 640px) {
+  .card {
+      @apply font-bold text-lg;
+  }
+}
+"#;
+
+        let result = processor
+            .process_content(input, "components.css", ProcessOptions::default())
+            .unwrap();
+        assert_eq!(result, expected);
+
+        // SCSS line comments and interpolation are left alone
+        let scss = ".a {\n  // @apply p-4 flex;\n  @apply p-4 #{$extra} flex;\n}\n";
+        let result = processor
+            .process_content(scss, "styles.scss", ProcessOptions::default())
+            .unwrap();
+        assert_eq!(result, scss);
+    }
+
+    #[test]
+    fn test_astro_component() {
+        let processor = FileProcessor::new();
+
+        let input = r#"---
+const title = "p-4 flex";
+const active = true;
+---
+<div class="p-4 flex m-2">
+  <span class='text-lg font-bold p-2'>{title}</span>
+  <a class:list={["p-4 flex", { "bg-blue-500 m-2 flex": active }]} href="/">Home</a>
+  <p class={`p-4 ${title}`}></p>
+  <input class="p-4 flex" />
+</div>
+"#;
+        let expected = r#"---
+const title = "p-4 flex";
+const active = true;
+---
+<div class="flex m-2 p-4">
+  <span class='p-2 font-bold text-lg'>{title}</span>
+  <a class:list={["flex p-4", { "flex m-2 bg-blue-500": active }]} href="/">Home</a>
+  <p class={`p-4 ${title}`}></p>
+  <input class="flex p-4" />
+</div>
+"#;
+
+        let result = processor
+            .process_content(input, "Component.astro", ProcessOptions::default())
+            .unwrap();
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn test_svelte_component() {
+        let processor = FileProcessor::new();
+
+        let input = r#"<script lang="ts">
+  let active = false;
+  const base = "p-4 flex";
+</script>
+
+{#if active}
+  <div class="p-4 flex m-2" class:hidden={!active} class:font-bold>
+    <span class='text-lg font-bold p-2'>{base}</span>
+  </div>
+{:else}
+  <p class="p-4 {active ? 'a' : 'b'} flex"></p>
+  <button class={active ? "p-4 flex" : "m-2 block"} on:click={() => (active = true)}>Go</button>
+{/if}
+
+<style>
+  .card { color: red; }
+</style>
+"#;
+        let expected = r#"<script lang="ts">
+  let active = false;
+  const base = "p-4 flex";
+</script>
+
+{#if active}
+  <div class="flex m-2 p-4" class:hidden={!active} class:font-bold>
+    <span class='p-2 font-bold text-lg'>{base}</span>
+  </div>
+{:else}
+  <p class="p-4 {active ? 'a' : 'b'} flex"></p>
+  <button class={active ? "flex p-4" : "block m-2"} on:click={() => (active = true)}>Go</button>
+{/if}
+
+<style>
+  .card { color: red; }
+</style>
+"#;
+
+        let result = processor
+            .process_content(input, "Component.svelte", ProcessOptions::default())
+            .unwrap();
+        assert_eq!(result, expected);
+
+        // Sorted output is stable
+        let again = processor
+            .process_content(&result, "Component.svelte", ProcessOptions::default())
+            .unwrap();
+        assert_eq!(again, expected);
+    }
+
+    #[test]
+    fn test_mdx_document() {
+        let processor = FileProcessor::new();
+
+        let input = r#"import { Callout } from "../components/callout";
+
+export const meta = { title: "Buttons" };
+
+# Buttons
+
+Use "p-4 flex" spacing, it's the house style.
+
+```html
+<button class="p-4 flex">Plain</button>
+```
+
+```jsx
+<button className="p-4 flex">Example</button>
+```
+
+<Callout className="p-4 flex m-2">
+
+  Markdown **inside** the callout, with a <span className="text-lg font-bold p-2">span</span>.
+
+</Callout>
+
+{cn("p-4 flex", "m-2 block")}
+"#;
+        let expected = r#"import { Callout } from "../components/callout";
+
+export const meta = { title: "Buttons" };
+
+# Buttons
+
+Use "p-4 flex" spacing, it's the house style.
+
+```html
+<button class="p-4 flex">Plain</button>
+```
+
+```jsx
+<button className="flex p-4">Example</button>
+```
+
+<Callout className="flex m-2 p-4">
+
+  Markdown **inside** the callout, with a <span className="p-2 font-bold text-lg">span</span>.
+
+</Callout>
+
+{cn("flex p-4", "block m-2")}
+"#;
+
+        let result = processor
+            .process_content(input, "guide.mdx", ProcessOptions::default())
+            .unwrap();
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn test_angular_template() {
+        let processor = FileProcessor::new();
+
+        let input = r#"<div class="p-4 flex m-2" [ngClass]="{'text-lg font-bold p-2': active, 'm-2 block': !active}">
+  <span class="p-4 {{ size }} flex" [class]="dynamic" [class.hidden]="!active">{{ title }}</span>
+  @if (active) {
+    <p class='text-white bg-blue-500 p-2'>Active</p>
+  }
+</div>
+"#;
+        let expected = r#"<div class="flex m-2 p-4" [ngClass]="{'p-2 font-bold text-lg': active, 'block m-2': !active}">
+  <span class="p-4 {{ size }} flex" [class]="dynamic" [class.hidden]="!active">{{ title }}</span>
+  @if (active) {
+    <p class='p-2 text-white bg-blue-500'>Active</p>
+  }
+</div>
+"#;
+
+        let result = processor
+            .process_content(input, "app.component.html", ProcessOptions::default())
+            .unwrap();
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn test_solid_class_list_keys() {
+        let processor = FileProcessor::new();
+        let input = r#"const el = (
+  <div
+    classList={{ "p-4 flex m-2": true, 'bg-red-500': active(), "text-white font-bold": isOn && big }}
+  />
+);"#;
+        let expected = r#"const el = (
+  <div
+    classList={{ "flex m-2 p-4": true, 'bg-red-500': active(), "font-bold text-white": isOn && big }}
+  />
+);"#;
+
+        let result = processor
+            .process_content(input, "Component.tsx", ProcessOptions::default())
+            .unwrap();
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn test_line_scan_matches_parser_on_common_patterns() {
+        let processor = FileProcessor::new();
+        let input = r#"import { cn } from "./utils";
+
+// Keep p-4 flex in this comment
+export function Card({ active }) {
+  const base = "p-4 bg-white flex rounded";
+  return (
+    <div className="shadow-md p-6 flex items-center m-2">
+      <span className={cn("text-lg font-bold p-2", active && "bg-blue-500 text-white p-4")}>
+        Hello
+      </span>
+      <p className='text-sm mt-2 text-gray-500'>Don't sort this text</p>
+    </div>
+  );
+}
+"#;
+
+        let parsed = processor
+            .process_content(input, "Card.tsx", ProcessOptions::default())
+            .unwrap();
+        let scanned = processor
+            .process_content_by_lines(input, "Card.tsx", ProcessOptions::default())
+            .unwrap();
+
+        assert_ne!(parsed, input);
+        assert_eq!(scanned, parsed);
+    }
+
+    #[test]
+    fn test_max_in_memory_uses_line_scan() {
+        // The line scan sorts strings in calls the parser leaves alone
+        let input = r#"const a = format("p-4 flex");"#;
+
+        let processor = FileProcessor::new();
+        let parsed = processor
+            .process_content(input, "test.tsx", ProcessOptions::default())
+            .unwrap();
+        assert_eq!(parsed, input);
+
+        let processor = FileProcessor::new().with_max_in_memory(Some(8));
+        let scanned = processor
+            .process_content(input, "test.tsx", ProcessOptions::default())
+            .unwrap();
+        assert_eq!(scanned, r#"const a = format("flex p-4");"#);
+    }
+
+    #[test]
+    fn test_max_in_memory_follows_quote_style() {
+        let config = crate::config::Config {
+            quote_style: "double".to_string(),
+            ..Default::default()
+        };
+        let input = r#"const a = cn('p-4 flex');
+const b = cn('flex p-4');
+<div className='before:content-["x"] p-4 block' />"#;
+        let expected = r#"const a = cn("flex p-4");
+const b = cn("flex p-4");
+<div className='block before:content-["x"] p-4' />"#;
+
+        // The line scan writes the same quotes as the parser
+        let parsed = FileProcessor::new_with_config(&config)
+            .process_content(input, "test.tsx", ProcessOptions::default())
+            .unwrap();
+        assert_eq!(parsed, expected);
+        let scanned = FileProcessor::new_with_config(&config)
+            .with_max_in_memory(Some(1))
+            .process_content(input, "test.tsx", ProcessOptions::default())
+            .unwrap();
+        assert_eq!(scanned, expected);
+    }
+
+    #[test]
+    fn test_max_in_memory_applies_sort_filters() {
+        let config = crate::config::Config {
+            sort_contexts: vec!["jsxAttribute".to_string(), "templateLiteral".to_string()],
+            template_tags: vec!["tw".to_string()],
+            ..Default::default()
+        };
+        let input = r#"const a = cn("p-4 flex");
+const b = "p-4 flex";
+const c = tw`p-4 flex`;
+const d = css`p-4 flex`;
+<div className="p-4 flex" />"#;
+        let expected = r#"const a = cn("p-4 flex");
+const b = "p-4 flex";
+const c = tw`flex p-4`;
+const d = css`p-4 flex`;
+<div className="flex p-4" />"#;
+
+        let parsed = FileProcessor::new_with_config(&config)
+            .process_content(input, "test.tsx", ProcessOptions::default())
+            .unwrap();
+        assert_eq!(parsed, expected);
+        let scanned = FileProcessor::new_with_config(&config)
+            .with_max_in_memory(Some(1))
+            .process_content(input, "test.tsx", ProcessOptions::default())
+            .unwrap();
+        assert_eq!(scanned, expected);
+
+        // Markdown prose and other code fences in MDX are left alone
+        let input = "Use \"p-4 flex\" for cards.\n\n```css\n.a { x: \"p-4 flex\" }\n```\n\n<div className=\"p-4 flex\" />\n";
+        let expected = "Use \"p-4 flex\" for cards.\n\n```css\n.a { x: \"p-4 flex\" }\n```\n\n<div className=\"flex p-4\" />\n";
+        let parsed = FileProcessor::new()
+            .process_content(input, "doc.mdx", ProcessOptions::default())
+            .unwrap();
+        assert_eq!(parsed, expected);
+        let scanned = FileProcessor::new()
+            .with_max_in_memory(Some(1))
+            .process_content(input, "doc.mdx", ProcessOptions::default())
+            .unwrap();
+        assert_eq!(scanned, expected);
+    }
+
+    #[test]
+    fn test_process_content_with_counts() {
+        let input = r#"<div className="p-4 flex"><span className="flex p-2">Hi</span></div>"#;
+
+        let processor = FileProcessor::new();
+        let (_, counts) = processor
+            .process_content_with_counts(input, "test.tsx", ProcessOptions::default())
+            .unwrap();
+        assert_eq!(
+            counts,
+            ClassCounts {
+                found: 2,
+                reordered: 1
+            }
+        );
+
+        let processor = FileProcessor::new().with_max_in_memory(Some(8));
+        let (_, scanned) = processor
+            .process_content_with_counts(input, "test.tsx", ProcessOptions::default())
+            .unwrap();
+        assert_eq!(scanned, counts);
+    }
+
+    #[test]
+    fn test_dry_run_write_checks_without_writing() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let file_path = temp_dir.path().join("test.tsx");
+        let input = r#"<div className="p-4 flex"></div>"#;
+        fs::write(&file_path, input).unwrap();
+        let file_path = file_path.to_string_lossy().to_string();
+
+        let options = ProcessOptions {
+            dry_run: true,
+            write: true,
+            check_formatted: false,
+        };
+        let processor = FileProcessor::new();
+        let result = processor
+            .process_content(input, &file_path, options.clone())
+            .unwrap();
+        assert_eq!(result, r#"<div className="flex p-4"></div>"#);
+        assert_eq!(fs::read_to_string(&file_path).unwrap(), input);
+
+        // A real write would fail on a read-only file, so the dry run does too
+        let mut permissions = fs::metadata(&file_path).unwrap().permissions();
+        permissions.set_readonly(true);
+        fs::set_permissions(&file_path, permissions).unwrap();
+        assert!(
+            processor
+                .process_content(input, &file_path, options)
+                .is_err()
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_in_place_edit_falls_back_when_length_changes() {
+        use std::os::unix::fs::MetadataExt;
+
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let mut config = Config::default();
+        config.safety.in_place_edit = true;
+        let processor = FileProcessor::new_with_config(&config);
+        let options = ProcessOptions {
+            dry_run: false,
+            write: true,
+            check_formatted: false,
+        };
+
+        // Sorting keeps the length, so the same file is edited
+        let file_path = temp_dir.path().join("same.tsx");
+        let input = r#"<div className="p-4 flex"></div>"#;
+        fs::write(&file_path, input).unwrap();
+        let inode = fs::metadata(&file_path).unwrap().ino();
+        processor
+            .process_content(input, &file_path.to_string_lossy(), options.clone())
+            .unwrap();
+        assert_eq!(
+            fs::read_to_string(&file_path).unwrap(),
+            r#"<div className="flex p-4"></div>"#
+        );
+        assert_eq!(fs::metadata(&file_path).unwrap().ino(), inode);
+
+        // Deduplication shortens it, so it is replaced atomically
+        let file_path = temp_dir.path().join("shorter.tsx");
+        let input = r#"<div className="p-4 p-4 flex"></div>"#;
+        fs::write(&file_path, input).unwrap();
+        let inode = fs::metadata(&file_path).unwrap().ino();
+        processor
+            .process_content(input, &file_path.to_string_lossy(), options)
+            .unwrap();
+        assert_eq!(
+            fs::read_to_string(&file_path).unwrap(),
+            r#"<div className="flex p-4"></div>"#
+        );
+        assert_ne!(fs::metadata(&file_path).unwrap().ino(), inode);
+    }
+
+    #[test]
+    fn test_min_classes_to_sort() {
+        let config = Config {
+            min_classes_to_sort: 3,
+            ..Config::default()
+        };
+        let processor = FileProcessor::new_with_config(&config);
+
+        let input = r#"<><a className="p-4 flex" /><b className={cn("m-2 p-4 flex")} /></>"#;
+        let result = processor
+            .process_content(input, "test.tsx", ProcessOptions::default())
+            .unwrap();
+        assert_eq!(
+            result,
+            r#"<><a className="p-4 flex" /><b className={cn("flex m-2 p-4")} /></>"#
+        );
+    }
+
+    #[test]
+    fn test_preserve_formatting_reorders_array_elements_only() {
+        let config = Config {
+            preserve_formatting: true,
+            ..Config::default()
+        };
+        let processor = FileProcessor::new_with_config(&config);
+
+        let input = r#"const classes = cn(['p-4',   "underline" ,'m-2', "block"]);"#;
+        let result = processor
+            .process_content(input, "test.tsx", ProcessOptions::default())
+            .unwrap();
+        assert_eq!(
+            result,
+            r#"const classes = cn(["block",   'm-2' ,'p-4', "underline"]);"#
+        );
+
+        // Without it, the array is rebuilt with one quote style and ", "
+        let result = FileProcessor::new()
+            .process_content(input, "test.tsx", ProcessOptions::default())
+            .unwrap();
+        assert_eq!(
+            result,
+            r#"const classes = cn(['block', 'm-2', 'p-4', 'underline']);"#
+        );
+    }
+
+    #[test]
+    fn test_preserve_formatting_multiline_array_and_duplicates() {
+        let config = Config {
+            preserve_formatting: true,
+            ..Config::default()
+        };
+        let processor = FileProcessor::new_with_config(&config);
+
+        let input = "const c = [\n  'p-4',\n  \"flex\",\n  'p-4',\n];";
+        let result = processor
+            .process_content(input, "test.ts", ProcessOptions::default())
+            .unwrap();
+        assert_eq!(result, "const c = [\n  \"flex\",\n  'p-4',\n];");
+
+        // Elements with several classes are left as written
+        let input = r#"const c = ["p-4 flex", 'm-2'];"#;
+        let result = processor
+            .process_content(input, "test.ts", ProcessOptions::default())
+            .unwrap();
+        assert_eq!(result, input);
+    }
+
+    #[test]
+    fn test_multiline_array_layout_kept() {
+        let processor = FileProcessor::new();
+
+        let input = "const c = cn([\n    'p-4',\n    'flex',\n    'm-2',\n  ]);";
+        let result = processor
+            .process_content(input, "test.ts", ProcessOptions::default())
+            .unwrap();
+        assert_eq!(
+            result,
+            "const c = cn([\n    'flex',\n    'm-2',\n    'p-4',\n  ]);"
+        );
+
+        // No trailing comma, and a lone element split onto its own lines
+        let input = "const c = cn([\n  'p-4 flex'\n]);";
+        let result = processor
+            .process_content(input, "test.ts", ProcessOptions::default())
+            .unwrap();
+        assert_eq!(result, "const c = cn([\n  'flex',\n  'p-4'\n]);");
+
+        // Arrays on one line are still rebuilt on one line
+        let input = "const c = cn(['p-4','flex']);";
+        let result = processor
+            .process_content(input, "test.ts", ProcessOptions::default())
+            .unwrap();
+        assert_eq!(result, "const c = cn(['flex', 'p-4']);");
+    }
+
+    #[test]
+    fn test_quoted_strings() {
+        let line = r#"a("x \" y", 'z') // "comment""#;
+        let ranges: Vec<&str> = quoted_strings(line)
+            .into_iter()
+            .map(|(start, end)| &line[start..end])
+            .collect();
+        assert_eq!(ranges, vec![r#""x \" y""#, "'z'"]);
+    }
+
+    #[test]
+    fn test_conditional_branches_sorted() {
+        let processor = FileProcessor::new();
+        let input = r#"const x = isActive ? "bg-blue-500 text-white p-4" : "bg-gray-200 p-2";
+const el = <div className={isActive ? "text-white p-4 flex" : "p-2 flex"} />;"#;
+        let expected = r#"const x = isActive ? "p-4 text-white bg-blue-500" : "p-2 bg-gray-200";
+const el = <div className={isActive ? "flex p-4 text-white" : "flex p-2"} />;"#;
+
+        let result = processor
+            .process_content(input, "test.tsx", ProcessOptions::default())
+            .unwrap();
+        assert_eq!(result, expected);
+    }
+
     #[test]
     fn test_string_concatenation_simple() {
         let processor = FileProcessor::new();
         let input = r#""p-4 flex m-2" + "items-center bg-white""#;
-        // The algorithm redistributes sorted classes maintaining the original split ratio
-        // Original left: 3 words, right: 2 words -> left gets first 3, right gets rest
-        let expected = r#""flex items-center m-2" + "p-4 bg-white""#;
+        // Each operand is sorted on its own; no class moves across the `+`
+        let expected = r#""flex m-2 p-4" + "items-center bg-white""#;
+
+        let result = processor
+            .process_content(input, "test.tsx", ProcessOptions::default())
+            .unwrap();
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn test_string_concatenation_keeps_operand_whitespace() {
+        let processor = FileProcessor::new();
+        let input = "const c = 'p-4 flex ' +\n  \"bg-white m-2\";";
+        let expected = "const c = 'flex p-4 ' +\n  \"m-2 bg-white\";";
+
+        let result = processor
+            .process_content(input, "test.tsx", ProcessOptions::default())
+            .unwrap();
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn test_process_content_detailed() {
+        let processor = FileProcessor::new();
+        let input = r#"export const A = () => (
+  <div className="p-4 flex">
+    <span className={cn("m-2 flex", "flex p-4")}>A</span>
+  </div>
+);
+"#;
+
+        let outcome = processor
+            .process_content_detailed(input, "test.tsx", ProcessOptions::default())
+            .unwrap();
+
+        assert!(outcome.changed());
+        assert_eq!(
+            outcome.content,
+            processor
+                .process_content(input, "test.tsx", ProcessOptions::default())
+                .unwrap()
+        );
+        assert_eq!(
+            outcome.counts,
+            ClassCounts {
+                found: 3,
+                reordered: 2
+            }
+        );
+
+        let changes: Vec<(usize, usize, &str, &str)> = outcome
+            .modifications
+            .iter()
+            .map(|m| {
+                (
+                    m.line,
+                    m.column,
+                    m.original_text.as_str(),
+                    m.new_text.as_str(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            changes,
+            [
+                (2, 18, r#""p-4 flex""#, r#""flex p-4""#),
+                (3, 25, r#""m-2 flex""#, r#""flex m-2""#),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_process_content_detailed_columns_after_multibyte_text() {
+        let processor = FileProcessor::new();
+        let input = r#"export const A = () => <p title="日本語 ✨">Ünïcode <b className="p-4 flex">B</b></p>;"#;
+
+        let outcome = processor
+            .process_content_detailed(input, "test.tsx", ProcessOptions::default())
+            .unwrap();
+
+        assert_eq!(outcome.modifications.len(), 1);
+        let modification = &outcome.modifications[0];
+        let expected_column = input[..input.find("\"p-4").unwrap()].chars().count() + 1;
+        assert_eq!(
+            (modification.line, modification.column),
+            (1, expected_column)
+        );
+        assert_eq!(expected_column, 62);
+    }
+
+    #[test]
+    fn test_process_content_detailed_without_changes() {
+        let processor = FileProcessor::new();
+        let input = r#"const a = cn("flex p-4");"#;
+
+        let outcome = processor
+            .process_content_detailed(input, "test.tsx", ProcessOptions::default())
+            .unwrap();
+
+        assert!(!outcome.changed());
+        assert_eq!(outcome.content, input);
+        assert_eq!(outcome.counts.found, 1);
+    }
+
+    #[test]
+    fn test_process_content_detailed_by_lines() {
+        let processor = FileProcessor::new().with_max_in_memory(Some(0));
+        let input = "const a = 1;\nconst b = cn(\"p-4 flex\");\n";
+
+        let outcome = processor
+            .process_content_detailed(input, "test.tsx", ProcessOptions::default())
+            .unwrap();
+
+        assert_eq!(outcome.modifications.len(), 1);
+        let modification = &outcome.modifications[0];
+        assert_eq!((modification.line, modification.column), (2, 14));
+        assert_eq!(modification.original_text, r#""p-4 flex""#);
+        assert_eq!(modification.new_text, r#""flex p-4""#);
+    }
+
+    #[test]
+    fn test_multiline_template_literal_whitespace_modes() {
+        let input = "const card = tw`\n  p-4\n  flex\n  m-2\n`;";
+        let cases = [
+            ("collapse", "const card = tw`flex m-2 p-4`;"),
+            ("preserve-edges", "const card = tw`\n  flex m-2 p-4\n`;"),
+            ("none", "const card = tw`\n  flex\n  m-2\n  p-4\n`;"),
+        ];
+
+        for (mode, expected) in cases {
+            let config = Config {
+                normalize_whitespace: mode.to_string(),
+                ..Default::default()
+            };
+            let result = FileProcessor::new_with_config(&config)
+                .process_content(input, "test.tsx", ProcessOptions::default())
+                .unwrap();
+            assert_eq!(result, expected, "{}", mode);
+        }
+
+        // Already sorted multiline strings are left alone when whitespace is kept
+        let config = Config {
+            normalize_whitespace: "none".to_string(),
+            ..Default::default()
+        };
+        let sorted = "const card = tw`\n  flex\n  m-2\n  p-4\n`;";
+        let outcome = FileProcessor::new_with_config(&config)
+            .process_content_detailed(sorted, "test.tsx", ProcessOptions::default())
+            .unwrap();
+        assert!(!outcome.changed());
+    }
+
+    #[test]
+    fn test_string_concatenation_collapse() {
+        let config = Config {
+            collapse_concatenation: true,
+            ..Default::default()
+        };
+        let processor = FileProcessor::new_with_config(&config);
+        let input = r#"const c = "p-4 flex m-2" + "items-center bg-white";"#;
+        let expected = r#"const c = "flex items-center m-2 p-4 bg-white";"#;
+
+        let result = processor
+            .process_content(input, "test.tsx", ProcessOptions::default())
+            .unwrap();
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn test_string_concatenation_collapse_with_mixed_quotes() {
+        let config = Config {
+            collapse_concatenation: true,
+            ..Default::default()
+        };
+        let processor = FileProcessor::new_with_config(&config);
+        let input = r#"const c = 'p-4 flex' + "before:content-['*'] m-2 text-sm";"#;
+        let expected = r#"const c = "before:content-['*'] flex m-2 p-4 text-sm";"#;
+
+        let result = processor
+            .process_content(input, "test.tsx", ProcessOptions::default())
+            .unwrap();
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn test_escaped_quotes_in_arbitrary_values() {
+        let processor = FileProcessor::new();
+        let input = r#"const a = cn('p-4 before:content-[\'\\\'] flex');
+const b = cn("p-4 after:content-[\"it's\"] flex");
+export const C = () => <div className="p-4 before:content-['don\'t'] flex">C</div>;
+"#;
+        let expected = r#"const a = cn('before:content-[\'\\\'] flex p-4');
+const b = cn("after:content-[\"it's\"] flex p-4");
+export const C = () => <div className="before:content-['don\'t'] flex p-4">C</div>;
+"#;
+
+        let result = processor
+            .process_content(input, "test.tsx", ProcessOptions::default())
+            .unwrap();
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn test_array_with_quotes_in_arbitrary_values() {
+        let processor = FileProcessor::new();
+        let input = r#"const classes = ['p-4', "before:content-['x']", 'flex'];"#;
+        let expected = r#"const classes = ["before:content-['x']", "flex", "p-4"];"#;
 
         let result = processor
             .process_content(input, "test.tsx", ProcessOptions::default())
             .unwrap();
         assert_eq!(result, expected);
+        assert!(FileParser::new().parse_file("test.tsx", &result).is_ok());
     }
 
     #[test]
@@ -1218,7 +3368,7 @@ const cardVariants = cva(['bg-white', 'rounded', 'shadow'], {
             r#"<div className={"p-4 flex m-2" + "items-center bg-white hover:bg-gray-100"}>"#;
         // The sorting algorithm handles each string separately
         let expected =
-            r#"<div className={"flex items-center m-2" + "p-4 hover:bg-gray-100 bg-white"}>"#;
+            r#"<div className={"flex m-2 p-4" + "items-center hover:bg-gray-100 bg-white"}>"#;
 
         let result = processor
             .process_content(input, "test.tsx", ProcessOptions::default())
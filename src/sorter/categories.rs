@@ -3,6 +3,8 @@ use std::collections::HashMap;
 
 // Order of categories in Tailwind CSS
 pub static CATEGORY_ORDER: &[&str] = &[
+    // `group` and `peer` markers, including named ones like `group/sidebar`
+    "markers",
     // Layout
     "layout",
     // Flexbox & Grid
@@ -33,6 +35,8 @@ pub static CATEGORY_ORDER: &[&str] = &[
     "svg",
     // Accessibility
     "accessibility",
+    // Arbitrary properties, such as `[mask-type:luminance]`
+    "arbitrary",
     // Unknown classes (custom, non-Tailwind)
     "unknown",
 ];
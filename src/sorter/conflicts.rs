@@ -0,0 +1,578 @@
+use std::collections::HashSet;
+
+// Classes that form a conflict group on their own, by group
+static KEYWORD_GROUPS: &[(&str, &[&str])] = &[
+    (
+        "display",
+        &[
+            "block",
+            "inline-block",
+            "inline",
+            "flex",
+            "inline-flex",
+            "table",
+            "inline-table",
+            "table-caption",
+            "table-cell",
+            "table-column",
+            "table-column-group",
+            "table-footer-group",
+            "table-header-group",
+            "table-row-group",
+            "table-row",
+            "flow-root",
+            "grid",
+            "inline-grid",
+            "contents",
+            "list-item",
+            "hidden",
+        ],
+    ),
+    (
+        "position",
+        &["static", "fixed", "absolute", "relative", "sticky"],
+    ),
+    ("visibility", &["visible", "invisible", "collapse"]),
+    (
+        "flex-direction",
+        &[
+            "flex-row",
+            "flex-row-reverse",
+            "flex-col",
+            "flex-col-reverse",
+        ],
+    ),
+    (
+        "flex-wrap",
+        &["flex-wrap", "flex-wrap-reverse", "flex-nowrap"],
+    ),
+    (
+        "text-align",
+        &[
+            "text-left",
+            "text-center",
+            "text-right",
+            "text-justify",
+            "text-start",
+            "text-end",
+        ],
+    ),
+    (
+        "font-size",
+        &[
+            "text-xs",
+            "text-sm",
+            "text-base",
+            "text-lg",
+            "text-xl",
+            "text-2xl",
+            "text-3xl",
+            "text-4xl",
+            "text-5xl",
+            "text-6xl",
+            "text-7xl",
+            "text-8xl",
+            "text-9xl",
+        ],
+    ),
+    (
+        "font-weight",
+        &[
+            "font-thin",
+            "font-extralight",
+            "font-light",
+            "font-normal",
+            "font-medium",
+            "font-semibold",
+            "font-bold",
+            "font-extrabold",
+            "font-black",
+        ],
+    ),
+    ("font-family", &["font-sans", "font-serif", "font-mono"]),
+    ("font-style", &["italic", "not-italic"]),
+    (
+        "text-decoration",
+        &["underline", "overline", "line-through", "no-underline"],
+    ),
+    (
+        "text-transform",
+        &["uppercase", "lowercase", "capitalize", "normal-case"],
+    ),
+    (
+        "border-w",
+        &["border", "border-0", "border-2", "border-4", "border-8"],
+    ),
+    ("rounded", &["rounded"]),
+    ("shadow", &["shadow"]),
+];
+
+// Prefixes whose classes conflict when the value is a number, a fraction,
+// an arbitrary value, or a known Tailwind keyword, by group; the longest
+// matching prefix wins
+static PREFIX_GROUPS: &[(&str, &str)] = &[
+    ("p-", "p"),
+    ("px-", "px"),
+    ("py-", "py"),
+    ("pt-", "pt"),
+    ("pr-", "pr"),
+    ("pb-", "pb"),
+    ("pl-", "pl"),
+    ("ps-", "ps"),
+    ("pe-", "pe"),
+    ("m-", "m"),
+    ("mx-", "mx"),
+    ("my-", "my"),
+    ("mt-", "mt"),
+    ("mr-", "mr"),
+    ("mb-", "mb"),
+    ("ml-", "ml"),
+    ("ms-", "ms"),
+    ("me-", "me"),
+    ("gap-", "gap"),
+    ("gap-x-", "gap-x"),
+    ("gap-y-", "gap-y"),
+    ("space-x-", "space-x"),
+    ("space-y-", "space-y"),
+    ("w-", "w"),
+    ("h-", "h"),
+    ("min-w-", "min-w"),
+    ("max-w-", "max-w"),
+    ("min-h-", "min-h"),
+    ("max-h-", "max-h"),
+    ("size-", "size"),
+    ("inset-", "inset"),
+    ("inset-x-", "inset-x"),
+    ("inset-y-", "inset-y"),
+    ("top-", "top"),
+    ("right-", "right"),
+    ("bottom-", "bottom"),
+    ("left-", "left"),
+    ("start-", "start"),
+    ("end-", "end"),
+    ("z-", "z"),
+    ("order-", "order"),
+    ("opacity-", "opacity"),
+    ("leading-", "leading"),
+    ("tracking-", "tracking"),
+    ("items-", "align-items"),
+    ("justify-", "justify-content"),
+    ("justify-items-", "justify-items"),
+    ("justify-self-", "justify-self"),
+    ("self-", "align-self"),
+    ("overflow-", "overflow"),
+    ("overflow-x-", "overflow-x"),
+    ("overflow-y-", "overflow-y"),
+    ("grid-cols-", "grid-cols"),
+    ("grid-rows-", "grid-rows"),
+    ("basis-", "basis"),
+    ("rounded-t-", "rounded-t"),
+    ("rounded-r-", "rounded-r"),
+    ("rounded-b-", "rounded-b"),
+    ("rounded-l-", "rounded-l"),
+    ("rounded-tl-", "rounded-tl"),
+    ("rounded-tr-", "rounded-tr"),
+    ("rounded-br-", "rounded-br"),
+    ("rounded-bl-", "rounded-bl"),
+    ("duration-", "duration"),
+    ("ease-", "ease"),
+    ("delay-", "delay"),
+    ("cursor-", "cursor"),
+];
+
+// Prefixes whose classes only conflict when the value is a color; the same
+// prefix also takes sizes, styles, and so on
+static COLOR_GROUPS: &[(&str, &str)] = &[
+    ("text-", "text-color"),
+    ("bg-", "bg-color"),
+    ("border-", "border-color"),
+    ("ring-", "ring-color"),
+    ("shadow-", "shadow-color"),
+    ("outline-", "outline-color"),
+    ("decoration-", "decoration-color"),
+    ("divide-", "divide-color"),
+    ("placeholder-", "placeholder-color"),
+    ("accent-", "accent-color"),
+    ("caret-", "caret-color"),
+    ("fill-", "fill"),
+    ("stroke-", "stroke"),
+];
+
+// Prefixes whose classes conflict when the value is a size
+static SIZE_GROUPS: &[(&str, &str, &[&str])] = &[
+    (
+        "rounded-",
+        "rounded",
+        &["none", "sm", "md", "lg", "xl", "2xl", "3xl", "full"],
+    ),
+    (
+        "shadow-",
+        "shadow",
+        &["sm", "md", "lg", "xl", "2xl", "inner", "none"],
+    ),
+];
+
+// Groups that also override other groups when they come later, as
+// `p-4` overrides an earlier `px-2`
+static CONFLICTING_GROUPS: &[(&str, &[&str])] = &[
+    ("p", &["px", "py", "pt", "pr", "pb", "pl", "ps", "pe"]),
+    ("px", &["pr", "pl"]),
+    ("py", &["pt", "pb"]),
+    ("m", &["mx", "my", "mt", "mr", "mb", "ml", "ms", "me"]),
+    ("mx", &["mr", "ml"]),
+    ("my", &["mt", "mb"]),
+    ("gap", &["gap-x", "gap-y"]),
+    ("size", &["w", "h"]),
+    (
+        "inset",
+        &[
+            "inset-x", "inset-y", "top", "right", "bottom", "left", "start", "end",
+        ],
+    ),
+    ("inset-x", &["right", "left"]),
+    ("inset-y", &["top", "bottom"]),
+    ("overflow", &["overflow-x", "overflow-y"]),
+    (
+        "rounded",
+        &[
+            "rounded-t",
+            "rounded-r",
+            "rounded-b",
+            "rounded-l",
+            "rounded-tl",
+            "rounded-tr",
+            "rounded-br",
+            "rounded-bl",
+        ],
+    ),
+    ("rounded-t", &["rounded-tl", "rounded-tr"]),
+    ("rounded-r", &["rounded-tr", "rounded-br"]),
+    ("rounded-b", &["rounded-br", "rounded-bl"]),
+    ("rounded-l", &["rounded-tl", "rounded-bl"]),
+    ("font-size", &["leading"]),
+];
+
+// Keyword values of prefixed classes; anything else, like the `card` of
+// `my-card`, is taken for a custom class
+static VALUE_KEYWORDS: &[&str] = &[
+    "auto",
+    "px",
+    "full",
+    "screen",
+    "min",
+    "max",
+    "fit",
+    "none",
+    "svh",
+    "lvh",
+    "dvh",
+    "svw",
+    "lvw",
+    "dvw",
+    "prose",
+    "xs",
+    "sm",
+    "md",
+    "lg",
+    "xl",
+    "start",
+    "end",
+    "center",
+    "between",
+    "around",
+    "evenly",
+    "stretch",
+    "baseline",
+    "normal",
+    "hidden",
+    "visible",
+    "scroll",
+    "clip",
+    "linear",
+    "in",
+    "out",
+    "in-out",
+    "pointer",
+    "default",
+    "wait",
+    "text",
+    "move",
+    "help",
+    "not-allowed",
+    "grab",
+    "grabbing",
+    "tighter",
+    "tight",
+    "snug",
+    "relaxed",
+    "loose",
+    "wide",
+    "wider",
+    "widest",
+    "first",
+    "last",
+    "subgrid",
+];
+
+static COLOR_PALETTE: &[&str] = &[
+    "slate", "gray", "zinc", "neutral", "stone", "red", "orange", "amber", "yellow", "lime",
+    "green", "emerald", "teal", "cyan", "sky", "blue", "indigo", "violet", "purple", "fuchsia",
+    "pink", "rose",
+];
+
+/// Remove classes overridden by a later class in the same conflict group
+///
+/// Mirrors tailwind-merge: for each group (per set of variants and `!`),
+/// only the last class is kept, so `p-2 p-4` becomes `p-4`. Groups that
+/// cover other groups, like `p` covering `px`, also drop earlier classes of
+/// those groups. Classes outside every group are always kept.
+pub fn merge_conflicts<'a>(classes: &[&'a str]) -> Vec<&'a str> {
+    let mut claimed: HashSet<(String, &'static str)> = HashSet::new();
+    let mut kept = Vec::with_capacity(classes.len());
+
+    for &class in classes.iter().rev() {
+        let Some((modifiers, group)) = conflict_key(class) else {
+            kept.push(class);
+            continue;
+        };
+
+        if claimed.contains(&(modifiers.clone(), group)) {
+            continue;
+        }
+
+        for &covered in covered_groups(group) {
+            claimed.insert((modifiers.clone(), covered));
+        }
+        claimed.insert((modifiers, group));
+        kept.push(class);
+    }
+
+    kept.reverse();
+    kept
+}
+
+/// The variants and importance of a class, and its conflict group
+fn conflict_key(class: &str) -> Option<(String, &'static str)> {
+    let (variants, base) = split_variants(class);
+
+    let (base, important) = match base.strip_prefix('!') {
+        Some(base) => (base, true),
+        None => match base.strip_suffix('!') {
+            Some(base) => (base, true),
+            None => (base, false),
+        },
+    };
+    let base = base.strip_prefix('-').unwrap_or(base);
+
+    let group = class_group(base)?;
+
+    // Variant order does not change what a class applies to
+    let mut variants = variants;
+    variants.sort_unstable();
+    let mut modifiers = variants.join(":");
+    if important {
+        modifiers.push('!');
+    }
+
+    Some((modifiers, group))
+}
+
+/// Split a class into its variants and base, ignoring colons inside
+/// arbitrary values such as `bg-[url(https://...)]`
+pub(super) fn split_variants(class: &str) -> (Vec<&str>, &str) {
+    let mut variants = Vec::new();
+    let mut depth = 0usize;
+    let mut start = 0;
+
+    for (i, c) in class.char_indices() {
+        match c {
+            '[' | '(' => depth += 1,
+            ']' | ')' => depth = depth.saturating_sub(1),
+            ':' if depth == 0 => {
+                variants.push(&class[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+
+    (variants, &class[start..])
+}
+
+/// The conflict group of a class without variants, if it has one
+fn class_group(base: &str) -> Option<&'static str> {
+    for (group, members) in KEYWORD_GROUPS {
+        if members.contains(&base) {
+            return Some(group);
+        }
+    }
+
+    for (prefix, group, sizes) in SIZE_GROUPS {
+        if let Some(value) = base.strip_prefix(prefix)
+            && (sizes.contains(&value) || is_arbitrary(value))
+            && !(*group == "shadow" && is_color(value))
+        {
+            return Some(group);
+        }
+    }
+
+    for (prefix, group) in COLOR_GROUPS {
+        if let Some(value) = base.strip_prefix(prefix)
+            && is_color(value)
+        {
+            return Some(group);
+        }
+    }
+
+    if let Some(value) = base.strip_prefix("border-")
+        && value.starts_with('[')
+        && is_length(value)
+    {
+        return Some("border-w");
+    }
+
+    let (prefix, group) = PREFIX_GROUPS
+        .iter()
+        .filter(|(prefix, _)| base.len() > prefix.len() && base.starts_with(prefix))
+        .max_by_key(|(prefix, _)| prefix.len())?;
+
+    let value = &base[prefix.len()..];
+    let known_value = value.starts_with(|c: char| c.is_ascii_digit())
+        || is_arbitrary(value)
+        || VALUE_KEYWORDS.contains(&value);
+    known_value.then_some(*group)
+}
+
+/// Whether a class value is a color, such as `blue-500`, `white/50`, or
+/// `[#fff]`
+pub(super) fn is_color(value: &str) -> bool {
+    let value = match value.split_once('/') {
+        Some((color, _opacity)) if !value.starts_with('[') => color,
+        _ => value,
+    };
+
+    if let Some(arbitrary) = value
+        .strip_prefix('[')
+        .and_then(|value| value.strip_suffix(']'))
+    {
+        return arbitrary.starts_with('#')
+            || arbitrary.starts_with("rgb")
+            || arbitrary.starts_with("hsl")
+            || arbitrary.starts_with("color:");
+    }
+
+    if matches!(
+        value,
+        "inherit" | "current" | "transparent" | "black" | "white"
+    ) {
+        return true;
+    }
+
+    value.split_once('-').is_some_and(|(palette, shade)| {
+        COLOR_PALETTE.contains(&palette)
+            && !shade.is_empty()
+            && shade.chars().all(|c| c.is_ascii_digit())
+    })
+}
+
+fn is_arbitrary(value: &str) -> bool {
+    value.starts_with('[') && value.ends_with(']')
+}
+
+/// Whether an arbitrary value is a length, such as `[3px]` or `[0.5rem]`
+fn is_length(value: &str) -> bool {
+    value
+        .strip_prefix('[')
+        .and_then(|value| value.strip_suffix(']'))
+        .is_some_and(|value| value.starts_with(|c: char| c.is_ascii_digit() || c == '.'))
+}
+
+/// Groups that a class of `group` overrides besides its own
+fn covered_groups(group: &str) -> &'static [&'static str] {
+    CONFLICTING_GROUPS
+        .iter()
+        .find(|(name, _)| *name == group)
+        .map_or(&[], |(_, covered)| covered)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn merge(classes: &str) -> String {
+        let classes: Vec<&str> = classes.split_whitespace().collect();
+        merge_conflicts(&classes).join(" ")
+    }
+
+    #[test]
+    fn test_spacing_conflicts() {
+        assert_eq!(merge("p-2 p-4"), "p-4");
+        assert_eq!(merge("px-2 py-1 p-4"), "p-4");
+        assert_eq!(merge("p-4 px-2"), "p-4 px-2");
+        assert_eq!(merge("m-2 -m-4"), "-m-4");
+        assert_eq!(merge("mt-2 mb-2 my-4"), "my-4");
+        assert_eq!(merge("gap-x-2 gap-4 gap-y-1"), "gap-4 gap-y-1");
+        assert_eq!(merge("p-[3px] p-2"), "p-2");
+    }
+
+    #[test]
+    fn test_display_conflicts() {
+        assert_eq!(merge("block flex"), "flex");
+        assert_eq!(merge("hidden inline-flex"), "inline-flex");
+        assert_eq!(merge("flex flex-col"), "flex flex-col");
+        assert_eq!(merge("flex-row flex-col"), "flex-col");
+        assert_eq!(merge("absolute relative"), "relative");
+    }
+
+    #[test]
+    fn test_color_conflicts() {
+        assert_eq!(merge("text-red-500 text-blue-500"), "text-blue-500");
+        assert_eq!(merge("text-red-500 text-lg"), "text-red-500 text-lg");
+        assert_eq!(merge("text-sm text-lg"), "text-lg");
+        assert_eq!(merge("bg-white/50 bg-[#fff]"), "bg-[#fff]");
+        assert_eq!(merge("bg-blue-500 bg-cover"), "bg-blue-500 bg-cover");
+        assert_eq!(
+            merge("border-2 border-red-500 border"),
+            "border-red-500 border"
+        );
+        assert_eq!(merge("shadow-lg shadow-black"), "shadow-lg shadow-black");
+        assert_eq!(
+            merge("shadow-black shadow-blue-500/20"),
+            "shadow-blue-500/20"
+        );
+    }
+
+    #[test]
+    fn test_variants_and_important_are_separate_groups() {
+        assert_eq!(merge("p-2 hover:p-4"), "p-2 hover:p-4");
+        assert_eq!(merge("hover:p-2 hover:p-4"), "hover:p-4");
+        assert_eq!(
+            merge("dark:hover:bg-red-500 hover:dark:bg-blue-500"),
+            "hover:dark:bg-blue-500"
+        );
+        assert_eq!(merge("!p-2 p-4"), "!p-2 p-4");
+        assert_eq!(merge("!p-2 p-4!"), "p-4!");
+    }
+
+    #[test]
+    fn test_unknown_and_arbitrary_classes_are_kept() {
+        assert_eq!(merge("card card"), "card card");
+        assert_eq!(merge("my-card my-4 my-auto"), "my-card my-auto");
+        assert_eq!(merge("card p-2 btn p-4"), "card btn p-4");
+        assert_eq!(
+            merge("[mask-type:luminance] [mask-type:alpha]"),
+            "[mask-type:luminance] [mask-type:alpha]"
+        );
+        assert_eq!(merge("text-[14px] text-[#fff]"), "text-[14px] text-[#fff]");
+    }
+
+    #[test]
+    fn test_split_variants() {
+        assert_eq!(split_variants("p-4"), (vec![], "p-4"));
+        assert_eq!(split_variants("md:hover:p-4"), (vec!["md", "hover"], "p-4"));
+        assert_eq!(
+            split_variants("hover:bg-[url(https://a.b/c.png)]"),
+            (vec!["hover"], "bg-[url(https://a.b/c.png)]")
+        );
+    }
+}
@@ -1,24 +1,297 @@
+use crate::config::Config;
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use std::borrow::Cow;
 use std::collections::{HashMap, HashSet};
+use std::sync::RwLock;
 
 pub use categories::*;
 
 mod categories;
+mod conflicts;
+mod prettier;
 
+/// Upper bound on cached class categories, so arbitrary values can't grow the cache forever
+const CATEGORY_CACHE_LIMIT: usize = 100_000;
+
+/// Category of each class seen so far, shared by every sorter and thread
+///
+/// Categories only depend on the static class data, so a lookup done while
+/// sorting one file is valid for all others.
+static CATEGORY_CACHE: Lazy<RwLock<HashMap<String, &'static str>>> =
+    Lazy::new(|| RwLock::new(HashMap::new()));
+
+/// How the whitespace of a class string is rewritten when it is sorted
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum WhitespaceMode {
+    /// Trim the string and separate classes with single spaces
+    #[default]
+    Collapse,
+    /// Keep leading and trailing whitespace, separating classes with single spaces
+    PreserveEdges,
+    /// Keep all whitespace where it was, moving only the classes
+    None,
+}
+
+impl WhitespaceMode {
+    /// Names accepted by `normalizeWhitespace`
+    pub const NAMES: &'static [&'static str] = &["collapse", "preserve-edges", "none"];
+}
+
+impl std::str::FromStr for WhitespaceMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "collapse" => Ok(WhitespaceMode::Collapse),
+            "preserve-edges" => Ok(WhitespaceMode::PreserveEdges),
+            "none" => Ok(WhitespaceMode::None),
+            _ => Err(format!(
+                "Invalid whitespace mode '{}'. Valid options: {}",
+                s,
+                Self::NAMES.join(", ")
+            )),
+        }
+    }
+}
+
+/// How classes of the same utility category are ordered against each other
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum IntraCategorySort {
+    /// Compare classes as plain strings, so `p-10` comes before `p-2`
+    #[default]
+    Lexical,
+    /// Compare trailing scale values (`2`, `0.5`, `1/2`) as numbers, so `p-2`
+    /// comes before `p-10`; other classes are compared as strings
+    Numeric,
+}
+
+impl IntraCategorySort {
+    /// Names accepted by `intraCategorySort`
+    pub const NAMES: &'static [&'static str] = &["lexical", "numeric"];
+}
+
+impl std::str::FromStr for IntraCategorySort {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "lexical" => Ok(IntraCategorySort::Lexical),
+            "numeric" => Ok(IntraCategorySort::Numeric),
+            _ => Err(format!(
+                "Invalid intra-category sort '{}'. Valid options: {}",
+                s,
+                Self::NAMES.join(", ")
+            )),
+        }
+    }
+}
+
+/// Prefixes that `groupPrefixes` keeps together within a category, in order
+pub const DEFAULT_CATEGORY_PREFIX_ORDER: &[(&str, &[&str])] = &[
+    (
+        "spacing",
+        &[
+            "m", "mx", "my", "mt", "mr", "mb", "ml", "p", "px", "py", "pt", "pr", "pb", "pl",
+            "space-x", "space-y",
+        ],
+    ),
+    (
+        "sizing",
+        &["size", "w", "min-w", "max-w", "h", "min-h", "max-h"],
+    ),
+];
+
+/// Category of the classes listed in `knownCustomClasses`
+pub const CUSTOM_CATEGORY: &str = "custom";
+
+/// Where the `custom` category of known custom classes goes in the category
+/// order
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CustomClassesPosition {
+    /// Before every Tailwind category
+    #[default]
+    First,
+    /// After every Tailwind category, before unknown classes
+    Last,
+}
+
+impl CustomClassesPosition {
+    /// Names accepted by `customClassesPosition`
+    pub const NAMES: &'static [&'static str] = &["first", "last"];
+}
+
+impl std::str::FromStr for CustomClassesPosition {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "first" => Ok(CustomClassesPosition::First),
+            "last" => Ok(CustomClassesPosition::Last),
+            _ => Err(format!(
+                "Invalid custom classes position '{}'. Valid options: {}",
+                s,
+                Self::NAMES.join(", ")
+            )),
+        }
+    }
+}
+
+/// How classes that match no utility and no known custom class are reported
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum UnknownClassSeverity {
+    /// Sort them last without reporting them
+    #[default]
+    Ignore,
+    /// Report them as warnings
+    Warn,
+    /// Report them as errors, failing the run
+    Error,
+}
+
+impl UnknownClassSeverity {
+    /// Names accepted by `unknownClasses`
+    pub const NAMES: &'static [&'static str] = &["ignore", "warn", "error"];
+}
+
+impl std::str::FromStr for UnknownClassSeverity {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "ignore" => Ok(UnknownClassSeverity::Ignore),
+            "warn" => Ok(UnknownClassSeverity::Warn),
+            "error" => Ok(UnknownClassSeverity::Error),
+            _ => Err(format!(
+                "Invalid unknown class severity '{}'. Valid options: {}",
+                s,
+                Self::NAMES.join(", ")
+            )),
+        }
+    }
+}
+
+/// Class names, and prefixes written with a trailing `*`, that sort
+/// together in the `custom` category
+#[derive(Debug, Clone, Default)]
+pub(crate) struct KnownCustomClasses {
+    names: HashSet<String>,
+    prefixes: Vec<String>,
+}
+
+impl KnownCustomClasses {
+    pub(crate) fn new(patterns: &[String]) -> Self {
+        let mut known = Self::default();
+        for pattern in patterns {
+            match pattern.strip_suffix('*') {
+                Some(prefix) => known.prefixes.push(prefix.to_string()),
+                None => {
+                    known.names.insert(pattern.clone());
+                }
+            }
+        }
+        known
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.names.is_empty() && self.prefixes.is_empty()
+    }
+
+    /// Whether `class` is a known custom class, with any variants and `!`
+    pub(crate) fn matches(&self, class: &str) -> bool {
+        if self.is_empty() {
+            return false;
+        }
+        let (_, base_class) = conflicts::split_variants(class);
+        let base_class = base_class.strip_prefix('!').unwrap_or(base_class);
+        self.names.contains(base_class)
+            || self
+                .prefixes
+                .iter()
+                .any(|prefix| base_class.starts_with(prefix.as_str()))
+    }
+}
+
+/// Why a class sorts where it does
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClassExplanation {
+    pub class: String,
+    /// Category the class was resolved to, such as "spacing"
+    pub category: &'static str,
+    /// Position of the category in the category order (0-based), or `None`
+    /// for categories left out of a custom order, which sort last
+    pub category_index: Option<usize>,
+    /// Variants such as `hover` or `md`, outermost first
+    pub variants: Vec<String>,
+}
+
+#[derive(Clone)]
 pub struct TailwindSorter {
     category_order: Vec<String>,
     class_categories: &'static HashMap<&'static str, &'static str>,
-    // Cache for category lookups to avoid repeated iteration
-    category_cache: std::cell::RefCell<HashMap<String, String>>,
     // Pre-computed category order map for O(1) lookups
     category_order_map: HashMap<String, usize>,
+    // Position of each variant (or variant group) in the configured variant order
+    variant_order_map: HashMap<String, usize>,
+    whitespace: WhitespaceMode,
+    separator: String,
+    dedupe: bool,
+    merge: bool,
+    min_classes: usize,
+    prettier_compat: bool,
+    intra_category_sort: IntraCategorySort,
+    // Prefix order within categories, keyed by category
+    prefix_orders: HashMap<String, Vec<String>>,
+    known_custom_classes: KnownCustomClasses,
 }
 
+/// Class strings with fewer classes than this are left as written by default
+pub const DEFAULT_MIN_CLASSES_TO_SORT: usize = 2;
+
+/// Variant groups that can be named in a variant order instead of listing
+/// each variant, in their own internal order
+pub const VARIANT_GROUPS: &[(&str, &[&str])] = &[("responsive", &["sm", "md", "lg", "xl", "2xl"])];
+
 impl TailwindSorter {
     pub fn new() -> Self {
         Self::new_with_custom_order(None)
     }
 
     pub fn new_with_custom_order(custom_order: Option<Vec<String>>) -> Self {
+        Self::new_with_orders(custom_order, Vec::new())
+    }
+
+    /// Create a sorter with the ordering and cleanup settings of a configuration
+    ///
+    /// `customOrder` is only used when `sortOrder` is `"custom"`.
+    pub fn from_config(config: &Config) -> Self {
+        let custom_order = if config.sort_order == "custom" && !config.custom_order.is_empty() {
+            Some(config.custom_order.clone())
+        } else {
+            None
+        };
+        Self::new_with_orders(custom_order, config.variant_order.clone())
+            .with_whitespace(config.normalize_whitespace.parse().unwrap_or_default())
+            .with_separator(&config.whitespace_separator)
+            .with_dedupe(!config.preserve_duplicates)
+            .with_merge(config.merge_conflicts)
+            .with_min_classes(config.min_classes_to_sort)
+            .with_prettier_compat(config.prettier_compat)
+            .with_intra_category_sort(config.intra_category_sort.parse().unwrap_or_default())
+            .with_category_prefix_order(prefix_orders(config))
+            .with_known_custom_classes(
+                &config.known_custom_classes,
+                config.custom_classes_position.parse().unwrap_or_default(),
+            )
+    }
+
+    /// Create a sorter with a custom category order and variant order
+    ///
+    /// The variant order lists variants (`hover`, `dark`) or variant groups
+    /// (`responsive`) by precedence. When it is empty, variants are compared
+    /// by count and then lexically.
+    pub fn new_with_orders(custom_order: Option<Vec<String>>, variant_order: Vec<String>) -> Self {
         let category_order = match custom_order {
             Some(order) => order,
             None => CATEGORY_ORDER.iter().map(|&s| s.to_string()).collect(),
@@ -31,22 +304,212 @@ impl TailwindSorter {
             .map(|(i, category)| (category.clone(), i))
             .collect();
 
+        let variant_order_map: HashMap<String, usize> = variant_order
+            .into_iter()
+            .enumerate()
+            .map(|(i, variant)| (variant, i))
+            .collect();
+
         Self {
             category_order,
             class_categories: &CLASS_CATEGORIES,
-            category_cache: std::cell::RefCell::new(HashMap::new()),
             category_order_map,
+            variant_order_map,
+            whitespace: WhitespaceMode::default(),
+            separator: " ".to_string(),
+            dedupe: true,
+            merge: false,
+            min_classes: DEFAULT_MIN_CLASSES_TO_SORT,
+            prettier_compat: false,
+            intra_category_sort: IntraCategorySort::default(),
+            prefix_orders: HashMap::new(),
+            known_custom_classes: KnownCustomClasses::default(),
+        }
+    }
+
+    /// Set how whitespace around and between classes is rewritten
+    pub fn with_whitespace(mut self, whitespace: WhitespaceMode) -> Self {
+        self.whitespace = whitespace;
+        self
+    }
+
+    /// Set the separator placed between sorted classes, unless whitespace is
+    /// kept where it was
+    pub fn with_separator(mut self, separator: &str) -> Self {
+        self.separator = separator.to_string();
+        self
+    }
+
+    /// Set whether repeated classes are removed, keeping the first occurrence
+    pub fn with_dedupe(mut self, dedupe: bool) -> Self {
+        self.dedupe = dedupe;
+        self
+    }
+
+    /// Set whether classes overridden by a later class of the same conflict
+    /// group are dropped, as tailwind-merge does at runtime
+    pub fn with_merge(mut self, merge: bool) -> Self {
+        self.merge = merge;
+        self
+    }
+
+    /// Set the fewest classes a string needs to be sorted; shorter strings,
+    /// including their whitespace, are returned unchanged
+    pub fn with_min_classes(mut self, min_classes: usize) -> Self {
+        self.min_classes = min_classes;
+        self
+    }
+
+    /// Set whether classes are ordered like prettier-plugin-tailwindcss,
+    /// replacing the category and variant orders
+    pub fn with_prettier_compat(mut self, prettier_compat: bool) -> Self {
+        self.prettier_compat = prettier_compat;
+        self
+    }
+
+    /// Set how classes of the same category are ordered against each other
+    pub fn with_intra_category_sort(mut self, intra_category_sort: IntraCategorySort) -> Self {
+        self.intra_category_sort = intra_category_sort;
+        self
+    }
+
+    /// Order the classes of each listed category by the position of their
+    /// prefix, before comparing them as the intra-category sort does
+    ///
+    /// A prefix like `px` matches `px` and `px-*`, and the longest matching
+    /// prefix counts. Classes matching none of a category's prefixes come
+    /// after the rest.
+    pub fn with_category_prefix_order(mut self, orders: HashMap<String, Vec<String>>) -> Self {
+        self.prefix_orders = orders;
+        self
+    }
+
+    /// Sort these classes together in a `custom` category at `position`
+    ///
+    /// Entries are class names, or prefixes when they end with `*`, as in
+    /// `u-*`; they match classes with any variants.
+    pub fn with_known_custom_classes(
+        mut self,
+        patterns: &[String],
+        position: CustomClassesPosition,
+    ) -> Self {
+        self.known_custom_classes = KnownCustomClasses::new(patterns);
+        if self.known_custom_classes.is_empty() {
+            return self;
         }
+
+        let index = match position {
+            CustomClassesPosition::First => 0,
+            CustomClassesPosition::Last => self
+                .category_order
+                .iter()
+                .position(|category| category == "unknown")
+                .unwrap_or(self.category_order.len()),
+        };
+        self.category_order
+            .insert(index, CUSTOM_CATEGORY.to_string());
+        self.category_order_map = self
+            .category_order
+            .iter()
+            .enumerate()
+            .map(|(i, category)| (category.clone(), i))
+            .collect();
+        self
     }
 
     pub fn sort_classes(&self, class_string: &str) -> String {
+        self.sort_classes_merging(class_string, self.merge)
+    }
+
+    /// Sort classes without resolving conflicts, for strings that are merged
+    /// at runtime anyway
+    pub fn sort_classes_unmerged(&self, class_string: &str) -> String {
+        self.sort_classes_merging(class_string, false)
+    }
+
+    /// Explain the category of each class in a class string, in sorted order
+    ///
+    /// With `prettierCompat`, classes are ordered by CSS property instead,
+    /// so the categories don't decide their order.
+    pub fn explain(&self, class_string: &str) -> Vec<ClassExplanation> {
+        self.sort_classes(class_string)
+            .split_whitespace()
+            .map(|class| {
+                let category = self.get_class_category(class);
+                let (variants, _) = conflicts::split_variants(class);
+                ClassExplanation {
+                    class: class.to_string(),
+                    category,
+                    category_index: self.category_order_map.get(category).copied(),
+                    variants: variants.into_iter().map(String::from).collect(),
+                }
+            })
+            .collect()
+    }
+
+    /// Classes in a class string that match no utility and no known custom
+    /// class, in the order written
+    ///
+    /// Sorting files a class under its longest matching prefix, so `flexx`
+    /// sorts with `flex`. Here a prefix that doesn't end in `-` has to match
+    /// a whole word, so typos like `flexx` and `p4` count as unknown.
+    pub fn unknown_classes<'c>(&self, class_string: &'c str) -> Vec<&'c str> {
+        class_string
+            .split_whitespace()
+            .filter(|class| !self.is_known_class(class))
+            .collect()
+    }
+
+    fn is_known_class(&self, class: &str) -> bool {
+        if self.known_custom_classes.matches(class) {
+            return true;
+        }
+
+        let (_, base_class) = conflicts::split_variants(class);
+        let base_class = base_class.strip_prefix('!').unwrap_or(base_class);
+        if is_marker(base_class)
+            || base_class.starts_with('[') && base_class.ends_with(']') && base_class.contains(':')
+        {
+            return true;
+        }
+
+        let base_class = base_class.strip_prefix('-').unwrap_or(base_class);
+        self.class_categories
+            .keys()
+            .any(|prefix| match base_class.strip_prefix(prefix) {
+                Some(rest) if prefix.ends_with('-') => !rest.is_empty(),
+                Some(rest) => rest.is_empty() || rest.starts_with(['-', '/']),
+                None => false,
+            })
+    }
+
+    fn sort_classes_merging(&self, class_string: &str, merge: bool) -> String {
         let trimmed = class_string.trim();
-        if trimmed.is_empty() {
+        if trimmed.is_empty()
+            || trimmed
+                .split_whitespace()
+                .nth(self.min_classes.saturating_sub(1))
+                .is_none()
+        {
             return class_string.to_string();
         }
 
+        let sorted = self.sort_trimmed(trimmed, merge);
+        match self.whitespace {
+            WhitespaceMode::Collapse => sorted,
+            WhitespaceMode::PreserveEdges => {
+                let leading = &class_string[..class_string.len() - class_string.trim_start().len()];
+                let trailing = &class_string[class_string.trim_end().len()..];
+                format!("{}{}{}", leading, sorted, trailing)
+            }
+            WhitespaceMode::None => restore_whitespace(class_string, &sorted),
+        }
+    }
+
+    /// Sort a trimmed, non-empty class string, joining classes with the separator
+    fn sort_trimmed(&self, trimmed: &str, merge: bool) -> String {
         // Optimize for single class - common case
-        if !trimmed.contains(' ') {
+        if !trimmed.contains(char::is_whitespace) {
             return trimmed.to_string();
         }
 
@@ -55,20 +518,29 @@ impl TailwindSorter {
         classes.extend(trimmed.split_whitespace());
 
         // Remove duplicates while preserving first occurrence - optimized version
-        if classes.len() > 1 {
+        if self.dedupe && classes.len() > 1 {
             let mut seen = HashSet::with_capacity(classes.len());
             classes.retain(|&class| seen.insert(class));
         }
 
+        if merge && classes.len() > 1 {
+            classes = conflicts::merge_conflicts(&classes);
+        }
+
         // Early return if only one class after deduplication
         if classes.len() == 1 {
             return classes[0].to_string();
         }
 
-        // Sort classes by category and within category
-        classes.sort_unstable_by(|&a, &b| self.compare_classes(a, b));
+        // Sort classes by category and within category. Prettier ordering
+        // keeps unknown classes as written, so it needs a stable sort
+        if self.prettier_compat {
+            classes.sort_by(|&a, &b| prettier::compare(a, b));
+        } else {
+            classes.sort_unstable_by(|&a, &b| self.compare_classes(a, b));
+        }
 
-        classes.join(" ")
+        classes.join(&self.separator)
     }
 
     fn compare_classes(&self, a: &str, b: &str) -> std::cmp::Ordering {
@@ -80,13 +552,28 @@ impl TailwindSorter {
         let category_b = self.get_class_category(&base_b);
 
         // First, compare by category order
-        let order_a = self.get_category_order(&category_a);
-        let order_b = self.get_category_order(&category_b);
+        let order_a = self.get_category_order(category_a);
+        let order_b = self.get_category_order(category_b);
 
         match order_a.cmp(&order_b) {
             std::cmp::Ordering::Equal => {
                 // Within the same category, compare base classes first
-                match base_a.cmp(&base_b) {
+                let prefixes = match self.prefix_orders.get(category_a) {
+                    Some(prefixes) if category_a == category_b => {
+                        prefix_position(prefixes, &base_a).cmp(&prefix_position(prefixes, &base_b))
+                    }
+                    _ => std::cmp::Ordering::Equal,
+                };
+                let bases = prefixes.then_with(|| match self.intra_category_sort {
+                    IntraCategorySort::Lexical => base_a.cmp(&base_b),
+                    IntraCategorySort::Numeric => numeric_sort_key(&base_a)
+                        .cmp(&numeric_sort_key(&base_b))
+                        .then_with(|| base_a.cmp(&base_b)),
+                });
+                match bases {
+                    std::cmp::Ordering::Equal if !self.variant_order_map.is_empty() => {
+                        self.compare_variants(&variants_a, &variants_b)
+                    }
                     std::cmp::Ordering::Equal => {
                         // If base classes are equal, compare variants
                         // Sort by number of variants first (fewer variants first)
@@ -105,42 +592,92 @@ impl TailwindSorter {
         }
     }
 
-    fn split_variants<'a>(&self, class: &'a str) -> (String, Vec<&'a str>) {
-        if let Some(last_colon) = class.rfind(':') {
-            let variants: Vec<&str> = class[..last_colon].split(':').collect();
-            let base = class[last_colon + 1..].to_string();
-            (base, variants)
-        } else {
-            (class.to_string(), Vec::new())
+    /// Compare variant lists using the configured variant order
+    ///
+    /// Stacked variants are compared by their highest-precedence variant
+    /// first, regardless of the order they are written in; a class with no
+    /// variants sorts before any of its variants.
+    fn compare_variants(&self, variants_a: &[&str], variants_b: &[&str]) -> std::cmp::Ordering {
+        let mut keys_a: Vec<_> = variants_a.iter().map(|v| self.variant_key(v)).collect();
+        let mut keys_b: Vec<_> = variants_b.iter().map(|v| self.variant_key(v)).collect();
+        keys_a.sort_unstable();
+        keys_b.sort_unstable();
+
+        keys_a.cmp(&keys_b).then_with(|| variants_a.cmp(variants_b))
+    }
+
+    /// Sort key of a single variant: its position in the variant order (or
+    /// its group's position and its place within the group); unknown
+    /// variants sort after all known ones, alphabetically
+    fn variant_key<'a>(&self, variant: &'a str) -> (usize, usize, &'a str) {
+        if let Some(&index) = self.variant_order_map.get(variant) {
+            return (index, 0, variant);
+        }
+
+        for (group, members) in VARIANT_GROUPS {
+            if let Some(&index) = self.variant_order_map.get(*group)
+                && let Some(position) = members.iter().position(|m| *m == variant)
+            {
+                return (index, position, variant);
+            }
         }
+
+        (usize::MAX, 0, variant)
     }
 
-    fn get_class_category(&self, class: &str) -> String {
-        // Check cache first
-        if let Some(cached) = self.category_cache.borrow().get(class) {
-            return cached.clone();
+    /// Split a class into its base utility and variants, leaving colons
+    /// inside arbitrary variants (`[&:hover]:`) and arbitrary properties
+    /// (`[mask-type:luminance]`) in place
+    fn split_variants<'a>(&self, class: &'a str) -> (String, Vec<&'a str>) {
+        let (variants, base) = conflicts::split_variants(class);
+        (base.to_string(), variants)
+    }
+
+    fn get_class_category(&self, class: &str) -> &'static str {
+        // Known custom classes depend on this sorter's configuration, so they
+        // are matched before the shared cache
+        if self.known_custom_classes.matches(class) {
+            return CUSTOM_CATEGORY;
         }
 
-        // Handle variants (e.g., "hover:bg-blue-500" -> "bg-blue-500")
-        let base_class = if let Some(colon_pos) = class.rfind(':') {
-            &class[colon_pos + 1..]
-        } else {
-            class
-        };
+        // Check the shared cache first
+        if let Some(&cached) = CATEGORY_CACHE
+            .read()
+            .ok()
+            .as_ref()
+            .and_then(|cache| cache.get(class))
+        {
+            return cached;
+        }
+
+        // Handle variants (e.g., "hover:bg-blue-500" or "[&:hover]:bg-blue-500" -> "bg-blue-500")
+        let (_, base_class) = conflicts::split_variants(class);
 
         // Handle important modifier (e.g., "!p-4" -> "p-4")
         let base_class = base_class.strip_prefix('!').unwrap_or(base_class);
 
+        // `group` and `peer` only mark elements for `group-*:` and `peer-*:` variants
+        if is_marker(base_class) {
+            return "markers";
+        }
+
+        // Arbitrary properties (e.g., "[mask-type:luminance]") have a category of their own
+        if base_class.starts_with('[') && base_class.ends_with(']') && base_class.contains(':') {
+            return "arbitrary";
+        }
+
         // Handle negative values (e.g., "-m-4" -> "m-4")
         let base_class = base_class.strip_prefix('-').unwrap_or(base_class);
 
         // Find the longest matching prefix - optimized with early returns for common cases
-        let category = self.find_category_optimized(base_class).to_string();
+        let category = self.find_category_optimized(base_class);
 
         // Cache the result
-        self.category_cache
-            .borrow_mut()
-            .insert(class.to_string(), category.clone());
+        if let Ok(mut cache) = CATEGORY_CACHE.write()
+            && cache.len() < CATEGORY_CACHE_LIMIT
+        {
+            cache.insert(class.to_string(), category);
+        }
 
         category
     }
@@ -208,6 +745,119 @@ impl TailwindSorter {
     }
 }
 
+/// The prefix orders configured by `groupPrefixes` and `categoryPrefixOrder`
+fn prefix_orders(config: &Config) -> HashMap<String, Vec<String>> {
+    let mut orders: HashMap<String, Vec<String>> = HashMap::new();
+    if config.group_prefixes {
+        for (category, prefixes) in DEFAULT_CATEGORY_PREFIX_ORDER {
+            orders.insert(
+                category.to_string(),
+                prefixes.iter().map(|prefix| prefix.to_string()).collect(),
+            );
+        }
+    }
+    orders.extend(config.category_prefix_order.clone());
+    orders
+}
+
+/// Position of the longest prefix in `prefixes` that a base class starts
+/// with, or `prefixes.len()` if none does
+fn prefix_position(prefixes: &[String], class: &str) -> usize {
+    let class = class.strip_prefix('!').unwrap_or(class);
+    let class = class.strip_prefix('-').unwrap_or(class);
+    prefixes
+        .iter()
+        .enumerate()
+        .filter(|(_, prefix)| {
+            class
+                .strip_prefix(prefix.as_str())
+                .is_some_and(|rest| rest.is_empty() || rest.starts_with('-'))
+        })
+        .max_by_key(|(_, prefix)| prefix.len())
+        .map_or(prefixes.len(), |(position, _)| position)
+}
+
+/// Whether a class is a `group` or `peer` marker, optionally named as in
+/// `group/sidebar`
+fn is_marker(class: &str) -> bool {
+    ["group", "peer"].iter().any(|marker| {
+        class
+            .strip_prefix(marker)
+            .is_some_and(|rest| rest.is_empty() || rest.len() > 1 && rest.starts_with('/'))
+    })
+}
+
+/// A class with its trailing scale value replaced by a zero-padded count of
+/// thousandths, so that comparing keys as strings orders values of the same
+/// utility numerically (`p-0.5`, `p-2`, `p-10`)
+///
+/// Classes without a numeric scale, such as `p-px` or `p-[3px]`, are their
+/// own key. Fractions only count when they are below one, so the opacity of
+/// a color (`bg-red-500/50`) is not read as a scale.
+fn numeric_sort_key(class: &str) -> Cow<'_, str> {
+    match class
+        .rsplit_once('-')
+        .and_then(|(prefix, scale)| Some((prefix, parse_scale(scale)?)))
+    {
+        Some((prefix, thousandths)) => Cow::Owned(format!("{}-{:015}", prefix, thousandths)),
+        None => Cow::Borrowed(class),
+    }
+}
+
+/// Parse a scale value (`4`, `2.5`, `1/3`) into thousandths
+fn parse_scale(scale: &str) -> Option<u64> {
+    if !scale.starts_with(|c: char| c.is_ascii_digit())
+        || !scale
+            .bytes()
+            .all(|b| b.is_ascii_digit() || b == b'.' || b == b'/')
+    {
+        return None;
+    }
+
+    let value = match scale.split_once('/') {
+        Some((numerator, denominator)) => {
+            let numerator: u64 = numerator.parse().ok()?;
+            let denominator: u64 = denominator.parse().ok()?;
+            if numerator >= denominator {
+                return None;
+            }
+            numerator as f64 / denominator as f64
+        }
+        None => scale.parse::<f64>().ok()?,
+    };
+
+    let thousandths = (value * 1000.0).round();
+    (thousandths < 1e15).then_some(thousandths as u64)
+}
+
+/// Lay the classes of `sorted` out in the whitespace of `original`
+///
+/// The first class takes the place of the first original class, and so on.
+/// When duplicates were removed, the separators left over at the end are
+/// dropped and the original trailing whitespace is kept.
+fn restore_whitespace(original: &str, sorted: &str) -> String {
+    let separators: Vec<&str> = original
+        .split(|c: char| !c.is_whitespace())
+        .filter(|run| !run.is_empty())
+        .collect();
+    let leading = &original[..original.len() - original.trim_start().len()];
+    let trailing = &original[original.trim_end().len()..];
+    // Only whitespace between classes counts as a separator
+    let interior = &separators
+        [usize::from(!leading.is_empty())..separators.len() - usize::from(!trailing.is_empty())];
+
+    let mut result = String::with_capacity(original.len());
+    result.push_str(leading);
+    for (i, class) in sorted.split_whitespace().enumerate() {
+        if i > 0 {
+            result.push_str(interior.get(i - 1).copied().unwrap_or(" "));
+        }
+        result.push_str(class);
+    }
+    result.push_str(trailing);
+    result
+}
+
 impl Default for TailwindSorter {
     fn default() -> Self {
         Self::new()
@@ -245,6 +895,67 @@ mod tests {
         assert_eq!(result, expected);
     }
 
+    #[test]
+    fn test_dedupe_disabled() {
+        let sorter = TailwindSorter::new().with_dedupe(false);
+        assert_eq!(sorter.sort_classes("p-4 flex p-4"), "flex p-4 p-4");
+        assert_eq!(sorter.sort_classes("flex flex"), "flex flex");
+
+        // Unknown classes are kept, duplicates included, after the known ones
+        assert_eq!(
+            sorter.sort_classes("card p-4 flex card p-4"),
+            "flex p-4 p-4 card card"
+        );
+
+        let sorter = TailwindSorter::new();
+        assert_eq!(
+            sorter.sort_classes("card p-4 flex card p-4"),
+            "flex p-4 card"
+        );
+    }
+
+    #[test]
+    fn test_whitespace_collapse() {
+        let sorter = TailwindSorter::new();
+        assert_eq!(sorter.sort_classes("  p-4   flex  "), "flex p-4");
+        assert_eq!(sorter.sort_classes("\n  p-4\n  flex\n"), "flex p-4");
+    }
+
+    #[test]
+    fn test_whitespace_preserve_edges() {
+        let sorter = TailwindSorter::new().with_whitespace(WhitespaceMode::PreserveEdges);
+        assert_eq!(sorter.sort_classes("  p-4   flex  "), "  flex p-4  ");
+        assert_eq!(
+            sorter.sort_classes("\n    p-4\n    flex\n    m-2\n  "),
+            "\n    flex m-2 p-4\n  "
+        );
+        assert_eq!(sorter.sort_classes(" flex "), " flex ");
+    }
+
+    #[test]
+    fn test_whitespace_none() {
+        let sorter = TailwindSorter::new().with_whitespace(WhitespaceMode::None);
+        assert_eq!(
+            sorter.sort_classes("\n    p-4\n    flex\n    m-2\n  "),
+            "\n    flex\n    m-2\n    p-4\n  "
+        );
+        assert_eq!(sorter.sort_classes("p-4  flex m-2"), "flex  m-2 p-4");
+
+        // Separators left over after removing duplicates are dropped
+        assert_eq!(
+            sorter.sort_classes("  p-4\n  flex\n  p-4\n"),
+            "  flex\n  p-4\n"
+        );
+    }
+
+    #[test]
+    fn test_whitespace_mode_from_str() {
+        assert_eq!("collapse".parse(), Ok(WhitespaceMode::Collapse));
+        assert_eq!("preserve-edges".parse(), Ok(WhitespaceMode::PreserveEdges));
+        assert_eq!("none".parse(), Ok(WhitespaceMode::None));
+        assert!("trim".parse::<WhitespaceMode>().is_err());
+    }
+
     #[test]
     fn test_single_class() {
         let sorter = TailwindSorter::new();
@@ -254,6 +965,185 @@ mod tests {
         assert_eq!(result, expected);
     }
 
+    #[test]
+    fn test_min_classes() {
+        // Single classes keep their whitespace by default
+        let sorter = TailwindSorter::new();
+        assert_eq!(sorter.sort_classes("  flex "), "  flex ");
+        assert_eq!(sorter.sort_classes(" p-4  flex "), "flex p-4");
+
+        let sorter = TailwindSorter::new().with_min_classes(3);
+        assert_eq!(sorter.sort_classes("p-4 flex"), "p-4 flex");
+        assert_eq!(sorter.sort_classes("p-4 p-4"), "p-4 p-4");
+        assert_eq!(sorter.sort_classes("p-4 m-2 flex"), "flex m-2 p-4");
+
+        let sorter = TailwindSorter::new().with_min_classes(0);
+        assert_eq!(sorter.sort_classes("  flex "), "flex");
+    }
+
+    #[test]
+    fn test_explain() {
+        let sorter = TailwindSorter::new();
+        // Classes are explained in the order they sort
+        let explained = sorter.explain("p-4 md:hover:flex custom-card");
+
+        assert_eq!(
+            explained,
+            [
+                ClassExplanation {
+                    class: "md:hover:flex".to_string(),
+                    category: "flexbox-grid",
+                    category_index: Some(2),
+                    variants: vec!["md".to_string(), "hover".to_string()],
+                },
+                ClassExplanation {
+                    class: "p-4".to_string(),
+                    category: "spacing",
+                    category_index: Some(3),
+                    variants: vec![],
+                },
+                ClassExplanation {
+                    class: "custom-card".to_string(),
+                    category: "unknown",
+                    category_index: Some(17),
+                    variants: vec![],
+                },
+            ]
+        );
+
+        // Categories left out of a custom order have no index
+        let sorter = TailwindSorter::new_with_custom_order(Some(vec!["spacing".to_string()]));
+        assert_eq!(sorter.explain("flex")[0].category_index, None);
+    }
+
+    #[test]
+    fn test_intra_category_sort() {
+        let input = "p-10 p-[3px] p-2 p-0.5 p-px";
+
+        let sorter = TailwindSorter::new();
+        assert_eq!(sorter.sort_classes(input), "p-0.5 p-10 p-2 p-[3px] p-px");
+
+        let sorter = TailwindSorter::new().with_intra_category_sort(IntraCategorySort::Numeric);
+        assert_eq!(sorter.sort_classes(input), "p-0.5 p-2 p-10 p-[3px] p-px");
+
+        // Fractions compare by value; variants still break ties
+        assert_eq!(
+            sorter.sort_classes("w-full w-1/2 w-10 w-1/3 md:w-2 w-2"),
+            "w-1/3 w-1/2 w-2 md:w-2 w-10 w-full"
+        );
+        // Negative values, and color shades with an opacity
+        assert_eq!(sorter.sort_classes("-mt-10 -mt-2"), "-mt-2 -mt-10");
+        assert_eq!(
+            sorter.sort_classes("bg-red-500/50 bg-red-50 bg-red-100"),
+            "bg-red-50 bg-red-100 bg-red-500/50"
+        );
+    }
+
+    #[test]
+    fn test_category_prefix_order() {
+        let input = "pt-1 py-2 p-4 px-2";
+        assert_eq!(
+            TailwindSorter::new().sort_classes(input),
+            "p-4 pt-1 px-2 py-2"
+        );
+
+        let sorter = TailwindSorter::from_config(&Config {
+            group_prefixes: true,
+            ..Default::default()
+        });
+        assert_eq!(sorter.sort_classes(input), "p-4 px-2 py-2 pt-1");
+        // Classes with the same prefix are still compared as before
+        assert_eq!(
+            sorter.sort_classes("pl-2 pb-4 md:p-2 p-8 p-2 pr-1 -mt-2 mx-auto m-4"),
+            "m-4 mx-auto -mt-2 p-2 md:p-2 p-8 pr-1 pb-4 pl-2"
+        );
+        assert_eq!(
+            sorter.sort_classes("h-4 max-w-md w-full min-w-0 size-8"),
+            "size-8 w-full min-w-0 max-w-md h-4"
+        );
+
+        // Configured categories replace the defaults, and prefixes that
+        // aren't listed come last
+        let sorter = TailwindSorter::from_config(&Config {
+            group_prefixes: true,
+            category_prefix_order: HashMap::from([(
+                "spacing".to_string(),
+                vec!["py".to_string(), "px".to_string()],
+            )]),
+            ..Default::default()
+        });
+        assert_eq!(sorter.sort_classes(input), "py-2 px-2 p-4 pt-1");
+        assert_eq!(sorter.sort_classes("h-4 w-full"), "w-full h-4");
+    }
+
+    #[test]
+    fn test_unknown_classes() {
+        let sorter = TailwindSorter::new();
+        assert_eq!(
+            sorter.unknown_classes("flexx flex-col p4 p-4 hover:-mt-2 !italic group/item widget"),
+            vec!["flexx", "p4", "widget"]
+        );
+        assert!(
+            sorter
+                .unknown_classes("md:border-2 border/50 [mask-type:luminance] peer p-[3px]")
+                .is_empty()
+        );
+        // A bare prefix isn't a class
+        assert_eq!(sorter.unknown_classes("p- flex"), vec!["p-"]);
+
+        // Known custom classes are allowed
+        let sorter = TailwindSorter::new()
+            .with_known_custom_classes(&["u-*".to_string()], CustomClassesPosition::First);
+        assert_eq!(sorter.unknown_classes("u-card md:u-pad ux"), vec!["ux"]);
+    }
+
+    #[test]
+    fn test_known_custom_classes() {
+        let known = vec![
+            "stack".to_string(),
+            "cluster".to_string(),
+            "u-*".to_string(),
+        ];
+        let sorter =
+            TailwindSorter::new().with_known_custom_classes(&known, CustomClassesPosition::First);
+
+        // Exact names and prefixes, with variants and `!`, sort together first
+        assert_eq!(
+            sorter.sort_classes("p-4 u-hidden md:stack flex widget cluster !u-pad"),
+            "!u-pad cluster md:stack u-hidden flex p-4 widget"
+        );
+        // The same classes in any order sort the same way
+        assert_eq!(
+            sorter.sort_classes("widget !u-pad cluster flex md:stack u-hidden p-4"),
+            "!u-pad cluster md:stack u-hidden flex p-4 widget"
+        );
+        // Only listed names match exactly; the prefix needs its `-`
+        assert_eq!(
+            sorter.sort_classes("stacked flex ux stack"),
+            "stack flex stacked ux"
+        );
+        assert_eq!(sorter.explain("stack")[0].category, CUSTOM_CATEGORY);
+        assert_eq!(sorter.explain("stack")[0].category_index, Some(0));
+
+        // After every Tailwind category, before unknown classes
+        let sorter =
+            TailwindSorter::new().with_known_custom_classes(&known, CustomClassesPosition::Last);
+        assert_eq!(
+            sorter.sort_classes("widget u-hidden p-4 stack flex"),
+            "flex p-4 stack u-hidden widget"
+        );
+
+        let sorter = TailwindSorter::from_config(&Config {
+            known_custom_classes: known.clone(),
+            custom_classes_position: "last".to_string(),
+            ..Default::default()
+        });
+        assert_eq!(sorter.sort_classes("u-hidden p-4"), "p-4 u-hidden");
+
+        // Other sorters sharing the category cache are unaffected
+        assert_eq!(TailwindSorter::new().get_class_category("stack"), "unknown");
+    }
+
     #[test]
     fn test_debug_button_classes() {
         let sorter = TailwindSorter::new();
@@ -296,6 +1186,71 @@ mod tests {
         assert!(flex_pos < hover_pos || p4_pos < hover_pos);
     }
 
+    #[test]
+    fn test_variant_order() {
+        let sorter = TailwindSorter::new_with_orders(
+            None,
+            vec![
+                "responsive".to_string(),
+                "dark".to_string(),
+                "hover".to_string(),
+                "focus".to_string(),
+            ],
+        );
+
+        let input = "focus:flex hover:flex lg:flex flex md:flex dark:flex";
+        assert_eq!(
+            sorter.sort_classes(input),
+            "flex md:flex lg:flex dark:flex hover:flex focus:flex"
+        );
+
+        // Unknown variants come after known ones, alphabetically
+        let input = "print:flex hover:flex aria-selected:flex";
+        assert_eq!(
+            sorter.sort_classes(input),
+            "hover:flex aria-selected:flex print:flex"
+        );
+    }
+
+    #[test]
+    fn test_variant_order_stacked_variants() {
+        let sorter = TailwindSorter::new_with_orders(
+            None,
+            vec![
+                "responsive".to_string(),
+                "dark".to_string(),
+                "hover".to_string(),
+            ],
+        );
+
+        // Base classes still decide first; variants only break ties
+        let input = "hover:bg-red-500 dark:md:hover:bg-red-500 p-4 bg-red-500 dark:bg-red-500 md:bg-red-500";
+        assert_eq!(
+            sorter.sort_classes(input),
+            "p-4 bg-red-500 md:bg-red-500 dark:md:hover:bg-red-500 dark:bg-red-500 hover:bg-red-500"
+        );
+    }
+
+    #[test]
+    fn test_sorter_is_shareable_across_threads() {
+        fn assert_sync<T: Send + Sync>() {}
+        assert_sync::<TailwindSorter>();
+
+        let sorter = TailwindSorter::new();
+        std::thread::scope(|scope| {
+            for _ in 0..4 {
+                scope.spawn(|| assert_eq!(sorter.sort_classes("p-4 flex m-2"), "flex m-2 p-4"));
+            }
+        });
+
+        // A fresh sorter sees categories resolved by earlier ones
+        assert!(CATEGORY_CACHE.read().unwrap().contains_key("p-4"));
+        assert_eq!(
+            TailwindSorter::new().sort_classes("m-2 p-4 flex"),
+            "flex m-2 p-4"
+        );
+    }
+
     #[test]
     fn test_important_modifier() {
         let sorter = TailwindSorter::new();
@@ -305,6 +1260,101 @@ mod tests {
         assert_eq!(result, expected);
     }
 
+    #[test]
+    fn test_arbitrary_variants() {
+        let sorter = TailwindSorter::new();
+
+        // The variant is stripped like a named one, so the utility decides the category
+        assert_eq!(
+            sorter.get_class_category("[&:hover]:bg-red-500"),
+            "backgrounds"
+        );
+        assert_eq!(
+            sorter.sort_classes("[&:hover]:bg-red-500 p-4 flex"),
+            "flex p-4 [&:hover]:bg-red-500"
+        );
+        assert_eq!(
+            sorter.sort_classes("hover:bg-red-500 [&:nth-child(3)]:p-2 bg-red-500 p-4"),
+            "[&:nth-child(3)]:p-2 p-4 bg-red-500 hover:bg-red-500"
+        );
+    }
+
+    #[test]
+    fn test_arbitrary_properties() {
+        let sorter = TailwindSorter::new();
+
+        assert_eq!(
+            sorter.get_class_category("[mask-type:luminance]"),
+            "arbitrary"
+        );
+        assert_eq!(
+            sorter.get_class_category("md:![mask-type:alpha]"),
+            "arbitrary"
+        );
+        // Arbitrary values are still categorized by their utility
+        assert_eq!(sorter.get_class_category("w-[10px]"), "sizing");
+
+        // After every known utility, before unknown classes
+        assert_eq!(
+            sorter.sort_classes("custom-card [mask-type:luminance] text-lg p-4 [--gap:1rem] flex"),
+            "flex p-4 text-lg [--gap:1rem] [mask-type:luminance] custom-card"
+        );
+        assert_eq!(
+            sorter
+                .sort_classes("hover:[mask-type:alpha] [&:hover]:bg-red-500 [mask-type:luminance]"),
+            "[&:hover]:bg-red-500 hover:[mask-type:alpha] [mask-type:luminance]"
+        );
+    }
+
+    #[test]
+    fn test_group_and_peer_markers() {
+        let sorter = TailwindSorter::new();
+        assert_eq!(sorter.sort_classes("p-4 group flex"), "group flex p-4");
+        assert_eq!(
+            sorter.sort_classes("relative peer/email group/sidebar block"),
+            "group/sidebar peer/email block relative"
+        );
+        // Only the bare and named markers are markers
+        assert_eq!(sorter.get_class_category("group"), "markers");
+        assert_eq!(sorter.get_class_category("peer/email"), "markers");
+        assert_eq!(sorter.get_class_category("group/"), "unknown");
+        assert_eq!(sorter.get_class_category("groupie"), "unknown");
+
+        // The marker category can be moved like any other
+        let sorter = TailwindSorter::new_with_custom_order(Some(vec![
+            "layout".to_string(),
+            "markers".to_string(),
+        ]));
+        assert_eq!(sorter.sort_classes("group block"), "block group");
+    }
+
+    #[test]
+    fn test_group_and_peer_variants() {
+        let sorter = TailwindSorter::new();
+        assert_eq!(
+            sorter.split_variants("group-hover:bg-blue-500"),
+            ("bg-blue-500".to_string(), vec!["group-hover"])
+        );
+        assert_eq!(
+            sorter.split_variants("peer-checked/email:hidden"),
+            ("hidden".to_string(), vec!["peer-checked/email"])
+        );
+        assert_eq!(
+            sorter.split_variants("group-[.is-open]:block"),
+            ("block".to_string(), vec!["group-[.is-open]"])
+        );
+        assert_eq!(
+            sorter.get_class_category("group-hover:bg-blue-500"),
+            "backgrounds"
+        );
+        assert_eq!(sorter.get_class_category("peer-checked:hidden"), "layout");
+
+        assert_eq!(
+            sorter.sort_classes("group-hover:text-white group p-4 text-black"),
+            "group p-4 text-black group-hover:text-white"
+        );
+    }
+
     #[test]
     fn test_negative_values() {
         let sorter = TailwindSorter::new();
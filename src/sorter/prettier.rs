@@ -0,0 +1,536 @@
+use super::conflicts::{is_color, split_variants};
+use std::cmp::Ordering;
+
+// Utilities in the order Tailwind generates their CSS, which is the order
+// prettier-plugin-tailwindcss sorts by. Entries ending in `-` match any
+// class starting with them; other entries match the class itself or the
+// class followed by `-`. `<color>` entries match color values only.
+static PROPERTY_ORDER: &[&str] = &[
+    "container",
+    "sr-only",
+    "not-sr-only",
+    "pointer-events-",
+    "visible",
+    "invisible",
+    "collapse",
+    "static",
+    "fixed",
+    "absolute",
+    "relative",
+    "sticky",
+    "inset",
+    "inset-x-",
+    "inset-y-",
+    "start-",
+    "end-",
+    "top-",
+    "right-",
+    "bottom-",
+    "left-",
+    "isolate",
+    "isolation-auto",
+    "z-",
+    "order-",
+    "col-",
+    "row-",
+    "float-",
+    "clear-",
+    "m-",
+    "mx-",
+    "my-",
+    "ms-",
+    "me-",
+    "mt-",
+    "mr-",
+    "mb-",
+    "ml-",
+    "box-",
+    "line-clamp-",
+    "block",
+    "inline-block",
+    "inline",
+    "flex",
+    "inline-flex",
+    "table",
+    "inline-table",
+    "table-caption",
+    "table-cell",
+    "table-column",
+    "table-column-group",
+    "table-footer-group",
+    "table-header-group",
+    "table-row-group",
+    "table-row",
+    "flow-root",
+    "grid",
+    "inline-grid",
+    "contents",
+    "list-item",
+    "hidden",
+    "aspect-",
+    "size-",
+    "h-",
+    "max-h-",
+    "min-h-",
+    "w-",
+    "min-w-",
+    "max-w-",
+    "flex-1",
+    "flex-auto",
+    "flex-initial",
+    "flex-none",
+    "shrink",
+    "grow",
+    "basis-",
+    "table-auto",
+    "table-fixed",
+    "caption-",
+    "border-collapse",
+    "border-separate",
+    "border-spacing-",
+    "origin-",
+    "translate-x-",
+    "translate-y-",
+    "rotate-",
+    "skew-x-",
+    "skew-y-",
+    "scale-",
+    "transform",
+    "animate-",
+    "cursor-",
+    "touch-",
+    "select-",
+    "resize",
+    "snap-",
+    "scroll-m-",
+    "scroll-mx-",
+    "scroll-my-",
+    "scroll-mt-",
+    "scroll-mr-",
+    "scroll-mb-",
+    "scroll-ml-",
+    "scroll-p-",
+    "scroll-px-",
+    "scroll-py-",
+    "scroll-pt-",
+    "scroll-pr-",
+    "scroll-pb-",
+    "scroll-pl-",
+    "list-",
+    "appearance-",
+    "columns-",
+    "break-before-",
+    "break-inside-",
+    "break-after-",
+    "auto-cols-",
+    "grid-flow-",
+    "auto-rows-",
+    "grid-cols-",
+    "grid-rows-",
+    "flex-row",
+    "flex-col",
+    "flex-wrap",
+    "flex-nowrap",
+    "place-content-",
+    "place-items-",
+    "content-",
+    "items-",
+    "justify-",
+    "justify-items-",
+    "gap-",
+    "gap-x-",
+    "gap-y-",
+    "space-x-",
+    "space-y-",
+    "divide-x",
+    "divide-y",
+    "divide-<color>",
+    "place-self-",
+    "self-",
+    "justify-self-",
+    "overflow-",
+    "overflow-x-",
+    "overflow-y-",
+    "overscroll-",
+    "scroll-auto",
+    "scroll-smooth",
+    "truncate",
+    "text-ellipsis",
+    "text-clip",
+    "hyphens-",
+    "whitespace-",
+    "text-wrap",
+    "text-nowrap",
+    "text-balance",
+    "text-pretty",
+    "break-normal",
+    "break-words",
+    "break-all",
+    "break-keep",
+    "rounded",
+    "border",
+    "border-x",
+    "border-y",
+    "border-s",
+    "border-e",
+    "border-t",
+    "border-r",
+    "border-b",
+    "border-l",
+    "border-solid",
+    "border-dashed",
+    "border-dotted",
+    "border-double",
+    "border-hidden",
+    "border-none",
+    "border-<color>",
+    "bg-<color>",
+    "bg-gradient-",
+    "bg-none",
+    "decoration-slice",
+    "decoration-clone",
+    "bg-auto",
+    "bg-cover",
+    "bg-contain",
+    "bg-fixed",
+    "bg-local",
+    "bg-scroll",
+    "bg-clip-",
+    "bg-bottom",
+    "bg-center",
+    "bg-left",
+    "bg-right",
+    "bg-top",
+    "bg-repeat",
+    "bg-no-repeat",
+    "bg-origin-",
+    "from-",
+    "via-",
+    "to-",
+    "fill-",
+    "stroke-",
+    "object-",
+    "p-",
+    "px-",
+    "py-",
+    "ps-",
+    "pe-",
+    "pt-",
+    "pr-",
+    "pb-",
+    "pl-",
+    "text-left",
+    "text-center",
+    "text-right",
+    "text-justify",
+    "text-start",
+    "text-end",
+    "indent-",
+    "align-",
+    "font-sans",
+    "font-serif",
+    "font-mono",
+    "text-xs",
+    "text-sm",
+    "text-base",
+    "text-lg",
+    "text-xl",
+    "text-2xl",
+    "text-3xl",
+    "text-4xl",
+    "text-5xl",
+    "text-6xl",
+    "text-7xl",
+    "text-8xl",
+    "text-9xl",
+    "font-",
+    "uppercase",
+    "lowercase",
+    "capitalize",
+    "normal-case",
+    "italic",
+    "not-italic",
+    "normal-nums",
+    "ordinal",
+    "slashed-zero",
+    "lining-nums",
+    "oldstyle-nums",
+    "proportional-nums",
+    "tabular-nums",
+    "diagonal-fractions",
+    "stacked-fractions",
+    "leading-",
+    "tracking-",
+    "text-<color>",
+    "underline",
+    "overline",
+    "line-through",
+    "no-underline",
+    "decoration-<color>",
+    "decoration-",
+    "underline-offset-",
+    "antialiased",
+    "subpixel-antialiased",
+    "placeholder-<color>",
+    "caret-",
+    "accent-",
+    "opacity-",
+    "bg-blend-",
+    "mix-blend-",
+    "shadow",
+    "shadow-<color>",
+    "outline",
+    "outline-<color>",
+    "ring",
+    "ring-<color>",
+    "ring-offset-",
+    "blur",
+    "brightness-",
+    "contrast-",
+    "drop-shadow",
+    "grayscale",
+    "hue-rotate-",
+    "invert",
+    "saturate-",
+    "sepia",
+    "filter",
+    "backdrop-",
+    "transition",
+    "delay-",
+    "duration-",
+    "ease-",
+    "will-change-",
+];
+
+// Prefixes that also take color values, matched against `<prefix><color>`
+static COLOR_PREFIXES: &[&str] = &[
+    "divide-",
+    "border-",
+    "bg-",
+    "text-",
+    "decoration-",
+    "placeholder-",
+    "shadow-",
+    "outline-",
+    "ring-",
+];
+
+// Variants in the order Tailwind registers them; a class sorts by the last
+// of its variants in this list
+static VARIANT_ORDER: &[&str] = &[
+    "*",
+    "first-letter",
+    "first-line",
+    "marker",
+    "selection",
+    "file",
+    "placeholder",
+    "backdrop",
+    "before",
+    "after",
+    "first",
+    "last",
+    "only",
+    "odd",
+    "even",
+    "first-of-type",
+    "last-of-type",
+    "only-of-type",
+    "visited",
+    "target",
+    "open",
+    "default",
+    "checked",
+    "indeterminate",
+    "placeholder-shown",
+    "autofill",
+    "optional",
+    "required",
+    "valid",
+    "invalid",
+    "in-range",
+    "out-of-range",
+    "read-only",
+    "empty",
+    "focus-within",
+    "hover",
+    "focus",
+    "focus-visible",
+    "active",
+    "enabled",
+    "disabled",
+    "group",
+    "peer",
+    "ltr",
+    "rtl",
+    "motion-safe",
+    "motion-reduce",
+    "dark",
+    "print",
+    "sm",
+    "md",
+    "lg",
+    "xl",
+    "2xl",
+    "portrait",
+    "landscape",
+    "contrast-more",
+    "contrast-less",
+    "forced-colors",
+];
+
+/// Compare two classes the way prettier-plugin-tailwindcss orders them
+///
+/// Classes Tailwind doesn't know come first, in the order they were written.
+/// Classes without variants come before classes with variants, and classes
+/// with variants are grouped by the variants they use. Within a group,
+/// classes follow Tailwind's property order.
+pub(super) fn compare(a: &str, b: &str) -> Ordering {
+    let (variants_a, base_a) = split_variants(a);
+    let (variants_b, base_b) = split_variants(b);
+
+    let (rank_a, rank_b) = match (property_rank(base_a), property_rank(base_b)) {
+        (None, None) => return Ordering::Equal,
+        (None, Some(_)) => return Ordering::Less,
+        (Some(_), None) => return Ordering::Greater,
+        (Some(rank_a), Some(rank_b)) => (rank_a, rank_b),
+    };
+
+    variant_keys(&variants_a)
+        .cmp(&variant_keys(&variants_b))
+        .then(rank_a.cmp(&rank_b))
+        .then_with(|| base_a.cmp(base_b))
+        .then_with(|| variants_a.cmp(&variants_b))
+}
+
+/// Position of a class without variants in the property order, or `None`
+/// for classes Tailwind doesn't generate
+///
+/// Arbitrary properties like `[mask-type:luminance]` sort after every
+/// other utility.
+fn property_rank(base: &str) -> Option<usize> {
+    let base = base.strip_prefix('!').unwrap_or(base);
+    let base = base.strip_suffix('!').unwrap_or(base);
+
+    if base.starts_with('[') && base.ends_with(']') && base.contains(':') {
+        return Some(PROPERTY_ORDER.len());
+    }
+
+    let base = base.strip_prefix('-').unwrap_or(base);
+
+    for prefix in COLOR_PREFIXES {
+        if let Some(value) = base.strip_prefix(prefix)
+            && is_color(value)
+            && let Some(rank) = PROPERTY_ORDER
+                .iter()
+                .position(|entry| entry.strip_suffix("<color>") == Some(prefix))
+        {
+            return Some(rank);
+        }
+    }
+
+    PROPERTY_ORDER
+        .iter()
+        .enumerate()
+        .filter(|(_, entry)| !entry.ends_with("<color>") && matches_entry(base, entry))
+        .max_by_key(|(_, entry)| entry.len())
+        .map(|(rank, _)| rank)
+}
+
+fn matches_entry(base: &str, entry: &str) -> bool {
+    if entry.ends_with('-') {
+        return base.len() > entry.len() && base.starts_with(entry);
+    }
+    base.strip_prefix(entry)
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with('-'))
+}
+
+/// Sort key of a set of variants: their positions in the variant order,
+/// latest first, so that classes are grouped by their last variant
+///
+/// `group-*` and `peer-*` variants sort by their group and then by the
+/// pseudo-class they wrap. Unknown variants sort after all known ones.
+fn variant_keys(variants: &[&str]) -> Vec<(usize, usize)> {
+    let mut keys: Vec<(usize, usize)> = variants.iter().map(|v| variant_key(v)).collect();
+    keys.sort_unstable_by(|a, b| b.cmp(a));
+    keys
+}
+
+fn variant_key(variant: &str) -> (usize, usize) {
+    let position = |name: &str| VARIANT_ORDER.iter().position(|v| *v == name);
+
+    if let Some(index) = position(variant) {
+        return (index, 0);
+    }
+
+    for group in ["group", "peer"] {
+        if let Some(state) = variant
+            .strip_prefix(group)
+            .and_then(|rest| rest.strip_prefix('-'))
+            && let Some(index) = position(group)
+        {
+            return (index, position(state).map_or(usize::MAX, |p| p + 1));
+        }
+    }
+
+    (usize::MAX, 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sort(classes: &str) -> String {
+        let mut classes: Vec<&str> = classes.split_whitespace().collect();
+        classes.sort_by(|a, b| compare(a, b));
+        classes.join(" ")
+    }
+
+    #[test]
+    fn test_property_order() {
+        assert_eq!(
+            sort("text-white px-4 py-2 bg-sky-700 mx-auto flex"),
+            "mx-auto flex bg-sky-700 px-4 py-2 text-white"
+        );
+        assert_eq!(
+            sort("font-bold text-sm text-center text-gray-500"),
+            "text-center text-sm font-bold text-gray-500"
+        );
+        assert_eq!(
+            sort("border-red-500 border-2 rounded-lg shadow"),
+            "rounded-lg border-2 border-red-500 shadow"
+        );
+    }
+
+    #[test]
+    fn test_unknown_classes_come_first_in_written_order() {
+        assert_eq!(
+            sort("p-4 card flex btn-primary"),
+            "card btn-primary flex p-4"
+        );
+    }
+
+    #[test]
+    fn test_variants_come_after_base_classes() {
+        assert_eq!(
+            sort("sm:px-8 hover:bg-sky-800 text-white sm:py-3 px-4"),
+            "px-4 text-white hover:bg-sky-800 sm:px-8 sm:py-3"
+        );
+        assert_eq!(
+            sort("md:hover:underline lg:flex md:block hover:underline"),
+            "hover:underline md:block md:hover:underline lg:flex"
+        );
+        assert_eq!(
+            sort("group-focus:flex group-hover:flex focus:flex"),
+            "focus:flex group-hover:flex group-focus:flex"
+        );
+    }
+
+    #[test]
+    fn test_important_negative_and_arbitrary_classes() {
+        assert_eq!(
+            sort("[mask-type:luminance] !p-4 -mt-2 flex"),
+            "-mt-2 flex !p-4 [mask-type:luminance]"
+        );
+    }
+}
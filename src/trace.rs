@@ -0,0 +1,304 @@
+use crate::{Result, WindWardenError};
+use serde::Serialize;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// A single decision recorded in the trace log
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum TraceEvent {
+    /// A file was accepted by discovery
+    Discovered { path: String },
+    /// A path was skipped during discovery
+    Skipped { path: String, reason: String },
+    /// A file was skipped because it is unchanged since a cached run
+    CacheHit { file: String },
+    /// A file was parsed successfully
+    Parsed { file: String, matches: usize },
+    /// A file failed to parse
+    ParseFailed { file: String, message: String },
+    /// A class string was found and sorted
+    Match {
+        file: String,
+        start: usize,
+        end: usize,
+        original: String,
+        sorted: String,
+        changed: bool,
+    },
+    /// A file was written to disk
+    Written { file: String, bytes: usize },
+    /// The pipeline finished processing a file
+    Processed {
+        file: String,
+        success: bool,
+        changes_made: bool,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        error: Option<String>,
+        duration_ms: u64,
+    },
+}
+
+impl TraceEvent {
+    /// A one-line summary for `--verbose`, or `None` for per-match detail
+    /// that is only useful in the JSON trace
+    pub fn describe(&self) -> Option<String> {
+        Some(match self {
+            TraceEvent::Discovered { path } => format!("{}: discovered", path),
+            TraceEvent::Skipped { path, reason } => format!("{}: skipped ({})", path, reason),
+            TraceEvent::CacheHit { file } => {
+                format!("{}: skipped (unchanged since the cached run)", file)
+            }
+            TraceEvent::Parsed { file, matches } => {
+                format!("{}: found {} class string(s)", file, matches)
+            }
+            TraceEvent::ParseFailed { file, message } => {
+                format!("{}: parse failed: {}", file, message)
+            }
+            TraceEvent::Match { .. } => return None,
+            TraceEvent::Written { file, bytes } => format!("{}: wrote {} bytes", file, bytes),
+            TraceEvent::Processed {
+                file,
+                success,
+                changes_made,
+                error,
+                ..
+            } => match (success, error) {
+                (false, Some(error)) => format!("{}: failed: {}", file, error),
+                (false, None) => format!("{}: failed", file),
+                _ if *changes_made => format!("{}: changed", file),
+                _ => format!("{}: unchanged", file),
+            },
+        })
+    }
+
+    /// The event's snake_case name, as used for the `event` field
+    pub fn name(&self) -> &'static str {
+        match self {
+            TraceEvent::Discovered { .. } => "discovered",
+            TraceEvent::Skipped { .. } => "skipped",
+            TraceEvent::CacheHit { .. } => "cache_hit",
+            TraceEvent::Parsed { .. } => "parsed",
+            TraceEvent::ParseFailed { .. } => "parse_failed",
+            TraceEvent::Match { .. } => "match",
+            TraceEvent::Written { .. } => "written",
+            TraceEvent::Processed { .. } => "processed",
+        }
+    }
+
+    /// The path the event is about
+    pub fn file(&self) -> &str {
+        match self {
+            TraceEvent::Discovered { path } | TraceEvent::Skipped { path, .. } => path,
+            TraceEvent::CacheHit { file }
+            | TraceEvent::Parsed { file, .. }
+            | TraceEvent::ParseFailed { file, .. }
+            | TraceEvent::Match { file, .. }
+            | TraceEvent::Written { file, .. }
+            | TraceEvent::Processed { file, .. } => file,
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct TraceRecord<'a> {
+    #[serde(rename = "timestampMs")]
+    timestamp_ms: u128,
+    #[serde(flatten)]
+    event: &'a TraceEvent,
+}
+
+/// Writes trace events as JSON lines to a file, and logs summaries of them
+/// when verbose
+///
+/// A disabled tracer does nothing; events are built lazily so tracing
+/// has no cost unless `--trace-file` or `--verbose` is given.
+#[derive(Clone, Default)]
+pub struct Tracer {
+    writer: Option<Arc<Mutex<BufWriter<File>>>>,
+    verbose: bool,
+}
+
+impl Tracer {
+    /// Create a tracer that discards all events
+    pub fn disabled() -> Self {
+        Self::default()
+    }
+
+    /// Create a tracer that writes events to the given file, truncating it
+    pub fn to_file(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let file = File::create(path)
+            .map_err(|e| WindWardenError::from_io_error(e, Some(&path.display().to_string())))?;
+
+        Ok(Self {
+            writer: Some(Arc::new(Mutex::new(BufWriter::new(file)))),
+            verbose: false,
+        })
+    }
+
+    /// Also log a summary of each event at info level
+    pub fn with_verbose(mut self, verbose: bool) -> Self {
+        self.verbose = verbose;
+        self
+    }
+
+    /// Check whether events are being recorded
+    pub fn is_enabled(&self) -> bool {
+        self.writer.is_some() || self.verbose
+    }
+
+    /// Record an event, building it only when tracing is enabled
+    pub fn emit(&self, event: impl FnOnce() -> TraceEvent) {
+        if !self.is_enabled() {
+            return;
+        }
+
+        let event = event();
+        if self.verbose
+            && let Some(line) = event.describe()
+        {
+            match &event {
+                TraceEvent::Processed { duration_ms, .. } => log::info!(
+                    event = event.name(),
+                    file = event.file(),
+                    duration_ms = *duration_ms;
+                    "{}",
+                    line
+                ),
+                _ => log::info!(event = event.name(), file = event.file(); "{}", line),
+            }
+        }
+
+        let Some(writer) = &self.writer else {
+            return;
+        };
+        let record = TraceRecord {
+            timestamp_ms: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_millis(),
+            event: &event,
+        };
+
+        // Tracing is best-effort and must never fail a run
+        if let Ok(line) = serde_json::to_string(&record)
+            && let Ok(mut writer) = writer.lock()
+        {
+            let _ = writeln!(writer, "{}", line);
+        }
+    }
+
+    /// Flush buffered events to disk
+    pub fn flush(&self) {
+        if let Some(writer) = &self.writer
+            && let Ok(mut writer) = writer.lock()
+        {
+            let _ = writer.flush();
+        }
+    }
+}
+
+impl std::fmt::Debug for Tracer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Tracer")
+            .field("enabled", &self.is_enabled())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_disabled_tracer_does_not_build_events() {
+        let tracer = Tracer::disabled();
+        assert!(!tracer.is_enabled());
+        tracer.emit(|| panic!("event should not be built when tracing is disabled"));
+    }
+
+    #[test]
+    fn test_verbose_tracer_is_enabled_without_a_file() {
+        let tracer = Tracer::disabled().with_verbose(true);
+        assert!(tracer.is_enabled());
+    }
+
+    #[test]
+    fn test_describe_events() {
+        let describe = |event: TraceEvent| event.describe();
+
+        assert_eq!(
+            describe(TraceEvent::Skipped {
+                path: "dist/app.js".to_string(),
+                reason: "excluded".to_string(),
+            })
+            .as_deref(),
+            Some("dist/app.js: skipped (excluded)")
+        );
+        assert_eq!(
+            describe(TraceEvent::Parsed {
+                file: "App.tsx".to_string(),
+                matches: 3,
+            })
+            .as_deref(),
+            Some("App.tsx: found 3 class string(s)")
+        );
+        assert_eq!(
+            describe(TraceEvent::Processed {
+                file: "App.tsx".to_string(),
+                success: true,
+                changes_made: false,
+                error: None,
+                duration_ms: 3,
+            })
+            .as_deref(),
+            Some("App.tsx: unchanged")
+        );
+        assert!(
+            describe(TraceEvent::Match {
+                file: "App.tsx".to_string(),
+                start: 0,
+                end: 10,
+                original: "p-4 flex".to_string(),
+                sorted: "flex p-4".to_string(),
+                changed: true,
+            })
+            .is_none()
+        );
+    }
+
+    #[test]
+    fn test_tracer_writes_json_lines() {
+        let temp_dir = TempDir::new().unwrap();
+        let trace_path = temp_dir.path().join("trace.jsonl");
+
+        let tracer = Tracer::to_file(&trace_path).unwrap();
+        tracer.emit(|| TraceEvent::Discovered {
+            path: "src/App.tsx".to_string(),
+        });
+        tracer.emit(|| TraceEvent::Parsed {
+            file: "src/App.tsx".to_string(),
+            matches: 2,
+        });
+        tracer.flush();
+
+        let content = fs::read_to_string(&trace_path).unwrap();
+        let lines: Vec<serde_json::Value> = content
+            .lines()
+            .map(|l| serde_json::from_str(l).unwrap())
+            .collect();
+
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0]["event"], "discovered");
+        assert_eq!(lines[0]["path"], "src/App.tsx");
+        assert_eq!(lines[1]["event"], "parsed");
+        assert_eq!(lines[1]["matches"], 2);
+        assert!(lines[1]["timestampMs"].is_number());
+    }
+}
@@ -0,0 +1,186 @@
+use crate::{Result, WindWardenError};
+use globset::{Glob, GlobSet, GlobSetBuilder};
+
+/// Expand `{a,b}` alternatives in a glob pattern into one pattern each
+///
+/// `globset` handles simple alternatives itself, but rejects nested groups
+/// and never matches empty alternatives like `a{,b}`, so patterns are
+/// expanded up front instead. Braces inside character classes and after a
+/// `\` are literal. Unbalanced braces are an error rather than being
+/// matched literally.
+pub fn expand_braces(pattern: &str) -> Result<Vec<String>> {
+    let Some((open, close)) = find_group(pattern)? else {
+        return Ok(vec![pattern.to_string()]);
+    };
+
+    let (prefix, suffix) = (&pattern[..open], &pattern[close + 1..]);
+    let mut expanded = Vec::new();
+    for alternative in split_alternatives(&pattern[open + 1..close]) {
+        expanded.extend(expand_braces(&format!(
+            "{}{}{}",
+            prefix, alternative, suffix
+        ))?);
+    }
+    Ok(expanded)
+}
+
+/// Compile glob patterns into one set, expanding braces in each
+pub fn build_glob_set<'a>(patterns: impl IntoIterator<Item = &'a str>) -> Result<GlobSet> {
+    let patterns: Vec<&str> = patterns.into_iter().collect();
+    let mut builder = GlobSetBuilder::new();
+    for pattern in &patterns {
+        for expanded in expand_braces(pattern)? {
+            builder.add(compile_glob(&expanded, pattern)?);
+        }
+    }
+    builder
+        .build()
+        .map_err(|e| WindWardenError::glob_pattern_error(patterns.join(", "), e.to_string()))
+}
+
+/// Compile one brace-free pattern, reporting errors against `original`
+pub fn compile_glob(pattern: &str, original: &str) -> Result<Glob> {
+    Glob::new(pattern)
+        .map_err(|e| WindWardenError::glob_pattern_error(original, e.kind().to_string()))
+}
+
+/// Byte offsets of the first top-level `{` and its matching `}`
+fn find_group(pattern: &str) -> Result<Option<(usize, usize)>> {
+    let mut open = Vec::new();
+    let mut first = None;
+
+    for (index, c) in literal_aware_chars(pattern) {
+        match c {
+            '{' => open.push(index),
+            '}' => {
+                let Some(start) = open.pop() else {
+                    return Err(WindWardenError::glob_pattern_error(
+                        pattern,
+                        format!(
+                            "unmatched '}}' at character {}; escape a literal brace as [}}]",
+                            index + 1
+                        ),
+                    ));
+                };
+                if open.is_empty() && first.is_none() {
+                    first = Some((start, index));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    if let Some(start) = open.first() {
+        return Err(WindWardenError::glob_pattern_error(
+            pattern,
+            format!(
+                "unclosed '{{' at character {}; close it with '}}' or escape a literal brace as [{{]",
+                start + 1
+            ),
+        ));
+    }
+    Ok(first)
+}
+
+/// Split the inside of a group on its top-level commas
+fn split_alternatives(group: &str) -> Vec<&str> {
+    let mut alternatives = Vec::new();
+    let mut depth = 0;
+    let mut start = 0;
+
+    for (index, c) in literal_aware_chars(group) {
+        match c {
+            '{' => depth += 1,
+            '}' => depth -= 1,
+            ',' if depth == 0 => {
+                alternatives.push(&group[start..index]);
+                start = index + 1;
+            }
+            _ => {}
+        }
+    }
+    alternatives.push(&group[start..]);
+    alternatives
+}
+
+/// The braces and commas of a pattern that aren't escaped or in a character class
+fn literal_aware_chars(pattern: &str) -> impl Iterator<Item = (usize, char)> + '_ {
+    let mut chars = pattern.char_indices().peekable();
+    std::iter::from_fn(move || {
+        while let Some((index, c)) = chars.next() {
+            match c {
+                '\\' => {
+                    chars.next();
+                }
+                '[' => {
+                    // A leading `!`, `^`, or `]` is part of the class
+                    chars.next_if(|&(_, c)| c == '!' || c == '^');
+                    chars.next_if(|&(_, c)| c == ']');
+                    for (_, c) in chars.by_ref() {
+                        if c == ']' {
+                            break;
+                        }
+                    }
+                }
+                '{' | '}' | ',' => return Some((index, c)),
+                _ => {}
+            }
+        }
+        None
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_expand_braces() {
+        assert_eq!(expand_braces("src/**/*.tsx").unwrap(), ["src/**/*.tsx"]);
+        assert_eq!(
+            expand_braces("src/*.{jsx,tsx}").unwrap(),
+            ["src/*.jsx", "src/*.tsx"]
+        );
+        assert_eq!(
+            expand_braces("{src,lib}/*.{js,ts}").unwrap(),
+            ["src/*.js", "src/*.ts", "lib/*.js", "lib/*.ts"]
+        );
+        assert_eq!(
+            expand_braces("*.{js,{jsx,tsx}}").unwrap(),
+            ["*.js", "*.jsx", "*.tsx"]
+        );
+        assert_eq!(
+            expand_braces("App{,.test}.tsx").unwrap(),
+            ["App.tsx", "App.test.tsx"]
+        );
+    }
+
+    #[test]
+    fn test_literal_braces_are_not_expanded() {
+        assert_eq!(expand_braces("[{]a,b[}].tsx").unwrap(), ["[{]a,b[}].tsx"]);
+        assert_eq!(expand_braces(r"\{a,b\}.tsx").unwrap(), [r"\{a,b\}.tsx"]);
+        assert_eq!(expand_braces("[!}]{a,b}").unwrap(), ["[!}]a", "[!}]b"]);
+    }
+
+    #[test]
+    fn test_unbalanced_braces_are_errors() {
+        let error = expand_braces("src/*.{jsx,tsx").unwrap_err().to_string();
+        assert!(error.contains("unclosed '{' at character 7"), "{}", error);
+
+        let error = expand_braces("src/*.jsx}").unwrap_err().to_string();
+        assert!(error.contains("unmatched '}' at character 10"), "{}", error);
+    }
+
+    #[test]
+    fn test_build_glob_set() {
+        let set = build_glob_set(["src/[AB]*.{jsx,tsx}", "App{,.test}.ts"]).unwrap();
+        assert!(set.is_match("src/App.tsx"));
+        assert!(set.is_match("src/Button.jsx"));
+        assert!(!set.is_match("src/Card.tsx"));
+        assert!(!set.is_match("src/App.js"));
+        assert!(set.is_match("App.ts"));
+        assert!(set.is_match("App.test.ts"));
+
+        assert!(build_glob_set(["src/[a.tsx"]).is_err());
+    }
+}
@@ -1,3 +1,4 @@
 // Utility modules for WindWarden
 
 pub mod error;
+pub mod glob;
@@ -0,0 +1,2 @@
+const base = "flex p-4 text-sm " + "rounded bg-white";
+const button = clsx("px-4 py-2", isActive && "bg-blue-500 text-white");
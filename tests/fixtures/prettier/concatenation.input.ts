@@ -0,0 +1,2 @@
+const base = "text-sm p-4 flex " + "bg-white rounded";
+const button = clsx("py-2 px-4", isActive && "text-white bg-blue-500");
@@ -0,0 +1 @@
+export const Card = () => <div className="card select2-dropdown p-3 shadow-xl">Card</div>;
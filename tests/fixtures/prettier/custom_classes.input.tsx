@@ -0,0 +1 @@
+export const Card = () => <div className="p-3 shadow-xl card select2-dropdown">Card</div>;
@@ -0,0 +1,9 @@
+export const Layout = () => (
+  <main className="relative mx-auto flex max-w-7xl flex-col items-center gap-4 px-6">
+    <h1 className="text-3xl font-bold tracking-tight text-gray-900">Title</h1>
+    <p className="mt-6 text-center text-lg leading-7 text-gray-600">Body</p>
+    <a className="rounded-md border border-gray-300 bg-white px-3.5 py-2.5 text-sm font-semibold shadow-sm">
+      Link
+    </a>
+  </main>
+);
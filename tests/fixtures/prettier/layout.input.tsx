@@ -0,0 +1,9 @@
+export const Layout = () => (
+  <main className="max-w-7xl px-6 mx-auto flex flex-col items-center gap-4 relative">
+    <h1 className="text-gray-900 font-bold tracking-tight text-3xl">Title</h1>
+    <p className="leading-7 text-gray-600 mt-6 text-lg text-center">Body</p>
+    <a className="rounded-md border border-gray-300 shadow-sm bg-white px-3.5 py-2.5 text-sm font-semibold">
+      Link
+    </a>
+  </main>
+);
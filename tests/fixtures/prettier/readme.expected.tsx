@@ -0,0 +1,7 @@
+export function Button() {
+  return (
+    <button className="bg-sky-700 px-4 py-2 text-white hover:bg-sky-800 sm:px-8 sm:py-3">
+      Save
+    </button>
+  );
+}
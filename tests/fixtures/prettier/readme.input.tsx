@@ -0,0 +1,7 @@
+export function Button() {
+  return (
+    <button className="text-white px-4 sm:px-8 py-2 sm:py-3 bg-sky-700 hover:bg-sky-800">
+      Save
+    </button>
+  );
+}
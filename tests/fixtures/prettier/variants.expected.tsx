@@ -0,0 +1,5 @@
+export const Nav = () => (
+  <nav className="hidden bg-white hover:bg-gray-50 focus:outline-none group-hover:text-white dark:bg-gray-900 md:flex">
+    Menu
+  </nav>
+);
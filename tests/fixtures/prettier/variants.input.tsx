@@ -0,0 +1,5 @@
+export const Nav = () => (
+  <nav className="md:flex dark:bg-gray-900 hidden bg-white hover:bg-gray-50 group-hover:text-white focus:outline-none">
+    Menu
+  </nav>
+);
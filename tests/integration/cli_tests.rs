@@ -150,6 +150,53 @@ fn test_thread_count_options() {
     }
 }
 
+#[test]
+fn test_thread_scaling_option() {
+    let temp_dir = TempDir::new().expect("Failed to create temp directory");
+    let file = temp_dir.path().join("test.tsx");
+    fs::write(
+        &file,
+        r#"export const Test = () => <div className="p-4 bg-red-500 flex">Test</div>;"#,
+    )
+    .expect("Failed to write test file");
+
+    let mut cmd = Command::cargo_bin("windwarden").unwrap();
+    cmd.arg("check")
+        .arg("--thread-scaling")
+        .arg("0.5")
+        .arg(&file)
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("test.tsx"));
+
+    for (args, error) in [
+        (
+            vec!["--thread-scaling", "1.5"],
+            "must be greater than 0 and at most 1",
+        ),
+        (
+            vec!["--thread-scaling", "0"],
+            "must be greater than 0 and at most 1",
+        ),
+        (
+            vec!["--thread-scaling", "0.5", "--processing", "sequential"],
+            "cannot be used with --processing sequential",
+        ),
+        (
+            vec!["--thread-scaling", "0.5", "--threads", "2"],
+            "cannot be used with",
+        ),
+    ] {
+        let mut cmd = Command::cargo_bin("windwarden").unwrap();
+        cmd.arg("format")
+            .args(&args)
+            .arg(&file)
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains(error));
+    }
+}
+
 #[test]
 fn test_file_extension_filtering() {
     let temp_dir = TempDir::new().expect("Failed to create temp directory");
@@ -254,6 +301,64 @@ fn test_exclude_patterns() {
         .stdout(predicate::str::contains("Total files:")); // Just check we get file count
 }
 
+#[test]
+fn test_cli_exclude_negation_overrides_config_ignore() {
+    let temp_dir = TempDir::new().expect("Failed to create temp directory");
+    fs::create_dir_all(temp_dir.path().join("generated")).expect("Failed to create dir");
+    for name in ["generated/Keep.tsx", "generated/Other.tsx"] {
+        fs::write(
+            temp_dir.path().join(name),
+            r#"export const A = () => <div className="p-4 flex">A</div>;"#,
+        )
+        .expect("Failed to write test file");
+    }
+    fs::write(
+        temp_dir.path().join(".windwarden.json"),
+        r#"{"ignorePaths": ["generated"]}"#,
+    )
+    .expect("Failed to write config");
+
+    let mut cmd = Command::cargo_bin("windwarden").unwrap();
+    cmd.current_dir(temp_dir.path())
+        .args([
+            "check",
+            "--format",
+            "check",
+            "--exclude",
+            "!generated/Keep.tsx",
+            ".",
+        ])
+        .assert()
+        .code(1)
+        .stdout(predicate::str::contains("Keep.tsx"))
+        .stdout(predicate::str::contains("Other.tsx").not());
+}
+
+#[test]
+fn test_override_globs_are_relative_to_config_file() {
+    let temp_dir = TempDir::new().expect("Failed to create temp directory");
+    let legacy = temp_dir.path().join("src/legacy");
+    fs::create_dir_all(&legacy).expect("Failed to create dir");
+    fs::write(
+        legacy.join("Card.tsx"),
+        r#"export const styles = legacyClasses("p-4 flex");"#,
+    )
+    .expect("Failed to write test file");
+    fs::write(
+        temp_dir.path().join(".windwarden.json"),
+        r#"{"overrides": [{"files": ["src/legacy/**"], "config": {"functionNames": ["legacyClasses"]}}]}"#,
+    )
+    .expect("Failed to write config");
+
+    // Run from a subdirectory, so paths relative to it don't start with src/
+    let mut cmd = Command::cargo_bin("windwarden").unwrap();
+    cmd.current_dir(temp_dir.path().join("src"))
+        .args(["check", "--format", "check", "."])
+        .assert()
+        .code(1)
+        .stdout(predicate::str::contains("Card.tsx"));
+}
+
 #[test]
 fn test_max_depth_option() {
     let temp_dir = TempDir::new().expect("Failed to create temp directory");
@@ -335,6 +440,9 @@ fn test_stats_flag() {
         .stdout(predicate::str::contains("Processed:"))
         .stdout(predicate::str::contains("Changed:"))
         .stdout(predicate::str::contains("Failed:"))
+        .stdout(predicate::str::contains(
+            "Classes: 1 processed, 1 reordered",
+        ))
         .stdout(predicate::str::contains("Success rate:"))
         .stdout(predicate::str::contains("Duration:"));
 }
@@ -388,6 +496,62 @@ fn test_progress_flag() {
         .stderr(predicate::str::contains("Processing")); // Progress output goes to stderr
 }
 
+#[test]
+fn test_progress_format_json() {
+    let temp_dir = TempDir::new().expect("Failed to create temp directory");
+
+    for i in 1..=3 {
+        fs::write(
+            temp_dir.path().join(format!("test{}.tsx", i)),
+            format!(
+                r#"export const Test{} = () => <div className="p-4 flex">Test{}</div>;"#,
+                i, i
+            ),
+        )
+        .expect("Failed to write test file");
+    }
+
+    let mut cmd = Command::cargo_bin("windwarden").unwrap();
+    let output = cmd
+        .arg("check")
+        .arg("--progress")
+        .arg("--progress-format")
+        .arg("json")
+        .arg("--format")
+        .arg("json")
+        .arg(temp_dir.path())
+        .output()
+        .expect("Failed to run windwarden");
+
+    // Every stderr line is a progress event, ending with the full count
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    let events: Vec<serde_json::Value> = stderr
+        .lines()
+        .map(|line| serde_json::from_str(line).expect("progress line is JSON"))
+        .collect();
+    assert_eq!(events.first().unwrap()["processed"], 0);
+    assert_eq!(
+        events.last().unwrap(),
+        &serde_json::json!({ "processed": 3, "total": 3 })
+    );
+
+    // The results on stdout stay parseable
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(report["results"].as_array().unwrap().len(), 3);
+}
+
+#[test]
+fn test_progress_format_requires_progress() {
+    let mut cmd = Command::cargo_bin("windwarden").unwrap();
+    cmd.arg("check")
+        .arg("--progress-format")
+        .arg("json")
+        .arg(".")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("--progress"));
+}
+
 #[test]
 fn test_follow_links_flag() {
     let temp_dir = TempDir::new().expect("Failed to create temp directory");
@@ -432,6 +596,118 @@ fn test_stdin_processing() {
         .stdout(predicate::str::contains("bg-red-500"));
 }
 
+#[test]
+fn test_stdin_filepath_selects_parser() {
+    // `<string>value` is a type assertion in .ts but invalid JSX in .tsx
+    let input = r#"const label = <string>value;
+const classes = cn("p-4 bg-red-500 flex");"#;
+
+    let mut cmd = Command::cargo_bin("windwarden").unwrap();
+    cmd.arg("--stdin")
+        .write_stdin(input)
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Parse error in stdin.tsx"));
+
+    let mut cmd = Command::cargo_bin("windwarden").unwrap();
+    cmd.arg("--stdin")
+        .arg("--stdin-filepath")
+        .arg("src/labels.ts")
+        .write_stdin(input)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(r#"cn("flex p-4 bg-red-500")"#));
+}
+
+#[test]
+fn test_stdin_json_batch() {
+    let input = r#"[
+        {"id": 1, "filepath": "a.tsx", "content": "<div className=\"p-4 flex\" />"},
+        {"id": "b", "content": "const c = cn(\"flex p-4\");"},
+        {"id": 3, "filepath": "c.tsx", "content": "const = ;"}
+    ]"#;
+
+    let mut cmd = Command::cargo_bin("windwarden").unwrap();
+    let output = cmd
+        .arg("--stdin-json")
+        .write_stdin(input)
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+
+    let responses: serde_json::Value = serde_json::from_slice(&output).unwrap();
+    assert_eq!(
+        responses[0],
+        serde_json::json!({"id": 1, "content": "<div className=\"flex p-4\" />", "changed": true})
+    );
+    assert_eq!(
+        responses[1],
+        serde_json::json!({"id": "b", "content": "const c = cn(\"flex p-4\");", "changed": false})
+    );
+    assert_eq!(responses[2]["id"], 3);
+    assert_eq!(responses[2]["content"], "const = ;");
+    assert_eq!(responses[2]["changed"], false);
+    assert!(
+        responses[2]["error"]
+            .as_str()
+            .unwrap()
+            .contains("Parse error in c.tsx")
+    );
+}
+
+#[test]
+fn test_stdin_json_malformed_input() {
+    for input in [
+        r#"[{"id": 1, "content": "#,
+        r#"[{"id": 1}]"#,
+        r#"{"id": 1}"#,
+    ] {
+        let mut cmd = Command::cargo_bin("windwarden").unwrap();
+        let output = cmd
+            .arg("--stdin-json")
+            .write_stdin(input)
+            .assert()
+            .failure()
+            .code(2)
+            .get_output()
+            .stdout
+            .clone();
+
+        let error: serde_json::Value = serde_json::from_slice(&output).unwrap();
+        assert!(
+            error["error"]
+                .as_str()
+                .unwrap()
+                .starts_with("Invalid --stdin-json input:"),
+            "{}",
+            input
+        );
+    }
+}
+
+#[test]
+fn test_stdin_report_changed() {
+    let mut cmd = Command::cargo_bin("windwarden").unwrap();
+    cmd.arg("--stdin")
+        .arg("--stdin-report-changed")
+        .write_stdin("const a = cn(\"p-4 flex\");\n")
+        .assert()
+        .success()
+        .stdout("const a = cn(\"flex p-4\");\n")
+        .stderr("changed: true\n");
+
+    let mut cmd = Command::cargo_bin("windwarden").unwrap();
+    cmd.arg("--stdin")
+        .arg("--stdin-report-changed")
+        .write_stdin("const a = cn(\"flex p-4\");\n")
+        .assert()
+        .success()
+        .stdout("const a = cn(\"flex p-4\");\n")
+        .stderr("changed: false\n");
+}
+
 #[test]
 fn test_invalid_command_combinations() {
     // Test with no command and no stdin
@@ -448,3 +724,1597 @@ fn test_invalid_command_combinations() {
         .assert()
         .failure(); // Should fail due to clap validation
 }
+
+#[test]
+fn test_trace_file_records_discovery_and_processing() {
+    let temp_dir = TempDir::new().expect("Failed to create temp directory");
+    let test_file = temp_dir.path().join("traced.tsx");
+    fs::write(
+        &test_file,
+        r#"export const Test = () => <div className="p-4 bg-red-500 flex">Test</div>;"#,
+    )
+    .expect("Failed to write test file");
+    let trace_path = temp_dir.path().join("trace.jsonl");
+
+    let mut cmd = Command::cargo_bin("windwarden").unwrap();
+    cmd.arg("format")
+        .arg("--trace-file")
+        .arg(&trace_path)
+        .arg(temp_dir.path())
+        .assert()
+        .success();
+
+    let trace = fs::read_to_string(&trace_path).expect("Trace file should exist");
+    let events: Vec<serde_json::Value> = trace
+        .lines()
+        .map(|line| serde_json::from_str(line).expect("Each trace line should be JSON"))
+        .collect();
+
+    let has_event = |name: &str| {
+        events.iter().any(|e| {
+            e["event"] == name
+                && e.as_object()
+                    .unwrap()
+                    .values()
+                    .any(|v| v.as_str().is_some_and(|s| s.ends_with("traced.tsx")))
+        })
+    };
+
+    assert!(has_event("discovered"));
+    assert!(has_event("parsed"));
+    assert!(has_event("match"));
+    assert!(has_event("processed"));
+}
+
+#[test]
+fn test_cache_reports_hits_on_second_run() {
+    let temp_dir = TempDir::new().expect("Failed to create temp directory");
+    let test_file = temp_dir.path().join("sorted.tsx");
+    fs::write(
+        &test_file,
+        r#"export const Test = () => <div className="flex p-4">Test</div>;"#,
+    )
+    .expect("Failed to write test file");
+    let cache_path = temp_dir.path().join("windwarden.cache");
+
+    let mut cmd = Command::cargo_bin("windwarden").unwrap();
+    cmd.arg("check")
+        .arg("--stats")
+        .arg("--cache-file")
+        .arg(&cache_path)
+        .arg(&test_file)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Cache hits").not());
+
+    assert!(cache_path.exists());
+
+    let mut cmd = Command::cargo_bin("windwarden").unwrap();
+    cmd.arg("check")
+        .arg("--stats")
+        .arg("--cache-file")
+        .arg(&cache_path)
+        .arg(&test_file)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Cache hits: 1"));
+}
+
+#[test]
+fn test_html_files_processed_when_enabled_in_config() {
+    let temp_dir = TempDir::new().expect("Failed to create temp directory");
+    let html_file = temp_dir.path().join("index.html");
+    fs::write(&html_file, r#"<div class="p-4 flex"></div>"#).expect("Failed to write test file");
+
+    // HTML files are not in the default extensions
+    let mut cmd = Command::cargo_bin("windwarden").unwrap();
+    cmd.arg("format")
+        .arg("--mode")
+        .arg("write")
+        .arg(temp_dir.path())
+        .assert()
+        .success();
+    assert_eq!(
+        fs::read_to_string(&html_file).unwrap(),
+        r#"<div class="p-4 flex"></div>"#
+    );
+
+    let config_file = temp_dir.path().join("windwarden.json");
+    fs::write(&config_file, r#"{ "html": true }"#).expect("Failed to write config");
+
+    let mut cmd = Command::cargo_bin("windwarden").unwrap();
+    cmd.arg("--config")
+        .arg(&config_file)
+        .arg("format")
+        .arg("--mode")
+        .arg("write")
+        .arg(temp_dir.path())
+        .assert()
+        .success();
+    assert_eq!(
+        fs::read_to_string(&html_file).unwrap(),
+        r#"<div class="flex p-4"></div>"#
+    );
+}
+
+#[test]
+fn test_css_apply_processed_when_enabled_in_config() {
+    let temp_dir = TempDir::new().expect("Failed to create temp directory");
+    let css_file = temp_dir.path().join("components.css");
+    let css = ".btn {\n  @apply p-4 flex;\n  color: red;\n}\n";
+    fs::write(&css_file, css).expect("Failed to write test file");
+
+    // CSS files are not in the default extensions
+    let mut cmd = Command::cargo_bin("windwarden").unwrap();
+    cmd.arg("format")
+        .arg("--mode")
+        .arg("write")
+        .arg(temp_dir.path())
+        .assert()
+        .success();
+    assert_eq!(fs::read_to_string(&css_file).unwrap(), css);
+
+    let config_file = temp_dir.path().join("windwarden.json");
+    fs::write(&config_file, r#"{ "css": true }"#).expect("Failed to write config");
+
+    let mut cmd = Command::cargo_bin("windwarden").unwrap();
+    cmd.arg("--config")
+        .arg(&config_file)
+        .arg("format")
+        .arg("--mode")
+        .arg("write")
+        .arg(temp_dir.path())
+        .assert()
+        .success();
+    assert_eq!(
+        fs::read_to_string(&css_file).unwrap(),
+        ".btn {\n  @apply flex p-4;\n  color: red;\n}\n"
+    );
+}
+
+#[test]
+fn test_junit_format_reports_unformatted_files_as_failures() {
+    let temp_dir = TempDir::new().expect("Failed to create temp directory");
+    fs::write(
+        temp_dir.path().join("sorted.tsx"),
+        r#"export const A = () => <div className="flex p-4">A</div>;"#,
+    )
+    .expect("Failed to write test file");
+    fs::write(
+        temp_dir.path().join("unsorted.tsx"),
+        r#"export const B = () => <div className="p-4 flex">B</div>;"#,
+    )
+    .expect("Failed to write test file");
+
+    let mut cmd = Command::cargo_bin("windwarden").unwrap();
+    cmd.arg("check")
+        .arg("--format")
+        .arg("junit")
+        .arg(temp_dir.path())
+        .assert()
+        .failure()
+        .code(1)
+        .stdout(predicate::str::starts_with("<?xml"))
+        .stdout(predicate::str::contains(
+            r#"tests="2" failures="1" errors="0""#,
+        ))
+        .stdout(predicate::str::contains("unsorted.tsx"))
+        .stdout(predicate::str::contains("<failure"));
+}
+
+#[test]
+fn test_summary_formats_report_class_counts() {
+    let temp_dir = TempDir::new().expect("Failed to create temp directory");
+    fs::write(
+        temp_dir.path().join("sorted.tsx"),
+        r#"export const A = () => <div className="flex p-4">A</div>;"#,
+    )
+    .expect("Failed to write test file");
+    fs::write(
+        temp_dir.path().join("unsorted.tsx"),
+        r#"export const B = () => <div className="p-4 flex"><span className="p-2 m-1">B</span></div>;"#,
+    )
+    .expect("Failed to write test file");
+
+    let mut cmd = Command::cargo_bin("windwarden").unwrap();
+    cmd.arg("format")
+        .arg("--format")
+        .arg("summary")
+        .arg(temp_dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Files processed: 2"))
+        .stdout(predicate::str::contains("Files changed: 1"))
+        .stdout(predicate::str::contains("Classes processed: 3"))
+        .stdout(predicate::str::contains("Classes sorted: 2"));
+
+    let mut cmd = Command::cargo_bin("windwarden").unwrap();
+    cmd.arg("check")
+        .arg("--format")
+        .arg("summary-json")
+        .arg(temp_dir.path())
+        .assert()
+        .failure()
+        .code(1)
+        .stdout(predicate::str::contains(r#""filesProcessed": 2"#))
+        .stdout(predicate::str::contains(r#""filesChanged": 1"#))
+        .stdout(predicate::str::contains(r#""classesProcessed": 3"#))
+        .stdout(predicate::str::contains(r#""classesSorted": 2"#));
+}
+
+#[test]
+fn test_fail_fast_reports_triggering_file() {
+    let temp_dir = TempDir::new().expect("Failed to create temp directory");
+    let file = temp_dir.path().join("unsorted.tsx");
+    fs::write(
+        &file,
+        r#"export const B = () => <div className="p-4 flex">B</div>;"#,
+    )
+    .expect("Failed to write test file");
+
+    let mut cmd = Command::cargo_bin("windwarden").unwrap();
+    cmd.arg("check")
+        .arg("--fail-fast")
+        .arg(temp_dir.path())
+        .assert()
+        .failure()
+        .code(1)
+        .stderr(predicate::str::contains(
+            "Stopped at first unformatted file:",
+        ))
+        .stderr(predicate::str::contains("unsorted.tsx"));
+
+    let mut cmd = Command::cargo_bin("windwarden").unwrap();
+    cmd.arg("format")
+        .arg("--fail-fast")
+        .arg(temp_dir.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("--mode verify"));
+}
+
+#[test]
+fn test_exclude_dir_skips_directories_by_name() {
+    let temp_dir = TempDir::new().expect("Failed to create temp directory");
+    let mocks = temp_dir.path().join("src").join("__mocks__");
+    fs::create_dir_all(&mocks).expect("Failed to create directory");
+    fs::write(
+        mocks.join("Mock.tsx"),
+        r#"export const M = () => <div className="p-4 flex">M</div>;"#,
+    )
+    .expect("Failed to write test file");
+    fs::write(
+        temp_dir.path().join("src").join("App.tsx"),
+        r#"export const A = () => <div className="flex p-4">A</div>;"#,
+    )
+    .expect("Failed to write test file");
+
+    let mut cmd = Command::cargo_bin("windwarden").unwrap();
+    cmd.arg("check")
+        .arg("--exclude-dir")
+        .arg("__mocks__")
+        .arg("--stats")
+        .arg(temp_dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Total files: 1"));
+}
+
+#[test]
+fn test_no_dedup_keeps_duplicate_classes() {
+    let temp_dir = TempDir::new().expect("Failed to create temp directory");
+    let file = temp_dir.path().join("App.tsx");
+    fs::write(
+        &file,
+        r#"export const A = () => <div className="card p-4 flex p-4">A</div>;"#,
+    )
+    .expect("Failed to write test file");
+
+    let mut cmd = Command::cargo_bin("windwarden").unwrap();
+    cmd.arg("format")
+        .arg("--mode")
+        .arg("write")
+        .arg("--no-dedup")
+        .arg(&file)
+        .assert()
+        .success();
+
+    let content = fs::read_to_string(&file).expect("Failed to read test file");
+    assert!(content.contains(r#"className="flex p-4 p-4 card""#));
+
+    let mut cmd = Command::cargo_bin("windwarden").unwrap();
+    cmd.arg("format")
+        .arg("--mode")
+        .arg("write")
+        .arg(&file)
+        .assert()
+        .success();
+
+    let content = fs::read_to_string(&file).expect("Failed to read test file");
+    assert!(content.contains(r#"className="flex p-4 card""#));
+
+    let mut cmd = Command::cargo_bin("windwarden").unwrap();
+    cmd.args(["--stdin", "--no-dedup"])
+        .write_stdin(r#"const c = cn("card p-4 flex p-4");"#)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(r#"cn("flex p-4 p-4 card")"#));
+}
+
+#[test]
+fn test_merge_with_stdin() {
+    let mut cmd = Command::cargo_bin("windwarden").unwrap();
+    cmd.args(["--stdin", "--merge"])
+        .write_stdin(r#"const c = cn("p-2 flex p-4");"#)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(r#"cn("flex p-4")"#));
+
+    let mut cmd = Command::cargo_bin("windwarden").unwrap();
+    cmd.args(["--stdin-json", "--merge"])
+        .write_stdin("[]")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("cannot be used with --stdin-json"));
+}
+
+#[test]
+fn test_report_unused_functions() {
+    let temp_dir = TempDir::new().expect("Failed to create temp directory");
+    fs::write(
+        temp_dir.path().join(".windwarden.json"),
+        r#"{ "functionNames": ["tw", "styles", "legacyClasses"] }"#,
+    )
+    .expect("Failed to write config");
+    fs::write(
+        temp_dir.path().join("App.tsx"),
+        r#"export const A = () => <div className={tw("flex p-4")}>A</div>;"#,
+    )
+    .expect("Failed to write test file");
+    fs::write(
+        temp_dir.path().join("Card.tsx"),
+        r#"export const C = ({ size }) => <div className={styles(size)}>C</div>;"#,
+    )
+    .expect("Failed to write test file");
+
+    let mut cmd = Command::cargo_bin("windwarden").unwrap();
+    cmd.current_dir(temp_dir.path())
+        .arg("check")
+        .arg("--report-unused-functions")
+        .arg(".")
+        .assert()
+        .success()
+        .stderr(predicate::str::contains(
+            "Unused functions: legacyClasses\n",
+        ));
+
+    // Calls in Svelte scripts count
+    fs::write(
+        temp_dir.path().join("Legacy.svelte"),
+        "<script>const c = legacyClasses(size);</script>\n<p class=\"flex\"></p>",
+    )
+    .expect("Failed to write test file");
+
+    let mut cmd = Command::cargo_bin("windwarden").unwrap();
+    cmd.current_dir(temp_dir.path())
+        .args(["check", "--report-unused-functions", "."])
+        .assert()
+        .success()
+        .stderr(predicate::str::contains(
+            "Unused functions: none (all configured functions were called)",
+        ));
+
+    // A file that fails to parse may call any of them
+    fs::remove_file(temp_dir.path().join("Legacy.svelte")).expect("Failed to remove file");
+    fs::write(temp_dir.path().join("Broken.tsx"), "legacyClasses(")
+        .expect("Failed to write test file");
+
+    let mut cmd = Command::cargo_bin("windwarden").unwrap();
+    cmd.current_dir(temp_dir.path())
+        .args(["check", "--report-unused-functions", "."])
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains(
+            "Unused functions: incomplete, 1 file could not be searched for calls; not called elsewhere: legacyClasses\n",
+        ));
+}
+
+#[test]
+fn test_sort_contexts_from_config() {
+    let temp_dir = TempDir::new().expect("Failed to create temp directory");
+    fs::write(
+        temp_dir.path().join(".windwarden.json"),
+        r#"{ "sortContexts": ["jsxAttribute", "supportedFunction", "array"] }"#,
+    )
+    .expect("Failed to write config");
+    let file = temp_dir.path().join("App.tsx");
+    fs::write(
+        &file,
+        "const base = `p-4 flex`;\nexport const A = () => <div className=\"p-4 flex\">A</div>;\n",
+    )
+    .expect("Failed to write test file");
+
+    let mut cmd = Command::cargo_bin("windwarden").unwrap();
+    cmd.current_dir(temp_dir.path())
+        .arg("format")
+        .arg("--mode")
+        .arg("write")
+        .arg("App.tsx")
+        .assert()
+        .success();
+
+    let content = fs::read_to_string(&file).expect("Failed to read test file");
+    assert!(content.contains("const base = `p-4 flex`;"));
+    assert!(content.contains(r#"className="flex p-4""#));
+}
+
+#[test]
+fn test_default_mode_from_config() {
+    let temp_dir = TempDir::new().expect("Failed to create temp directory");
+    fs::write(
+        temp_dir.path().join(".windwarden.json"),
+        r#"{ "defaultMode": "write" }"#,
+    )
+    .expect("Failed to write config");
+    let file = temp_dir.path().join("App.tsx");
+    fs::write(
+        &file,
+        r#"export const A = () => <div className="p-4 flex">A</div>;"#,
+    )
+    .expect("Failed to write test file");
+
+    // An explicit --mode wins over the config
+    let mut cmd = Command::cargo_bin("windwarden").unwrap();
+    cmd.current_dir(temp_dir.path())
+        .arg("format")
+        .arg("--mode")
+        .arg("check")
+        .arg("App.tsx")
+        .assert()
+        .success();
+    let content = fs::read_to_string(&file).expect("Failed to read test file");
+    assert!(content.contains(r#"className="p-4 flex""#));
+
+    let mut cmd = Command::cargo_bin("windwarden").unwrap();
+    cmd.current_dir(temp_dir.path())
+        .arg("format")
+        .arg("App.tsx")
+        .assert()
+        .success();
+    let content = fs::read_to_string(&file).expect("Failed to read test file");
+    assert!(content.contains(r#"className="flex p-4""#));
+
+    fs::write(
+        temp_dir.path().join(".windwarden.json"),
+        r#"{ "defaultMode": "format" }"#,
+    )
+    .expect("Failed to write config");
+    let mut cmd = Command::cargo_bin("windwarden").unwrap();
+    cmd.current_dir(temp_dir.path())
+        .arg("format")
+        .arg("App.tsx")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Ambiguous defaultMode 'format'"));
+}
+
+#[test]
+fn test_verbose_logs_decisions_to_stderr() {
+    let temp_dir = TempDir::new().expect("Failed to create temp directory");
+    fs::write(
+        temp_dir.path().join("App.tsx"),
+        r#"export const A = () => <div className="p-4 flex">A</div>;"#,
+    )
+    .expect("Failed to write test file");
+    fs::write(
+        temp_dir.path().join("Sorted.tsx"),
+        r#"export const S = () => <div className="flex p-4">S</div>;"#,
+    )
+    .expect("Failed to write test file");
+    fs::write(temp_dir.path().join("notes.txt"), "p-4 flex").expect("Failed to write file");
+
+    let mut cmd = Command::cargo_bin("windwarden").unwrap();
+    let output = cmd
+        .current_dir(temp_dir.path())
+        .arg("format")
+        .arg("--verbose")
+        .arg("--format")
+        .arg("json")
+        .arg(".")
+        .output()
+        .unwrap();
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("notes.txt: skipped (extension)"));
+    assert!(stderr.contains("App.tsx: found 1 class string(s)"));
+    assert!(stderr.contains("App.tsx: changed"));
+    assert!(stderr.contains("Sorted.tsx: unchanged"));
+
+    // stdout stays machine-readable
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert!(json.is_object() || json.is_array());
+}
+
+#[test]
+fn test_json_log_format() {
+    let temp_dir = TempDir::new().expect("Failed to create temp directory");
+    fs::write(
+        temp_dir.path().join("App.tsx"),
+        r#"export const A = () => <div className="p-4 flex">A</div>;"#,
+    )
+    .expect("Failed to write test file");
+
+    let mut cmd = Command::cargo_bin("windwarden").unwrap();
+    let output = cmd
+        .current_dir(temp_dir.path())
+        .arg("check")
+        .arg("--verbose")
+        .arg("--log-format")
+        .arg("json")
+        .arg("--format")
+        .arg("json")
+        .arg(".")
+        .output()
+        .unwrap();
+
+    // Every diagnostic line is a JSON object
+    let records: Vec<serde_json::Value> = String::from_utf8_lossy(&output.stderr)
+        .lines()
+        .map(|line| serde_json::from_str(line).expect("stderr line is not JSON"))
+        .collect();
+    let processed = records
+        .iter()
+        .find(|record| record["event"] == "processed")
+        .expect("no processed event");
+    assert_eq!(processed["level"], "info");
+    assert!(processed["file"].as_str().unwrap().ends_with("App.tsx"));
+    assert!(processed["duration_ms"].is_u64());
+
+    // stdout stays the result report
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert!(json.is_object() || json.is_array());
+}
+
+#[test]
+fn test_print_config() {
+    let temp_dir = TempDir::new().expect("Failed to create temp directory");
+    fs::write(
+        temp_dir.path().join(".windwarden.json"),
+        r#"{"functionNames": ["tw"], "verifyExitCode": 3}"#,
+    )
+    .expect("Failed to write config");
+    let component = r#"export const A = () => <div className="p-4 flex">A</div>;"#;
+    fs::write(temp_dir.path().join("App.tsx"), component).expect("Failed to write test file");
+
+    let mut cmd = Command::cargo_bin("windwarden").unwrap();
+    let output = cmd
+        .current_dir(temp_dir.path())
+        .arg("--no-dedup")
+        .arg("format")
+        .arg("--mode")
+        .arg("write")
+        .arg("--threads")
+        .arg("2")
+        .arg("--exclude")
+        .arg("legacy/**")
+        .arg("--extensions")
+        .arg("tsx,astro")
+        .arg("--print-config")
+        .arg(".")
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let resolved: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(resolved["config"]["threads"], 2);
+    assert_eq!(resolved["config"]["preserveDuplicates"], true);
+    // CLI excludes follow the configured ones, as in the run
+    let ignore_paths = resolved["config"]["ignorePaths"].as_array().unwrap();
+    assert_eq!(ignore_paths.first().unwrap(), "node_modules");
+    assert_eq!(ignore_paths.last().unwrap(), "legacy/**");
+    assert_eq!(
+        resolved["config"]["fileExtensions"],
+        serde_json::json!(["tsx", "astro"])
+    );
+    assert_eq!(resolved["sources"]["threads"], "cli");
+    assert_eq!(resolved["sources"]["ignorePaths"], "cli");
+    assert_eq!(resolved["sources"]["fileExtensions"], "cli");
+    assert_eq!(resolved["sources"]["preserveDuplicates"], "cli");
+    assert_eq!(resolved["sources"]["verifyExitCode"], "file");
+    assert_eq!(resolved["sources"]["sortOrder"], "default");
+    let function_names = resolved["functionNames"].as_array().unwrap();
+    assert!(function_names.iter().any(|name| name == "tw"));
+    assert!(function_names.iter().any(|name| name == "cn"));
+
+    // Nothing is processed
+    assert_eq!(
+        fs::read_to_string(temp_dir.path().join("App.tsx")).unwrap(),
+        component
+    );
+}
+
+#[test]
+fn test_fix_and_check_aliases() {
+    let temp_dir = TempDir::new().expect("Failed to create temp directory");
+    let file_path = temp_dir.path().join("App.tsx");
+    let unsorted = r#"export const A = () => <div className="p-4 flex">A</div>;"#;
+    let sorted = r#"export const A = () => <div className="flex p-4">A</div>;"#;
+    fs::write(&file_path, unsorted).expect("Failed to write test file");
+
+    // --check is --mode verify: fails without writing
+    let mut cmd = Command::cargo_bin("windwarden").unwrap();
+    cmd.current_dir(temp_dir.path())
+        .args(["format", "--check", "App.tsx"])
+        .assert()
+        .code(1);
+    assert_eq!(fs::read_to_string(&file_path).unwrap(), unsorted);
+
+    // --fix --dry-run is --mode write --dry-run: still doesn't write
+    let mut cmd = Command::cargo_bin("windwarden").unwrap();
+    cmd.current_dir(temp_dir.path())
+        .args(["format", "--fix", "--dry-run", "App.tsx"])
+        .assert()
+        .success();
+    assert_eq!(fs::read_to_string(&file_path).unwrap(), unsorted);
+
+    // --fix is --mode write
+    let mut cmd = Command::cargo_bin("windwarden").unwrap();
+    cmd.current_dir(temp_dir.path())
+        .args(["format", "--fix", "App.tsx"])
+        .assert()
+        .success();
+    assert_eq!(fs::read_to_string(&file_path).unwrap(), sorted);
+
+    let mut cmd = Command::cargo_bin("windwarden").unwrap();
+    cmd.current_dir(temp_dir.path())
+        .args(["format", "--check", "App.tsx"])
+        .assert()
+        .success();
+}
+
+#[test]
+fn test_mode_aliases_conflict_with_mode() {
+    for args in [
+        &["--fix", "--mode", "write"][..],
+        &["--check", "--mode", "verify"],
+        &["--fix", "--check"],
+    ] {
+        let mut cmd = Command::cargo_bin("windwarden").unwrap();
+        cmd.arg("format")
+            .args(args)
+            .arg("src/")
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains("cannot be used with"));
+    }
+}
+
+#[test]
+fn test_stats_break_down_failures() {
+    let temp_dir = TempDir::new().expect("Failed to create temp directory");
+    fs::write(
+        temp_dir.path().join("App.tsx"),
+        r#"export const A = () => <div className="p-4 flex">A</div>;"#,
+    )
+    .expect("Failed to write test file");
+    fs::write(temp_dir.path().join("Broken.tsx"), "const = <div (;")
+        .expect("Failed to write test file");
+    fs::write(temp_dir.path().join("Binary.tsx"), [0xff, 0xfe, 0x00])
+        .expect("Failed to write test file");
+
+    // Parse errors fail the run in write mode too; files that aren't UTF-8
+    // are skipped rather than failed
+    let mut cmd = Command::cargo_bin("windwarden").unwrap();
+    cmd.current_dir(temp_dir.path())
+        .args(["format", "--mode", "write", "--stats", "."])
+        .assert()
+        .code(2)
+        .stdout(predicate::str::contains("  Failed: 1"))
+        .stdout(predicate::str::contains("    Parse errors: 1"))
+        .stdout(predicate::str::contains("  Skipped (encoding): 1"))
+        .stdout(predicate::str::contains("  Success rate: 50.0%"))
+        .stdout(predicate::str::contains("IO errors").not())
+        .stdout(predicate::str::contains("Too large").not())
+        .stderr(predicate::str::contains(
+            "Skipped ./Binary.tsx: not valid UTF-8",
+        ));
+
+    let mut cmd = Command::cargo_bin("windwarden").unwrap();
+    cmd.current_dir(temp_dir.path())
+        .args(["format", "--strict-encoding", "--stats", "."])
+        .assert()
+        .code(2)
+        .stdout(predicate::str::contains("  Failed: 2"))
+        .stdout(predicate::str::contains("    IO errors: 1"))
+        .stdout(predicate::str::contains("Skipped (encoding)").not());
+}
+
+#[test]
+fn test_max_concurrent_reads() {
+    let temp_dir = TempDir::new().expect("Failed to create temp directory");
+    for i in 0..6 {
+        fs::write(
+            temp_dir.path().join(format!("C{}.tsx", i)),
+            r#"export const C = () => <div className="p-4 flex">C</div>;"#,
+        )
+        .expect("Failed to write test file");
+    }
+
+    for flags in [
+        &["--max-concurrent-reads", "2"][..],
+        &["--no-parallel-io"][..],
+    ] {
+        let mut cmd = Command::cargo_bin("windwarden").unwrap();
+        cmd.arg("check")
+            .args(flags)
+            .arg(temp_dir.path())
+            .assert()
+            .failure()
+            .code(1)
+            .stdout(predicate::str::contains("6 files not formatted"));
+    }
+
+    let mut cmd = Command::cargo_bin("windwarden").unwrap();
+    cmd.arg("format")
+        .arg("--max-concurrent-reads")
+        .arg("0")
+        .arg(temp_dir.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "--max-concurrent-reads must be at least 1",
+        ));
+}
+
+#[test]
+fn test_check_format_matches_prettier() {
+    let temp_dir = TempDir::new().expect("Failed to create temp directory");
+    fs::write(
+        temp_dir.path().join("sorted.tsx"),
+        r#"export const A = () => <div className="flex p-4">A</div>;"#,
+    )
+    .expect("Failed to write test file");
+    fs::write(
+        temp_dir.path().join("unsorted.tsx"),
+        r#"export const B = () => <div className="p-4 flex">B</div>;"#,
+    )
+    .expect("Failed to write test file");
+
+    let mut cmd = Command::cargo_bin("windwarden").unwrap();
+    cmd.current_dir(temp_dir.path())
+        .arg("check")
+        .arg("--format")
+        .arg("check")
+        .arg(".")
+        .assert()
+        .failure()
+        .code(1)
+        .stdout(predicate::str::starts_with("Checking formatting...\n"))
+        .stdout(predicate::str::contains("[warn] ./unsorted.tsx\n"))
+        .stdout(predicate::str::contains("] ./sorted.tsx").not())
+        .stdout(predicate::str::ends_with(
+            "[warn] Code style issues found in the above file. Run WindWarden with --mode write to fix.\n",
+        ));
+
+    let mut cmd = Command::cargo_bin("windwarden").unwrap();
+    let output = cmd
+        .current_dir(temp_dir.path())
+        .arg("check")
+        .arg("--format")
+        .arg("check-json")
+        .arg(".")
+        .output()
+        .unwrap();
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(report["summary"]["totalFiles"], 2);
+    assert_eq!(report["summary"]["formattedFiles"], 1);
+    assert_eq!(report["unformattedFiles"][0]["filePath"], "./unsorted.tsx");
+    assert!(report.get("failedFiles").is_none());
+
+    let mut cmd = Command::cargo_bin("windwarden").unwrap();
+    cmd.current_dir(temp_dir.path())
+        .arg("check")
+        .arg("--format")
+        .arg("check")
+        .arg("sorted.tsx")
+        .assert()
+        .success()
+        .stdout("Checking formatting...\nAll matched files use WindWarden code style!\n");
+}
+
+#[test]
+fn test_verify_exit_code() {
+    let temp_dir = TempDir::new().expect("Failed to create temp directory");
+    fs::write(
+        temp_dir.path().join("unsorted.tsx"),
+        r#"export const B = () => <div className="p-4 flex">B</div>;"#,
+    )
+    .expect("Failed to write test file");
+
+    let mut cmd = Command::cargo_bin("windwarden").unwrap();
+    cmd.arg("check")
+        .arg("--verify-exit-code")
+        .arg("3")
+        .arg(temp_dir.path())
+        .assert()
+        .code(3);
+
+    fs::write(
+        temp_dir.path().join(".windwarden.json"),
+        r#"{"verifyExitCode": 4}"#,
+    )
+    .expect("Failed to write config");
+
+    let mut cmd = Command::cargo_bin("windwarden").unwrap();
+    cmd.current_dir(temp_dir.path())
+        .arg("format")
+        .arg("--mode")
+        .arg("verify")
+        .arg(".")
+        .assert()
+        .code(4);
+
+    // Processing errors keep their own exit code, even alongside unformatted files
+    fs::write(temp_dir.path().join("broken.tsx"), "const A = () => <div")
+        .expect("Failed to write test file");
+
+    let mut cmd = Command::cargo_bin("windwarden").unwrap();
+    cmd.current_dir(temp_dir.path())
+        .arg("check")
+        .arg(".")
+        .assert()
+        .code(2);
+
+    let mut cmd = Command::cargo_bin("windwarden").unwrap();
+    cmd.arg("check")
+        .arg("--verify-exit-code")
+        .arg("2")
+        .arg(temp_dir.path())
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains("reserved for errors"));
+}
+
+#[test]
+fn test_include_patterns() {
+    let temp_dir = TempDir::new().expect("Failed to create temp directory");
+    fs::create_dir_all(temp_dir.path().join("src")).expect("Failed to create dir");
+    for name in ["src/home.page.tsx", "src/about.page.tsx", "src/Header.tsx"] {
+        fs::write(
+            temp_dir.path().join(name),
+            r#"export const A = () => <div className="p-4 flex">A</div>;"#,
+        )
+        .expect("Failed to write test file");
+    }
+
+    let mut cmd = Command::cargo_bin("windwarden").unwrap();
+    cmd.current_dir(temp_dir.path())
+        .arg("check")
+        .arg("--format")
+        .arg("check")
+        .arg("--include")
+        .arg("**/home.page.tsx")
+        .arg("--include")
+        .arg("**/about.page.tsx")
+        .arg(".")
+        .assert()
+        .code(1)
+        .stdout(predicate::str::contains("[warn] ./src/home.page.tsx"))
+        .stdout(predicate::str::contains("[warn] ./src/about.page.tsx"))
+        .stdout(predicate::str::contains("Header.tsx").not());
+
+    fs::write(
+        temp_dir.path().join(".windwarden.json"),
+        r#"{"includePaths": ["src/*.page.tsx"]}"#,
+    )
+    .expect("Failed to write config");
+
+    let mut cmd = Command::cargo_bin("windwarden").unwrap();
+    cmd.current_dir(temp_dir.path())
+        .arg("format")
+        .arg("--mode")
+        .arg("write")
+        .arg(".")
+        .assert()
+        .success();
+
+    let header = fs::read_to_string(temp_dir.path().join("src/Header.tsx")).unwrap();
+    assert!(header.contains(r#""p-4 flex""#));
+    let home = fs::read_to_string(temp_dir.path().join("src/home.page.tsx")).unwrap();
+    assert!(home.contains(r#""flex p-4""#));
+}
+
+#[test]
+fn test_include_patterns_match_relative_to_walked_directory() {
+    let temp_dir = TempDir::new().expect("Failed to create temp directory");
+    fs::create_dir_all(temp_dir.path().join("src/pages")).expect("Failed to create dir");
+    for name in ["src/pages/Home.tsx", "src/Header.tsx"] {
+        fs::write(
+            temp_dir.path().join(name),
+            r#"export const A = () => <div className="p-4 flex">A</div>;"#,
+        )
+        .expect("Failed to write test file");
+    }
+    fs::write(
+        temp_dir.path().join(".windwarden.json"),
+        r#"{"includePaths": ["pages/*.tsx"]}"#,
+    )
+    .expect("Failed to write config");
+
+    let absolute_src = temp_dir.path().join("src");
+    for root in ["src/", absolute_src.to_str().unwrap()] {
+        let mut cmd = Command::cargo_bin("windwarden").unwrap();
+        cmd.current_dir(temp_dir.path())
+            .args(["check", "--format", "check", root])
+            .assert()
+            .code(1)
+            .stdout(predicate::str::contains("Home.tsx"))
+            .stdout(predicate::str::contains("Header.tsx").not());
+    }
+}
+
+#[test]
+fn test_doctor_command() {
+    let temp_dir = TempDir::new().expect("Failed to create temp directory");
+
+    let mut cmd = Command::cargo_bin("windwarden").unwrap();
+    cmd.current_dir(temp_dir.path())
+        .arg("doctor")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "✓ Configuration is valid (no configuration file found, using defaults)",
+        ))
+        .stdout(predicate::str::contains("✓ Glob patterns compile"))
+        .stdout(predicate::str::contains("✓ Thread count:"))
+        .stdout(predicate::str::ends_with("All checks passed\n"));
+
+    // Unused custom orders aren't validated on load, so doctor catches their typos
+    fs::write(
+        temp_dir.path().join(".windwarden.json"),
+        r#"{"customOrder": ["layout", "spacng"], "includePaths": ["src/[a"]}"#,
+    )
+    .expect("Failed to write config");
+
+    let mut cmd = Command::cargo_bin("windwarden").unwrap();
+    cmd.current_dir(temp_dir.path())
+        .arg("doctor")
+        .assert()
+        .code(1)
+        .stdout(predicate::str::contains(
+            "✗ Unknown customOrder categories: spacng",
+        ))
+        .stdout(predicate::str::contains("✗ Invalid glob patterns"))
+        .stdout(predicate::str::ends_with("2 checks failed\n"));
+
+    fs::write(
+        temp_dir.path().join(".windwarden.json"),
+        r#"{"sortOrder": "fancy"}"#,
+    )
+    .expect("Failed to write config");
+
+    let mut cmd = Command::cargo_bin("windwarden").unwrap();
+    cmd.current_dir(temp_dir.path())
+        .arg("doctor")
+        .assert()
+        .code(1)
+        .stdout(predicate::str::contains("✗ Configuration is invalid"))
+        .stdout(predicate::str::contains("Invalid sort_order 'fancy'"))
+        .stdout(predicate::str::contains(
+            "- Configuration checks skipped until the configuration loads",
+        ));
+}
+
+#[test]
+fn test_glob_path_arguments() {
+    let temp_dir = TempDir::new().expect("Failed to create temp directory");
+    fs::create_dir_all(temp_dir.path().join("src")).expect("Failed to create dir");
+    for name in ["App.jsx", "App.tsx", "Button.tsx", "Card.tsx", "util.ts"] {
+        fs::write(
+            temp_dir.path().join("src").join(name),
+            r#"export const a = cn("p-4 flex");"#,
+        )
+        .expect("Failed to write test file");
+    }
+
+    let check = |pattern: &str| {
+        let output = Command::cargo_bin("windwarden")
+            .unwrap()
+            .current_dir(temp_dir.path())
+            .args(["check", "--format", "check-json", pattern])
+            .output()
+            .unwrap();
+        let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        let mut files: Vec<String> = report["unformattedFiles"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|file| file["filePath"].as_str().unwrap().to_string())
+            .collect();
+        files.sort();
+        files
+    };
+
+    assert_eq!(check("src/*.{jsx,ts}"), ["src/App.jsx", "src/util.ts"]);
+    assert_eq!(check("src/[AB]*.tsx"), ["src/App.tsx", "src/Button.tsx"]);
+    // Braces alone make a pattern, and nested groups expand too
+    assert_eq!(check("src/App.{jsx,tsx}"), ["src/App.jsx", "src/App.tsx"]);
+    assert_eq!(
+        check("src/{App,{Button,Card}}.tsx"),
+        ["src/App.tsx", "src/Button.tsx", "src/Card.tsx"]
+    );
+
+    let mut cmd = Command::cargo_bin("windwarden").unwrap();
+    cmd.current_dir(temp_dir.path())
+        .args(["check", "src/*.{jsx,tsx"])
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains(
+            "Invalid glob pattern 'src/*.{jsx,tsx': unclosed '{' at character 7",
+        ));
+}
+
+#[test]
+fn test_since_git_ref() {
+    let temp_dir = TempDir::new().expect("Failed to create temp directory");
+    let repo = temp_dir.path();
+    let git = |args: &[&str]| {
+        let status = std::process::Command::new("git")
+            .args(["-c", "user.name=Test", "-c", "user.email=test@example.com"])
+            .args(args)
+            .current_dir(repo)
+            .status()
+            .expect("Failed to run git");
+        assert!(status.success(), "git {:?} failed", args);
+    };
+    let unsorted = r#"export const A = () => <div className="p-4 flex">A</div>;"#;
+
+    fs::create_dir_all(repo.join("src")).unwrap();
+    for name in [
+        "changed.tsx",
+        "untouched.tsx",
+        "deleted.tsx",
+        "old-name.tsx",
+    ] {
+        fs::write(repo.join("src").join(name), unsorted).unwrap();
+    }
+    git(&["init", "-q"]);
+    git(&["add", "."]);
+    git(&["commit", "-q", "-m", "baseline"]);
+
+    fs::write(
+        repo.join("src/changed.tsx"),
+        format!("{}\nexport const B = 1;\n", unsorted),
+    )
+    .unwrap();
+    git(&["rm", "-q", "src/deleted.tsx"]);
+    git(&["mv", "src/old-name.tsx", "src/new-name.tsx"]);
+
+    let mut cmd = Command::cargo_bin("windwarden").unwrap();
+    cmd.current_dir(repo)
+        .args(["check", "--format", "check", "--since", "HEAD", "."])
+        .assert()
+        .code(1)
+        .stdout(predicate::str::contains("[warn] ./src/changed.tsx"))
+        .stdout(predicate::str::contains("[warn] ./src/new-name.tsx"))
+        .stdout(predicate::str::contains("untouched.tsx").not())
+        .stdout(predicate::str::contains("in 2 files"));
+
+    let mut cmd = Command::cargo_bin("windwarden").unwrap();
+    cmd.current_dir(repo)
+        .args(["check", "--verbose", "--since", "HEAD", "."])
+        .assert()
+        .code(1)
+        .stderr(predicate::str::contains(
+            "./src/untouched.tsx: skipped (unchanged)",
+        ));
+
+    // Discovery filters still apply to the changed files
+    let mut cmd = Command::cargo_bin("windwarden").unwrap();
+    cmd.current_dir(repo)
+        .args(["check", "--format", "check", "--since", "HEAD"])
+        .args(["--exclude", "**/new-name.tsx", "."])
+        .assert()
+        .code(1)
+        .stdout(predicate::str::contains("new-name.tsx").not())
+        .stdout(predicate::str::contains("[warn] ./src/changed.tsx"));
+
+    let mut cmd = Command::cargo_bin("windwarden").unwrap();
+    cmd.current_dir(repo)
+        .args(["check", "--since", "no-such-ref", "."])
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains("Unknown git ref 'no-such-ref'"));
+}
+
+#[test]
+fn test_side_by_side_diff() {
+    let temp_dir = TempDir::new().expect("Failed to create temp directory");
+    fs::write(
+        temp_dir.path().join("App.tsx"),
+        r#"export const A = () => <div className="p-4 flex">A</div>;"#,
+    )
+    .expect("Failed to write test file");
+    fs::write(
+        temp_dir.path().join(".windwarden.json"),
+        r#"{"coloredOutput": false}"#,
+    )
+    .expect("Failed to write config");
+
+    let mut cmd = Command::cargo_bin("windwarden").unwrap();
+    cmd.current_dir(temp_dir.path())
+        .env("COLUMNS", "140")
+        .args(["format", "--diff", "--diff-style", "side-by-side", "App.tsx"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            r#"   1 export const A = () => <div className="p-4 flex">A</div>;       │    1 export const A = () => <div className="flex p-4">A</div>;"#,
+        ))
+        .stdout(predicate::str::contains("\x1b[").not());
+
+    // Too narrow for two columns, so the unified diff is shown
+    let mut cmd = Command::cargo_bin("windwarden").unwrap();
+    cmd.current_dir(temp_dir.path())
+        .env("COLUMNS", "50")
+        .args([
+            "format",
+            "--diff",
+            "--diff-style",
+            "side-by-side",
+            "App.tsx",
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            r#"+export const A = () => <div className="flex p-4">A</div>;"#,
+        ))
+        .stdout(predicate::str::contains("│").not());
+}
+
+#[test]
+fn test_interactive_requires_a_terminal() {
+    let temp_dir = TempDir::new().expect("Failed to create temp directory");
+    let file = temp_dir.path().join("App.tsx");
+    let content = r#"export const A = () => <div className="p-4 flex">A</div>;"#;
+    fs::write(&file, content).expect("Failed to write test file");
+
+    // Test stdin is a pipe, not a terminal
+    let mut cmd = Command::cargo_bin("windwarden").unwrap();
+    cmd.args(["format", "--fix", "--interactive"])
+        .arg(temp_dir.path())
+        .write_stdin("y\n")
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains("--interactive needs a terminal"));
+    assert_eq!(fs::read_to_string(&file).unwrap(), content);
+
+    let mut cmd = Command::cargo_bin("windwarden").unwrap();
+    cmd.args(["format", "--check", "--interactive"])
+        .arg(temp_dir.path())
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains(
+            "--interactive can only be used with --mode write",
+        ));
+}
+
+#[test]
+fn test_output_writes_results_to_a_file() {
+    let temp_dir = TempDir::new().expect("Failed to create temp directory");
+    fs::write(
+        temp_dir.path().join("App.tsx"),
+        r#"export const A = () => <div className="p-4 flex">A</div>;"#,
+    )
+    .expect("Failed to write test file");
+    let report = temp_dir.path().join("reports/nested/windwarden.json");
+
+    let mut cmd = Command::cargo_bin("windwarden").unwrap();
+    cmd.args(["check", "--format", "json", "--output"])
+        .arg(&report)
+        .arg(temp_dir.path().join("App.tsx"))
+        .assert()
+        .code(1)
+        .stdout(predicate::str::is_empty());
+
+    let json: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&report).unwrap()).unwrap();
+    assert_eq!(json["tool"], "windwarden");
+    assert_eq!(json["warningCount"], 1);
+
+    // Text output is written without colors
+    let text_report = temp_dir.path().join("windwarden.txt");
+    let mut cmd = Command::cargo_bin("windwarden").unwrap();
+    cmd.args(["format", "--diff", "-o"])
+        .arg(&text_report)
+        .arg(temp_dir.path().join("App.tsx"))
+        .assert()
+        .success()
+        .stdout(predicate::str::is_empty());
+    let text = fs::read_to_string(&text_report).unwrap();
+    assert!(text.contains(r#"className="flex p-4""#));
+    assert!(!text.contains('\u{1b}'));
+}
+
+#[test]
+fn test_explain_prints_class_categories() {
+    let temp_dir = TempDir::new().expect("Failed to create temp directory");
+    let file = temp_dir.path().join("App.tsx");
+    let source = r#"export const A = () => <div className="p-4 hover:bg-red-500 flex">A</div>;"#;
+    fs::write(&file, source).expect("Failed to write test file");
+
+    let mut cmd = Command::cargo_bin("windwarden").unwrap();
+    cmd.args(["format", "--explain"])
+        .arg(&file)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            r#""p-4 hover:bg-red-500 flex" → "flex p-4 hover:bg-red-500""#,
+        ))
+        .stdout(predicate::str::contains("  flex → flexbox-grid (#2)"))
+        .stdout(predicate::str::contains("  p-4 → spacing (#3)"))
+        .stdout(predicate::str::contains(
+            "  hover:bg-red-500 → backgrounds (#6) [variants: hover]",
+        ));
+    // Nothing is written
+    assert_eq!(fs::read_to_string(&file).unwrap(), source);
+
+    // Custom orders are reflected, and stdin is read for `-`
+    fs::write(
+        temp_dir.path().join(".windwarden.json"),
+        r#"{"sortOrder": "custom", "customOrder": ["spacing", "flexbox-grid"]}"#,
+    )
+    .expect("Failed to write config");
+    let mut cmd = Command::cargo_bin("windwarden").unwrap();
+    let output = cmd
+        .current_dir(temp_dir.path())
+        .args(["format", "--explain", "-", "--format", "json"])
+        .write_stdin(r#"const c = cn("flex p-4 bg-red-500");"#)
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+
+    let json: serde_json::Value = serde_json::from_slice(&output).unwrap();
+    assert_eq!(json[0]["sorted"], "p-4 flex bg-red-500");
+    assert_eq!(json[0]["classes"][0]["categoryIndex"], 0);
+    assert_eq!(json[0]["classes"][2]["category"], "backgrounds");
+    assert_eq!(
+        json[0]["classes"][2]["categoryIndex"],
+        serde_json::Value::Null
+    );
+
+    // --stdin-filepath picks the parser for `-`
+    let mut cmd = Command::cargo_bin("windwarden").unwrap();
+    cmd.current_dir(temp_dir.path())
+        .args(["--stdin-filepath", "Card.astro", "format", "--explain", "-"])
+        .write_stdin("---\nconst title = 'Card';\n---\n<div class=\"flex p-4\">{title}</div>\n")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            r#"Card.astro:4:12: "flex p-4" → "p-4 flex""#,
+        ));
+
+    let mut cmd = Command::cargo_bin("windwarden").unwrap();
+    cmd.args(["--stdin-filepath", "Card.astro", "format", "src/"])
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains(
+            "--stdin-filepath can only be used with --stdin or --explain -",
+        ));
+}
+
+#[test]
+fn test_explain_takes_one_file() {
+    let mut cmd = Command::cargo_bin("windwarden").unwrap();
+    cmd.args(["format", "--explain", "a.tsx", "b.tsx"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "--explain takes a single file, or - for stdin",
+        ));
+}
+
+#[test]
+fn test_config_validate_stdin() {
+    let temp_dir = TempDir::new().expect("Failed to create temp directory");
+
+    let mut cmd = Command::cargo_bin("windwarden").unwrap();
+    cmd.current_dir(temp_dir.path())
+        .args(["config", "validate", "--stdin"])
+        .write_stdin(r#"{"sortOrder": "custom", "customOrder": ["spacing", "layout"]}"#)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("✓ Configuration is valid"));
+
+    let mut cmd = Command::cargo_bin("windwarden").unwrap();
+    cmd.current_dir(temp_dir.path())
+        .args(["config", "validate", "--stdin"])
+        .write_stdin(r#"{"sortOrder": "fancy"}"#)
+        .assert()
+        .code(1)
+        .stderr(predicate::str::contains("✗ Configuration is invalid"))
+        .stderr(predicate::str::contains("Invalid sort_order 'fancy'"));
+
+    // No configuration file is needed or written
+    assert!(fs::read_dir(temp_dir.path()).unwrap().next().is_none());
+}
+
+#[test]
+fn test_config_init_interactive_without_terminal() {
+    let temp_dir = TempDir::new().expect("Failed to create temp directory");
+
+    // Without a terminal to answer on, the defaults are written
+    let mut cmd = Command::cargo_bin("windwarden").unwrap();
+    cmd.current_dir(temp_dir.path())
+        .args(["config", "init", "--interactive"])
+        .write_stdin("custom\n")
+        .assert()
+        .success()
+        .stderr(predicate::str::contains(
+            "--interactive needs a terminal to read answers from; writing the default configuration",
+        ));
+
+    let content = fs::read_to_string(temp_dir.path().join(".windwarden.json")).unwrap();
+    let config: serde_json::Value = serde_json::from_str(&content).unwrap();
+    assert_eq!(config["sortOrder"], "official");
+}
+
+#[test]
+fn test_manifest_selects_files() {
+    let temp_dir = TempDir::new().expect("Failed to create temp directory");
+    let unsorted = r#"export const A = () => <div className="p-4 flex">A</div>;"#;
+    fs::create_dir_all(temp_dir.path().join("src")).unwrap();
+    fs::write(temp_dir.path().join("src/sorted.tsx"), unsorted).unwrap();
+    fs::write(temp_dir.path().join("src/skipped.tsx"), unsorted).unwrap();
+    fs::write(
+        temp_dir.path().join("manifest.json"),
+        r#"[
+            {"path": "src/sorted.tsx", "sort": true},
+            {"path": "src/skipped.tsx", "sort": false},
+            {"path": "src/missing.tsx", "sort": true}
+        ]"#,
+    )
+    .unwrap();
+
+    let mut cmd = Command::cargo_bin("windwarden").unwrap();
+    cmd.current_dir(temp_dir.path())
+        .args(["format", "--mode", "write", "--manifest", "manifest.json"])
+        .assert()
+        .success()
+        .stderr(predicate::str::contains(
+            "lists a file that does not exist: src/missing.tsx",
+        ));
+
+    let sorted = fs::read_to_string(temp_dir.path().join("src/sorted.tsx")).unwrap();
+    assert!(sorted.contains(r#"className="flex p-4""#));
+    let skipped = fs::read_to_string(temp_dir.path().join("src/skipped.tsx")).unwrap();
+    assert_eq!(skipped, unsorted);
+
+    // With paths, the manifest filters the discovered files
+    fs::write(temp_dir.path().join("src/sorted.tsx"), unsorted).unwrap();
+    let mut cmd = Command::cargo_bin("windwarden").unwrap();
+    cmd.current_dir(temp_dir.path())
+        .args(["check", "--manifest", "manifest.json", "src"])
+        .assert()
+        .code(1)
+        .stdout(predicate::str::contains("sorted.tsx"))
+        .stdout(predicate::str::contains("skipped.tsx").not());
+
+    let mut cmd = Command::cargo_bin("windwarden").unwrap();
+    cmd.current_dir(temp_dir.path())
+        .args(["check", "--verbose", "--manifest", "manifest.json", "src"])
+        .assert()
+        .code(1)
+        .stderr(predicate::str::contains(
+            "src/skipped.tsx: skipped (not selected)",
+        ));
+
+    fs::write(
+        temp_dir.path().join("manifest.json"),
+        r#"[{"path": "a.tsx"}]"#,
+    )
+    .unwrap();
+    let mut cmd = Command::cargo_bin("windwarden").unwrap();
+    cmd.current_dir(temp_dir.path())
+        .args(["check", "--manifest", "manifest.json"])
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains("Invalid manifest manifest.json"));
+}
+
+#[test]
+fn test_max_files_guard() {
+    let temp_dir = TempDir::new().expect("Failed to create temp directory");
+    let unsorted = r#"export const A = () => <div className="p-4 flex">A</div>;"#;
+    for i in 0..3 {
+        fs::write(temp_dir.path().join(format!("C{}.tsx", i)), unsorted).unwrap();
+    }
+
+    let mut cmd = Command::cargo_bin("windwarden").unwrap();
+    cmd.current_dir(temp_dir.path())
+        .args(["format", "--mode", "write", "--max-files", "2", "."])
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains(
+            "Found 3 files to process, more than the limit of 2",
+        ));
+    let content = fs::read_to_string(temp_dir.path().join("C0.tsx")).unwrap();
+    assert_eq!(content, unsorted);
+
+    // The config limit applies too, and --force lifts it
+    fs::write(
+        temp_dir.path().join(".windwarden.json"),
+        r#"{"maxFiles": 2}"#,
+    )
+    .unwrap();
+    let mut cmd = Command::cargo_bin("windwarden").unwrap();
+    cmd.current_dir(temp_dir.path())
+        .args(["check", "."])
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains("--max-files or maxFiles"));
+
+    let mut cmd = Command::cargo_bin("windwarden").unwrap();
+    cmd.current_dir(temp_dir.path())
+        .args(["check", "--force", "."])
+        .assert()
+        .code(1)
+        .stdout(predicate::str::contains("C2.tsx"));
+}
+
+#[test]
+#[cfg(unix)]
+fn test_on_change_runs_for_written_files() {
+    let temp_dir = TempDir::new().expect("Failed to create temp directory");
+    fs::write(
+        temp_dir.path().join("Unsorted.tsx"),
+        r#"export const A = () => <div className="p-4 flex">A</div>;"#,
+    )
+    .unwrap();
+    fs::write(
+        temp_dir.path().join("Sorted.tsx"),
+        r#"export const B = () => <div className="flex p-4">B</div>;"#,
+    )
+    .unwrap();
+
+    // Only the file that was written gets the hook
+    let mut cmd = Command::cargo_bin("windwarden").unwrap();
+    cmd.current_dir(temp_dir.path())
+        .args([
+            "format",
+            "--mode",
+            "write",
+            "--on-change",
+            "echo changed >> hook.log",
+            ".",
+        ])
+        .assert()
+        .success();
+    let log = fs::read_to_string(temp_dir.path().join("hook.log")).unwrap();
+    assert_eq!(log.trim(), "changed ./Unsorted.tsx");
+
+    // A failing hook fails the run, after the file is written
+    fs::write(
+        temp_dir.path().join("Unsorted.tsx"),
+        r#"export const A = () => <div className="p-4 flex">A</div>;"#,
+    )
+    .unwrap();
+    let mut cmd = Command::cargo_bin("windwarden").unwrap();
+    cmd.current_dir(temp_dir.path())
+        .args(["format", "--fix", "--on-change", "test -d", "."])
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains(
+            "--on-change `test -d` failed for ./Unsorted.tsx (exit status: 1)",
+        ));
+    let content = fs::read_to_string(temp_dir.path().join("Unsorted.tsx")).unwrap();
+    assert!(content.contains(r#"className="flex p-4""#));
+
+    let mut cmd = Command::cargo_bin("windwarden").unwrap();
+    cmd.current_dir(temp_dir.path())
+        .args(["format", "--check", "--on-change", "git add", "."])
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains(
+            "--on-change can only be used with --mode write",
+        ));
+}
+
+#[test]
+fn test_strict_unknown_reports_unknown_classes() {
+    let temp_dir = TempDir::new().expect("Failed to create temp directory");
+    fs::write(
+        temp_dir.path().join("Button.tsx"),
+        r#"export const A = () => <div className="flex flexx m-2 p-4">A</div>;"#,
+    )
+    .unwrap();
+
+    // Unknown classes aren't reported by default
+    let mut cmd = Command::cargo_bin("windwarden").unwrap();
+    cmd.current_dir(temp_dir.path())
+        .args(["check", "Button.tsx"])
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("unknown class").not());
+
+    let mut cmd = Command::cargo_bin("windwarden").unwrap();
+    cmd.current_dir(temp_dir.path())
+        .args(["check", "--strict-unknown", "Button.tsx"])
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains(
+            "Button.tsx:1:39: unknown class 'flexx'",
+        ));
+
+    // stdin still prints the formatted output
+    let mut cmd = Command::cargo_bin("windwarden").unwrap();
+    cmd.args(["--stdin", "--strict-unknown"])
+        .write_stdin(r#"const c = cn("p-4 flexx flex");"#)
+        .assert()
+        .code(2)
+        .stdout(predicate::str::contains(r#"cn("flex flexx p-4")"#))
+        .stderr(predicate::str::contains(
+            "stdin.tsx:1:14: unknown class 'flexx'",
+        ));
+
+    // Allowed classes and warnings don't fail the run
+    fs::write(
+        temp_dir.path().join("Button.tsx"),
+        r#"export const A = () => <div className="flexx flex m-2 p-4">A</div>;"#,
+    )
+    .unwrap();
+    fs::write(
+        temp_dir.path().join(".windwarden.json"),
+        r#"{"unknownClasses": "warn", "knownCustomClasses": ["flexx"]}"#,
+    )
+    .unwrap();
+    let mut cmd = Command::cargo_bin("windwarden").unwrap();
+    cmd.current_dir(temp_dir.path())
+        .args(["check", "Button.tsx"])
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("unknown class").not());
+}
+
+#[test]
+fn test_config_from_environment_variable() {
+    let temp_dir = TempDir::new().expect("Failed to create temp directory");
+    fs::write(
+        temp_dir.path().join("App.tsx"),
+        r#"export const A = () => <div className={tw("p-4 flex")}>A</div>;"#,
+    )
+    .unwrap();
+    fs::write(
+        temp_dir.path().join("custom.json"),
+        r#"{"functionNames": ["other"]}"#,
+    )
+    .unwrap();
+    fs::write(temp_dir.path().join(".windwarden.json"), "{}").unwrap();
+
+    // The variable takes precedence over the discovered file
+    let mut cmd = Command::cargo_bin("windwarden").unwrap();
+    cmd.current_dir(temp_dir.path())
+        .env("WINDWARDEN_CONFIG", r#"{"functionNames": ["tw"]}"#)
+        .args(["check", "App.tsx"])
+        .assert()
+        .code(1);
+
+    // --config takes precedence over the variable
+    let mut cmd = Command::cargo_bin("windwarden").unwrap();
+    cmd.current_dir(temp_dir.path())
+        .env("WINDWARDEN_CONFIG", r#"{"functionNames": ["tw"]}"#)
+        .args(["--config", "custom.json", "check", "App.tsx"])
+        .assert()
+        .success();
+
+    let mut cmd = Command::cargo_bin("windwarden").unwrap();
+    cmd.current_dir(temp_dir.path())
+        .env("WINDWARDEN_CONFIG", r#"{"functionNames": ["tw"]"#)
+        .args(["check", "App.tsx"])
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains(
+            "Invalid configuration in the WINDWARDEN_CONFIG environment variable",
+        ));
+}
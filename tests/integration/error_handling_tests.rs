@@ -99,9 +99,10 @@ function SyntaxErrorComponent() {
         .arg("check")
         .arg(temp_dir.path().join("syntax_error.jsx"))
         .assert()
-        .failure() // Should fail due to parse errors
+        .code(2) // Files that fail to parse are errors, not formatting issues
         .stdout(predicate::str::contains("Failed to process:"))
-        .stdout(predicate::str::contains("Parse error"));
+        .stdout(predicate::str::contains("Parse error"))
+        .stdout(predicate::str::contains("at line 9, column"));
 }
 
 #[test]
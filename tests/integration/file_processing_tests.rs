@@ -162,6 +162,59 @@ fn test_format_command_verify_mode() {
         .stdout(predicate::str::contains("not formatted"));
 }
 
+#[test]
+fn test_verify_summary_line() {
+    let temp_dir = TempDir::new().expect("Failed to create temp directory");
+    fs::write(
+        temp_dir.path().join("sorted.tsx"),
+        r#"export const A = () => <div className="flex p-4">A</div>;"#,
+    )
+    .expect("Failed to write test file");
+    fs::write(
+        temp_dir.path().join("unsorted.tsx"),
+        r#"export const B = () => <div className="p-4 flex">B</div>;"#,
+    )
+    .expect("Failed to write test file");
+    fs::write(
+        temp_dir.path().join("broken.tsx"),
+        r#"export const C = () => <div className="p-4 flex">"#,
+    )
+    .expect("Failed to write test file");
+
+    let mut cmd = Command::cargo_bin("windwarden").unwrap();
+    let output = cmd
+        .arg("check")
+        .arg("--summary-line")
+        .arg(temp_dir.path())
+        .output()
+        .expect("Failed to run windwarden");
+
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert_eq!(
+        stdout.lines().last(),
+        Some("unformatted=1 errors=1 total=3"),
+        "{}",
+        stdout
+    );
+}
+
+#[test]
+fn test_summary_line_requires_verify_text_output() {
+    let temp_dir = create_test_directory();
+
+    let mut cmd = Command::cargo_bin("windwarden").unwrap();
+    cmd.arg("check")
+        .arg("--summary-line")
+        .arg("--format")
+        .arg("json")
+        .arg(temp_dir.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "--summary-line can only be used with --mode verify and --format text",
+        ));
+}
+
 #[test]
 fn test_check_command() {
     let temp_dir = create_test_directory();
@@ -174,6 +227,31 @@ fn test_check_command() {
         .stdout(predicate::str::contains("not formatted"));
 }
 
+#[test]
+fn test_mdx_files_are_processed_by_default() {
+    let temp_dir = TempDir::new().expect("Failed to create temp directory");
+    let doc = temp_dir.path().join("guide.mdx");
+    fs::write(
+        &doc,
+        "# Guide\n\nKeep \"p-4 flex\" in prose.\n\n```html\n<div class=\"p-4 flex\"></div>\n```\n\n<div className=\"p-4 flex\">Hi</div>\n",
+    )
+    .expect("Failed to write test file");
+
+    let mut cmd = Command::cargo_bin("windwarden").unwrap();
+    cmd.arg("format")
+        .arg("--mode")
+        .arg("write")
+        .arg(temp_dir.path())
+        .assert()
+        .success();
+
+    let content = fs::read_to_string(&doc).expect("Failed to read test file");
+    assert_eq!(
+        content,
+        "# Guide\n\nKeep \"p-4 flex\" in prose.\n\n```html\n<div class=\"p-4 flex\"></div>\n```\n\n<div className=\"flex p-4\">Hi</div>\n"
+    );
+}
+
 #[test]
 fn test_directory_processing() {
     let temp_dir = create_test_directory();
@@ -712,3 +790,121 @@ export const FormattedComponent = () => (
         .stdout(predicate::str::contains("would be formatted"))
         .stdout(predicate::str::contains("Statistics:"));
 }
+
+#[cfg(unix)]
+#[test]
+fn test_write_mode_preserves_file_permissions() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let temp_dir = TempDir::new().expect("Failed to create temp directory");
+    let file = temp_dir.path().join("Private.tsx");
+    fs::write(
+        &file,
+        r#"export const P = () => <div className="p-4 flex">P</div>;"#,
+    )
+    .expect("Failed to write test file");
+    fs::set_permissions(&file, fs::Permissions::from_mode(0o600))
+        .expect("Failed to set permissions");
+
+    let mut cmd = Command::cargo_bin("windwarden").unwrap();
+    cmd.arg("format")
+        .arg("--mode")
+        .arg("write")
+        .arg(&file)
+        .assert()
+        .success();
+
+    assert!(
+        fs::read_to_string(&file)
+            .unwrap()
+            .contains(r#"className="flex p-4""#)
+    );
+    let mode = fs::metadata(&file).unwrap().permissions().mode();
+    assert_eq!(mode & 0o777, 0o600);
+}
+
+#[test]
+fn test_write_dry_run_leaves_files_untouched() {
+    let temp_dir = TempDir::new().expect("Failed to create temp directory");
+    let file = temp_dir.path().join("Card.tsx");
+    let original = r#"export const C = () => <div className="p-4 flex">C</div>;"#;
+    fs::write(&file, original).expect("Failed to write test file");
+    let modified = fs::metadata(&file).unwrap().modified().unwrap();
+
+    let mut cmd = Command::cargo_bin("windwarden").unwrap();
+    cmd.arg("format")
+        .arg("--mode")
+        .arg("write")
+        .arg("--dry-run")
+        .arg(&file)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            r#"+export const C = () => <div className="flex p-4">C</div>;"#,
+        ));
+
+    let mut cmd = Command::cargo_bin("windwarden").unwrap();
+    cmd.arg("format")
+        .arg("--mode")
+        .arg("write")
+        .arg("--dry-run")
+        .arg("--format")
+        .arg("json")
+        .arg(&file)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(r#""ruleId": "class-order""#))
+        .stdout(predicate::str::contains(
+            r#""output": "export const C = () => <div className=\"flex p-4\">C</div>;""#,
+        ));
+
+    assert_eq!(fs::read_to_string(&file).unwrap(), original);
+    assert_eq!(fs::metadata(&file).unwrap().modified().unwrap(), modified);
+    let entries = fs::read_dir(temp_dir.path()).unwrap().count();
+    assert_eq!(entries, 1, "no temp or backup files should be created");
+
+    let mut cmd = Command::cargo_bin("windwarden").unwrap();
+    cmd.arg("format")
+        .arg("--dry-run")
+        .arg(&file)
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("--mode write"));
+}
+
+#[test]
+fn test_config_overrides_apply_to_matching_files() {
+    let temp_dir = TempDir::new().expect("Failed to create temp directory");
+    let content = r#"export const styles = storyClasses("p-4 flex");"#;
+
+    fs::write(temp_dir.path().join("Button.stories.tsx"), content)
+        .expect("Failed to write story file");
+    fs::write(temp_dir.path().join("Button.tsx"), content).expect("Failed to write component");
+
+    let config_content = r#"{
+  "overrides": [
+    { "files": ["**/*.stories.tsx"], "config": { "functionNames": ["storyClasses"] } }
+  ]
+}"#;
+    fs::write(temp_dir.path().join(".windwarden.json"), config_content)
+        .expect("Failed to write config file");
+
+    let mut cmd = Command::cargo_bin("windwarden").unwrap();
+    cmd.arg("--config")
+        .arg(temp_dir.path().join(".windwarden.json"))
+        .arg("format")
+        .arg("--mode")
+        .arg("write")
+        .arg(temp_dir.path())
+        .assert()
+        .success();
+
+    assert_eq!(
+        fs::read_to_string(temp_dir.path().join("Button.stories.tsx")).unwrap(),
+        r#"export const styles = storyClasses("flex p-4");"#
+    );
+    assert_eq!(
+        fs::read_to_string(temp_dir.path().join("Button.tsx")).unwrap(),
+        content
+    );
+}
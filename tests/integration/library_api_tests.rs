@@ -0,0 +1,70 @@
+use windwarden::config::{Config, ConfigManager};
+use windwarden::sort_class_string;
+
+#[test]
+fn test_sort_class_string_default_order() {
+    let config = Config::default();
+    assert_eq!(
+        sort_class_string("text-white p-4 flex bg-blue-500 md:p-8", &config),
+        "flex p-4 md:p-8 text-white bg-blue-500"
+    );
+    assert_eq!(sort_class_string("flex p-4", &config), "flex p-4");
+    assert_eq!(sort_class_string("", &config), "");
+}
+
+#[test]
+fn test_sort_class_string_custom_order() {
+    let config = Config {
+        sort_order: "custom".to_string(),
+        custom_order: vec!["spacing".to_string(), "typography".to_string()],
+        ..Config::default()
+    };
+    assert_eq!(
+        sort_class_string("flex text-white p-4", &config),
+        "p-4 text-white flex"
+    );
+
+    // customOrder only applies with sortOrder "custom"
+    let official = Config {
+        sort_order: "official".to_string(),
+        ..config
+    };
+    assert_eq!(
+        sort_class_string("flex text-white p-4", &official),
+        sort_class_string("flex text-white p-4", &Config::default())
+    );
+}
+
+#[test]
+fn test_sort_class_string_cleanup_settings() {
+    let config = Config::default();
+    assert_eq!(sort_class_string("p-4 flex p-4", &config), "flex p-4");
+
+    let config = Config {
+        preserve_duplicates: true,
+        ..Config::default()
+    };
+    assert_eq!(sort_class_string("p-4 flex p-4", &config), "flex p-4 p-4");
+}
+
+#[test]
+fn test_validate_config_without_a_file() {
+    assert!(ConfigManager::validate(&Config::default()).is_ok());
+
+    let config = Config {
+        sort_order: "custom".to_string(),
+        ..Config::default()
+    };
+    let error = ConfigManager::validate(&config).unwrap_err();
+    assert!(error.to_string().contains("custom_order must be provided"));
+
+    // Generated JSON can be parsed and validated in one step
+    let config = ConfigManager::parse_config(r#"{"threads": 4}"#, "generated").unwrap();
+    assert_eq!(config.threads, 4);
+    let error = ConfigManager::parse_config(r#"{"threads": "four"}"#, "generated").unwrap_err();
+    assert!(
+        error
+            .to_string()
+            .contains("Invalid configuration in generated")
+    );
+}
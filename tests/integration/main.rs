@@ -7,14 +7,18 @@
 // - cargo test --test integration cli_tests
 // - cargo test --test integration error_handling_tests
 // - cargo test --test integration file_processing_tests
+// - cargo test --test integration library_api_tests
 // - cargo test --test integration output_reporting_tests
 // - cargo test --test integration performance_tests
+// - cargo test --test integration prettier_compat_tests
 
 mod cli_tests;
 mod error_handling_tests;
 mod file_processing_tests;
+mod library_api_tests;
 mod output_reporting_tests;
 mod performance_tests;
+mod prettier_compat_tests;
 
 #[cfg(test)]
 mod test_runner {
@@ -32,8 +36,10 @@ mod test_runner {
         println!("- CLI Tests: Basic command-line interface functionality");
         println!("- Error Handling Tests: Error scenarios and recovery");
         println!("- File Processing Tests: Core file processing workflows");
+        println!("- Library API Tests: Public functions for embedding WindWarden");
         println!("- Output Reporting Tests: Correct reporting of changes and formatting status");
         println!("- Performance Tests: Performance and scalability validation");
+        println!("- Prettier Compat Tests: Parity with prettier-plugin-tailwindcss");
         println!();
 
         if env::var("RUST_LOG").is_err() {
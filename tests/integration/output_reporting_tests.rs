@@ -268,6 +268,40 @@ fn test_diff_output_shows_actual_changes() {
         .stdout(predicate::str::contains("+    <button className=\""));
 }
 
+#[test]
+fn test_diff_context_limits_surrounding_lines() {
+    let temp_dir = TempDir::new().unwrap();
+
+    let test_file = temp_dir.path().join("test.tsx");
+    fs::write(
+        &test_file,
+        r#"function Button() {
+  const label = "Click me";
+  return (
+    <button className="p-4 bg-blue-500 text-white flex items-center">
+      {label}
+    </button>
+  );
+}"#,
+    )
+    .unwrap();
+
+    let mut cmd = Command::cargo_bin("windwarden").unwrap();
+    cmd.arg("format")
+        .arg("--mode")
+        .arg("check")
+        .arg("--diff")
+        .arg("--diff-context")
+        .arg("1")
+        .arg(&test_file)
+        .env("NO_COLOR", "1")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("@@ -3,3 +3,3 @@"))
+        .stdout(predicate::str::contains("   return ("))
+        .stdout(predicate::str::contains("const label").not());
+}
+
 #[test]
 fn test_no_config_uses_official_tailwind_order() {
     let temp_dir = TempDir::new().unwrap();
@@ -299,3 +333,95 @@ fn test_no_config_uses_official_tailwind_order() {
         // Should reorder to official Tailwind order: flex items-center first, then spacing, then colors
         .stdout(predicate::str::contains("flex items-center"));
 }
+
+#[test]
+fn test_json_format_includes_failed_files() {
+    let temp_dir = TempDir::new().unwrap();
+    fs::write(
+        temp_dir.path().join("valid.tsx"),
+        r#"export const A = () => <div className="p-4 flex">A</div>;"#,
+    )
+    .unwrap();
+    fs::write(
+        temp_dir.path().join("broken.tsx"),
+        "export const B = () => <div className=\"flex\">\n  const = (;\n",
+    )
+    .unwrap();
+
+    let output = Command::cargo_bin("windwarden")
+        .unwrap()
+        .args(["check", "--format", "json"])
+        .arg(temp_dir.path())
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(2));
+
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let results = report["results"].as_array().unwrap();
+    assert_eq!(results.len(), 2);
+    assert_eq!(report["errorCount"], 1);
+    assert_eq!(report["warningCount"], 1);
+
+    let file = |name: &str| {
+        results
+            .iter()
+            .find(|r| r["filePath"].as_str().unwrap().ends_with(name))
+            .unwrap()
+    };
+    assert_eq!(file("valid.tsx")["messages"][0]["ruleId"], "class-order");
+
+    let broken = file("broken.tsx");
+    assert_eq!(broken["errorCount"], 1);
+    let error = &broken["messages"][0];
+    assert_eq!(error["ruleId"], "parse-error");
+    assert_eq!(error["severity"], "error");
+    assert!(error["line"].as_u64().unwrap() > 1);
+    assert!(error["message"].as_str().unwrap().contains("Parse error"));
+}
+
+#[test]
+fn test_output_order_matches_between_sequential_and_parallel() {
+    let temp_dir = TempDir::new().unwrap();
+    for dir in ["b", "a", "a/nested"] {
+        fs::create_dir_all(temp_dir.path().join(dir)).unwrap();
+        for i in 0..8 {
+            fs::write(
+                temp_dir.path().join(dir).join(format!("C{}.tsx", i)),
+                r#"export const A = () => <div className="p-4 flex">A</div>;"#,
+            )
+            .unwrap();
+        }
+    }
+
+    let run = |args: &[&str]| {
+        let output = Command::cargo_bin("windwarden")
+            .unwrap()
+            .current_dir(temp_dir.path())
+            .args(["check"])
+            .args(args)
+            .arg(".")
+            .output()
+            .unwrap();
+        String::from_utf8(output.stdout).unwrap()
+    };
+
+    for format in ["text", "json"] {
+        let sequential = run(&["--processing", "sequential", "--format", format]);
+        assert_eq!(
+            sequential,
+            run(&["--processing", "parallel", "--format", format])
+        );
+        assert_eq!(sequential, run(&["--threads", "4", "--format", format]));
+    }
+
+    // Files are listed by path
+    let text = run(&["--processing", "parallel"]);
+    let listed: Vec<&str> = text
+        .lines()
+        .filter_map(|line| line.trim().strip_prefix("./"))
+        .collect();
+    let mut sorted = listed.clone();
+    sorted.sort();
+    assert_eq!(listed.len(), 24);
+    assert_eq!(listed, sorted);
+}
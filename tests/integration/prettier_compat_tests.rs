@@ -0,0 +1,64 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use windwarden::ProcessOptions;
+use windwarden::config::Config;
+use windwarden::processor::FileProcessor;
+
+/// Input files of the prettier fixtures, each next to a `.expected` twin
+/// holding what prettier-plugin-tailwindcss produces for it
+fn fixture_inputs() -> Vec<PathBuf> {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/prettier");
+    let mut inputs: Vec<PathBuf> = fs::read_dir(dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.contains(".input."))
+        })
+        .collect();
+    inputs.sort();
+    inputs
+}
+
+#[test]
+fn test_prettier_fixtures_match_byte_for_byte() {
+    let config = Config {
+        prettier_compat: true,
+        ..Config::default()
+    };
+    let processor = FileProcessor::new_with_config(&config);
+
+    let inputs = fixture_inputs();
+    assert!(!inputs.is_empty(), "no prettier fixtures found");
+
+    for input in inputs {
+        let expected_path = input.to_str().unwrap().replace(".input.", ".expected.");
+        let source = fs::read_to_string(&input).unwrap();
+        let expected = fs::read_to_string(&expected_path).unwrap();
+
+        let output = processor
+            .process_content(&source, input.to_str().unwrap(), ProcessOptions::default())
+            .unwrap();
+        assert_eq!(
+            output,
+            expected,
+            "{} differs from prettier",
+            input.display()
+        );
+    }
+}
+
+#[test]
+fn test_default_order_differs_from_prettier() {
+    // Without the compat mode, classes keep WindWarden's category order
+    let processor = FileProcessor::new_with_config(&Config::default());
+    let output = processor
+        .process_content(
+            r#"<a className="text-white px-4 bg-sky-700" />"#,
+            "test.tsx",
+            ProcessOptions::default(),
+        )
+        .unwrap();
+    assert_eq!(output, r#"<a className="px-4 text-white bg-sky-700" />"#);
+}